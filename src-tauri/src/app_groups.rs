@@ -0,0 +1,136 @@
+//! Display groups that roll several apps up under one name on the dashboard
+//!
+//! Unlike categories, groups are purely presentational: usage stays recorded
+//! per app, and grouped views sum the members under the group name.
+
+use crate::database::AppUsage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A named set of apps shown together (e.g. "Browsers" = Firefox + Chrome)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppGroup {
+    pub name: String,
+    pub members: Vec<String>,
+}
+
+/// Map each member app to the group it belongs to
+pub fn membership(groups: &[AppGroup]) -> HashMap<String, String> {
+    groups
+        .iter()
+        .flat_map(|group| {
+            group
+                .members
+                .iter()
+                .map(move |member| (member.clone(), group.name.clone()))
+        })
+        .collect()
+}
+
+/// Sum usage of grouped apps under their group name. Apps that aren't in a
+/// group are passed through unchanged. A group keeps a category only if all of
+/// its members with usage share it.
+pub fn group_app_usage(apps: Vec<AppUsage>, membership: &HashMap<String, String>) -> Vec<AppUsage> {
+    let mut result: Vec<AppUsage> = Vec::new();
+    let mut group_index: HashMap<String, usize> = HashMap::new();
+
+    for app in apps {
+        let Some(group_name) = membership.get(&app.app_name) else {
+            result.push(app);
+            continue;
+        };
+
+        match group_index.get(group_name) {
+            Some(&index) => {
+                let entry = &mut result[index];
+                entry.duration_seconds += app.duration_seconds;
+                entry.session_count += app.session_count;
+                if entry.category != app.category {
+                    entry.category = None;
+                }
+                entry.pinned |= app.pinned;
+            }
+            None => {
+                group_index.insert(group_name.clone(), result.len());
+                result.push(AppUsage {
+                    app_name: group_name.clone(),
+                    icon: None,
+                    ..app
+                });
+            }
+        }
+    }
+
+    result.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then(b.duration_seconds.cmp(&a.duration_seconds))
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(name: &str, seconds: i64, category: &str) -> AppUsage {
+        AppUsage {
+            app_name: name.to_string(),
+            duration_seconds: seconds,
+            session_count: 1,
+            category: Some(category.to_string()),
+            pinned: false,
+            icon: None,
+        }
+    }
+
+    fn browsers() -> Vec<AppGroup> {
+        vec![AppGroup {
+            name: "Browsers".to_string(),
+            members: vec!["Firefox".to_string(), "Chrome".to_string()],
+        }]
+    }
+
+    #[test]
+    fn test_grouped_apps_are_summed() {
+        let apps = vec![
+            usage("Firefox", 600, "Browser"),
+            usage("Code", 900, "Development"),
+            usage("Chrome", 500, "Browser"),
+        ];
+
+        let grouped = group_app_usage(apps, &membership(&browsers()));
+
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(grouped[0].app_name, "Browsers");
+        assert_eq!(grouped[0].duration_seconds, 1100);
+        assert_eq!(grouped[0].session_count, 2);
+        assert_eq!(grouped[0].category.as_deref(), Some("Browser"));
+    }
+
+    #[test]
+    fn test_ungrouped_apps_stay_separate() {
+        let apps = vec![
+            usage("Code", 900, "Development"),
+            usage("Slack", 300, "Communication"),
+        ];
+
+        let grouped = group_app_usage(apps, &membership(&browsers()));
+
+        let names: Vec<&str> = grouped.iter().map(|a| a.app_name.as_str()).collect();
+        assert_eq!(names, vec!["Code", "Slack"]);
+    }
+
+    #[test]
+    fn test_mixed_category_group_has_no_category() {
+        let apps = vec![
+            usage("Firefox", 600, "Browser"),
+            usage("Chrome", 500, "Work"),
+        ];
+
+        let grouped = group_app_usage(apps, &membership(&browsers()));
+
+        assert_eq!(grouped.len(), 1);
+        assert_eq!(grouped[0].category, None);
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Mutex;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstalledApp {
@@ -11,6 +13,191 @@ pub struct InstalledApp {
     pub categories: Vec<String>,
 }
 
+/// Where a search result was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppSource {
+    Installed,
+    Tracked,
+    Both,
+}
+
+/// A ranked app search result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppSearchResult {
+    pub name: String,
+    pub source: AppSource,
+    pub score: u32,
+}
+
+/// Outcome of bulk-assigning categories from installed apps
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CategoryAssignmentSummary {
+    /// Tracked apps that received a category
+    pub categorized: usize,
+    /// Tracked apps with no matching installed app (or no mappable category)
+    pub unmatched: usize,
+}
+
+/// Normalize an app name for matching: lowercase alphanumerics only
+fn normalize_app_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Keys an installed app can be matched by: its name and its executable's file name
+fn installed_app_keys(app: &InstalledApp) -> Vec<String> {
+    let mut keys = vec![normalize_app_name(&app.name)];
+    if let Some(exec) = &app.exec {
+        let program = exec.split_whitespace().next().unwrap_or_default();
+        let file_name = program.rsplit(['/', '\\']).next().unwrap_or(program);
+        let file_name = file_name.strip_suffix(".exe").unwrap_or(file_name);
+        keys.push(normalize_app_name(file_name));
+    }
+    keys.retain(|k| !k.is_empty());
+    keys
+}
+
+/// Pair each uncategorized tracked app with the category of a matching
+/// installed app. Returns the (app, category) assignments and how many apps
+/// couldn't be categorized.
+pub fn match_installed_categories(
+    uncategorized: &[String],
+    installed: &[InstalledApp],
+) -> (Vec<(String, String)>, usize) {
+    let mut categories: HashMap<String, String> = HashMap::new();
+    for app in installed {
+        if let Some(category) = map_category(&app.categories) {
+            for key in installed_app_keys(app) {
+                categories.entry(key).or_insert_with(|| category.clone());
+            }
+        }
+    }
+
+    let mut assignments = Vec::new();
+    let mut unmatched = 0;
+    for name in uncategorized {
+        match categories.get(&normalize_app_name(name)) {
+            Some(category) => assignments.push((name.clone(), category.clone())),
+            None => unmatched += 1,
+        }
+    }
+    (assignments, unmatched)
+}
+
+/// Score how well `query` matches `candidate` (case-insensitive).
+/// Prefix matches beat substring matches, which beat subsequence matches.
+/// Returns None when the query doesn't match at all.
+pub fn match_score(query: &str, candidate: &str) -> Option<u32> {
+    let query = query.trim().to_lowercase();
+    let candidate = candidate.to_lowercase();
+
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    if candidate.starts_with(&query) {
+        return Some(300);
+    }
+
+    if let Some(pos) = candidate.find(&query) {
+        // Matches at the start of a word rank above mid-word matches
+        let at_word_start = candidate[..pos]
+            .chars()
+            .last()
+            .is_some_and(|c| !c.is_alphanumeric());
+        return Some(if at_word_start { 250 } else { 200 });
+    }
+
+    // Subsequence: every query char appears in order
+    let mut chars = candidate.chars();
+    if query.chars().all(|q| chars.any(|c| c == q)) {
+        return Some(100);
+    }
+
+    None
+}
+
+/// Fuzzy-search app names from the installed and tracked lists.
+/// Results are deduplicated case-insensitively and ranked by score, then by
+/// shorter name, then alphabetically.
+pub fn search_apps(
+    query: &str,
+    installed: &[String],
+    tracked: &[String],
+    limit: usize,
+) -> Vec<AppSearchResult> {
+    let mut results: Vec<AppSearchResult> = Vec::new();
+
+    let sources = installed
+        .iter()
+        .map(|name| (name, AppSource::Installed))
+        .chain(tracked.iter().map(|name| (name, AppSource::Tracked)));
+
+    for (name, source) in sources {
+        let Some(score) = match_score(query, name) else {
+            continue;
+        };
+
+        if let Some(existing) = results
+            .iter_mut()
+            .find(|r| r.name.eq_ignore_ascii_case(name))
+        {
+            if existing.source != source {
+                existing.source = AppSource::Both;
+            }
+            continue;
+        }
+
+        results.push(AppSearchResult {
+            name: name.clone(),
+            source,
+            score,
+        });
+    }
+
+    results.sort_by(|a, b| rank_matches((a.score, &a.name), (b.score, &b.name)));
+    results.truncate(limit);
+    results
+}
+
+/// Order two `(score, name)` matches: higher score first, then shorter name,
+/// then alphabetically
+fn rank_matches(a: (u32, &str), b: (u32, &str)) -> std::cmp::Ordering {
+    b.0.cmp(&a.0)
+        .then(a.1.len().cmp(&b.1.len()))
+        .then(a.1.to_lowercase().cmp(&b.1.to_lowercase()))
+}
+
+/// Largest data URL accepted as a custom app icon
+pub const MAX_CUSTOM_ICON_BYTES: usize = 256 * 1024;
+
+/// Check a user-chosen icon: either an existing image file or an image data
+/// URL no larger than `MAX_CUSTOM_ICON_BYTES`
+pub fn validate_custom_icon(icon: &str) -> Result<(), String> {
+    if icon.starts_with("data:") {
+        if !icon.starts_with("data:image/") || !icon.contains(',') {
+            return Err("Icon data URL must contain an image".to_string());
+        }
+        if icon.len() > MAX_CUSTOM_ICON_BYTES {
+            return Err(format!(
+                "Icon is too large ({} KB, max {} KB)",
+                icon.len() / 1024,
+                MAX_CUSTOM_ICON_BYTES / 1024
+            ));
+        }
+        return Ok(());
+    }
+
+    if PathBuf::from(icon).is_file() {
+        Ok(())
+    } else {
+        Err(format!("Icon file not found: {}", icon))
+    }
+}
+
 /// Resolve an icon name or path to an absolute file path.
 /// Returns None if the icon cannot be found.
 pub fn resolve_icon_path(icon: &str) -> Option<String> {
@@ -200,6 +387,64 @@ pub fn get_installed_apps() -> Vec<InstalledApp> {
     }
 }
 
+/// Result of the last installed-app scan, kept until invalidated
+pub struct InstalledAppsCache {
+    apps: Mutex<Option<Vec<InstalledApp>>>,
+}
+
+impl InstalledAppsCache {
+    pub const fn new() -> Self {
+        Self {
+            apps: Mutex::new(None),
+        }
+    }
+
+    /// The cached apps, running `scan` first if nothing is cached
+    pub fn get_or_scan(&self, scan: impl FnOnce() -> Vec<InstalledApp>) -> Vec<InstalledApp> {
+        let mut apps = self.apps.lock().unwrap_or_else(|e| e.into_inner());
+        apps.get_or_insert_with(scan).clone()
+    }
+
+    /// Drop the cached apps so the next lookup scans again
+    pub fn invalidate(&self) {
+        *self.apps.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+impl Default for InstalledAppsCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static INSTALLED_APPS: InstalledAppsCache = InstalledAppsCache::new();
+
+/// Installed applications, scanned once and then served from the cache
+pub fn cached_installed_apps() -> Vec<InstalledApp> {
+    INSTALLED_APPS.get_or_scan(get_installed_apps)
+}
+
+/// Forget the cached scan so newly installed apps show up
+pub fn refresh_installed_apps() {
+    INSTALLED_APPS.invalidate();
+}
+
+/// Installed apps matching `query`, ranked the same way as `search_apps`
+pub fn search_installed_apps(query: &str) -> Vec<InstalledApp> {
+    matching_installed_apps(query, cached_installed_apps())
+}
+
+/// Keep the apps whose name matches `query` per `match_score`, best first
+fn matching_installed_apps(query: &str, apps: Vec<InstalledApp>) -> Vec<InstalledApp> {
+    let mut matches: Vec<(u32, InstalledApp)> = apps
+        .into_iter()
+        .filter_map(|app| match_score(query, &app.name).map(|score| (score, app)))
+        .collect();
+
+    matches.sort_by(|a, b| rank_matches((a.0, &a.1.name), (b.0, &b.1.name)));
+    matches.into_iter().map(|(_, app)| app).collect()
+}
+
 /// Get all installed applications from .desktop files (Linux)
 #[cfg(target_os = "linux")]
 fn get_installed_apps_linux() -> Vec<InstalledApp> {
@@ -496,7 +741,6 @@ fn clean_exec(exec: &str) -> String {
 }
 
 /// Map app categories from .desktop to our simplified categories
-#[allow(dead_code)]
 pub fn map_category(desktop_categories: &[String]) -> Option<String> {
     for cat in desktop_categories {
         let cat_lower = cat.to_lowercase();
@@ -569,3 +813,164 @@ pub fn map_category(desktop_categories: &[String]) -> Option<String> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(name: &str) -> InstalledApp {
+        InstalledApp {
+            name: name.to_string(),
+            exec: None,
+            icon: None,
+            desktop_file: String::new(),
+            categories: vec![],
+        }
+    }
+
+    #[test]
+    fn test_installed_app_search_ranks_prefix_first() {
+        let apps = vec![
+            installed("GNOME Terminal"),
+            installed("Firefox"),
+            installed("Terminator"),
+            installed("Calculator"),
+        ];
+
+        let results: Vec<String> = matching_installed_apps("TERM", apps.clone())
+            .into_iter()
+            .map(|app| app.name)
+            .collect();
+        assert_eq!(results, vec!["Terminator", "GNOME Terminal"]);
+
+        assert!(matching_installed_apps("zzz", apps.clone()).is_empty());
+        assert_eq!(matching_installed_apps("", apps).len(), 4);
+    }
+
+    #[test]
+    fn test_installed_apps_cache_scans_once_until_invalidated() {
+        let cache = InstalledAppsCache::new();
+        let scans = std::cell::Cell::new(0);
+        let scan = || {
+            scans.set(scans.get() + 1);
+            vec![installed("Firefox")]
+        };
+
+        assert_eq!(cache.get_or_scan(scan).len(), 1);
+        assert_eq!(cache.get_or_scan(scan).len(), 1);
+        assert_eq!(scans.get(), 1);
+
+        cache.invalidate();
+        cache.get_or_scan(scan);
+        assert_eq!(scans.get(), 2);
+    }
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_match_score_ordering() {
+        let prefix = match_score("fir", "Firefox").unwrap();
+        let word = match_score("man", "File Manager").unwrap();
+        let contains = match_score("fox", "Firefox").unwrap();
+        let subsequence = match_score("fir", "File Manager").unwrap();
+
+        assert!(prefix > word);
+        assert!(word > contains);
+        assert!(contains > subsequence);
+        assert_eq!(match_score("xyz", "Firefox"), None);
+    }
+
+    #[test]
+    fn test_search_ranks_prefix_first() {
+        let installed = names(&["File Manager", "Firefox", "Thunderbird"]);
+        let results = search_apps("fir", &installed, &[], 10);
+
+        let ranked: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(ranked, vec!["Firefox", "File Manager"]);
+    }
+
+    #[test]
+    fn test_search_dedupes_and_marks_source() {
+        let installed = names(&["Firefox", "Discord"]);
+        let tracked = names(&["firefox", "Kitty"]);
+        let results = search_apps("", &installed, &tracked, 10);
+
+        assert_eq!(results.len(), 3);
+        let firefox = results.iter().find(|r| r.name == "Firefox").unwrap();
+        assert_eq!(firefox.source, AppSource::Both);
+        let kitty = results.iter().find(|r| r.name == "Kitty").unwrap();
+        assert_eq!(kitty.source, AppSource::Tracked);
+        let discord = results.iter().find(|r| r.name == "Discord").unwrap();
+        assert_eq!(discord.source, AppSource::Installed);
+    }
+
+    #[test]
+    fn test_search_respects_limit() {
+        let installed = names(&["Firefox", "Files", "Figma"]);
+        assert_eq!(search_apps("f", &installed, &[], 2).len(), 2);
+    }
+
+    fn installed(name: &str, exec: &str, categories: &[&str]) -> InstalledApp {
+        InstalledApp {
+            name: name.to_string(),
+            exec: Some(exec.to_string()),
+            icon: None,
+            desktop_file: format!("{}.desktop", exec),
+            categories: names(categories),
+        }
+    }
+
+    #[test]
+    fn test_match_installed_categories() {
+        let installed_apps = vec![
+            installed(
+                "Visual Studio Code",
+                "/usr/bin/code --unity-launch",
+                &["Development", "IDE"],
+            ),
+            installed(
+                "Telegram Desktop",
+                "telegram-desktop",
+                &["InstantMessaging"],
+            ),
+            installed("Mystery", "mystery", &["X-Unknown"]),
+        ];
+        let tracked = names(&[
+            "Visual Studio Code",
+            "telegram-desktop",
+            "Mystery",
+            "Unknown",
+        ]);
+
+        let (assignments, unmatched) = match_installed_categories(&tracked, &installed_apps);
+
+        assert_eq!(
+            assignments,
+            vec![
+                ("Visual Studio Code".to_string(), "Development".to_string()),
+                ("telegram-desktop".to_string(), "Communication".to_string()),
+            ]
+        );
+        assert_eq!(unmatched, 2);
+    }
+
+    #[test]
+    fn test_custom_icon_validation() {
+        assert!(validate_custom_icon("data:image/png;base64,iVBORw0KGgo=").is_ok());
+        assert!(validate_custom_icon("data:text/html,<b>hi</b>").is_err());
+
+        let oversized = format!(
+            "data:image/png;base64,{}",
+            "A".repeat(MAX_CUSTOM_ICON_BYTES)
+        );
+        assert!(validate_custom_icon(&oversized).is_err());
+
+        let file = crate::test_support::TempPath::new("icon.png");
+        fs::write(&file, b"png").unwrap();
+        assert!(validate_custom_icon(&file.to_string_lossy()).is_ok());
+        fs::remove_file(&file).unwrap();
+        assert!(validate_custom_icon(&file.to_string_lossy()).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+//! Scheduled daily exports
+//!
+//! Once a day, after the configured local time, usage history is exported to
+//! a timestamped file in the chosen directory and older automatic exports are
+//! pruned. The date of the last run is stored in the database so the export
+//! fires once per day even when the app is restarted.
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Key the settings are stored under in the database
+pub const SETTINGS_KEY: &str = "auto_export_settings";
+/// Key the local date (YYYY-MM-DD) of the last automatic export is stored under
+pub const LAST_RUN_KEY: &str = "last_auto_export_date";
+
+/// Prefix for automatic export files; only these are ever pruned
+const FILE_PREFIX: &str = "wellbeing-auto-export-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl AutoExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AutoExportFormat::Csv => "csv",
+            AutoExportFormat::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoExportSettings {
+    pub enabled: bool,
+    /// Local time of day to export at
+    pub hour: u32,
+    pub minute: u32,
+    pub format: AutoExportFormat,
+    /// Directory to write exports to; defaults to the app data directory
+    pub directory: Option<String>,
+    /// Number of automatic exports to keep
+    pub retention_count: usize,
+    /// Replace app names and categories with stable placeholders
+    #[serde(default)]
+    pub anonymize: bool,
+}
+
+impl Default for AutoExportSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hour: 2,
+            minute: 0,
+            format: AutoExportFormat::Csv,
+            directory: None,
+            retention_count: 7,
+            anonymize: false,
+        }
+    }
+}
+
+impl AutoExportSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.hour > 23 || self.minute > 59 {
+            return Err(format!(
+                "Invalid export time {:02}:{:02}",
+                self.hour, self.minute
+            ));
+        }
+        if self.retention_count == 0 {
+            return Err("Keep at least one automatic export".to_string());
+        }
+        if matches!(&self.directory, Some(dir) if dir.trim().is_empty()) {
+            return Err("Export directory is empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// Where exports are written
+    pub fn export_dir(&self) -> PathBuf {
+        match &self.directory {
+            Some(dir) => PathBuf::from(dir),
+            None => dirs::data_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("wellbeing")
+                .join("exports"),
+        }
+    }
+}
+
+/// Whether the export should run now: enabled, past the scheduled time, and
+/// not already run today
+pub fn should_run(
+    settings: &AutoExportSettings,
+    now: NaiveDateTime,
+    last_run: Option<NaiveDate>,
+) -> bool {
+    let Some(scheduled) = NaiveTime::from_hms_opt(settings.hour, settings.minute, 0) else {
+        return false;
+    };
+    settings.enabled && now.time() >= scheduled && last_run != Some(now.date())
+}
+
+/// Timestamped file name, so names sort in creation order
+pub fn export_file_name(now: NaiveDateTime, format: AutoExportFormat) -> String {
+    format!(
+        "{}{}.{}",
+        FILE_PREFIX,
+        now.format("%Y%m%d-%H%M%S"),
+        format.extension()
+    )
+}
+
+/// Delete the oldest automatic exports in `dir` beyond `keep`.
+/// Returns how many files were removed.
+pub fn prune_old_exports(dir: &Path, keep: usize) -> std::io::Result<usize> {
+    let mut exports: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(FILE_PREFIX))
+        })
+        .collect();
+
+    if exports.len() <= keep {
+        return Ok(0);
+    }
+
+    exports.sort();
+    let excess = exports.len() - keep;
+    for path in &exports[..excess] {
+        std::fs::remove_file(path)?;
+    }
+    Ok(excess)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{date} {time}"), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn enabled() -> AutoExportSettings {
+        AutoExportSettings {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_runs_once_after_scheduled_time() {
+        let settings = enabled();
+
+        assert!(!should_run(&settings, at("2024-03-10", "01:59"), None));
+        assert!(should_run(&settings, at("2024-03-10", "02:00"), None));
+        assert!(should_run(
+            &settings,
+            at("2024-03-10", "14:30"),
+            Some(date("2024-03-09"))
+        ));
+        assert!(!should_run(
+            &settings,
+            at("2024-03-10", "14:30"),
+            Some(date("2024-03-10"))
+        ));
+    }
+
+    #[test]
+    fn test_disabled_never_runs() {
+        let settings = AutoExportSettings::default();
+        assert!(!should_run(&settings, at("2024-03-10", "23:00"), None));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_settings() {
+        assert!(enabled().validate().is_ok());
+        assert!(AutoExportSettings {
+            hour: 24,
+            ..enabled()
+        }
+        .validate()
+        .is_err());
+        assert!(AutoExportSettings {
+            retention_count: 0,
+            ..enabled()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_prune_keeps_newest_exports() {
+        let dir = TempPath::new("auto-export");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for day in 1..=4 {
+            let name = export_file_name(
+                at(&format!("2024-03-0{day}"), "02:00"),
+                AutoExportFormat::Csv,
+            );
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+        std::fs::write(dir.join("manual-export.csv"), "").unwrap();
+
+        assert_eq!(prune_old_exports(&dir, 2).unwrap(), 2);
+
+        let mut remaining: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            vec![
+                "manual-export.csv",
+                "wellbeing-auto-export-20240303-020000.csv",
+                "wellbeing-auto-export-20240304-020000.csv",
+            ]
+        );
+    }
+}
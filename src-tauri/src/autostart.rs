@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct AutostartStatus {
@@ -50,8 +50,34 @@ mod platform {
         dirs::config_dir().map(|p| p.join("autostart"))
     }
 
+    /// Quote an argument for a systemd `ExecStart=` line, escaping specifiers
+    /// and variable references
+    fn systemd_quote(arg: &str) -> String {
+        format!(
+            "\"{}\"",
+            arg.replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('%', "%%")
+                .replace('$', "$$")
+        )
+    }
+
+    /// Quote an argument for a desktop entry `Exec=` key. Backslashes are
+    /// doubled twice since the string unescaping runs before the quoting rules
+    fn desktop_exec_quote(arg: &str) -> String {
+        let escaped = arg
+            .replace('\\', "\\\\\\\\")
+            .replace('"', "\\\"")
+            .replace('`', "\\`")
+            .replace('$', "\\$");
+        format!("\"{}\"", escaped).replace('%', "%%")
+    }
+
     /// Generate systemd service file content
-    fn generate_systemd_service(binary_path: &str) -> String {
+    fn generate_systemd_service(binary_path: &str, db_path: Option<&str>) -> String {
+        let db_path_arg = db_path
+            .map(|path| format!(" --db-path {}", systemd_quote(path)))
+            .unwrap_or_default();
         format!(
             r#"[Unit]
 Description=Digital Wellbeing - Screen Time Tracker
@@ -60,25 +86,29 @@ After=graphical-session.target
 
 [Service]
 Type=simple
-ExecStart={binary_path} --background
+ExecStart={binary_path} --background{db_path_arg}
 Restart=on-failure
 RestartSec=5
 
 [Install]
 WantedBy=default.target
 "#,
-            binary_path = binary_path
+            binary_path = binary_path,
+            db_path_arg = db_path_arg
         )
     }
 
     /// Generate XDG autostart desktop entry content
-    fn generate_autostart_desktop(binary_path: &str) -> String {
+    fn generate_autostart_desktop(binary_path: &str, db_path: Option<&str>) -> String {
+        let db_path_arg = db_path
+            .map(|path| format!(" --db-path {}", desktop_exec_quote(path)))
+            .unwrap_or_default();
         format!(
             r#"[Desktop Entry]
 Type=Application
 Name=Digital Wellbeing
 Comment=Track and manage your screen time
-Exec={binary_path} --background
+Exec={binary_path} --background{db_path_arg}
 Icon=wellbeing
 Terminal=false
 Categories=Utility;
@@ -86,13 +116,15 @@ StartupNotify=false
 X-GNOME-Autostart-enabled=true
 X-GNOME-Autostart-Delay=5
 "#,
-            binary_path = binary_path
+            binary_path = binary_path,
+            db_path_arg = db_path_arg
         )
     }
 
-    pub fn install_autostart() -> Result<String, String> {
+    pub fn install_autostart(db_path: Option<&Path>) -> Result<String, String> {
         let binary_path = get_app_binary_path().ok_or("Could not find application binary")?;
         let binary_str = binary_path.to_string_lossy().to_string();
+        let db_path = db_path.map(|path| path.to_string_lossy().to_string());
 
         let mut methods_installed = Vec::new();
 
@@ -102,7 +134,7 @@ X-GNOME-Autostart-Delay=5
                 .map_err(|e| format!("Failed to create systemd directory: {}", e))?;
 
             let service_path = systemd_dir.join("wellbeing.service");
-            let service_content = generate_systemd_service(&binary_str);
+            let service_content = generate_systemd_service(&binary_str, db_path.as_deref());
 
             fs::write(&service_path, service_content)
                 .map_err(|e| format!("Failed to write systemd service: {}", e))?;
@@ -128,7 +160,7 @@ X-GNOME-Autostart-Delay=5
                 .map_err(|e| format!("Failed to create autostart directory: {}", e))?;
 
             let desktop_path = autostart_dir.join("wellbeing.desktop");
-            let desktop_content = generate_autostart_desktop(&binary_str);
+            let desktop_content = generate_autostart_desktop(&binary_str, db_path.as_deref());
 
             fs::write(&desktop_path, desktop_content)
                 .map_err(|e| format!("Failed to write autostart entry: {}", e))?;
@@ -235,6 +267,28 @@ X-GNOME-Autostart-Delay=5
 
         status
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_entries_carry_the_db_path_override() {
+            let service = generate_systemd_service("/usr/bin/wellbeing", Some("/data/my db.db"));
+            assert!(service.contains(
+                "ExecStart=/usr/bin/wellbeing --background --db-path \"/data/my db.db\"\n"
+            ));
+
+            let desktop = generate_autostart_desktop("/usr/bin/wellbeing", Some("/data/100%.db"));
+            assert!(desktop
+                .contains("Exec=/usr/bin/wellbeing --background --db-path \"/data/100%%.db\"\n"));
+
+            let service = generate_systemd_service("/usr/bin/wellbeing", None);
+            assert!(service.contains("ExecStart=/usr/bin/wellbeing --background\n"));
+            let desktop = generate_autostart_desktop("/usr/bin/wellbeing", None);
+            assert!(desktop.contains("Exec=/usr/bin/wellbeing --background\n"));
+        }
+    }
 }
 
 // ============================================================
@@ -248,7 +302,7 @@ mod platform {
     const REGISTRY_RUN_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
     const APP_REGISTRY_NAME: &str = "DigitalWellbeing";
 
-    pub fn install_autostart() -> Result<String, String> {
+    pub fn install_autostart(db_path: Option<&Path>) -> Result<String, String> {
         use winreg::enums::*;
         use winreg::RegKey;
 
@@ -261,8 +315,12 @@ mod platform {
             .create_subkey(REGISTRY_RUN_KEY)
             .map_err(|e| format!("Failed to open registry Run key: {}", e))?;
 
-        // Value is the path to the exe with --background flag
-        let value = format!("\"{}\" --background", binary_str);
+        // Value is the path to the exe with --background flag, plus the
+        // database location when it was overridden
+        let mut value = format!("\"{}\" --background", binary_str);
+        if let Some(db_path) = db_path {
+            value.push_str(&format!(" --db-path \"{}\"", db_path.to_string_lossy()));
+        }
         run_key
             .set_value(APP_REGISTRY_NAME, &value)
             .map_err(|e| format!("Failed to set registry value: {}", e))?;
@@ -315,9 +373,151 @@ mod platform {
     }
 }
 
+// ============================================================
+// macOS implementation
+// ============================================================
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use std::process::Command;
+
+    const LAUNCH_AGENT_LABEL: &str = "com.digitalwellbeing";
+
+    /// Get the path of the LaunchAgent plist
+    fn get_launch_agent_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|p| {
+            p.join("Library/LaunchAgents")
+                .join(format!("{}.plist", LAUNCH_AGENT_LABEL))
+        })
+    }
+
+    /// Escape text for use inside a plist string element
+    fn escape_xml(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    /// Generate LaunchAgent plist content
+    fn generate_launch_agent(binary_path: &str, db_path: Option<&str>) -> String {
+        let db_path_args = db_path
+            .map(|path| {
+                format!(
+                    "\n        <string>--db-path</string>\n        <string>{}</string>",
+                    escape_xml(path)
+                )
+            })
+            .unwrap_or_default();
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{binary_path}</string>
+        <string>--background</string>{db_path_args}
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+            label = LAUNCH_AGENT_LABEL,
+            binary_path = escape_xml(binary_path),
+            db_path_args = db_path_args
+        )
+    }
+
+    pub fn install_autostart(db_path: Option<&Path>) -> Result<String, String> {
+        let binary_path = get_app_binary_path().ok_or("Could not find application binary")?;
+        let binary_str = binary_path.to_string_lossy().to_string();
+        let db_path = db_path.map(|path| path.to_string_lossy().to_string());
+
+        let plist_path = get_launch_agent_path().ok_or("Could not find home directory")?;
+        if let Some(parent) = plist_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create LaunchAgents directory: {}", e))?;
+        }
+
+        // Unload any previous version so launchd picks up the new file
+        if plist_path.exists() {
+            let _ = Command::new("launchctl")
+                .arg("unload")
+                .arg(&plist_path)
+                .output();
+        }
+
+        fs::write(
+            &plist_path,
+            generate_launch_agent(&binary_str, db_path.as_deref()),
+        )
+        .map_err(|e| format!("Failed to write LaunchAgent: {}", e))?;
+
+        let output = Command::new("launchctl")
+            .arg("load")
+            .arg(&plist_path)
+            .output()
+            .map_err(|e| format!("Failed to run launchctl: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "launchctl load failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok("Autostart installed via: LaunchAgent".to_string())
+    }
+
+    pub fn uninstall_autostart() -> Result<String, String> {
+        let Some(plist_path) = get_launch_agent_path() else {
+            return Ok("No autostart configuration found to remove".to_string());
+        };
+        if !plist_path.exists() {
+            return Ok("No autostart configuration found to remove".to_string());
+        }
+
+        let _ = Command::new("launchctl")
+            .arg("unload")
+            .arg(&plist_path)
+            .output();
+
+        fs::remove_file(&plist_path).map_err(|e| format!("Failed to remove LaunchAgent: {}", e))?;
+
+        Ok("Autostart removed: LaunchAgent".to_string())
+    }
+
+    pub fn get_autostart_status() -> AutostartStatus {
+        let mut status = AutostartStatus {
+            enabled: false,
+            systemd_installed: false, // N/A on macOS
+            systemd_running: false,   // N/A on macOS
+            xdg_installed: false,     // N/A on macOS
+        };
+
+        let installed = get_launch_agent_path().is_some_and(|path| path.exists());
+        if installed {
+            let output = Command::new("launchctl").arg("list").output();
+            if let Ok(out) = output {
+                status.enabled = String::from_utf8_lossy(&out.stdout)
+                    .lines()
+                    .any(|line| line.split_whitespace().last() == Some(LAUNCH_AGENT_LABEL));
+            }
+        }
+
+        status
+    }
+}
+
 // Re-export platform functions at module level
-pub fn install_autostart() -> Result<String, String> {
-    platform::install_autostart()
+/// Install the autostart entry. `db_path` is passed to the background process
+/// as `--db-path` so it opens the same database as the app that installed it.
+pub fn install_autostart(db_path: Option<&Path>) -> Result<String, String> {
+    platform::install_autostart(db_path)
 }
 
 pub fn uninstall_autostart() -> Result<String, String> {
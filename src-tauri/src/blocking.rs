@@ -0,0 +1,482 @@
+//! App blocking settings and outcome reporting
+//!
+//! Closing an app can silently fail (Wayland, permissions, a window class that
+//! doesn't match the process name). After a block attempt the tracker re-checks
+//! the active window and, if the app is still in front, escalates according to
+//! [`BlockSettings::on_failure`].
+
+use crate::window_tracker::{extract_app_name, get_active_window_name};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use tokio::process::Command;
+
+/// app_meta key holding the saved [`BlockSettings`]
+pub const SETTINGS_KEY: &str = "block_settings";
+
+/// How long to wait after a block attempt before checking whether it worked
+pub const BLOCK_VERIFY_DELAY: Duration = Duration::from_millis(1500);
+
+/// Default wait between an app going over its limit and the limit popup
+pub const DEFAULT_BLOCK_GRACE_SECONDS: u32 = 30;
+
+/// Default wait between asking an app's process to quit and force-killing it
+pub const DEFAULT_KILL_ESCALATION_SECONDS: u32 = 3;
+
+/// Longest wait allowed before force-killing, so a block can't hang for long
+const MAX_KILL_ESCALATION_SECONDS: u32 = 30;
+
+/// What to do when an app couldn't be closed automatically
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockFailureAction {
+    /// Try closing the app once more, then notify
+    Retry,
+    /// Tell the user the app couldn't be closed
+    Notify,
+    /// Keep the limit popup on top of the app
+    Overlay,
+}
+
+/// Settings controlling how blocking is enforced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockSettings {
+    pub on_failure: BlockFailureAction,
+    /// Seconds an over-limit app stays usable before the limit popup appears
+    #[serde(default = "default_block_grace_seconds")]
+    pub block_grace_seconds: u32,
+    /// Seconds a process gets to quit on its own before it is force-killed
+    #[serde(default = "default_kill_escalation_seconds")]
+    pub kill_escalation_seconds: u32,
+    /// Log and notify instead of closing apps, for trying out limits
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn default_block_grace_seconds() -> u32 {
+    DEFAULT_BLOCK_GRACE_SECONDS
+}
+
+fn default_kill_escalation_seconds() -> u32 {
+    DEFAULT_KILL_ESCALATION_SECONDS
+}
+
+impl BlockSettings {
+    /// Wait before force-killing a process, clamped to 1-30 seconds
+    pub fn kill_escalation_delay(&self) -> Duration {
+        Duration::from_secs(
+            self.kill_escalation_seconds
+                .clamp(1, MAX_KILL_ESCALATION_SECONDS) as u64,
+        )
+    }
+}
+
+impl Default for BlockSettings {
+    fn default() -> Self {
+        Self {
+            on_failure: BlockFailureAction::Notify,
+            block_grace_seconds: DEFAULT_BLOCK_GRACE_SECONDS,
+            kill_escalation_seconds: DEFAULT_KILL_ESCALATION_SECONDS,
+            dry_run: false,
+        }
+    }
+}
+
+/// What to do about an over-limit app on this tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraceAction {
+    /// The grace period just started: warn the user once
+    Warn { seconds_left: u32 },
+    /// Still within the grace period
+    Wait,
+    /// The grace period is over: show the limit popup
+    Block,
+}
+
+/// Grace timers for over-limit apps, keyed by app name. A timer starts the
+/// first time an app is seen over its limit and is dropped when the user
+/// switches away from it.
+#[derive(Debug, Default)]
+pub struct BlockGrace {
+    first_exceeded: HashMap<String, i64>,
+}
+
+impl BlockGrace {
+    /// Advance the grace timer for `app_name`, which is over its limit at `now`
+    pub fn check(&mut self, app_name: &str, now: i64, grace_seconds: u32) -> GraceAction {
+        let Some(&started) = self.first_exceeded.get(app_name) else {
+            self.first_exceeded.insert(app_name.to_string(), now);
+            if grace_seconds == 0 {
+                return GraceAction::Block;
+            }
+            return GraceAction::Warn {
+                seconds_left: grace_seconds,
+            };
+        };
+
+        if now - started >= grace_seconds as i64 {
+            GraceAction::Block
+        } else {
+            GraceAction::Wait
+        }
+    }
+
+    /// Drop the timers of every app except the active one
+    pub fn retain_active(&mut self, active_app: Option<&str>) {
+        self.first_exceeded
+            .retain(|app, _| Some(app.as_str()) == active_app);
+    }
+}
+
+/// The mechanism used to close an app
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockMethod {
+    /// Asked the window manager to close the app's windows
+    WindowClose,
+    /// Terminated the app's process
+    ProcessKill,
+    /// No blocking mechanism on this platform
+    Unsupported,
+    /// Nothing was closed: dry run mode only logs what would have happened
+    DryRun,
+}
+
+/// Outcome of a block attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockResult {
+    /// Whether any close mechanism was run
+    pub attempted: bool,
+    /// Whether the app is no longer the active window afterwards
+    pub succeeded: bool,
+    pub method: BlockMethod,
+}
+
+/// Close an over-limit app's windows (Linux) or process (Windows), as the
+/// tracker does when enforcing a limit. In dry run mode only logs.
+pub async fn close_app(app_name: &str, settings: &BlockSettings) -> BlockMethod {
+    if settings.dry_run {
+        tracing::info!(app = %app_name, "Dry run: would close app windows");
+        return BlockMethod::DryRun;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // Try to close windows of the app using wmctrl
+        let _ = command("wmctrl").args(["-c", app_name]).output().await;
+
+        // Also try xdotool to close active window if it matches
+        let _ = command("xdotool")
+            .args(["getactivewindow", "windowclose"])
+            .output()
+            .await;
+
+        BlockMethod::WindowClose
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // On Windows, ask the process to quit and force-kill it if it doesn't
+        spawn_terminate_process(app_name, settings.kill_escalation_delay());
+
+        BlockMethod::ProcessKill
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        let _ = app_name;
+        BlockMethod::Unsupported
+    }
+}
+
+/// Close an app's windows and end its process, as when the user asks for an
+/// app to be blocked right away. In dry run mode only logs.
+pub async fn kill_app(app_name: &str, settings: &BlockSettings) -> BlockMethod {
+    if settings.dry_run {
+        tracing::info!(app = %app_name, "Dry run: would terminate app");
+        return BlockMethod::DryRun;
+    }
+
+    // On Linux, use wmctrl to close app windows first
+    #[cfg(target_os = "linux")]
+    {
+        let _ = command("wmctrl").args(["-c", app_name]).output().await;
+    }
+
+    // Then ask the process to quit, force-killing it only if it doesn't
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    {
+        spawn_terminate_process(app_name, settings.kill_escalation_delay());
+        BlockMethod::ProcessKill
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        let _ = app_name;
+        BlockMethod::Unsupported
+    }
+}
+
+/// Decide whether a block worked given the app that is active afterwards
+pub fn block_succeeded(blocked_app: &str, active_app_after: Option<&str>) -> bool {
+    !active_app_after.is_some_and(|active| active.eq_ignore_ascii_case(blocked_app))
+}
+
+/// Wait briefly, then check whether `app_name` is still the active app
+pub async fn verify_block(app_name: &str) -> bool {
+    tokio::time::sleep(BLOCK_VERIFY_DELAY).await;
+
+    let active_app = get_active_window_name()
+        .ok()
+        .flatten()
+        .and_then(|name| extract_app_name(&name));
+
+    block_succeeded(app_name, active_app.as_deref())
+}
+
+/// Next step when terminating a process, after waiting for it to exit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminationStep {
+    /// The process is gone
+    Done,
+    /// It ignored the polite request: kill it forcibly
+    ForceKill,
+    /// It survived a forced kill too
+    GiveUp,
+}
+
+/// Decide what to do given whether the process is still running and whether
+/// it has already been force-killed
+pub fn termination_step(still_running: bool, forced: bool) -> TerminationStep {
+    match (still_running, forced) {
+        (false, _) => TerminationStep::Done,
+        (true, false) => TerminationStep::ForceKill,
+        (true, true) => TerminationStep::GiveUp,
+    }
+}
+
+/// A process tool command. On Windows it runs without flashing a console.
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+fn command(program: &str) -> Command {
+    #[cfg_attr(not(target_os = "windows"), allow(unused_mut))]
+    let mut command = Command::new(program);
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command
+}
+
+/// Terminate `app_name`'s process on its own task, so waiting out the
+/// escalation delay doesn't hold up the caller
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+fn spawn_terminate_process(app_name: &str, escalation_delay: Duration) {
+    let app_name = app_name.to_string();
+    tokio::spawn(async move {
+        terminate_process(&app_name, escalation_delay).await;
+    });
+}
+
+/// Ask `app_name`'s process to quit, and force-kill it if it is still running
+/// after `escalation_delay`. Returns whether the process is gone.
+pub async fn terminate_process(app_name: &str, escalation_delay: Duration) -> bool {
+    let mut forced = false;
+    if !request_quit(app_name).await {
+        force_kill(app_name).await;
+        forced = true;
+    }
+
+    loop {
+        tokio::time::sleep(escalation_delay).await;
+        match termination_step(process_running(app_name).await, forced) {
+            TerminationStep::Done => return true,
+            TerminationStep::ForceKill => {
+                tracing::info!(app = %app_name, "App ignored quit request; force-killing");
+                force_kill(app_name).await;
+                forced = true;
+            }
+            TerminationStep::GiveUp => {
+                tracing::warn!(app = %app_name, "App still running after force kill");
+                return false;
+            }
+        }
+    }
+}
+
+/// Politely ask the process to exit. Returns false if the request itself failed.
+async fn request_quit(app_name: &str) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        // SIGTERM lets editors and the like save state before exiting.
+        // pkill exits non-zero when nothing matched, which isn't a failure here.
+        command("pkill")
+            .args(["-TERM", "-x", app_name])
+            .status()
+            .await
+            .is_ok()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // Without /F, taskkill asks the app's windows to close
+        command("taskkill")
+            .args(["/IM", &format!("{}.exe", app_name)])
+            .status()
+            .await
+            .is_ok_and(|status| status.success())
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        let _ = app_name;
+        true
+    }
+}
+
+/// Kill the process without giving it a chance to clean up
+async fn force_kill(app_name: &str) {
+    #[cfg(target_os = "linux")]
+    {
+        let _ = command("pkill")
+            .args(["-KILL", "-x", app_name])
+            .output()
+            .await;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = command("taskkill")
+            .args(["/IM", &format!("{}.exe", app_name), "/F"])
+            .output()
+            .await;
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        let _ = app_name;
+    }
+}
+
+/// Whether a process named `app_name` is running
+async fn process_running(app_name: &str) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        command("pgrep")
+            .args(["-x", app_name])
+            .output()
+            .await
+            .is_ok_and(|output| output.status.success())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let image = format!("{}.exe", app_name);
+        command("tasklist")
+            .args(["/FI", &format!("IMAGENAME eq {}", image), "/NH"])
+            .output()
+            .await
+            .is_ok_and(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .to_lowercase()
+                    .contains(&image.to_lowercase())
+            })
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        let _ = app_name;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_succeeded_when_app_gone() {
+        assert!(block_succeeded("Discord", Some("Firefox")));
+        assert!(block_succeeded("Discord", None));
+    }
+
+    #[test]
+    fn test_block_failed_when_app_still_active() {
+        assert!(!block_succeeded("Discord", Some("Discord")));
+        assert!(!block_succeeded("Discord", Some("discord")));
+    }
+
+    #[test]
+    fn test_block_grace_waits_then_blocks() {
+        let mut grace = BlockGrace::default();
+        assert_eq!(
+            grace.check("Discord", 1_000, 30),
+            GraceAction::Warn { seconds_left: 30 }
+        );
+        assert_eq!(grace.check("Discord", 1_001, 30), GraceAction::Wait);
+        assert_eq!(grace.check("Discord", 1_029, 30), GraceAction::Wait);
+        assert_eq!(grace.check("Discord", 1_030, 30), GraceAction::Block);
+        assert_eq!(grace.check("Discord", 1_031, 30), GraceAction::Block);
+    }
+
+    #[test]
+    fn test_block_grace_resets_on_switch_away() {
+        let mut grace = BlockGrace::default();
+        grace.check("Discord", 1_000, 30);
+        grace.retain_active(Some("Discord"));
+        assert_eq!(grace.check("Discord", 1_020, 30), GraceAction::Wait);
+
+        grace.retain_active(Some("Firefox"));
+        assert_eq!(
+            grace.check("Discord", 1_040, 30),
+            GraceAction::Warn { seconds_left: 30 }
+        );
+        assert_eq!(grace.check("Discord", 1_060, 30), GraceAction::Wait);
+    }
+
+    #[test]
+    fn test_zero_grace_blocks_immediately() {
+        let mut grace = BlockGrace::default();
+        assert_eq!(grace.check("Discord", 1_000, 0), GraceAction::Block);
+    }
+
+    #[test]
+    fn test_termination_escalates_only_while_running() {
+        assert_eq!(termination_step(false, false), TerminationStep::Done);
+        assert_eq!(termination_step(true, false), TerminationStep::ForceKill);
+        assert_eq!(termination_step(true, true), TerminationStep::GiveUp);
+        assert_eq!(termination_step(false, true), TerminationStep::Done);
+    }
+
+    #[test]
+    fn test_kill_escalation_delay_is_clamped() {
+        let mut settings = BlockSettings::default();
+        assert_eq!(settings.kill_escalation_delay(), Duration::from_secs(3));
+
+        settings.kill_escalation_seconds = 0;
+        assert_eq!(settings.kill_escalation_delay(), Duration::from_secs(1));
+
+        settings.kill_escalation_seconds = 600;
+        assert_eq!(settings.kill_escalation_delay(), Duration::from_secs(30));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_closes_nothing() {
+        let settings = BlockSettings {
+            dry_run: true,
+            ..Default::default()
+        };
+        assert_eq!(close_app("Discord", &settings).await, BlockMethod::DryRun);
+        assert_eq!(kill_app("Discord", &settings).await, BlockMethod::DryRun);
+        assert!(!BlockSettings::default().dry_run);
+    }
+
+    #[test]
+    fn test_default_failure_action_notifies() {
+        assert_eq!(
+            BlockSettings::default().on_failure,
+            BlockFailureAction::Notify
+        );
+    }
+}
@@ -1,4 +1,8 @@
+use crate::settings_store;
+use crate::settings_validation::{self, SettingsRepair};
+use crate::settings_version::VersionedSettings;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -16,6 +20,23 @@ pub struct BreakSettings {
     pub show_notification: bool,
     /// Whether to play a sound (uses system notification sound)
     pub play_sound: bool,
+    /// Whether to remind about short eye-strain breaks (20-20-20 rule)
+    #[serde(default)]
+    pub micro_break_enabled: bool,
+    /// Work minutes between micro-break reminders
+    #[serde(default = "default_micro_break_interval_minutes")]
+    pub micro_break_interval_minutes: u32,
+    /// Settings format version, see `settings_version`
+    #[serde(default = "current_settings_version")]
+    pub version: u32,
+}
+
+fn default_micro_break_interval_minutes() -> u32 {
+    20
+}
+
+fn current_settings_version() -> u32 {
+    <BreakSettings as VersionedSettings>::CURRENT_VERSION
 }
 
 impl Default for BreakSettings {
@@ -26,23 +47,50 @@ impl Default for BreakSettings {
             break_minutes: 5,
             show_notification: true,
             play_sound: true,
+            micro_break_enabled: false,
+            micro_break_interval_minutes: default_micro_break_interval_minutes(),
+            version: current_settings_version(),
         }
     }
 }
 
+/// File in the config directory that break settings are saved to
+const BREAK_SETTINGS_FILE: &str = "break_settings.json";
+
 /// Break reminder state
 pub struct BreakReminder {
     settings: Arc<Mutex<BreakSettings>>,
     is_on_break: AtomicBool,
+    /// Set when a break reminder fired and the user hasn't started a break yet
+    break_due: AtomicBool,
     minutes_worked: Arc<Mutex<u32>>,
+    /// Work minutes since the last micro-break reminder
+    micro_minutes: Arc<Mutex<u32>>,
+    /// File settings are saved to; None keeps them in memory
+    settings_path: Option<PathBuf>,
 }
 
 impl BreakReminder {
+    /// Reminder with default settings kept in memory
     pub fn new() -> Self {
+        Self::with_settings_path(None)
+    }
+
+    /// Reminder with the settings saved by the last run
+    pub fn load() -> Self {
+        Self::with_settings_path(settings_store::settings_path(BREAK_SETTINGS_FILE))
+    }
+
+    /// Reminder saving its settings to `settings_path`; None keeps them in memory
+    pub(crate) fn with_settings_path(settings_path: Option<PathBuf>) -> Self {
+        let settings: BreakSettings = settings_store::load_or_default(settings_path.as_deref());
         Self {
-            settings: Arc::new(Mutex::new(BreakSettings::default())),
+            settings: Arc::new(Mutex::new(settings)),
             is_on_break: AtomicBool::new(false),
+            break_due: AtomicBool::new(false),
             minutes_worked: Arc::new(Mutex::new(0)),
+            micro_minutes: Arc::new(Mutex::new(0)),
+            settings_path,
         }
     }
 
@@ -50,38 +98,72 @@ impl BreakReminder {
         self.settings.lock().await.clone()
     }
 
-    pub async fn update_settings(&self, settings: BreakSettings) {
+    pub async fn update_settings(&self, mut settings: BreakSettings) {
+        let repairs = settings_validation::validate_break_settings(&mut settings);
+        settings_validation::log_repairs(&repairs);
+        if let Some(path) = &self.settings_path {
+            if let Err(e) = settings_store::save(path, &settings) {
+                tracing::error!(error = %e, "Failed to save break settings");
+            }
+        }
         *self.settings.lock().await = settings;
     }
 
+    /// Validate the current settings in place, returning any repairs made
+    pub async fn validate_settings(&self) -> Vec<SettingsRepair> {
+        let mut settings = self.settings.lock().await;
+        let repairs = settings_validation::validate_break_settings(&mut settings);
+        settings_validation::log_repairs(&repairs);
+        repairs
+    }
+
     pub fn is_on_break(&self) -> bool {
         self.is_on_break.load(Ordering::SeqCst)
     }
 
+    pub fn is_break_due(&self) -> bool {
+        self.break_due.load(Ordering::SeqCst)
+    }
+
     pub async fn get_minutes_worked(&self) -> u32 {
         *self.minutes_worked.lock().await
     }
 
     pub async fn reset_timer(&self) {
         *self.minutes_worked.lock().await = 0;
+        *self.micro_minutes.lock().await = 0;
         self.is_on_break.store(false, Ordering::SeqCst);
+        self.break_due.store(false, Ordering::SeqCst);
     }
 
+    /// Start a break. The caller records it in the database, so the count per
+    /// day outlives a restart.
     pub async fn start_break(&self) {
         self.is_on_break.store(true, Ordering::SeqCst);
+        self.break_due.store(false, Ordering::SeqCst);
         *self.minutes_worked.lock().await = 0;
+        *self.micro_minutes.lock().await = 0;
     }
 
     pub async fn end_break(&self) {
         self.is_on_break.store(false, Ordering::SeqCst);
     }
 
+    /// Put off the break so the next reminder fires after `minutes` more of work.
+    /// Snoozing for a full work interval or longer just restarts the interval.
+    pub async fn snooze(&self, minutes: u32) {
+        let work_minutes = self.settings.lock().await.work_minutes;
+        *self.minutes_worked.lock().await = work_minutes.saturating_sub(minutes);
+        self.break_due.store(false, Ordering::SeqCst);
+    }
+
     /// Called every minute to track work time
-    /// Returns true if a break notification should be sent
+    /// Returns the break or micro-break notification that should be sent, if any.
+    /// Micro-breaks run on their own counter; when both are due the full break wins.
     pub async fn tick(&self) -> Option<BreakNotification> {
         let settings = self.settings.lock().await;
 
-        if !settings.enabled {
+        if !settings.enabled && !settings.micro_break_enabled {
             return None;
         }
 
@@ -90,18 +172,43 @@ impl BreakReminder {
             return None;
         }
 
-        let mut minutes = self.minutes_worked.lock().await;
-        *minutes += 1;
+        let micro_due = settings.micro_break_enabled && {
+            let mut micro_minutes = self.micro_minutes.lock().await;
+            *micro_minutes += 1;
+            if *micro_minutes >= settings.micro_break_interval_minutes {
+                *micro_minutes = 0;
+                true
+            } else {
+                false
+            }
+        };
+
+        if settings.enabled {
+            let mut minutes = self.minutes_worked.lock().await;
+            *minutes += 1;
+
+            if *minutes >= settings.work_minutes {
+                // Time for a break!
+                *minutes = 0;
+                self.break_due.store(true, Ordering::SeqCst);
+                return Some(BreakNotification {
+                    kind: BreakKind::Break,
+                    title: "Time for a break!".to_string(),
+                    message: format!(
+                        "You've been working for {} minutes. Take a {} minute break.",
+                        settings.work_minutes, settings.break_minutes
+                    ),
+                    show_notification: settings.show_notification,
+                    play_sound: settings.play_sound,
+                });
+            }
+        }
 
-        if *minutes >= settings.work_minutes {
-            // Time for a break!
-            *minutes = 0;
+        if micro_due {
             return Some(BreakNotification {
-                title: "Time for a break!".to_string(),
-                message: format!(
-                    "You've been working for {} minutes. Take a {} minute break.",
-                    settings.work_minutes, settings.break_minutes
-                ),
+                kind: BreakKind::MicroBreak,
+                title: "Rest your eyes".to_string(),
+                message: "Look 20 feet away for 20 seconds.".to_string(),
                 show_notification: settings.show_notification,
                 play_sound: settings.play_sound,
             });
@@ -111,8 +218,24 @@ impl BreakReminder {
     }
 }
 
+impl Default for BreakReminder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Which kind of break a reminder is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakKind {
+    /// The regular break at the end of a work interval
+    Break,
+    /// A short eye-strain break that doesn't affect the work interval
+    MicroBreak,
+}
+
 #[derive(Debug, Clone)]
 pub struct BreakNotification {
+    pub kind: BreakKind,
     pub title: String,
     pub message: String,
     pub show_notification: bool,
@@ -120,12 +243,128 @@ pub struct BreakNotification {
 }
 
 impl BreakNotification {
-    /// Send the notification using platform-native notification system
-    pub fn send(&self) {
+    /// Send the notification, through the notification plugin when `app` is set
+    pub fn send(&self, app: Option<&tauri::AppHandle>) {
         if !self.show_notification {
             return;
         }
 
-        crate::notifications::send_notification(&self.title, &self.message);
+        crate::notifications::notify(app, &self.title, &self.message, "normal");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    #[tokio::test]
+    async fn test_settings_persist_across_restarts() {
+        let path = TempPath::new("break-settings.json");
+
+        let reminder = BreakReminder::with_settings_path(Some(path.to_path_buf()));
+        reminder
+            .update_settings(BreakSettings {
+                enabled: true,
+                work_minutes: 45,
+                break_minutes: 10,
+                micro_break_enabled: true,
+                ..Default::default()
+            })
+            .await;
+
+        let reopened = BreakReminder::with_settings_path(Some(path.to_path_buf()));
+        let settings = reopened.get_settings().await;
+        assert!(settings.enabled);
+        assert_eq!(settings.work_minutes, 45);
+        assert_eq!(settings.break_minutes, 10);
+        assert!(settings.micro_break_enabled);
+    }
+
+    async fn enabled_reminder() -> BreakReminder {
+        let reminder = BreakReminder::new();
+        reminder
+            .update_settings(BreakSettings {
+                enabled: true,
+                work_minutes: 25,
+                ..Default::default()
+            })
+            .await;
+        reminder
+    }
+
+    #[tokio::test]
+    async fn test_snooze_reminds_after_snooze_minutes() {
+        let reminder = enabled_reminder().await;
+        for _ in 0..25 {
+            reminder.tick().await;
+        }
+        assert!(reminder.is_break_due());
+
+        reminder.snooze(2).await;
+        assert!(!reminder.is_break_due());
+        assert!(!reminder.is_on_break());
+
+        assert!(reminder.tick().await.is_none());
+        assert!(reminder.tick().await.is_some());
+        assert!(reminder.is_break_due());
+    }
+
+    #[tokio::test]
+    async fn test_micro_and_main_breaks_fire_at_their_intervals() {
+        let reminder = BreakReminder::new();
+        reminder
+            .update_settings(BreakSettings {
+                enabled: true,
+                work_minutes: 25,
+                micro_break_enabled: true,
+                micro_break_interval_minutes: 20,
+                ..Default::default()
+            })
+            .await;
+
+        let mut fired = Vec::new();
+        for minute in 1..=40 {
+            if let Some(notification) = reminder.tick().await {
+                fired.push((minute, notification.kind));
+            }
+        }
+
+        assert_eq!(
+            fired,
+            vec![
+                (20, BreakKind::MicroBreak),
+                (25, BreakKind::Break),
+                (40, BreakKind::MicroBreak),
+            ]
+        );
+        // The micro-break at minute 40 didn't reset the main interval
+        assert_eq!(reminder.get_minutes_worked().await, 15);
+    }
+
+    #[tokio::test]
+    async fn test_micro_breaks_without_main_breaks() {
+        let reminder = BreakReminder::new();
+        reminder
+            .update_settings(BreakSettings {
+                enabled: false,
+                micro_break_enabled: true,
+                micro_break_interval_minutes: 2,
+                ..Default::default()
+            })
+            .await;
+
+        assert!(reminder.tick().await.is_none());
+        let notification = reminder.tick().await.unwrap();
+        assert_eq!(notification.kind, BreakKind::MicroBreak);
+        assert!(notification.message.contains("20 feet"));
+        assert_eq!(reminder.get_minutes_worked().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_snooze_longer_than_work_interval() {
+        let reminder = enabled_reminder().await;
+        reminder.snooze(40).await;
+        assert_eq!(reminder.get_minutes_worked().await, 0);
     }
 }
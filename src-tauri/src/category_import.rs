@@ -0,0 +1,216 @@
+//! Bulk category assignment from a CSV of `app_name,category` rows
+//!
+//! Fields may be quoted the same way the usage export escapes them: wrapped in
+//! double quotes, with embedded quotes doubled. Each row is parsed on its own,
+//! so a malformed row is skipped without affecting the rest.
+
+use serde::{Deserialize, Serialize};
+
+/// Categories offered in the UI. Others are accepted but reported as custom.
+pub const KNOWN_CATEGORIES: [&str; 9] = [
+    "Productivity",
+    "Development",
+    "Communication",
+    "Entertainment",
+    "Social Media",
+    "Gaming",
+    "Utilities",
+    "Education",
+    "Other",
+];
+
+const MAX_APP_NAME_LEN: usize = 256;
+const MAX_CATEGORY_LEN: usize = 64;
+
+/// A row that wasn't applied, with the 1-based line number
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedRow {
+    pub line: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CategoryImportSummary {
+    pub applied: usize,
+    pub skipped: Vec<SkippedRow>,
+    /// Categories that were applied but aren't in `KNOWN_CATEGORIES`
+    pub custom_categories: Vec<String>,
+}
+
+/// Split one CSV line into fields, honouring quoted fields
+pub fn parse_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut in_quotes = false;
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() && !quoted => {
+                in_quotes = true;
+                quoted = true;
+            }
+            '"' => return Err("unexpected quote inside an unquoted field".to_string()),
+            ',' if !in_quotes => {
+                fields.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            _ if quoted && !in_quotes => {
+                return Err("text after a closing quote".to_string());
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+fn validate_row(fields: &[String]) -> Result<(String, String), String> {
+    let [app_name, category] = fields else {
+        return Err(format!("expected 2 fields, found {}", fields.len()));
+    };
+    let app_name = app_name.trim();
+    let category = category.trim();
+
+    if app_name.is_empty() {
+        return Err("app name is empty".to_string());
+    }
+    if app_name.len() > MAX_APP_NAME_LEN || app_name.chars().any(char::is_control) {
+        return Err("app name is too long or contains control characters".to_string());
+    }
+    if category.is_empty() {
+        return Err("category is empty".to_string());
+    }
+    if category.len() > MAX_CATEGORY_LEN || category.chars().any(char::is_control) {
+        return Err("category is too long or contains control characters".to_string());
+    }
+
+    // Match known categories case-insensitively so "gaming" lands in "Gaming"
+    let category = KNOWN_CATEGORIES
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(category))
+        .map_or_else(|| category.to_string(), |known| known.to_string());
+
+    Ok((app_name.to_string(), category))
+}
+
+/// Parse the CSV into (app_name, category) assignments and skipped rows.
+/// Blank lines and an `app_name,category` header are ignored.
+pub fn parse_category_csv(csv: &str) -> (Vec<(String, String)>, Vec<SkippedRow>) {
+    let mut assignments = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (index, line) in csv.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let result = parse_csv_line(line).and_then(|fields| {
+            if index == 0 && is_header(&fields) {
+                return Ok(None);
+            }
+            validate_row(&fields).map(Some)
+        });
+
+        match result {
+            Ok(Some(assignment)) => assignments.push(assignment),
+            Ok(None) => {}
+            Err(reason) => skipped.push(SkippedRow {
+                line: line_number,
+                reason,
+            }),
+        }
+    }
+
+    (assignments, skipped)
+}
+
+fn is_header(fields: &[String]) -> bool {
+    matches!(fields, [app, category]
+        if ["app_name", "app name", "app"].contains(&app.trim().to_lowercase().as_str())
+            && category.trim().eq_ignore_ascii_case("category"))
+}
+
+/// Categories among `assignments` that aren't in `KNOWN_CATEGORIES`
+pub fn custom_categories(assignments: &[(String, String)]) -> Vec<String> {
+    let mut custom: Vec<String> = assignments
+        .iter()
+        .map(|(_, category)| category)
+        .filter(|category| !KNOWN_CATEGORIES.contains(&category.as_str()))
+        .cloned()
+        .collect();
+    custom.sort();
+    custom.dedup();
+    custom
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(app: &str, category: &str) -> (String, String) {
+        (app.to_string(), category.to_string())
+    }
+
+    #[test]
+    fn test_well_formed_csv() {
+        let csv = "app_name,category\n\
+                   Firefox,Productivity\n\
+                   \"Slack, Inc.\",communication\n\
+                   \"The \"\"Best\"\" Game\",Gaming\n";
+
+        let (assignments, skipped) = parse_category_csv(csv);
+
+        assert!(skipped.is_empty());
+        assert_eq!(
+            assignments,
+            vec![
+                pair("Firefox", "Productivity"),
+                pair("Slack, Inc.", "Communication"),
+                pair("The \"Best\" Game", "Gaming"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_category_is_allowed() {
+        let (assignments, skipped) = parse_category_csv("Obsidian,Notes\n");
+
+        assert!(skipped.is_empty());
+        assert_eq!(assignments, vec![pair("Obsidian", "Notes")]);
+        assert_eq!(custom_categories(&assignments), vec!["Notes"]);
+    }
+
+    #[test]
+    fn test_malformed_rows_are_skipped() {
+        let csv = "Firefox,Productivity\n\
+                   Code\n\
+                   \"Unclosed,Development\n\
+                   ,Gaming\n\
+                   Steam,Gaming\n";
+
+        let (assignments, skipped) = parse_category_csv(csv);
+
+        assert_eq!(
+            assignments,
+            vec![pair("Firefox", "Productivity"), pair("Steam", "Gaming")]
+        );
+        let lines: Vec<usize> = skipped.iter().map(|s| s.line).collect();
+        assert_eq!(lines, vec![2, 3, 4]);
+        assert_eq!(skipped[0].reason, "expected 2 fields, found 1");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::database::AppUsage;
+use crate::goals::is_weekend;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -7,10 +8,118 @@ pub struct DailyStats {
     pub apps: Vec<AppUsage>,
 }
 
+/// app_meta key holding the saved [`DisplaySettings`]
+pub const DISPLAY_SETTINGS_KEY: &str = "display_settings";
+
+/// How usage lists are presented
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplaySettings {
+    /// Apps used for less than this many seconds are left out of usage lists.
+    /// They still count toward totals.
+    pub min_app_seconds: i64,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            min_app_seconds: 60,
+        }
+    }
+}
+
+impl DailyStats {
+    /// Build stats for display, dropping apps below `min_app_seconds` from the
+    /// list while keeping them in `total_seconds`. Pinned apps are always shown.
+    pub fn for_display(apps: Vec<AppUsage>, total_seconds: i64, min_app_seconds: i64) -> Self {
+        Self {
+            total_seconds,
+            apps: apps
+                .into_iter()
+                .filter(|app| app.pinned || app.duration_seconds >= min_app_seconds)
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WeeklyStats {
     pub days: Vec<DayStats>,
     pub total_seconds: i64,
+    pub average_daily_seconds: i64,
+}
+
+/// Time spent in one window title of an app
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TitleUsage {
+    pub title: String,
+    pub total_seconds: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MonthlyStats {
+    pub days: Vec<DayStats>,
+    pub total_seconds: i64,
+}
+
+/// When usage is heaviest
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsagePatterns {
+    /// Hour of day, 0-23
+    pub peak_hour: Option<UsagePeak>,
+    /// Day of week, 0 = Sunday .. 6 = Saturday
+    pub peak_weekday: Option<UsagePeak>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsagePeak {
+    pub value: u32,
+    pub total_seconds: i64,
+}
+
+/// All-time most-used apps, overall and per category
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsageHighlights {
+    pub top_app: Option<AppTotal>,
+    pub category_champions: Vec<CategoryChampion>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppTotal {
+    pub app_name: String,
+    pub total_seconds: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryChampion {
+    pub category: String,
+    pub app_name: String,
+    pub total_seconds: i64,
+}
+
+/// Usage over the last 7 days against the 7 days before
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WeekComparison {
+    pub this_week_seconds: i64,
+    pub last_week_seconds: i64,
+    /// Change relative to last week; None when there's no usage last week
+    pub percent_change: Option<f64>,
+}
+
+/// The app in use right now, for live display
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CurrentActivity {
+    pub app_name: Option<String>,
+    /// Seconds since the current session started
+    pub session_seconds: i64,
+}
+
+/// Today's usage so far against yesterday's up to the same time of day
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Pacing {
+    pub today_seconds: i64,
+    pub yesterday_seconds: i64,
+    /// Whether today's usage so far is higher than yesterday's at this time
+    pub ahead_of_yesterday: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -19,3 +128,136 @@ pub struct DayStats {
     pub timestamp: i64,
     pub total_seconds: i64,
 }
+
+/// An app whose usage today is past its daily limit
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverLimitApp {
+    pub app_name: String,
+    pub limit_minutes: i32,
+    pub used_seconds: i64,
+    pub over_by_seconds: i64,
+    pub block_when_exceeded: bool,
+}
+
+/// Pick the apps strictly over their limit from `get_all_limit_status` rows,
+/// most over first
+pub fn over_limit_apps(statuses: Vec<(String, i32, i64, bool)>) -> Vec<OverLimitApp> {
+    let mut apps: Vec<OverLimitApp> = statuses
+        .into_iter()
+        .filter_map(
+            |(app_name, limit_minutes, used_seconds, block_when_exceeded)| {
+                let over_by_seconds = used_seconds - (limit_minutes as i64) * 60;
+                (over_by_seconds > 0).then_some(OverLimitApp {
+                    app_name,
+                    limit_minutes,
+                    used_seconds,
+                    over_by_seconds,
+                    block_when_exceeded,
+                })
+            },
+        )
+        .collect();
+
+    apps.sort_by(|a, b| b.over_by_seconds.cmp(&a.over_by_seconds));
+    apps
+}
+
+/// Average usage per day, optionally leaving weekend days out
+pub fn average_daily_seconds(days: &[DayStats], include_weekends: bool) -> i64 {
+    let counted: Vec<i64> = days
+        .iter()
+        .filter(|day| {
+            include_weekends
+                || chrono::NaiveDate::parse_from_str(&day.date, "%Y-%m-%d")
+                    .map(|date| !is_weekend(date))
+                    .unwrap_or(true)
+        })
+        .map(|day| day.total_seconds)
+        .collect();
+
+    if counted.is_empty() {
+        0
+    } else {
+        counted.iter().sum::<i64>() / counted.len() as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str, total_seconds: i64) -> DayStats {
+        DayStats {
+            date: date.to_string(),
+            timestamp: 0,
+            total_seconds,
+        }
+    }
+
+    #[test]
+    fn test_average_with_and_without_weekends() {
+        // Friday, Saturday, Sunday, Monday
+        let days = vec![
+            day("2026-01-02", 3600),
+            day("2026-01-03", 18000),
+            day("2026-01-04", 14400),
+            day("2026-01-05", 7200),
+        ];
+
+        assert_eq!(average_daily_seconds(&days, true), 10800);
+        assert_eq!(average_daily_seconds(&days, false), 5400);
+    }
+
+    #[test]
+    fn test_average_of_no_days() {
+        assert_eq!(average_daily_seconds(&[], false), 0);
+    }
+
+    #[test]
+    fn test_only_apps_over_their_limit_are_listed() {
+        let statuses = vec![
+            ("Under".to_string(), 30, 1200, true),
+            ("At".to_string(), 30, 1800, true),
+            ("SlightlyOver".to_string(), 30, 1860, false),
+            ("WayOver".to_string(), 10, 1500, true),
+        ];
+
+        let over = over_limit_apps(statuses);
+
+        let names: Vec<&str> = over.iter().map(|a| a.app_name.as_str()).collect();
+        assert_eq!(names, vec!["WayOver", "SlightlyOver"]);
+        assert_eq!(over[0].over_by_seconds, 900);
+        assert!(over[0].block_when_exceeded);
+        assert_eq!(over[1].over_by_seconds, 60);
+        assert!(!over[1].block_when_exceeded);
+    }
+
+    fn app(name: &str, seconds: i64, pinned: bool) -> AppUsage {
+        AppUsage {
+            app_name: name.to_string(),
+            duration_seconds: seconds,
+            session_count: 1,
+            category: None,
+            pinned,
+            icon: None,
+        }
+    }
+
+    #[test]
+    fn test_short_apps_hidden_but_counted() {
+        let apps = vec![
+            app("Firefox", 3600, false),
+            app("Spotify", 4, false),
+            app("Notes", 30, true),
+            app("Terminal", 60, false),
+        ];
+        let total = apps.iter().map(|a| a.duration_seconds).sum();
+
+        let stats =
+            DailyStats::for_display(apps, total, DisplaySettings::default().min_app_seconds);
+
+        let names: Vec<&str> = stats.apps.iter().map(|a| a.app_name.as_str()).collect();
+        assert_eq!(names, vec!["Firefox", "Notes", "Terminal"]);
+        assert_eq!(stats.total_seconds, 3694);
+    }
+}
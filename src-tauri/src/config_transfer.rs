@@ -0,0 +1,662 @@
+//! Exporting and importing the app's configuration
+//!
+//! A config bundle holds settings and definitions only: limits, categories,
+//! category limits, app groups, the daily cap, focus/break/notification settings and goals. No
+//! usage history is included, so it can be shared or kept in version control.
+//! On import each section is validated on its own and an invalid section is
+//! rejected without affecting the rest. Sections missing from the bundle are
+//! left as they are.
+
+use crate::app_groups::AppGroup;
+use crate::auto_export::AutoExportSettings;
+use crate::blocking::BlockSettings;
+use crate::break_reminder::BreakSettings;
+use crate::commands::DisplaySettings;
+use crate::focus_mode::FocusSettings;
+use crate::goals::{Goal, WeekStart, WeeklyFocusGoal};
+use crate::notification_settings::NotificationSettings;
+use crate::settings_validation::{self, SettingsRepair};
+use crate::window_tracker::TitleAwareApp;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Bumped when the bundle layout changes incompatibly
+pub const CONFIG_FORMAT_VERSION: u32 = 1;
+
+/// Longest limit or cap that can be imported (24 hours)
+const MAX_LIMIT_MINUTES: i32 = 24 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitConfig {
+    pub app_name: String,
+    pub daily_limit_minutes: i32,
+    #[serde(default)]
+    pub weekend_limit_minutes: Option<i32>,
+    #[serde(default)]
+    pub block_when_exceeded: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryConfig {
+    pub app_name: String,
+    pub category: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryLimitConfig {
+    pub category: String,
+    pub daily_limit_minutes: i32,
+    #[serde(default)]
+    pub block_when_exceeded: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlobalCapConfig {
+    pub limit_minutes: i32,
+    pub block_when_exceeded: bool,
+    /// Apps that don't count toward the cap
+    #[serde(default)]
+    pub excluded_apps: Vec<String>,
+}
+
+/// Definitions stored in the database
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigDefinitions {
+    pub limits: Option<Vec<LimitConfig>>,
+    pub categories: Option<Vec<CategoryConfig>>,
+    pub category_limits: Option<Vec<CategoryLimitConfig>>,
+    pub app_groups: Option<Vec<AppGroup>>,
+    pub global_cap: Option<GlobalCapConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalsConfig {
+    pub goals: Vec<Goal>,
+    pub weekends_count: bool,
+    #[serde(default)]
+    pub week_start: WeekStart,
+    #[serde(default)]
+    pub weekly_focus_goal: Option<WeeklyFocusGoal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBundle {
+    pub format_version: u32,
+    /// RFC 3339 time the bundle was written
+    #[serde(default)]
+    pub exported_at: String,
+    #[serde(flatten)]
+    pub definitions: ConfigDefinitions,
+    pub focus: Option<FocusSettings>,
+    pub breaks: Option<BreakSettings>,
+    pub notifications: Option<NotificationSettings>,
+    pub goals: Option<GoalsConfig>,
+    pub block_settings: Option<BlockSettings>,
+    pub display: Option<DisplaySettings>,
+    pub title_aware_apps: Option<Vec<TitleAwareApp>>,
+    pub auto_export: Option<AutoExportSettings>,
+}
+
+impl ConfigBundle {
+    /// An empty bundle to fill in section by section
+    pub fn new(exported_at: String) -> Self {
+        Self {
+            format_version: CONFIG_FORMAT_VERSION,
+            exported_at,
+            definitions: ConfigDefinitions::default(),
+            focus: None,
+            breaks: None,
+            notifications: None,
+            goals: None,
+            block_settings: None,
+            display: None,
+            title_aware_apps: None,
+            auto_export: None,
+        }
+    }
+
+    /// Names of the sections present in the bundle
+    pub fn sections(&self) -> Vec<String> {
+        let present = [
+            ("limits", self.definitions.limits.is_some()),
+            ("categories", self.definitions.categories.is_some()),
+            (
+                "category_limits",
+                self.definitions.category_limits.is_some(),
+            ),
+            ("app_groups", self.definitions.app_groups.is_some()),
+            ("global_cap", self.definitions.global_cap.is_some()),
+            ("focus", self.focus.is_some()),
+            ("breaks", self.breaks.is_some()),
+            ("notifications", self.notifications.is_some()),
+            ("goals", self.goals.is_some()),
+            ("block_settings", self.block_settings.is_some()),
+            ("display", self.display.is_some()),
+            ("title_aware_apps", self.title_aware_apps.is_some()),
+            ("auto_export", self.auto_export.is_some()),
+        ];
+        present
+            .into_iter()
+            .filter(|(_, present)| *present)
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+}
+
+/// A section that failed validation and was not applied
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RejectedSection {
+    pub section: String,
+    pub reason: String,
+}
+
+/// An existing value that the import replaced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigConflict {
+    pub section: String,
+    pub item: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigImportReport {
+    pub applied: Vec<String>,
+    pub rejected: Vec<RejectedSection>,
+    pub conflicts: Vec<ConfigConflict>,
+    /// Fixes made to otherwise valid settings sections
+    pub repairs: Vec<SettingsRepair>,
+}
+
+fn check_name(kind: &str, name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err(format!("{} name is empty", kind));
+    }
+    if name.chars().any(char::is_control) {
+        return Err(format!(
+            "{} name '{}' contains control characters",
+            kind, name
+        ));
+    }
+    Ok(())
+}
+
+fn check_minutes(what: &str, minutes: i32) -> Result<(), String> {
+    if minutes <= 0 || minutes > MAX_LIMIT_MINUTES {
+        return Err(format!("{} of {} minutes is out of range", what, minutes));
+    }
+    Ok(())
+}
+
+fn validate_limits(limits: &[LimitConfig]) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for limit in limits {
+        check_name("app", &limit.app_name)?;
+        check_minutes(
+            &format!("limit for '{}'", limit.app_name),
+            limit.daily_limit_minutes,
+        )?;
+        if let Some(weekend_minutes) = limit.weekend_limit_minutes {
+            check_minutes(
+                &format!("weekend limit for '{}'", limit.app_name),
+                weekend_minutes,
+            )?;
+        }
+        if !seen.insert(&limit.app_name) {
+            return Err(format!("'{}' has more than one limit", limit.app_name));
+        }
+    }
+    Ok(())
+}
+
+fn validate_categories(categories: &[CategoryConfig]) -> Result<(), String> {
+    for assignment in categories {
+        check_name("app", &assignment.app_name)?;
+        check_name("category", &assignment.category)?;
+    }
+    Ok(())
+}
+
+fn validate_category_limits(limits: &[CategoryLimitConfig]) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for limit in limits {
+        check_name("category", &limit.category)?;
+        check_minutes(
+            &format!("limit for category '{}'", limit.category),
+            limit.daily_limit_minutes,
+        )?;
+        if !seen.insert(&limit.category) {
+            return Err(format!("'{}' has more than one limit", limit.category));
+        }
+    }
+    Ok(())
+}
+
+fn validate_app_groups(groups: &[AppGroup]) -> Result<(), String> {
+    let mut group_of: HashMap<&str, &str> = HashMap::new();
+    for group in groups {
+        check_name("group", &group.name)?;
+        for member in &group.members {
+            check_name("app", member)?;
+            if let Some(other) = group_of.insert(member, &group.name) {
+                return Err(format!(
+                    "'{}' is in both '{}' and '{}'",
+                    member, other, group.name
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_goals(goals: &GoalsConfig) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for goal in &goals.goals {
+        if goal.id.trim().is_empty() {
+            return Err(format!("goal '{}' has no id", goal.name));
+        }
+        if !seen.insert(&goal.id) {
+            return Err(format!("goal id '{}' is used more than once", goal.id));
+        }
+    }
+    if goals
+        .weekly_focus_goal
+        .as_ref()
+        .is_some_and(|goal| goal.target_minutes == 0)
+    {
+        return Err("weekly focus target must be at least one minute".to_string());
+    }
+    Ok(())
+}
+
+fn validate_title_aware_apps(apps: &[TitleAwareApp]) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for app in apps {
+        check_name("app", &app.app_name)?;
+        if !seen.insert(app.app_name.to_lowercase()) {
+            return Err(format!("'{}' is listed more than once", app.app_name));
+        }
+    }
+    Ok(())
+}
+
+/// Drop a section that fails `check` from the bundle, recording why
+fn reject_invalid<T>(
+    section: &str,
+    value: &mut Option<T>,
+    check: impl FnOnce(&T) -> Result<(), String>,
+    rejected: &mut Vec<RejectedSection>,
+) {
+    if let Some(Err(reason)) = value.as_ref().map(check) {
+        rejected.push(RejectedSection {
+            section: section.to_string(),
+            reason,
+        });
+        *value = None;
+    }
+}
+
+/// Validate each section, removing the ones that can't be applied. Settings
+/// sections with fixable problems are repaired the same way as on startup.
+pub fn validate_bundle(bundle: &mut ConfigBundle) -> ConfigImportReport {
+    let mut report = ConfigImportReport::default();
+    let rejected = &mut report.rejected;
+    let definitions = &mut bundle.definitions;
+
+    reject_invalid(
+        "limits",
+        &mut definitions.limits,
+        |l| validate_limits(l),
+        rejected,
+    );
+    reject_invalid(
+        "categories",
+        &mut definitions.categories,
+        |c| validate_categories(c),
+        rejected,
+    );
+    reject_invalid(
+        "category_limits",
+        &mut definitions.category_limits,
+        |l| validate_category_limits(l),
+        rejected,
+    );
+    reject_invalid(
+        "app_groups",
+        &mut definitions.app_groups,
+        |g| validate_app_groups(g),
+        rejected,
+    );
+    reject_invalid(
+        "global_cap",
+        &mut definitions.global_cap,
+        |cap| {
+            check_minutes("daily cap", cap.limit_minutes)?;
+            cap.excluded_apps
+                .iter()
+                .try_for_each(|app| check_name("app", app))
+        },
+        rejected,
+    );
+    reject_invalid("goals", &mut bundle.goals, validate_goals, rejected);
+    reject_invalid(
+        "title_aware_apps",
+        &mut bundle.title_aware_apps,
+        |apps| validate_title_aware_apps(apps),
+        rejected,
+    );
+    reject_invalid(
+        "auto_export",
+        &mut bundle.auto_export,
+        AutoExportSettings::validate,
+        rejected,
+    );
+
+    if let Some(focus) = bundle.focus.as_mut() {
+        report
+            .repairs
+            .extend(settings_validation::validate_focus_settings(focus));
+    }
+    if let Some(breaks) = bundle.breaks.as_mut() {
+        report
+            .repairs
+            .extend(settings_validation::validate_break_settings(breaks));
+    }
+    if let Some(notifications) = bundle.notifications.as_mut() {
+        report
+            .repairs
+            .extend(settings_validation::validate_notification_settings(
+                notifications,
+            ));
+    }
+    if let Some(display) = bundle.display.as_mut() {
+        display.min_app_seconds = display.min_app_seconds.max(0);
+    }
+
+    report
+}
+
+/// Existing definitions that importing `imported` would change
+pub fn definition_conflicts(
+    current: &ConfigDefinitions,
+    imported: &ConfigDefinitions,
+) -> Vec<ConfigConflict> {
+    let mut conflicts = Vec::new();
+    let mut conflict = |section: &str, item: &str, message: String| {
+        conflicts.push(ConfigConflict {
+            section: section.to_string(),
+            item: item.to_string(),
+            message,
+        })
+    };
+
+    if let (Some(current), Some(imported)) = (&current.limits, &imported.limits) {
+        for limit in imported {
+            if let Some(existing) = current.iter().find(|l| l.app_name == limit.app_name) {
+                if existing != limit {
+                    conflict(
+                        "limits",
+                        &limit.app_name,
+                        format!(
+                            "limit changed from {} to {} minutes",
+                            existing.daily_limit_minutes, limit.daily_limit_minutes
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    if let (Some(current), Some(imported)) = (&current.categories, &imported.categories) {
+        for assignment in imported {
+            if let Some(existing) = current.iter().find(|c| c.app_name == assignment.app_name) {
+                if existing.category != assignment.category {
+                    conflict(
+                        "categories",
+                        &assignment.app_name,
+                        format!(
+                            "category changed from '{}' to '{}'",
+                            existing.category, assignment.category
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    if let (Some(current), Some(imported)) = (&current.category_limits, &imported.category_limits) {
+        for limit in imported {
+            if let Some(existing) = current.iter().find(|l| l.category == limit.category) {
+                if existing != limit {
+                    conflict(
+                        "category_limits",
+                        &limit.category,
+                        format!(
+                            "limit changed from {} to {} minutes",
+                            existing.daily_limit_minutes, limit.daily_limit_minutes
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    if let (Some(current), Some(imported)) = (&current.app_groups, &imported.app_groups) {
+        for group in imported {
+            for member in &group.members {
+                if let Some(existing) = current
+                    .iter()
+                    .find(|g| g.name != group.name && g.members.contains(member))
+                {
+                    conflict(
+                        "app_groups",
+                        member,
+                        format!("moved from '{}' to '{}'", existing.name, group.name),
+                    );
+                }
+            }
+        }
+    }
+
+    if let (Some(existing), Some(cap)) = (&current.global_cap, &imported.global_cap) {
+        if existing.limit_minutes != cap.limit_minutes
+            || existing.block_when_exceeded != cap.block_when_exceeded
+        {
+            conflict(
+                "global_cap",
+                "daily cap",
+                format!(
+                    "cap changed from {} to {} minutes",
+                    existing.limit_minutes, cap.limit_minutes
+                ),
+            );
+        }
+    }
+
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+
+    fn limit(app: &str, minutes: i32) -> LimitConfig {
+        LimitConfig {
+            app_name: app.to_string(),
+            daily_limit_minutes: minutes,
+            weekend_limit_minutes: None,
+            block_when_exceeded: false,
+        }
+    }
+
+    fn category_limit(category: &str, minutes: i32) -> CategoryLimitConfig {
+        CategoryLimitConfig {
+            category: category.to_string(),
+            daily_limit_minutes: minutes,
+            block_when_exceeded: true,
+        }
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        let mut db = test_db();
+        db.set_limit_with_block("Firefox", 60, true).unwrap();
+        db.set_limit_with_schedule("Slack", 30, Some(90), false)
+            .unwrap();
+        db.set_app_categories(&[("Firefox".to_string(), "Productivity".to_string())])
+            .unwrap();
+        db.set_app_group("Browsers", &["Firefox".to_string(), "Chromium".to_string()])
+            .unwrap();
+        db.set_category_limit("Social", 60, true).unwrap();
+        db.set_global_cap(240, true).unwrap();
+        db.set_app_cap_excluded("Code", true).unwrap();
+
+        let focus = FocusSettings {
+            blocked_apps: vec!["Steam".to_string()],
+            ..Default::default()
+        };
+
+        let mut bundle = ConfigBundle::new("2026-03-18T10:00:00Z".to_string());
+        bundle.definitions = db.get_config_definitions().unwrap();
+        bundle.focus = Some(focus);
+        bundle.goals = Some(GoalsConfig {
+            goals: vec![],
+            weekends_count: false,
+            week_start: WeekStart::Sunday,
+            weekly_focus_goal: Some(WeeklyFocusGoal {
+                target_minutes: 600,
+                notify: true,
+            }),
+        });
+        let json = serde_json::to_string(&bundle).unwrap();
+
+        // A fresh install has none of it
+        let mut restored = test_db();
+        assert_eq!(
+            restored.get_config_definitions().unwrap(),
+            ConfigDefinitions {
+                limits: Some(vec![]),
+                categories: Some(vec![]),
+                category_limits: Some(vec![]),
+                app_groups: Some(vec![]),
+                global_cap: None,
+            }
+        );
+
+        let mut imported: ConfigBundle = serde_json::from_str(&json).unwrap();
+        let report = validate_bundle(&mut imported);
+        assert!(report.rejected.is_empty());
+        assert!(report.repairs.is_empty());
+        restored
+            .apply_config_definitions(&imported.definitions)
+            .unwrap();
+
+        assert_eq!(
+            restored.get_config_definitions().unwrap(),
+            bundle.definitions
+        );
+        assert_eq!(imported.focus.unwrap().blocked_apps, vec!["Steam"]);
+        let goals = imported.goals.unwrap();
+        assert_eq!(goals.week_start, WeekStart::Sunday);
+        assert!(!goals.weekends_count);
+        assert_eq!(
+            imported.sections(),
+            vec![
+                "limits",
+                "categories",
+                "category_limits",
+                "app_groups",
+                "global_cap",
+                "focus",
+                "goals"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_sections_are_rejected_alone() {
+        let mut bundle = ConfigBundle::new(String::new());
+        bundle.definitions.limits = Some(vec![limit("Firefox", 60), limit("Slack", -5)]);
+        bundle.definitions.app_groups = Some(vec![
+            AppGroup {
+                name: "Browsers".to_string(),
+                members: vec!["Firefox".to_string()],
+            },
+            AppGroup {
+                name: "Work".to_string(),
+                members: vec!["Firefox".to_string()],
+            },
+        ]);
+        bundle.definitions.categories = Some(vec![CategoryConfig {
+            app_name: "Firefox".to_string(),
+            category: "Productivity".to_string(),
+        }]);
+        bundle.definitions.category_limits = Some(vec![
+            category_limit("Social", 60),
+            category_limit("Social", 30),
+        ]);
+
+        let report = validate_bundle(&mut bundle);
+
+        let rejected: Vec<&str> = report.rejected.iter().map(|r| r.section.as_str()).collect();
+        assert_eq!(rejected, vec!["limits", "category_limits", "app_groups"]);
+        assert_eq!(bundle.sections(), vec!["categories"]);
+    }
+
+    #[test]
+    fn test_conflicts_report_changed_definitions() {
+        let current = ConfigDefinitions {
+            limits: Some(vec![limit("Firefox", 60), limit("Slack", 30)]),
+            categories: Some(vec![CategoryConfig {
+                app_name: "Firefox".to_string(),
+                category: "Productivity".to_string(),
+            }]),
+            category_limits: Some(vec![category_limit("Social", 60)]),
+            app_groups: Some(vec![AppGroup {
+                name: "Chat".to_string(),
+                members: vec!["Slack".to_string()],
+            }]),
+            global_cap: None,
+        };
+        let imported = ConfigDefinitions {
+            limits: Some(vec![
+                limit("Firefox", 90),
+                limit("Slack", 30),
+                limit("Steam", 20),
+            ]),
+            categories: Some(vec![CategoryConfig {
+                app_name: "Firefox".to_string(),
+                category: "Entertainment".to_string(),
+            }]),
+            category_limits: Some(vec![
+                category_limit("Social", 30),
+                category_limit("Games", 60),
+            ]),
+            app_groups: Some(vec![AppGroup {
+                name: "Work".to_string(),
+                members: vec!["Slack".to_string()],
+            }]),
+            global_cap: Some(GlobalCapConfig {
+                limit_minutes: 240,
+                block_when_exceeded: false,
+                excluded_apps: vec![],
+            }),
+        };
+
+        let conflicts = definition_conflicts(&current, &imported);
+
+        let items: Vec<(&str, &str)> = conflicts
+            .iter()
+            .map(|c| (c.section.as_str(), c.item.as_str()))
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                ("limits", "Firefox"),
+                ("categories", "Firefox"),
+                ("category_limits", "Social"),
+                ("app_groups", "Slack"),
+            ]
+        );
+    }
+}
@@ -1,18 +1,46 @@
-use crate::migrations;
-use chrono::Utc;
+use crate::app_groups::AppGroup;
+use crate::config_transfer::{
+    CategoryConfig, CategoryLimitConfig, ConfigDefinitions, GlobalCapConfig, LimitConfig,
+};
+use crate::focus_mode::{FocusHistoryEntry, FocusReward};
+use crate::goals::{Goal, GoalsState};
+use crate::idle::{IdleRatio, IdleReason, IdleReasonTotal};
+use crate::limit_popup::EmergencyGrant;
+use crate::migrations::{self, MigrationRecord};
+use chrono::{Datelike, Utc};
 use rusqlite::{Connection, OptionalExtension, Result as SqliteResult};
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// How long a computed daily summary is served before being recomputed.
+/// Writes to sessions or categories invalidate it sooner.
+pub const DAILY_SUMMARY_TTL: Duration = Duration::from_secs(10);
+
+/// How many times a write is attempted when SQLite reports the database busy
+/// or locked, on top of the connection's `busy_timeout`
+const BUSY_RETRY_ATTEMPTS: u32 = 3;
+
+/// Delay before the first busy retry; doubled for each further attempt
+const BUSY_RETRY_BACKOFF: Duration = Duration::from_millis(50);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct App {
     pub id: i64,
     pub name: String,
     pub path: Option<String>,
+    /// The user's custom icon if set, otherwise the scanned one
     pub icon_path: Option<String>,
     pub category: Option<String>,
     pub is_blocked: bool,
     pub created_at: i64,
+    /// Pinned apps are listed first on the dashboard
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub has_custom_icon: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,12 +56,28 @@ pub struct CategoryUsage {
     pub app_count: i64,
 }
 
-#[allow(dead_code)]
+/// One app's usage within a category breakdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryAppUsage {
+    pub app_name: String,
+    pub duration_seconds: i64,
+}
+
+/// A category's usage with the apps that make it up, most used first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryBreakdown {
+    pub category: String,
+    pub total_seconds: i64,
+    pub apps: Vec<CategoryAppUsage>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsageSession {
     pub id: i64,
     pub app_id: i64,
     pub app_name: String,
+    #[serde(default)]
+    pub category: Option<String>,
     pub start_time: i64,
     pub end_time: i64,
     pub duration_seconds: i64,
@@ -45,6 +89,9 @@ pub struct AppLimit {
     pub app_id: i64,
     pub app_name: String,
     pub daily_limit_minutes: i32,
+    /// Limit on Saturdays and Sundays; `daily_limit_minutes` applies when unset
+    #[serde(default)]
+    pub weekend_limit_minutes: Option<i32>,
     pub block_when_exceeded: bool,
 }
 
@@ -54,6 +101,11 @@ pub struct AppUsage {
     pub duration_seconds: i64,
     pub session_count: i64,
     pub category: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    /// Custom icon if set, otherwise the scanned one
+    #[serde(default)]
+    pub icon: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,8 +117,190 @@ pub struct ExportRecord {
     pub session_count: i64,
 }
 
+/// The daily screen-time cap across all apps and today's usage against it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalCapStatus {
+    pub limit_minutes: i32,
+    pub block_when_exceeded: bool,
+    /// Today's usage of apps that count toward the cap
+    pub used_seconds: i64,
+    pub exceeded: bool,
+    /// Apps still tracked but not counted toward the cap
+    pub excluded_apps: Vec<String>,
+}
+
+/// A user-defined category with its display color
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Category {
+    pub name: String,
+    /// Hex color (#RRGGBB)
+    pub color: String,
+    /// Whether time in this category counts as productive rather than distracting
+    pub is_productive: bool,
+}
+
+/// A category's daily limit and today's usage of its apps against it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryLimitStatus {
+    pub category: String,
+    pub limit_minutes: i32,
+    pub block_when_exceeded: bool,
+    pub used_seconds: i64,
+    pub exceeded: bool,
+}
+
+/// The longest single session, with the local date it started on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    #[serde(flatten)]
+    pub session: UsageSession,
+    pub date: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayRecord {
+    pub date: String,
+    pub total_seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppRecord {
+    pub app_name: String,
+    pub total_seconds: i64,
+}
+
+/// Current and longest runs of consecutive days meeting some condition
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyStreak {
+    pub current: u32,
+    pub longest: u32,
+}
+
+/// Extra limit minutes granted to an app for one day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitBonus {
+    pub app_name: String,
+    /// Local date the bonus applies to (YYYY-MM-DD)
+    pub date: String,
+    pub minutes: i64,
+    pub reason: String,
+    pub granted_at: i64,
+}
+
+/// All-time personal records; each is None until there's usage to rank
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonalRecords {
+    pub longest_session: Option<SessionRecord>,
+    pub busiest_day: Option<DayRecord>,
+    pub most_used_app: Option<AppRecord>,
+}
+
+/// Everything the dashboard needs about today, computed in one pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailySummary {
+    /// Local date the summary covers (YYYY-MM-DD)
+    pub date: String,
+    pub total_seconds: i64,
+    pub apps: Vec<AppUsage>,
+    pub categories: Vec<CategoryUsage>,
+}
+
+struct CachedSummary {
+    summary: DailySummary,
+    computed_at: Instant,
+}
+
+/// Applied migrations plus how many ran when this database was opened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationHistory {
+    pub migrations: Vec<MigrationRecord>,
+    pub applied_this_session: u32,
+}
+
+/// Whether an error is a transient lock that's worth retrying
+fn is_busy_error(error: &rusqlite::Error) -> bool {
+    matches!(
+        error,
+        rusqlite::Error::SqliteFailure(e, _)
+            if matches!(
+                e.code,
+                rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+            )
+    )
+}
+
+/// Run a write, retrying with backoff while the database is busy. Gives up
+/// after `BUSY_RETRY_ATTEMPTS` so a stuck database still reports an error.
+fn with_busy_retry<T>(mut op: impl FnMut() -> SqliteResult<T>) -> SqliteResult<T> {
+    let mut backoff = BUSY_RETRY_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Err(e) if is_busy_error(&e) && attempt < BUSY_RETRY_ATTEMPTS => {
+                tracing::warn!(attempt, error = %e, "Database busy, retrying write");
+                std::thread::sleep(backoff);
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Serialize a value stored as a JSON column
+fn to_json<T: Serialize>(value: &T) -> SqliteResult<String> {
+    serde_json::to_string(value).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+/// Parse a JSON column, logging and ignoring values that no longer parse
+fn from_json<T: serde::de::DeserializeOwned>(json: Option<String>, column: &str) -> Option<T> {
+    let json = json?;
+    serde_json::from_str(&json)
+        .map_err(|e| tracing::warn!(column, error = %e, "Ignoring unreadable saved value"))
+        .ok()
+}
+
+/// Timestamp for a `YYYY-MM-DD` day string, falling back to now if it doesn't parse
+fn day_timestamp(day_str: &str) -> i64 {
+    chrono::NaiveDate::parse_from_str(day_str, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(12, 0, 0)) // Use noon to avoid timezone edge cases
+        .map(|dt| dt.and_utc().timestamp())
+        .unwrap_or_else(|| Utc::now().timestamp())
+}
+
+/// Timestamp of midnight in `tz` at the start of the 7-day window ending on
+/// `today`. If a DST change skips that midnight, the first hour after it is used.
+fn week_start_in<Tz: chrono::TimeZone>(tz: &Tz, today: chrono::NaiveDate) -> i64 {
+    timestamp_in(
+        tz,
+        (today - chrono::Duration::days(6)).and_time(chrono::NaiveTime::MIN),
+    )
+}
+
+/// Timestamp of a wall-clock time in `tz`. A time skipped by a DST change
+/// moves an hour later; an ambiguous one resolves to its first occurrence.
+pub(crate) fn timestamp_in<Tz: chrono::TimeZone>(tz: &Tz, local: chrono::NaiveDateTime) -> i64 {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(local + chrono::Duration::hours(1)))
+                .earliest()
+        })
+        .map(|dt| dt.timestamp())
+        .unwrap_or_else(|| local.and_utc().timestamp())
+}
+
+/// Whether weekend limits apply on `weekday`
+fn is_weekend_day(weekday: chrono::Weekday) -> bool {
+    matches!(weekday, chrono::Weekday::Sat | chrono::Weekday::Sun)
+}
+
 pub struct Database {
     conn: Connection,
+    summary_cache: RefCell<Option<CachedSummary>>,
+    /// Number of migrations applied by `run_migrations` on startup
+    migrations_applied: u32,
 }
 
 impl Database {
@@ -77,18 +311,35 @@ impl Database {
         // WAL allows readers to proceed without blocking writers and vice versa,
         // which is critical since the background tracker writes every 5 seconds
         // while the UI reads concurrently.
-        conn.pragma_update(None, "journal_mode", "WAL")?;
+        let journal_mode: String =
+            conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get(0))?;
+        // In-memory databases always report "memory"; anything else means
+        // the file system or another open connection kept WAL from being enabled
+        if !journal_mode.eq_ignore_ascii_case("wal") && journal_mode != "memory" {
+            tracing::warn!(
+                journal_mode = %journal_mode,
+                "Could not enable WAL mode; concurrent access may hit locks"
+            );
+        }
 
         // Set a busy timeout so queries wait instead of immediately failing
         // when the database is locked by another operation.
         conn.pragma_update(None, "busy_timeout", 5000)?;
 
+        // In WAL mode NORMAL is still safe against corruption and avoids an
+        // fsync on every commit
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+
         // Enable foreign keys (off by default in SQLite)
         conn.pragma_update(None, "foreign_keys", "ON")?;
 
-        let db = Database { conn };
+        let mut db = Database {
+            conn,
+            summary_cache: RefCell::new(None),
+            migrations_applied: 0,
+        };
         db.init_schema()?;
-        db.run_migrations()?;
+        db.migrations_applied = db.run_migrations()?;
         db.ensure_schema_columns()?;
         Ok(db)
     }
@@ -136,10 +387,26 @@ impl Database {
         Ok(())
     }
 
+    /// Execute a single write statement, retrying if the database is busy
+    fn execute_write<P: rusqlite::Params + Copy>(
+        &self,
+        sql: &str,
+        params: P,
+    ) -> SqliteResult<usize> {
+        with_busy_retry(|| self.conn.execute(sql, params))
+    }
+
     /// Run database migrations to update schema
-    fn run_migrations(&self) -> SqliteResult<()> {
-        migrations::run_migrations(&self.conn)?;
-        Ok(())
+    fn run_migrations(&self) -> SqliteResult<u32> {
+        migrations::run_migrations(&self.conn)
+    }
+
+    /// Applied migrations from the schema_version table, oldest first
+    pub fn get_migration_history(&self) -> SqliteResult<MigrationHistory> {
+        Ok(MigrationHistory {
+            migrations: migrations::get_migration_history(&self.conn)?,
+            applied_this_session: self.migrations_applied,
+        })
     }
 
     /// Ensure all expected columns exist in the schema.
@@ -154,6 +421,9 @@ impl Database {
             "ALTER TABLE apps ADD COLUMN category TEXT",
             "ALTER TABLE apps ADD COLUMN is_blocked INTEGER DEFAULT 0",
             "ALTER TABLE app_limits ADD COLUMN block_when_exceeded INTEGER DEFAULT 0",
+            "ALTER TABLE apps ADD COLUMN pinned INTEGER DEFAULT 0",
+            "ALTER TABLE apps ADD COLUMN excluded_from_cap INTEGER DEFAULT 0",
+            "ALTER TABLE apps ADD COLUMN custom_icon TEXT",
         ];
 
         for stmt in &alter_statements {
@@ -187,10 +457,11 @@ impl Database {
             return Ok(row);
         }
 
-        self.conn.execute(
+        self.execute_write(
             "INSERT INTO apps (name, path) VALUES (?1, ?2)",
             &[name, &path.unwrap_or_default()],
         )?;
+        self.invalidate_daily_summary();
         Ok(self.conn.last_insert_rowid())
     }
 
@@ -201,40 +472,56 @@ impl Database {
         app_name: &str,
         duration_seconds: i64,
     ) -> SqliteResult<()> {
-        let tx = self.conn.transaction()?;
+        with_busy_retry(|| {
+            let tx = self.conn.transaction()?;
 
-        // Get or create app
-        let app_id: i64 =
-            match tx.query_row("SELECT id FROM apps WHERE name = ?1", &[app_name], |row| {
-                row.get(0)
-            }) {
-                Ok(id) => id,
-                Err(_) => {
-                    tx.execute(
-                        "INSERT INTO apps (name, path) VALUES (?1, ?2)",
-                        &[app_name, ""],
-                    )?;
-                    tx.last_insert_rowid()
-                }
-            };
+            // Get or create app
+            let app_id: i64 =
+                match tx.query_row("SELECT id FROM apps WHERE name = ?1", &[app_name], |row| {
+                    row.get(0)
+                }) {
+                    Ok(id) => id,
+                    Err(_) => {
+                        tx.execute(
+                            "INSERT INTO apps (name, path) VALUES (?1, ?2)",
+                            &[app_name, ""],
+                        )?;
+                        tx.last_insert_rowid()
+                    }
+                };
 
-        let now = Utc::now().timestamp();
-        let start_time = now - duration_seconds;
+            let now = Utc::now().timestamp();
+            let start_time = now - duration_seconds;
 
-        // Create session with all data at once
-        tx.execute(
-            "INSERT INTO usage_sessions (app_id, start_time, end_time, duration_seconds) VALUES (?1, ?2, ?3, ?4)",
-            rusqlite::params![app_id, start_time, now, duration_seconds],
-        )?;
+            // Create session with all data at once
+            tx.execute(
+                "INSERT INTO usage_sessions (app_id, start_time, end_time, duration_seconds) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![app_id, start_time, now, duration_seconds],
+            )?;
 
-        tx.commit()
+            tx.commit()
+        })?;
+        self.invalidate_daily_summary();
+        Ok(())
     }
 
     pub fn start_session(&self, app_id: i64, start_time: i64) -> SqliteResult<i64> {
-        self.conn.execute(
-            "INSERT INTO usage_sessions (app_id, start_time, end_time, duration_seconds) VALUES (?1, ?2, ?2, 0)",
-            rusqlite::params![app_id, start_time],
+        self.start_session_with_title(app_id, start_time, None)
+    }
+
+    /// Start a session, recording the window title it started with
+    pub fn start_session_with_title(
+        &self,
+        app_id: i64,
+        start_time: i64,
+        window_title: Option<&str>,
+    ) -> SqliteResult<i64> {
+        self.execute_write(
+            "INSERT INTO usage_sessions (app_id, start_time, end_time, duration_seconds, window_title)
+             VALUES (?1, ?2, ?2, 0, ?3)",
+            rusqlite::params![app_id, start_time, window_title],
         )?;
+        self.invalidate_daily_summary();
         Ok(self.conn.last_insert_rowid())
     }
 
@@ -248,19 +535,21 @@ impl Database {
             },
         )?;
 
-        self.conn.execute(
+        self.execute_write(
             "UPDATE usage_sessions SET end_time = ?1, duration_seconds = ?2 WHERE id = ?3",
             rusqlite::params![end_time, duration, session_id],
         )?;
+        self.invalidate_daily_summary();
 
         Ok(())
     }
 
     pub fn update_session_duration(&self, session_id: i64, end_time: i64) -> SqliteResult<()> {
-        self.conn.execute(
+        self.execute_write(
             "UPDATE usage_sessions SET end_time = ?1, duration_seconds = ?1 - start_time WHERE id = ?2",
             rusqlite::params![end_time, session_id],
         )?;
+        self.invalidate_daily_summary();
         Ok(())
     }
 
@@ -282,6 +571,11 @@ impl Database {
     }
 
     pub fn get_daily_usage(&self) -> SqliteResult<Vec<AppUsage>> {
+        self.get_daily_usage_for(chrono::Local::now().date_naive())
+    }
+
+    /// Per-app usage for a single local calendar day
+    pub fn get_daily_usage_for(&self, date: chrono::NaiveDate) -> SqliteResult<Vec<AppUsage>> {
         // Use SQLite's local time calculation
         // For in-progress sessions (end_time == start_time, duration_seconds == 0),
         // compute duration dynamically so they appear immediately in the UI
@@ -293,21 +587,24 @@ impl Database {
                              ELSE us.duration_seconds
                         END
                     ), 0) as total_duration,
-                    COUNT(us.id), a.category
+                    COUNT(us.id), a.category, COALESCE(a.pinned, 0),
+                    COALESCE(a.custom_icon, a.icon_path)
              FROM apps a
              LEFT JOIN usage_sessions us ON a.id = us.app_id 
-                AND date(us.start_time, 'unixepoch', 'localtime') = date('now', 'localtime')
+                AND date(us.start_time, 'unixepoch', 'localtime') = ?1
              GROUP BY a.id
              HAVING total_duration > 0 OR COUNT(us.id) > 0
-             ORDER BY total_duration DESC",
+             ORDER BY COALESCE(a.pinned, 0) DESC, total_duration DESC",
         )?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map([date.format("%Y-%m-%d").to_string()], |row| {
             Ok(AppUsage {
                 app_name: row.get(0)?,
                 duration_seconds: row.get(1)?,
                 session_count: row.get(2)?,
                 category: row.get(3)?,
+                pinned: row.get::<_, i32>(4)? != 0,
+                icon: row.get(5)?,
             })
         })?;
 
@@ -318,8 +615,65 @@ impl Database {
         Ok(result)
     }
 
+    /// Usage for a local date grouped by category, with each category's apps
+    /// nested under it. Apps without a category are under "Uncategorized".
+    pub fn get_category_app_breakdown(
+        &self,
+        date: chrono::NaiveDate,
+    ) -> SqliteResult<Vec<CategoryBreakdown>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT COALESCE(a.category, 'Uncategorized') as category, a.name,
+                    SUM(
+                        CASE WHEN us.duration_seconds = 0 AND us.end_time = us.start_time
+                             THEN MAX(strftime('%s','now') - us.start_time, 0)
+                             ELSE us.duration_seconds
+                        END
+                    ) as total
+             FROM usage_sessions us
+             JOIN apps a ON us.app_id = a.id
+             WHERE date(us.start_time, 'unixepoch', 'localtime') = ?1
+             GROUP BY a.id
+             ORDER BY category, total DESC, a.name",
+        )?;
+
+        let rows = stmt.query_map([date.format("%Y-%m-%d").to_string()], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                CategoryAppUsage {
+                    app_name: row.get(1)?,
+                    duration_seconds: row.get(2)?,
+                },
+            ))
+        })?;
+
+        let mut breakdown: Vec<CategoryBreakdown> = Vec::new();
+        for row in rows {
+            let (category, app) = row?;
+            match breakdown.last_mut() {
+                Some(last) if last.category == category => {
+                    last.total_seconds += app.duration_seconds;
+                    last.apps.push(app);
+                }
+                _ => breakdown.push(CategoryBreakdown {
+                    category,
+                    total_seconds: app.duration_seconds,
+                    apps: vec![app],
+                }),
+            }
+        }
+        breakdown.sort_by(|a, b| b.total_seconds.cmp(&a.total_seconds));
+        Ok(breakdown)
+    }
+
     pub fn get_weekly_stats(&self) -> SqliteResult<Vec<(i64, i64)>> {
-        let week_ago = Utc::now().timestamp() - (7 * 24 * 60 * 60);
+        self.get_weekly_stats_for(chrono::Local::now().date_naive())
+    }
+
+    /// Daily totals for the 7 local calendar days ending on `today`. The window
+    /// starts at local midnight 6 days earlier rather than 168 hours ago, so a
+    /// DST change can't pull in part of an eighth day or drop an hour.
+    pub fn get_weekly_stats_for(&self, today: chrono::NaiveDate) -> SqliteResult<Vec<(i64, i64)>> {
+        let week_start = week_start_in(&chrono::Local, today);
 
         let mut stmt = self.conn.prepare(
             "SELECT DATE(start_time, 'unixepoch', 'localtime') as day, SUM(duration_seconds)
@@ -329,15 +683,82 @@ impl Database {
              ORDER BY day ASC",
         )?;
 
-        let rows = stmt.query_map([week_ago], |row| {
+        let rows = stmt.query_map([week_start], |row| {
+            let day_str: String = row.get(0)?;
+            Ok((day_timestamp(&day_str), row.get(1)?))
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Time spent in `app_name` on `date` per window title, largest first.
+    /// Sessions recorded without a title are left out.
+    pub fn get_title_breakdown(
+        &self,
+        app_name: &str,
+        date: chrono::NaiveDate,
+    ) -> SqliteResult<Vec<(String, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT us.window_title, SUM(us.duration_seconds) as total
+             FROM usage_sessions us
+             JOIN apps a ON a.id = us.app_id
+             WHERE a.name = ?1
+               AND us.window_title IS NOT NULL
+               AND date(us.start_time, 'unixepoch', 'localtime') = ?2
+             GROUP BY us.window_title
+             ORDER BY total DESC",
+        )?;
+        let rows = stmt.query_map(
+            rusqlite::params![app_name, date.format("%Y-%m-%d").to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        rows.collect()
+    }
+
+    /// Every session starting on the local date `date` (`YYYY-MM-DD`), in start order
+    pub fn get_sessions_for_date(&self, date: &str) -> SqliteResult<Vec<UsageSession>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT us.id, us.app_id, a.name, us.start_time, us.end_time, us.duration_seconds,
+                    a.category
+             FROM usage_sessions us
+             JOIN apps a ON a.id = us.app_id
+             WHERE date(us.start_time, 'unixepoch', 'localtime') = ?1
+             ORDER BY us.start_time ASC, us.id ASC",
+        )?;
+        let rows = stmt.query_map([date], |row| {
+            Ok(UsageSession {
+                id: row.get(0)?,
+                app_id: row.get(1)?,
+                app_name: row.get(2)?,
+                category: row.get(6)?,
+                start_time: row.get(3)?,
+                end_time: row.get(4)?,
+                duration_seconds: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Daily totals for the last 30 local days including today, in the same
+    /// shape as `get_weekly_stats`. Days without usage are omitted.
+    pub fn get_monthly_stats(&self) -> SqliteResult<Vec<(i64, i64)>> {
+        let first_day = chrono::Local::now().date_naive() - chrono::Duration::days(29);
+
+        let mut stmt = self.conn.prepare(
+            "SELECT DATE(start_time, 'unixepoch', 'localtime') as day, SUM(duration_seconds)
+             FROM usage_sessions
+             WHERE DATE(start_time, 'unixepoch', 'localtime') >= ?1
+             GROUP BY day
+             ORDER BY day ASC",
+        )?;
+
+        let rows = stmt.query_map([first_day.format("%Y-%m-%d").to_string()], |row| {
             let day_str: String = row.get(0)?;
-            // Parse the date string, falling back to current time if parsing fails
-            let day = chrono::NaiveDate::parse_from_str(&day_str, "%Y-%m-%d")
-                .ok()
-                .and_then(|d| d.and_hms_opt(12, 0, 0)) // Use noon to avoid timezone edge cases
-                .map(|dt| dt.and_utc().timestamp())
-                .unwrap_or_else(|| Utc::now().timestamp());
-            Ok((day, row.get(1)?))
+            Ok((day_timestamp(&day_str), row.get(1)?))
         })?;
 
         let mut result = Vec::new();
@@ -347,9 +768,102 @@ impl Database {
         Ok(result)
     }
 
+    /// Total seconds for this week and last week: (this_week, last_week).
+    /// This week is the last 7 local days including today, last week the 7 before that.
+    pub fn get_week_comparison(&self) -> SqliteResult<(i64, i64)> {
+        let today = chrono::Local::now().date_naive();
+        let this_week_start = today - chrono::Duration::days(6);
+        let last_week_start = this_week_start - chrono::Duration::days(7);
+
+        self.conn.query_row(
+            "SELECT
+                COALESCE(SUM(CASE WHEN DATE(start_time, 'unixepoch', 'localtime') >= ?1
+                             THEN duration_seconds END), 0),
+                COALESCE(SUM(CASE WHEN DATE(start_time, 'unixepoch', 'localtime') < ?1
+                             THEN duration_seconds END), 0)
+             FROM usage_sessions
+             WHERE DATE(start_time, 'unixepoch', 'localtime') >= ?2",
+            [
+                this_week_start.format("%Y-%m-%d").to_string(),
+                last_week_start.format("%Y-%m-%d").to_string(),
+            ],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    }
+
+    /// Seconds of usage on a local date up to `seconds_into_day` past local
+    /// midnight. Sessions running across that point count only up to it.
+    pub fn get_usage_until_time_of_day(
+        &self,
+        date: chrono::NaiveDate,
+        seconds_into_day: i64,
+    ) -> SqliteResult<i64> {
+        self.conn.query_row(
+            "SELECT COALESCE(SUM(MIN(
+                        CASE WHEN duration_seconds = 0 AND end_time = start_time
+                             THEN MAX(strftime('%s','now') - start_time, 0)
+                             ELSE duration_seconds
+                        END,
+                        CAST(strftime('%s', ?1, 'utc') AS INTEGER) + ?2 - start_time
+                    )), 0)
+             FROM usage_sessions
+             WHERE DATE(start_time, 'unixepoch', 'localtime') = ?1
+               AND start_time < CAST(strftime('%s', ?1, 'utc') AS INTEGER) + ?2",
+            rusqlite::params![date.format("%Y-%m-%d").to_string(), seconds_into_day],
+            |row| row.get(0),
+        )
+    }
+
+    /// Seconds used on a local date between `start_hour` and `end_hour`,
+    /// counting only the part of each session inside the window. A window that
+    /// wraps past midnight, like 23 to 6, covers both ends of the day:
+    /// 00:00-06:00 and 23:00-24:00.
+    pub fn get_usage_in_hour_window(
+        &self,
+        date: chrono::NaiveDate,
+        start_hour: u32,
+        end_hour: u32,
+    ) -> SqliteResult<i64> {
+        let ranges = if start_hour <= end_hour {
+            vec![(start_hour, end_hour)]
+        } else {
+            vec![(0, end_hour), (start_hour, 24)]
+        };
+
+        let at_hour = |hour: u32| {
+            let time = date.and_time(chrono::NaiveTime::MIN) + chrono::Duration::hours(hour as i64);
+            timestamp_in(&chrono::Local, time)
+        };
+        let mut total = 0;
+        for (from, to) in ranges.into_iter().filter(|(from, to)| from < to) {
+            total += self.get_usage_between(at_hour(from), at_hour(to))?;
+        }
+        Ok(total)
+    }
+
+    /// Seconds of session time falling between two timestamps
+    fn get_usage_between(&self, start: i64, end: i64) -> SqliteResult<i64> {
+        self.conn.query_row(
+            "SELECT COALESCE(SUM(MAX(MIN(session_end, ?2) - MAX(start_time, ?1), 0)), 0)
+             FROM (
+                 SELECT start_time,
+                        CASE WHEN duration_seconds = 0 AND end_time = start_time
+                             THEN CAST(strftime('%s', 'now') AS INTEGER)
+                             ELSE end_time
+                        END AS session_end
+                 FROM usage_sessions
+                 WHERE start_time < ?2
+                   AND (start_time + duration_seconds > ?1
+                        OR (duration_seconds = 0 AND end_time = start_time))
+             )",
+            [start, end],
+            |row| row.get(0),
+        )
+    }
+
     pub fn set_limit(&self, app_name: &str, minutes: i32) -> SqliteResult<()> {
         let app_id = self.get_or_create_app(app_name, None)?;
-        self.conn.execute(
+        self.execute_write(
             "INSERT OR REPLACE INTO app_limits (app_id, daily_limit_minutes) VALUES (?1, ?2)",
             rusqlite::params![app_id, minutes as i64],
         )?;
@@ -370,7 +884,8 @@ impl Database {
 
     pub fn get_all_limits(&self) -> SqliteResult<Vec<AppLimit>> {
         let mut stmt = self.conn.prepare(
-            "SELECT al.id, al.app_id, a.name, al.daily_limit_minutes, COALESCE(al.block_when_exceeded, 0)
+            "SELECT al.id, al.app_id, a.name, al.daily_limit_minutes, COALESCE(al.block_when_exceeded, 0),
+                    al.weekend_limit_minutes
              FROM app_limits al
              JOIN apps a ON al.app_id = a.id",
         )?;
@@ -381,6 +896,7 @@ impl Database {
                 app_id: row.get(1)?,
                 app_name: row.get(2)?,
                 daily_limit_minutes: row.get(3)?,
+                weekend_limit_minutes: row.get(5)?,
                 block_when_exceeded: row.get::<_, i32>(4)? != 0,
             })
         })?;
@@ -393,7 +909,7 @@ impl Database {
     }
 
     pub fn remove_limit(&self, app_name: &str) -> SqliteResult<()> {
-        self.conn.execute(
+        self.execute_write(
             "DELETE FROM app_limits WHERE app_id = (SELECT id FROM apps WHERE name = ?1)",
             &[app_name],
         )?;
@@ -405,53 +921,286 @@ impl Database {
         app_name: &str,
         minutes: i32,
         block_when_exceeded: bool,
+    ) -> SqliteResult<()> {
+        self.set_limit_with_schedule(app_name, minutes, None, block_when_exceeded)
+    }
+
+    /// Set a limit with a separate weekend limit; `None` uses `minutes` every day
+    pub fn set_limit_with_schedule(
+        &self,
+        app_name: &str,
+        minutes: i32,
+        weekend_minutes: Option<i32>,
+        block_when_exceeded: bool,
     ) -> SqliteResult<()> {
         let app_id = self.get_or_create_app(app_name, None)?;
-        self.conn.execute(
-            "INSERT OR REPLACE INTO app_limits (app_id, daily_limit_minutes, block_when_exceeded) VALUES (?1, ?2, ?3)",
-            rusqlite::params![app_id, minutes as i64, block_when_exceeded as i32],
+        self.execute_write(
+            "INSERT OR REPLACE INTO app_limits (app_id, daily_limit_minutes, weekend_limit_minutes, block_when_exceeded)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![
+                app_id,
+                minutes as i64,
+                weekend_minutes.map(|m| m as i64),
+                block_when_exceeded as i32
+            ],
         )?;
         Ok(())
     }
 
-    pub fn set_app_category(&self, app_name: &str, category: &str) -> SqliteResult<()> {
-        self.conn.execute(
-            "UPDATE apps SET category = ?1 WHERE name = ?2",
-            rusqlite::params![category, app_name],
+    /// Update only the blocking flag of an existing limit.
+    /// Returns false if the app has no limit set.
+    pub fn set_limit_blocking(
+        &self,
+        app_name: &str,
+        block_when_exceeded: bool,
+    ) -> SqliteResult<bool> {
+        let updated = self.execute_write(
+            "UPDATE app_limits SET block_when_exceeded = ?1
+             WHERE app_id = (SELECT id FROM apps WHERE name = ?2)",
+            rusqlite::params![block_when_exceeded as i32, app_name],
         )?;
-        Ok(())
+        Ok(updated > 0)
     }
 
-    pub fn get_hourly_usage(&self) -> SqliteResult<Vec<HourlyUsage>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT CAST(strftime('%H', start_time, 'unixepoch', 'localtime') AS INTEGER) as hour, 
-                    SUM(
-                        CASE WHEN duration_seconds = 0 AND end_time = start_time
-                             THEN MAX(strftime('%s','now') - start_time, 0)
-                             ELSE duration_seconds
-                        END
-                    ) as total
-             FROM usage_sessions
-             WHERE date(start_time, 'unixepoch', 'localtime') = date('now', 'localtime')
-             GROUP BY hour
-             ORDER BY hour ASC",
+    /// Get the blocking flag of an app's limit (None if the app has no limit)
+    pub fn get_limit_blocking(&self, app_name: &str) -> SqliteResult<Option<bool>> {
+        self.conn
+            .query_row(
+                "SELECT COALESCE(al.block_when_exceeded, 0) FROM app_limits al
+                 JOIN apps a ON al.app_id = a.id
+                 WHERE a.name = ?1",
+                [app_name],
+                |row| Ok(row.get::<_, i32>(0)? != 0),
+            )
+            .optional()
+    }
+
+    /// Close sessions left open by a crash that started before `older_than`.
+    ///
+    /// A session that was flushed already ends at its last flush. One still
+    /// zero-length was never flushed, so queries would count it as in
+    /// progress up to now; it is given `max_seconds`, the longest it can have
+    /// run without a flush, instead. Returns how many were closed.
+    pub fn close_orphaned_sessions(
+        &self,
+        older_than: i64,
+        max_seconds: i64,
+    ) -> SqliteResult<usize> {
+        let closed = self.execute_write(
+            "UPDATE usage_sessions SET end_time = start_time + ?2, duration_seconds = ?2
+             WHERE duration_seconds = 0 AND end_time = start_time AND start_time < ?1",
+            rusqlite::params![older_than, max_seconds],
         )?;
+        if closed > 0 {
+            self.invalidate_daily_summary();
+        }
+        Ok(closed)
+    }
 
-        let rows = stmt.query_map([], |row| {
-            Ok(HourlyUsage {
-                hour: row.get(0)?,
-                total_seconds: row.get(1)?,
-            })
-        })?;
+    /// Remove limits with a negative daily limit (e.g. from a corrupted or hand-edited DB).
+    /// Returns the names of the apps whose limits were removed.
+    pub fn repair_invalid_limits(&self) -> SqliteResult<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.name FROM app_limits al
+             JOIN apps a ON al.app_id = a.id
+             WHERE al.daily_limit_minutes < 0",
+        )?;
+        let names = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<SqliteResult<Vec<String>>>()?;
 
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
+        if !names.is_empty() {
+            self.conn
+                .execute("DELETE FROM app_limits WHERE daily_limit_minutes < 0", [])?;
         }
-        Ok(result)
+
+        Ok(names)
     }
 
-    pub fn get_category_usage(&self) -> SqliteResult<Vec<CategoryUsage>> {
+    /// Move all of `from_name`'s sessions, limit, limit bonuses, group
+    /// membership and tracking exclusion onto `to_name`, creating the target
+    /// if needed, then delete the source app. The target also takes the
+    /// source's category and icons where it has none, and its pinned and
+    /// cap-excluded flags. A limit or group already set on the target wins.
+    /// Returns false if the source app doesn't exist.
+    pub fn merge_apps(&mut self, from_name: &str, to_name: &str) -> SqliteResult<bool> {
+        if from_name == to_name {
+            return self
+                .conn
+                .query_row(
+                    "SELECT 1 FROM apps WHERE name = ?1",
+                    [from_name],
+                    |_| Ok(()),
+                )
+                .optional()
+                .map(|found| found.is_some());
+        }
+
+        let merged = with_busy_retry(|| {
+            let tx = self.conn.transaction()?;
+            let from_id: Option<i64> = tx
+                .query_row("SELECT id FROM apps WHERE name = ?1", [from_name], |row| {
+                    row.get(0)
+                })
+                .optional()?;
+            let Some(from_id) = from_id else {
+                return Ok(false);
+            };
+
+            tx.execute(
+                "INSERT OR IGNORE INTO apps (name, path) VALUES (?1, '')",
+                [to_name],
+            )?;
+            let to_id: i64 =
+                tx.query_row("SELECT id FROM apps WHERE name = ?1", [to_name], |row| {
+                    row.get(0)
+                })?;
+
+            tx.execute(
+                "UPDATE usage_sessions SET app_id = ?1 WHERE app_id = ?2",
+                [to_id, from_id],
+            )?;
+            tx.execute(
+                "UPDATE OR IGNORE app_limits SET app_id = ?1 WHERE app_id = ?2",
+                [to_id, from_id],
+            )?;
+            tx.execute("DELETE FROM app_limits WHERE app_id = ?1", [from_id])?;
+            tx.execute(
+                "UPDATE apps SET
+                     category = COALESCE(apps.category, src.category),
+                     icon_path = COALESCE(apps.icon_path, src.icon_path),
+                     custom_icon = COALESCE(apps.custom_icon, src.custom_icon),
+                     pinned = MAX(COALESCE(apps.pinned, 0), COALESCE(src.pinned, 0)),
+                     excluded_from_cap = MAX(
+                         COALESCE(apps.excluded_from_cap, 0),
+                         COALESCE(src.excluded_from_cap, 0)
+                     )
+                 FROM (SELECT * FROM apps WHERE id = ?2) AS src
+                 WHERE apps.id = ?1",
+                [to_id, from_id],
+            )?;
+            tx.execute(
+                "UPDATE limit_bonuses SET app_name = ?1 WHERE app_name = ?2",
+                [to_name, from_name],
+            )?;
+            tx.execute(
+                "UPDATE OR IGNORE app_group_members SET app_name = ?1 WHERE app_name = ?2",
+                [to_name, from_name],
+            )?;
+            tx.execute(
+                "DELETE FROM app_group_members WHERE app_name = ?1",
+                [from_name],
+            )?;
+            tx.execute(
+                "UPDATE OR IGNORE excluded_apps SET name = ?1 WHERE name = ?2",
+                [to_name, from_name],
+            )?;
+            tx.execute("DELETE FROM excluded_apps WHERE name = ?1", [from_name])?;
+            tx.execute("DELETE FROM apps WHERE id = ?1", [from_id])?;
+            tx.commit()?;
+            Ok(true)
+        })?;
+
+        if merged {
+            self.invalidate_daily_summary();
+        }
+        Ok(merged)
+    }
+
+    /// Pin or unpin an app on the dashboard. Returns false if the app isn't tracked.
+    pub fn set_app_pinned(&self, app_name: &str, pinned: bool) -> SqliteResult<bool> {
+        let updated = self.execute_write(
+            "UPDATE apps SET pinned = ?1 WHERE name = ?2",
+            rusqlite::params![pinned as i32, app_name],
+        )?;
+        self.invalidate_daily_summary();
+        Ok(updated > 0)
+    }
+
+    /// Set or clear (`None`) the user's icon for an app.
+    /// Returns false if the app doesn't exist.
+    pub fn set_app_icon(&self, app_name: &str, icon: Option<&str>) -> SqliteResult<bool> {
+        let updated = self.execute_write(
+            "UPDATE apps SET custom_icon = ?1 WHERE name = ?2",
+            rusqlite::params![icon, app_name],
+        )?;
+        self.invalidate_daily_summary();
+        Ok(updated > 0)
+    }
+
+    pub fn set_app_category(&self, app_name: &str, category: &str) -> SqliteResult<()> {
+        self.execute_write(
+            "UPDATE apps SET category = ?1 WHERE name = ?2",
+            rusqlite::params![category, app_name],
+        )?;
+        self.invalidate_daily_summary();
+        Ok(())
+    }
+
+    /// Apply many category assignments in one transaction, creating apps
+    /// that haven't been tracked yet
+    pub fn set_app_categories(&mut self, assignments: &[(String, String)]) -> SqliteResult<()> {
+        with_busy_retry(|| {
+            let tx = self.conn.transaction()?;
+            for (app_name, category) in assignments {
+                tx.execute(
+                    "INSERT OR IGNORE INTO apps (name, path) VALUES (?1, '')",
+                    rusqlite::params![app_name],
+                )?;
+                tx.execute(
+                    "UPDATE apps SET category = ?1 WHERE name = ?2",
+                    rusqlite::params![category, app_name],
+                )?;
+            }
+            tx.commit()
+        })?;
+        self.invalidate_daily_summary();
+        Ok(())
+    }
+
+    pub fn get_hourly_usage(&self) -> SqliteResult<Vec<HourlyUsage>> {
+        self.get_hourly_usage_for(chrono::Local::now().date_naive())
+    }
+
+    /// Usage per local hour of a single local calendar day, for hours with usage
+    pub fn get_hourly_usage_for(&self, date: chrono::NaiveDate) -> SqliteResult<Vec<HourlyUsage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT CAST(strftime('%H', start_time, 'unixepoch', 'localtime') AS INTEGER) as hour, 
+                    SUM(
+                        CASE WHEN duration_seconds = 0 AND end_time = start_time
+                             THEN MAX(strftime('%s','now') - start_time, 0)
+                             ELSE duration_seconds
+                        END
+                    ) as total
+             FROM usage_sessions
+             WHERE date(start_time, 'unixepoch', 'localtime') = ?1
+             GROUP BY hour
+             ORDER BY hour ASC",
+        )?;
+
+        let rows = stmt.query_map([date.format("%Y-%m-%d").to_string()], |row| {
+            Ok(HourlyUsage {
+                hour: row.get(0)?,
+                total_seconds: row.get(1)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    pub fn get_category_usage(&self) -> SqliteResult<Vec<CategoryUsage>> {
+        self.get_category_usage_for(chrono::Local::now().date_naive())
+    }
+
+    /// Per-category usage for a single local calendar day
+    pub fn get_category_usage_for(
+        &self,
+        date: chrono::NaiveDate,
+    ) -> SqliteResult<Vec<CategoryUsage>> {
         let mut stmt = self.conn.prepare(
             "SELECT COALESCE(a.category, 'Uncategorized') as category, 
                     SUM(
@@ -463,12 +1212,12 @@ impl Database {
                     COUNT(DISTINCT a.id) as app_count
              FROM usage_sessions us
              JOIN apps a ON us.app_id = a.id
-             WHERE date(us.start_time, 'unixepoch', 'localtime') = date('now', 'localtime')
+             WHERE date(us.start_time, 'unixepoch', 'localtime') = ?1
              GROUP BY COALESCE(a.category, 'Uncategorized')
              ORDER BY total DESC",
         )?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map([date.format("%Y-%m-%d").to_string()], |row| {
             Ok(CategoryUsage {
                 category: row.get(0)?,
                 total_seconds: row.get(1)?,
@@ -483,295 +1232,3203 @@ impl Database {
         Ok(result)
     }
 
-    pub fn is_app_blocked(&self, app_name: &str) -> SqliteResult<bool> {
-        // Check if app has a limit with blocking enabled and usage exceeded
-        // Use dynamic duration for in-progress sessions
-        let result: Option<(i32, i64)> = self
-            .conn
+    /// Local hour of day (0-23) with the most usage, and its total seconds.
+    /// Covers sessions started within `range` of timestamps, or all history.
+    pub fn get_peak_hour(&self, range: Option<(i64, i64)>) -> SqliteResult<Option<(u32, i64)>> {
+        self.get_peak_bucket("%H", range)
+    }
+
+    /// Local weekday (0 = Sunday .. 6 = Saturday) with the most usage, and its
+    /// total seconds. Covers sessions started within `range`, or all history.
+    pub fn get_peak_weekday(&self, range: Option<(i64, i64)>) -> SqliteResult<Option<(u32, i64)>> {
+        self.get_peak_bucket("%w", range)
+    }
+
+    /// The `strftime(format)` bucket of session start times with the most usage
+    fn get_peak_bucket(
+        &self,
+        format: &str,
+        range: Option<(i64, i64)>,
+    ) -> SqliteResult<Option<(u32, i64)>> {
+        let (start, end) = range.unwrap_or((i64::MIN, i64::MAX));
+        self.conn
             .query_row(
-                "SELECT al.daily_limit_minutes, COALESCE(SUM(
-                    CASE WHEN us.duration_seconds = 0 AND us.end_time = us.start_time
-                         THEN MAX(strftime('%s','now') - us.start_time, 0)
-                         ELSE us.duration_seconds
-                    END
-                 ), 0)
-             FROM apps a
-             JOIN app_limits al ON a.id = al.app_id AND al.block_when_exceeded = 1
-             LEFT JOIN usage_sessions us ON a.id = us.app_id 
-                AND date(us.start_time, 'unixepoch', 'localtime') = date('now', 'localtime')
-             WHERE a.name = ?1
-             GROUP BY a.id",
-                rusqlite::params![app_name],
+                "SELECT CAST(strftime(?1, start_time, 'unixepoch', 'localtime') AS INTEGER) as bucket,
+                        SUM(duration_seconds) as total
+                 FROM usage_sessions
+                 WHERE start_time >= ?2 AND start_time <= ?3
+                 GROUP BY bucket
+                 HAVING total > 0
+                 ORDER BY total DESC, bucket ASC
+                 LIMIT 1",
+                rusqlite::params![format, start, end],
                 |row| Ok((row.get(0)?, row.get(1)?)),
             )
-            .optional()?;
-
-        if let Some((limit_minutes, used_seconds)) = result {
-            let limit_seconds = (limit_minutes as i64) * 60;
-            return Ok(used_seconds >= limit_seconds);
-        }
-
-        Ok(false)
+            .optional()
     }
 
-    pub fn get_all_apps(&self) -> SqliteResult<Vec<App>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, name, path, icon_path, category, COALESCE(is_blocked, 0), created_at FROM apps"
-        )?;
-
-        let rows = stmt.query_map([], |row| {
-            Ok(App {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                path: row.get(2)?,
-                icon_path: row.get(3)?,
-                category: row.get(4)?,
-                is_blocked: row.get::<_, i32>(5)? != 0,
-                created_at: row.get(6)?,
-            })
-        })?;
-
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
-        }
-        Ok(result)
+    /// The app with the most usage across all recorded sessions
+    pub fn get_all_time_top_app(&self) -> SqliteResult<Option<(String, i64)>> {
+        self.conn
+            .query_row(
+                "SELECT a.name, SUM(us.duration_seconds) as total
+                 FROM usage_sessions us
+                 JOIN apps a ON us.app_id = a.id
+                 GROUP BY a.id
+                 ORDER BY total DESC, a.name ASC
+                 LIMIT 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
     }
 
-    /// Get limit status for all apps with limits set
-    /// Returns: (app_name, limit_minutes, used_seconds, block_when_exceeded)
-    pub fn get_all_limit_status(&self) -> SqliteResult<Vec<(String, i32, i64, bool)>> {
+    /// The most-used app of each category across all recorded sessions, as
+    /// (category, app, seconds), biggest first. Apps without a category count
+    /// as "Uncategorized".
+    pub fn get_top_app_per_category(&self) -> SqliteResult<Vec<(String, String, i64)>> {
         let mut stmt = self.conn.prepare(
-            "SELECT a.name, al.daily_limit_minutes,
-                    COALESCE(SUM(
-                        CASE WHEN us.duration_seconds = 0 AND us.end_time = us.start_time
-                             THEN MAX(strftime('%s','now') - us.start_time, 0)
-                             ELSE us.duration_seconds
-                        END
-                    ), 0),
-                    al.block_when_exceeded
-             FROM apps a
-             JOIN app_limits al ON a.id = al.app_id
-             LEFT JOIN usage_sessions us ON a.id = us.app_id 
-                AND date(us.start_time, 'unixepoch', 'localtime') = date('now', 'localtime')
-             GROUP BY a.id",
+            "SELECT COALESCE(a.category, 'Uncategorized') as category, a.name,
+                    SUM(us.duration_seconds) as total
+             FROM usage_sessions us
+             JOIN apps a ON us.app_id = a.id
+             GROUP BY a.id
+             ORDER BY total DESC, a.name ASC",
         )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
 
-        let rows = stmt.query_map([], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, i32>(1)?,
-                row.get::<_, i64>(2)?,
-                row.get::<_, i32>(3)? != 0,
-            ))
-        })?;
-
-        let mut result = Vec::new();
+        // Rows are sorted by usage, so the first app seen in a category is its top app
+        let mut champions: Vec<(String, String, i64)> = Vec::new();
         for row in rows {
-            result.push(row?);
+            let (category, app, seconds) = row?;
+            if !champions.iter().any(|(c, _, _)| *c == category) {
+                champions.push((category, app, seconds));
+            }
         }
-        Ok(result)
+        Ok(champions)
     }
 
-    /// Delete usage sessions older than the specified number of days.
-    /// Returns the number of deleted rows.
-    pub fn cleanup_old_data(&self, retention_days: i64) -> SqliteResult<usize> {
-        let cutoff = Utc::now().timestamp() - (retention_days * 24 * 60 * 60);
+    /// Today's per-app, per-category and total usage.
+    /// Served from a short-lived cache so dashboard commands that all need
+    /// today's numbers don't each re-aggregate the sessions table.
+    pub fn get_daily_summary(&self) -> SqliteResult<DailySummary> {
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
 
-        let deleted = self.conn.execute(
-            "DELETE FROM usage_sessions WHERE end_time < ?1",
-            rusqlite::params![cutoff],
-        )?;
+        if let Some(cached) = self.summary_cache.borrow().as_ref() {
+            if cached.summary.date == today && cached.computed_at.elapsed() < DAILY_SUMMARY_TTL {
+                return Ok(cached.summary.clone());
+            }
+        }
 
-        // Optionally vacuum to reclaim space (can be slow, so we skip it for now)
-        // self.conn.execute("VACUUM", [])?;
+        let apps = self.get_daily_usage()?;
+        let categories = self.get_category_usage()?;
+        let summary = DailySummary {
+            date: today,
+            total_seconds: apps.iter().map(|a| a.duration_seconds).sum(),
+            apps,
+            categories,
+        };
 
-        Ok(deleted)
+        *self.summary_cache.borrow_mut() = Some(CachedSummary {
+            summary: summary.clone(),
+            computed_at: Instant::now(),
+        });
+        Ok(summary)
     }
 
-    /// Get the count of usage sessions and approximate database size info
-    pub fn get_storage_stats(&self) -> SqliteResult<(i64, i64, Option<String>)> {
-        let session_count: i64 =
-            self.conn
-                .query_row("SELECT COUNT(*) FROM usage_sessions", [], |row| row.get(0))?;
-
-        let oldest_timestamp: Option<i64> = self
-            .conn
-            .query_row("SELECT MIN(start_time) FROM usage_sessions", [], |row| {
-                row.get(0)
-            })
-            .optional()?
-            .flatten();
+    /// Drop the cached daily summary so the next read recomputes it
+    pub fn invalidate_daily_summary(&self) {
+        self.summary_cache.borrow_mut().take();
+    }
 
-        let oldest_date = oldest_timestamp.map(|ts| {
-            chrono::DateTime::from_timestamp(ts, 0)
-                .map(|dt| dt.format("%Y-%m-%d").to_string())
-                .unwrap_or_else(|| "Unknown".to_string())
-        });
+    /// Replace the members of a display group. An app belongs to at most one
+    /// group, so members are moved out of any group they were in before.
+    pub fn set_app_group(&mut self, group_name: &str, members: &[String]) -> SqliteResult<()> {
+        with_busy_retry(|| {
+            let tx = self.conn.transaction()?;
+            tx.execute(
+                "DELETE FROM app_group_members WHERE group_name = ?1",
+                rusqlite::params![group_name],
+            )?;
+            for member in members {
+                tx.execute(
+                    "INSERT OR REPLACE INTO app_group_members (app_name, group_name) VALUES (?1, ?2)",
+                    rusqlite::params![member, group_name],
+                )?;
+            }
+            tx.commit()
+        })
+    }
 
-        Ok((session_count, oldest_timestamp.unwrap_or(0), oldest_date))
+    pub fn remove_app_group(&self, group_name: &str) -> SqliteResult<()> {
+        self.execute_write(
+            "DELETE FROM app_group_members WHERE group_name = ?1",
+            rusqlite::params![group_name],
+        )?;
+        Ok(())
     }
 
-    /// Export usage data within a date range
-    /// Returns: Vec of (date, app_name, category, duration_seconds, session_count)
-    pub fn export_usage_data(
-        &self,
-        start_timestamp: i64,
-        end_timestamp: i64,
-    ) -> SqliteResult<Vec<ExportRecord>> {
+    pub fn get_app_groups(&self) -> SqliteResult<Vec<AppGroup>> {
         let mut stmt = self.conn.prepare(
-            "SELECT 
-                date(us.start_time, 'unixepoch', 'localtime') as date,
-                a.name as app_name,
-                COALESCE(a.category, 'Uncategorized') as category,
-                SUM(us.duration_seconds) as total_seconds,
-                COUNT(us.id) as session_count
-             FROM usage_sessions us
-             JOIN apps a ON us.app_id = a.id
-             WHERE us.start_time >= ?1 AND us.start_time <= ?2
-             GROUP BY date, a.id
-             ORDER BY date DESC, total_seconds DESC",
+            "SELECT group_name, app_name FROM app_group_members ORDER BY group_name, app_name",
         )?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))?;
 
-        let rows = stmt.query_map(rusqlite::params![start_timestamp, end_timestamp], |row| {
-            Ok(ExportRecord {
-                date: row.get(0)?,
-                app_name: row.get(1)?,
-                category: row.get(2)?,
-                duration_seconds: row.get(3)?,
-                session_count: row.get(4)?,
-            })
-        })?;
-
-        let mut result = Vec::new();
+        let mut groups: Vec<AppGroup> = Vec::new();
         for row in rows {
-            result.push(row?);
+            let (group_name, app_name) = row?;
+            match groups.last_mut() {
+                Some(group) if group.name == group_name => group.members.push(app_name),
+                _ => groups.push(AppGroup {
+                    name: group_name,
+                    members: vec![app_name],
+                }),
+            }
         }
-        Ok(result)
+        Ok(groups)
     }
 
-    /// Get daily totals within a date range for historical analysis
-    /// Returns: Vec of (date_string, total_seconds)
-    pub fn get_daily_totals_in_range(
+    /// Open an idle period; its end time stays NULL until it is ended
+    pub fn start_idle_period(&self, reason: IdleReason, start_time: i64) -> SqliteResult<i64> {
+        self.execute_write(
+            "INSERT INTO idle_periods (start_time, end_time, reason) VALUES (?1, NULL, ?2)",
+            rusqlite::params![start_time, reason.as_str()],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn end_idle_period(&self, id: i64, end_time: i64) -> SqliteResult<()> {
+        self.execute_write(
+            "UPDATE idle_periods SET end_time = MAX(?1, start_time)
+             WHERE id = ?2 AND end_time IS NULL",
+            rusqlite::params![end_time, id],
+        )?;
+        Ok(())
+    }
+
+    /// Close idle periods left open by an unclean shutdown. When they really
+    /// ended is unknown, so they are closed at their start rather than
+    /// counting the downtime as idle. Returns how many were closed.
+    pub fn close_open_idle_periods(&self) -> SqliteResult<usize> {
+        self.execute_write(
+            "UPDATE idle_periods SET end_time = start_time WHERE end_time IS NULL",
+            [],
+        )
+    }
+
+    pub fn record_idle_period(
         &self,
-        start_timestamp: i64,
-        end_timestamp: i64,
-    ) -> SqliteResult<Vec<(String, i64)>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT 
-                date(us.start_time, 'unixepoch', 'localtime') as date,
-                SUM(us.duration_seconds) as total_seconds
-             FROM usage_sessions us
-             WHERE us.start_time >= ?1 AND us.start_time <= ?2
-             GROUP BY date
-             ORDER BY date ASC",
+        reason: IdleReason,
+        start_time: i64,
+        end_time: i64,
+    ) -> SqliteResult<i64> {
+        self.execute_write(
+            "INSERT INTO idle_periods (start_time, end_time, reason) VALUES (?1, ?2, ?3)",
+            rusqlite::params![start_time, end_time, reason.as_str()],
         )?;
+        Ok(self.conn.last_insert_rowid())
+    }
 
-        let rows = stmt.query_map(rusqlite::params![start_timestamp, end_timestamp], |row| {
-            Ok((row.get(0)?, row.get(1)?))
+    /// Tracked usage vs idle time for a local calendar day, with idle time
+    /// broken down by reason
+    pub fn get_idle_ratio_for(&self, date: chrono::NaiveDate) -> SqliteResult<IdleRatio> {
+        let date_str = date.format("%Y-%m-%d").to_string();
+        let active_seconds: i64 = self
+            .get_daily_usage_for(date)?
+            .iter()
+            .map(|a| a.duration_seconds)
+            .sum();
+
+        let mut stmt = self.conn.prepare(
+            "SELECT reason,
+                    SUM(COALESCE(end_time, MAX(CAST(strftime('%s','now') AS INTEGER), start_time))
+                        - start_time) as total
+             FROM idle_periods
+             WHERE date(start_time, 'unixepoch', 'localtime') = ?1
+             GROUP BY reason
+             ORDER BY total DESC",
+        )?;
+        let rows = stmt.query_map([&date_str], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
         })?;
 
-        let mut result = Vec::new();
+        let mut by_reason = Vec::new();
         for row in rows {
-            result.push(row?);
+            let (reason, seconds) = row?;
+            match IdleReason::parse(&reason) {
+                Some(reason) => by_reason.push(IdleReasonTotal { reason, seconds }),
+                None => tracing::warn!(reason = %reason, "Skipping unknown idle reason"),
+            }
         }
-        Ok(result)
+
+        let idle_seconds: i64 = by_reason.iter().map(|r| r.seconds).sum();
+        let tracked = active_seconds + idle_seconds;
+        let idle_ratio = if tracked > 0 {
+            idle_seconds as f64 / tracked as f64
+        } else {
+            0.0
+        };
+
+        Ok(IdleRatio {
+            date: date_str,
+            active_seconds,
+            idle_seconds,
+            idle_ratio,
+            by_reason,
+        })
     }
 
-    /// Get app usage breakdown within a date range for historical analysis
-    /// Returns: Vec of AppUsage with totals for the entire range
-    pub fn get_app_usage_in_range(
-        &self,
-        start_timestamp: i64,
-        end_timestamp: i64,
-    ) -> SqliteResult<Vec<AppUsage>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT 
-                a.name,
-                COALESCE(SUM(us.duration_seconds), 0) as total_seconds,
-                COUNT(us.id) as session_count,
-                a.category
-             FROM usage_sessions us
-             JOIN apps a ON us.app_id = a.id
-             WHERE us.start_time >= ?1 AND us.start_time <= ?2
-             GROUP BY a.id
-             ORDER BY total_seconds DESC",
+    /// Count a break started on `date`
+    pub fn record_break(&self, date: chrono::NaiveDate) -> SqliteResult<()> {
+        self.execute_write(
+            "INSERT INTO breaks_taken (date, count) VALUES (?1, 1)
+             ON CONFLICT(date) DO UPDATE SET count = count + 1",
+            [date.format("%Y-%m-%d").to_string()],
         )?;
+        Ok(())
+    }
 
-        let rows = stmt.query_map(rusqlite::params![start_timestamp, end_timestamp], |row| {
-            Ok(AppUsage {
+    /// Number of breaks started on `date`
+    pub fn breaks_taken_on(&self, date: chrono::NaiveDate) -> SqliteResult<u32> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT count FROM breaks_taken WHERE date = ?1",
+                [date.format("%Y-%m-%d").to_string()],
+                |row| row.get(0),
+            )
+            .optional()?
+            .unwrap_or(0))
+    }
+
+    pub fn record_focus_session(&self, entry: &FocusHistoryEntry) -> SqliteResult<i64> {
+        self.execute_write(
+            "INSERT INTO focus_sessions (start_time, end_time, planned_minutes, completed, schedule_name)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                entry.start_time,
+                entry.end_time,
+                entry.planned_minutes,
+                entry.completed as i32,
+                entry.schedule_name
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Grant the focus reward bonus for `date` to the reward's apps and every
+    /// app in its categories. Returns the apps that received it.
+    pub fn grant_focus_reward(
+        &mut self,
+        reward: &FocusReward,
+        date: &str,
+    ) -> SqliteResult<Vec<String>> {
+        let mut targets: Vec<String> = reward.apps.clone();
+        for category in &reward.categories {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT name FROM apps WHERE category = ?1")?;
+            let names = stmt.query_map(rusqlite::params![category], |row| row.get(0))?;
+            for name in names {
+                targets.push(name?);
+            }
+        }
+        targets.sort();
+        targets.dedup();
+
+        let now = Utc::now().timestamp();
+        with_busy_retry(|| {
+            let tx = self.conn.transaction()?;
+            for app_name in &targets {
+                tx.execute(
+                    "INSERT INTO limit_bonuses (app_name, date, minutes, reason, granted_at)
+                     VALUES (?1, ?2, ?3, 'focus_reward', ?4)",
+                    rusqlite::params![app_name, date, reward.bonus_minutes, now],
+                )?;
+            }
+            tx.commit()
+        })?;
+        Ok(targets)
+    }
+
+    /// Bonuses granted for a local date (YYYY-MM-DD), oldest first
+    pub fn get_limit_bonuses(&self, date: &str) -> SqliteResult<Vec<LimitBonus>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT app_name, date, minutes, reason, granted_at
+             FROM limit_bonuses
+             WHERE date = ?1
+             ORDER BY granted_at ASC, id ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![date], |row| {
+            Ok(LimitBonus {
                 app_name: row.get(0)?,
-                duration_seconds: row.get(1)?,
-                session_count: row.get(2)?,
-                category: row.get(3)?,
+                date: row.get(1)?,
+                minutes: row.get(2)?,
+                reason: row.get(3)?,
+                granted_at: row.get(4)?,
             })
         })?;
+        rows.collect()
+    }
 
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
-        }
-        Ok(result)
+    /// All recorded focus sessions, oldest first
+    pub fn get_focus_history(&self) -> SqliteResult<Vec<FocusHistoryEntry>> {
+        self.get_focus_history_in_range(i64::MIN, i64::MAX)
     }
 
-    /// Get category usage within a date range for historical analysis
-    pub fn get_category_usage_in_range(
+    /// Focus sessions started between two timestamps, oldest first
+    pub fn get_focus_history_in_range(
         &self,
         start_timestamp: i64,
         end_timestamp: i64,
-    ) -> SqliteResult<Vec<CategoryUsage>> {
+    ) -> SqliteResult<Vec<FocusHistoryEntry>> {
         let mut stmt = self.conn.prepare(
-            "SELECT 
-                COALESCE(a.category, 'Uncategorized') as category,
-                SUM(us.duration_seconds) as total_seconds,
-                COUNT(DISTINCT a.id) as app_count
-             FROM usage_sessions us
-             JOIN apps a ON us.app_id = a.id
-             WHERE us.start_time >= ?1 AND us.start_time <= ?2
-             GROUP BY COALESCE(a.category, 'Uncategorized')
-             ORDER BY total_seconds DESC",
+            "SELECT start_time, end_time, planned_minutes, completed, schedule_name
+             FROM focus_sessions
+             WHERE start_time >= ?1 AND start_time <= ?2
+             ORDER BY start_time ASC",
         )?;
+        let rows = stmt.query_map([start_timestamp, end_timestamp], |row| {
+            Ok(FocusHistoryEntry {
+                start_time: row.get(0)?,
+                end_time: row.get(1)?,
+                planned_minutes: row.get(2)?,
+                completed: row.get::<_, i32>(3)? != 0,
+                schedule_name: row.get(4)?,
+            })
+        })?;
+        rows.collect()
+    }
 
-        let rows = stmt.query_map(rusqlite::params![start_timestamp, end_timestamp], |row| {
-            Ok(CategoryUsage {
-                category: row.get(0)?,
-                total_seconds: row.get(1)?,
-                app_count: row.get(2)?,
+    /// Record an emergency access grant so it survives a restart
+    pub fn record_emergency_grant(&self, grant: &EmergencyGrant) -> SqliteResult<()> {
+        self.execute_write(
+            "INSERT INTO emergency_grants (app_name, granted_at, expires_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![grant.app_name, grant.granted_at, grant.expires_at],
+        )?;
+        Ok(())
+    }
+
+    /// Delete emergency grants that expired before `before`. Returns how many
+    /// were deleted.
+    pub fn delete_emergency_grants_expired_before(&self, before: i64) -> SqliteResult<usize> {
+        self.execute_write(
+            "DELETE FROM emergency_grants WHERE expires_at < ?1",
+            [before],
+        )
+    }
+
+    /// Emergency grants given at or after `since`, oldest first
+    pub fn get_emergency_grants_since(&self, since: i64) -> SqliteResult<Vec<EmergencyGrant>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT app_name, granted_at, expires_at
+             FROM emergency_grants
+             WHERE granted_at >= ?1
+             ORDER BY granted_at ASC",
+        )?;
+        let rows = stmt.query_map([since], |row| {
+            Ok(EmergencyGrant {
+                app_name: row.get(0)?,
+                granted_at: row.get(1)?,
+                expires_at: row.get(2)?,
             })
         })?;
+        rows.collect()
+    }
 
-        let mut result = Vec::new();
+    /// Save goals, achievement progress and streaks, replacing what was stored
+    pub fn save_goals_state(&mut self, state: &GoalsState) -> SqliteResult<()> {
+        let goals = state
+            .goals
+            .iter()
+            .map(|goal| Ok((goal.id.clone(), to_json(goal)?)))
+            .collect::<SqliteResult<Vec<_>>>()?;
+        let week_start = to_json(&state.week_start)?;
+        let weekly_focus_goal = to_json(&state.weekly_focus_goal)?;
+        let challenge_results = to_json(&state.challenge_results)?;
+        let completed_challenges = to_json(&state.completed_challenges)?;
+
+        with_busy_retry(|| {
+            let tx = self.conn.transaction()?;
+            // Upsert in place so unchanged rows aren't rewritten, then drop
+            // goals that were deleted
+            let saved_ids = tx
+                .prepare("SELECT id FROM goals")?
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<SqliteResult<Vec<_>>>()?;
+            for id in saved_ids {
+                if !goals.iter().any(|(goal_id, _)| *goal_id == id) {
+                    tx.execute("DELETE FROM goals WHERE id = ?1", [&id])?;
+                }
+            }
+            for (position, (id, data)) in goals.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO goals (id, position, data) VALUES (?1, ?2, ?3)
+                     ON CONFLICT(id) DO UPDATE SET position = excluded.position, data = excluded.data
+                     WHERE position != excluded.position OR data != excluded.data",
+                    rusqlite::params![id, position as i64, data],
+                )?;
+            }
+            for achievement in state.achievements.values() {
+                tx.execute(
+                    "INSERT INTO achievements (id, progress, earned_at) VALUES (?1, ?2, ?3)
+                     ON CONFLICT(id) DO UPDATE SET progress = excluded.progress,
+                        earned_at = excluded.earned_at
+                     WHERE progress IS NOT excluded.progress OR earned_at IS NOT excluded.earned_at",
+                    rusqlite::params![achievement.id, achievement.progress, achievement.earned_at],
+                )?;
+            }
+            tx.execute(
+                "INSERT OR REPLACE INTO goals_state (id, current_streak, longest_streak,
+                    total_goals_met, focus_sessions_completed, weekends_count, week_start,
+                    weekly_focus_goal, challenge_results, completed_challenges)
+                 VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![
+                    state.current_streak,
+                    state.longest_streak,
+                    state.total_goals_met,
+                    state.focus_sessions_completed,
+                    state.weekends_count as i32,
+                    week_start,
+                    weekly_focus_goal,
+                    challenge_results,
+                    completed_challenges
+                ],
+            )?;
+            tx.commit()
+        })
+    }
+
+    /// Load the saved goals state, or a fresh one if nothing was saved.
+    /// Achievements are matched against the current list, so ones added in a
+    /// newer version start unearned and removed ones are dropped.
+    pub fn load_goals_state(&self) -> SqliteResult<GoalsState> {
+        let mut state = GoalsState::new();
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, data FROM goals ORDER BY position")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
         for row in rows {
-            result.push(row?);
+            let (id, data) = row?;
+            match serde_json::from_str::<Goal>(&data) {
+                Ok(goal) => state.goals.push(goal),
+                Err(e) => tracing::warn!(goal = %id, error = %e, "Skipping unreadable saved goal"),
+            }
         }
-        Ok(result)
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, progress, earned_at FROM achievements")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i32>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (id, progress, earned_at) = row?;
+            if let Some(achievement) = state.achievements.get_mut(&id) {
+                achievement.progress = progress;
+                achievement.earned_at = earned_at;
+            }
+        }
+
+        let saved = self
+            .conn
+            .query_row(
+                "SELECT current_streak, longest_streak, total_goals_met, focus_sessions_completed,
+                        weekends_count, week_start, weekly_focus_goal, challenge_results,
+                        completed_challenges
+                 FROM goals_state WHERE id = 1",
+                [],
+                |row| {
+                    Ok((
+                        (
+                            row.get::<_, i32>(0)?,
+                            row.get::<_, i32>(1)?,
+                            row.get::<_, i32>(2)?,
+                            row.get::<_, i32>(3)?,
+                            row.get::<_, i32>(4)? != 0,
+                        ),
+                        (
+                            row.get::<_, Option<String>>(5)?,
+                            row.get::<_, Option<String>>(6)?,
+                            row.get::<_, Option<String>>(7)?,
+                            row.get::<_, Option<String>>(8)?,
+                        ),
+                    ))
+                },
+            )
+            .optional()?;
+
+        if let Some((counters, json)) = saved {
+            (
+                state.current_streak,
+                state.longest_streak,
+                state.total_goals_met,
+                state.focus_sessions_completed,
+                state.weekends_count,
+            ) = counters;
+            let (week_start, weekly_focus_goal, challenge_results, completed_challenges) = json;
+            if let Some(value) = from_json(week_start, "week_start") {
+                state.week_start = value;
+            }
+            if let Some(value) = from_json(weekly_focus_goal, "weekly_focus_goal") {
+                state.weekly_focus_goal = value;
+            }
+            if let Some(value) = from_json(challenge_results, "challenge_results") {
+                state.challenge_results = value;
+            }
+            if let Some(value) = from_json(completed_challenges, "completed_challenges") {
+                state.completed_challenges = value;
+            }
+        }
+
+        Ok(state)
     }
 
-    /// Get all blocked apps in a single query (fixes N+1 query problem)
-    /// Returns app names where block_when_exceeded is true AND usage exceeds limit
-    pub fn get_blocked_apps(&self) -> SqliteResult<Vec<String>> {
+    /// First local date with any recorded usage or focus session
+    fn earliest_activity_date(&self) -> SqliteResult<Option<chrono::NaiveDate>> {
+        let first: Option<String> = self.conn.query_row(
+            "SELECT date(MIN(start_time), 'unixepoch', 'localtime') FROM (
+                SELECT MIN(start_time) as start_time FROM usage_sessions
+                UNION ALL
+                SELECT MIN(start_time) FROM focus_sessions
+             )",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(first.and_then(|date| chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()))
+    }
+
+    /// Streaks of days for which `qualifies` holds, from the first day with any
+    /// recorded activity up to `today`. Today is still in progress, so when it
+    /// doesn't qualify (yet) the current streak is the one ending yesterday.
+    pub fn compute_daily_streak(
+        &self,
+        today: chrono::NaiveDate,
+        mut qualifies: impl FnMut(chrono::NaiveDate) -> bool,
+    ) -> SqliteResult<DailyStreak> {
+        let Some(first) = self.earliest_activity_date()? else {
+            return Ok(DailyStreak::default());
+        };
+
+        let mut run = 0;
+        let mut longest = 0;
+        for day in first.iter_days().take_while(|day| *day < today) {
+            run = if qualifies(day) { run + 1 } else { 0 };
+            longest = longest.max(run);
+        }
+        if qualifies(today) {
+            run += 1;
+            longest = longest.max(run);
+        }
+
+        Ok(DailyStreak {
+            current: run,
+            longest,
+        })
+    }
+
+    /// Usage counted toward the global cap per local date (YYYY-MM-DD)
+    pub fn get_cap_usage_by_day(&self) -> SqliteResult<HashMap<String, i64>> {
         let mut stmt = self.conn.prepare(
-            "SELECT a.name
+            "SELECT date(us.start_time, 'unixepoch', 'localtime') as day,
+                    SUM(
+                        CASE WHEN us.duration_seconds = 0 AND us.end_time = us.start_time
+                             THEN MAX(strftime('%s','now') - us.start_time, 0)
+                             ELSE us.duration_seconds
+                        END
+                    )
+             FROM usage_sessions us
+             JOIN apps a ON a.id = us.app_id
+             WHERE COALESCE(a.excluded_from_cap, 0) = 0
+             GROUP BY day",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    pub fn is_app_blocked(&self, app_name: &str) -> SqliteResult<bool> {
+        self.is_app_blocked_on(app_name, chrono::Local::now().weekday())
+    }
+
+    /// `is_app_blocked`, applying the limit for `weekday`
+    pub fn is_app_blocked_on(
+        &self,
+        app_name: &str,
+        weekday: chrono::Weekday,
+    ) -> SqliteResult<bool> {
+        // Check if app has a limit with blocking enabled and usage exceeded
+        // Use dynamic duration for in-progress sessions
+        let result: Option<(i32, i64)> = self
+            .conn
+            .query_row(
+                "SELECT CASE WHEN ?2 THEN COALESCE(al.weekend_limit_minutes, al.daily_limit_minutes)
+                             ELSE al.daily_limit_minutes END
+                    + COALESCE(tb.minutes, 0),
+                    COALESCE(SUM(
+                    CASE WHEN us.duration_seconds = 0 AND us.end_time = us.start_time
+                         THEN MAX(strftime('%s','now') - us.start_time, 0)
+                         ELSE us.duration_seconds
+                    END
+                 ), 0)
              FROM apps a
-             JOIN app_limits al ON a.id = al.app_id
+             JOIN app_limits al ON a.id = al.app_id AND al.block_when_exceeded = 1
+             LEFT JOIN todays_limit_bonuses tb ON tb.app_name = a.name
              LEFT JOIN usage_sessions us ON a.id = us.app_id 
                 AND date(us.start_time, 'unixepoch', 'localtime') = date('now', 'localtime')
-             WHERE al.block_when_exceeded = 1
-             GROUP BY a.id
-             HAVING COALESCE(SUM(
-                 CASE WHEN us.duration_seconds = 0 AND us.end_time = us.start_time
-                      THEN MAX(strftime('%s','now') - us.start_time, 0)
-                      ELSE us.duration_seconds
-                 END
-             ), 0) >= (al.daily_limit_minutes * 60)",
+             WHERE a.name = ?1
+             GROUP BY a.id",
+                rusqlite::params![app_name, is_weekend_day(weekday)],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        if let Some((limit_minutes, used_seconds)) = result {
+            let limit_seconds = (limit_minutes as i64) * 60;
+            if used_seconds >= limit_seconds {
+                return Ok(true);
+            }
+        }
+
+        // Blocked if the app's category is over a blocking category limit
+        if self.is_category_blocked(app_name)? {
+            return Ok(true);
+        }
+
+        // Otherwise blocked if the global cap is exceeded and the app counts toward it
+        if self.is_cap_excluded(app_name)? {
+            return Ok(false);
+        }
+        Ok(self
+            .get_global_cap_status()?
+            .is_some_and(|cap| cap.block_when_exceeded && cap.exceeded))
+    }
+
+    /// Add a category or update its color and productivity flag
+    pub fn upsert_category(&self, category: &Category) -> SqliteResult<()> {
+        self.execute_write(
+            "INSERT INTO categories (name, color, is_productive) VALUES (?1, ?2, ?3)
+             ON CONFLICT(name) DO UPDATE SET color = excluded.color,
+                                             is_productive = excluded.is_productive",
+            rusqlite::params![category.name, category.color, category.is_productive as i32],
         )?;
+        Ok(())
+    }
 
-        let rows = stmt.query_map([], |row| row.get(0))?;
+    pub fn get_categories(&self) -> SqliteResult<Vec<Category>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name, color, is_productive FROM categories ORDER BY name")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Category {
+                name: row.get(0)?,
+                color: row.get(1)?,
+                is_productive: row.get::<_, i32>(2)? != 0,
+            })
+        })?;
+        rows.collect()
+    }
 
-        let mut result = Vec::new();
-        for row in rows {
-            result.push(row?);
+    /// Delete a category and its limit, moving its apps to Uncategorized.
+    /// Returns false if the category doesn't exist.
+    pub fn delete_category(&mut self, name: &str) -> SqliteResult<bool> {
+        let deleted = with_busy_retry(|| {
+            let tx = self.conn.transaction()?;
+            let deleted = tx.execute("DELETE FROM categories WHERE name = ?1", [name])?;
+            if deleted > 0 {
+                tx.execute(
+                    "UPDATE apps SET category = NULL WHERE category = ?1",
+                    [name],
+                )?;
+                tx.execute("DELETE FROM category_limits WHERE category = ?1", [name])?;
+            }
+            tx.commit()?;
+            Ok(deleted > 0)
+        })?;
+
+        if deleted {
+            self.invalidate_daily_summary();
         }
-        Ok(result)
+        Ok(deleted)
+    }
+
+    /// Set a daily limit shared by all apps in `category`
+    pub fn set_category_limit(
+        &self,
+        category: &str,
+        minutes: i32,
+        block_when_exceeded: bool,
+    ) -> SqliteResult<()> {
+        self.execute_write(
+            "INSERT OR REPLACE INTO category_limits (category, daily_limit_minutes, block_when_exceeded)
+             VALUES (?1, ?2, ?3)",
+            rusqlite::params![category, minutes, block_when_exceeded as i32],
+        )?;
+        Ok(())
+    }
+
+    pub fn remove_category_limit(&self, category: &str) -> SqliteResult<()> {
+        self.execute_write(
+            "DELETE FROM category_limits WHERE category = ?1",
+            [category],
+        )?;
+        Ok(())
+    }
+
+    /// Every category limit with today's usage summed across the category's
+    /// apps. Apps without a category count as "Uncategorized".
+    pub fn get_category_limit_status(&self) -> SqliteResult<Vec<CategoryLimitStatus>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT cl.category, cl.daily_limit_minutes, cl.block_when_exceeded,
+                    COALESCE((
+                        SELECT SUM(
+                            CASE WHEN us.duration_seconds = 0 AND us.end_time = us.start_time
+                                 THEN MAX(strftime('%s','now') - us.start_time, 0)
+                                 ELSE us.duration_seconds
+                            END
+                        )
+                        FROM usage_sessions us
+                        JOIN apps a ON us.app_id = a.id
+                        WHERE COALESCE(a.category, 'Uncategorized') = cl.category
+                          AND date(us.start_time, 'unixepoch', 'localtime') = date('now', 'localtime')
+                    ), 0)
+             FROM category_limits cl
+             ORDER BY cl.category",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let limit_minutes: i32 = row.get(1)?;
+            let used_seconds: i64 = row.get(3)?;
+            Ok(CategoryLimitStatus {
+                category: row.get(0)?,
+                limit_minutes,
+                block_when_exceeded: row.get::<_, i32>(2)? != 0,
+                used_seconds,
+                exceeded: used_seconds >= limit_minutes as i64 * 60,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Whether the app's category has a blocking limit that's been reached today
+    fn is_category_blocked(&self, app_name: &str) -> SqliteResult<bool> {
+        let category: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT COALESCE(category, 'Uncategorized') FROM apps WHERE name = ?1",
+                [app_name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(category) = category else {
+            return Ok(false);
+        };
+
+        Ok(self.get_category_limit_status()?.iter().any(|status| {
+            status.category == category && status.block_when_exceeded && status.exceeded
+        }))
+    }
+
+    /// Set the daily cap on total usage across all non-excluded apps
+    pub fn set_global_cap(&self, minutes: i32, block_when_exceeded: bool) -> SqliteResult<()> {
+        self.execute_write(
+            "INSERT OR REPLACE INTO global_limit (id, daily_limit_minutes, block_when_exceeded)
+             VALUES (1, ?1, ?2)",
+            rusqlite::params![minutes, block_when_exceeded as i32],
+        )?;
+        Ok(())
+    }
+
+    /// Read a value from the key/value table
+    pub fn get_meta(&self, key: &str) -> SqliteResult<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT value FROM app_meta WHERE key = ?1",
+                rusqlite::params![key],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    pub fn set_meta(&self, key: &str, value: &str) -> SqliteResult<()> {
+        self.execute_write(
+            "INSERT OR REPLACE INTO app_meta (key, value) VALUES (?1, ?2)",
+            rusqlite::params![key, value],
+        )?;
+        Ok(())
+    }
+
+    /// Limits, categories, category limits, app groups and the daily cap, for
+    /// a config export
+    pub fn get_config_definitions(&self) -> SqliteResult<ConfigDefinitions> {
+        let limits = self
+            .get_all_limits()?
+            .into_iter()
+            .map(|limit| LimitConfig {
+                app_name: limit.app_name,
+                daily_limit_minutes: limit.daily_limit_minutes,
+                weekend_limit_minutes: limit.weekend_limit_minutes,
+                block_when_exceeded: limit.block_when_exceeded,
+            })
+            .collect();
+
+        let mut stmt = self.conn.prepare(
+            "SELECT name, category FROM apps WHERE category IS NOT NULL AND category != ''
+             ORDER BY name COLLATE NOCASE",
+        )?;
+        let categories = stmt
+            .query_map([], |row| {
+                Ok(CategoryConfig {
+                    app_name: row.get(0)?,
+                    category: row.get(1)?,
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+
+        let category_limits = self
+            .get_category_limit_status()?
+            .into_iter()
+            .map(|status| CategoryLimitConfig {
+                category: status.category,
+                daily_limit_minutes: status.limit_minutes,
+                block_when_exceeded: status.block_when_exceeded,
+            })
+            .collect();
+
+        let global_cap = self.get_global_cap_status()?.map(|cap| GlobalCapConfig {
+            limit_minutes: cap.limit_minutes,
+            block_when_exceeded: cap.block_when_exceeded,
+            excluded_apps: cap.excluded_apps,
+        });
+
+        Ok(ConfigDefinitions {
+            limits: Some(limits),
+            categories: Some(categories),
+            category_limits: Some(category_limits),
+            app_groups: Some(self.get_app_groups()?),
+            global_cap,
+        })
+    }
+
+    /// Apply imported definitions in one transaction. Limits, categories,
+    /// category limits and groups are added or replaced by name; ones not in
+    /// the import are kept.
+    /// The daily cap and its exclusions are replaced outright.
+    pub fn apply_config_definitions(
+        &mut self,
+        definitions: &ConfigDefinitions,
+    ) -> SqliteResult<()> {
+        with_busy_retry(|| {
+            let tx = self.conn.transaction()?;
+            let ensure_app = |name: &str| {
+                tx.execute(
+                    "INSERT OR IGNORE INTO apps (name, path) VALUES (?1, '')",
+                    rusqlite::params![name],
+                )
+            };
+
+            for limit in definitions.limits.iter().flatten() {
+                ensure_app(&limit.app_name)?;
+                tx.execute(
+                    "INSERT OR REPLACE INTO app_limits
+                        (app_id, daily_limit_minutes, block_when_exceeded, weekend_limit_minutes)
+                     SELECT id, ?2, ?3, ?4 FROM apps WHERE name = ?1",
+                    rusqlite::params![
+                        limit.app_name,
+                        limit.daily_limit_minutes,
+                        limit.block_when_exceeded as i32,
+                        limit.weekend_limit_minutes
+                    ],
+                )?;
+            }
+
+            for assignment in definitions.categories.iter().flatten() {
+                ensure_app(&assignment.app_name)?;
+                tx.execute(
+                    "UPDATE apps SET category = ?1 WHERE name = ?2",
+                    rusqlite::params![assignment.category, assignment.app_name],
+                )?;
+            }
+
+            for limit in definitions.category_limits.iter().flatten() {
+                tx.execute(
+                    "INSERT OR REPLACE INTO category_limits (category, daily_limit_minutes, block_when_exceeded)
+                     VALUES (?1, ?2, ?3)",
+                    rusqlite::params![
+                        limit.category,
+                        limit.daily_limit_minutes,
+                        limit.block_when_exceeded as i32
+                    ],
+                )?;
+            }
+
+            for group in definitions.app_groups.iter().flatten() {
+                tx.execute(
+                    "DELETE FROM app_group_members WHERE group_name = ?1",
+                    rusqlite::params![group.name],
+                )?;
+                for member in &group.members {
+                    tx.execute(
+                        "INSERT OR REPLACE INTO app_group_members (app_name, group_name) VALUES (?1, ?2)",
+                        rusqlite::params![member, group.name],
+                    )?;
+                }
+            }
+
+            if let Some(cap) = &definitions.global_cap {
+                tx.execute(
+                    "INSERT OR REPLACE INTO global_limit (id, daily_limit_minutes, block_when_exceeded)
+                     VALUES (1, ?1, ?2)",
+                    rusqlite::params![cap.limit_minutes, cap.block_when_exceeded as i32],
+                )?;
+                tx.execute("UPDATE apps SET excluded_from_cap = 0", [])?;
+                for app in &cap.excluded_apps {
+                    ensure_app(app)?;
+                    tx.execute(
+                        "UPDATE apps SET excluded_from_cap = 1 WHERE name = ?1",
+                        rusqlite::params![app],
+                    )?;
+                }
+            }
+
+            tx.commit()
+        })?;
+        self.invalidate_daily_summary();
+        Ok(())
+    }
+
+    pub fn remove_global_cap(&self) -> SqliteResult<()> {
+        self.execute_write("DELETE FROM global_limit", [])?;
+        Ok(())
+    }
+
+    pub fn get_global_cap_status(&self) -> SqliteResult<Option<GlobalCapStatus>> {
+        let cap: Option<(i32, bool)> = self
+            .conn
+            .query_row(
+                "SELECT daily_limit_minutes, COALESCE(block_when_exceeded, 0)
+                 FROM global_limit WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get::<_, i32>(1)? != 0)),
+            )
+            .optional()?;
+
+        let Some((limit_minutes, block_when_exceeded)) = cap else {
+            return Ok(None);
+        };
+
+        let used_seconds: i64 = self.conn.query_row(
+            "SELECT COALESCE(SUM(
+                CASE WHEN us.duration_seconds = 0 AND us.end_time = us.start_time
+                     THEN MAX(strftime('%s','now') - us.start_time, 0)
+                     ELSE us.duration_seconds
+                END
+             ), 0)
+             FROM usage_sessions us
+             JOIN apps a ON a.id = us.app_id
+             WHERE COALESCE(a.excluded_from_cap, 0) = 0
+               AND date(us.start_time, 'unixepoch', 'localtime') = date('now', 'localtime')",
+            [],
+            |row| row.get(0),
+        )?;
+
+        Ok(Some(GlobalCapStatus {
+            limit_minutes,
+            block_when_exceeded,
+            used_seconds,
+            exceeded: used_seconds >= (limit_minutes as i64) * 60,
+            excluded_apps: self.get_cap_excluded_apps()?,
+        }))
+    }
+
+    /// Exclude an app from (or include it in) the global cap. The app is
+    /// created if it hasn't been tracked yet so it can be excluded up front.
+    pub fn set_app_cap_excluded(&self, app_name: &str, excluded: bool) -> SqliteResult<()> {
+        self.get_or_create_app(app_name, None)?;
+        self.execute_write(
+            "UPDATE apps SET excluded_from_cap = ?1 WHERE name = ?2",
+            rusqlite::params![excluded as i32, app_name],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_cap_excluded_apps(&self) -> SqliteResult<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name FROM apps WHERE COALESCE(excluded_from_cap, 0) = 1
+             ORDER BY name COLLATE NOCASE",
+        )?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    fn is_cap_excluded(&self, app_name: &str) -> SqliteResult<bool> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT COALESCE(excluded_from_cap, 0) FROM apps WHERE name = ?1",
+                [app_name],
+                |row| row.get::<_, i32>(0),
+            )
+            .optional()?
+            .is_some_and(|excluded| excluded != 0))
+    }
+
+    /// Stop tracking an app. Excluding it again is a no-op.
+    pub fn add_excluded_app(&self, app_name: &str) -> SqliteResult<()> {
+        self.execute_write(
+            "INSERT OR IGNORE INTO excluded_apps (name) VALUES (?1)",
+            [app_name],
+        )?;
+        Ok(())
+    }
+
+    /// Resume tracking an app. Returns false if it wasn't excluded.
+    pub fn remove_excluded_app(&self, app_name: &str) -> SqliteResult<bool> {
+        let removed =
+            self.execute_write("DELETE FROM excluded_apps WHERE name = ?1", [app_name])?;
+        Ok(removed > 0)
+    }
+
+    pub fn get_excluded_apps(&self) -> SqliteResult<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name FROM excluded_apps ORDER BY name COLLATE NOCASE")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    pub fn is_app_excluded(&self, app_name: &str) -> SqliteResult<bool> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT 1 FROM excluded_apps WHERE name = ?1",
+                [app_name],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some())
+    }
+
+    /// Delete every recorded session of an app. The app itself and its
+    /// settings are kept. Returns the number of sessions removed.
+    pub fn purge_app_sessions(&self, app_name: &str) -> SqliteResult<usize> {
+        let purged = self.execute_write(
+            "DELETE FROM usage_sessions
+             WHERE app_id = (SELECT id FROM apps WHERE name = ?1)",
+            [app_name],
+        )?;
+        if purged > 0 {
+            self.invalidate_daily_summary();
+        }
+        Ok(purged)
+    }
+
+    /// Every tracked app with its all-time usage
+    pub fn get_app_totals(&self) -> SqliteResult<Vec<(String, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.name, COALESCE(SUM(us.duration_seconds), 0)
+             FROM apps a
+             LEFT JOIN usage_sessions us ON a.id = us.app_id
+             GROUP BY a.id
+             ORDER BY a.name",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    pub fn get_all_apps(&self) -> SqliteResult<Vec<App>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, path, COALESCE(custom_icon, icon_path), category,
+                    COALESCE(is_blocked, 0), created_at, COALESCE(pinned, 0),
+                    custom_icon IS NOT NULL
+             FROM apps
+             ORDER BY COALESCE(pinned, 0) DESC, name COLLATE NOCASE",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(App {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                path: row.get(2)?,
+                icon_path: row.get(3)?,
+                category: row.get(4)?,
+                is_blocked: row.get::<_, i32>(5)? != 0,
+                created_at: row.get(6)?,
+                pinned: row.get::<_, i32>(7)? != 0,
+                has_custom_icon: row.get(8)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Get limit status for all apps with limits set
+    /// Returns: (app_name, limit_minutes, used_seconds, block_when_exceeded)
+    pub fn get_all_limit_status(&self) -> SqliteResult<Vec<(String, i32, i64, bool)>> {
+        self.get_all_limit_status_on(chrono::Local::now().weekday())
+    }
+
+    /// `get_all_limit_status`, applying the limits for `weekday`
+    pub fn get_all_limit_status_on(
+        &self,
+        weekday: chrono::Weekday,
+    ) -> SqliteResult<Vec<(String, i32, i64, bool)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.name,
+                    CASE WHEN ?1 THEN COALESCE(al.weekend_limit_minutes, al.daily_limit_minutes)
+                         ELSE al.daily_limit_minutes END
+                    + COALESCE(tb.minutes, 0),
+                    COALESCE(SUM(
+                        CASE WHEN us.duration_seconds = 0 AND us.end_time = us.start_time
+                             THEN MAX(strftime('%s','now') - us.start_time, 0)
+                             ELSE us.duration_seconds
+                        END
+                    ), 0),
+                    al.block_when_exceeded
+             FROM apps a
+             JOIN app_limits al ON a.id = al.app_id
+             LEFT JOIN todays_limit_bonuses tb ON tb.app_name = a.name
+             LEFT JOIN usage_sessions us ON a.id = us.app_id 
+                AND date(us.start_time, 'unixepoch', 'localtime') = date('now', 'localtime')
+             GROUP BY a.id",
+        )?;
+
+        let rows = stmt.query_map([is_weekend_day(weekday)], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i32>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i32>(3)? != 0,
+            ))
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Every limited app with its remaining minutes today and the fraction of
+    /// its limit used, most used first. Apps past their limit have zero or
+    /// negative minutes remaining.
+    pub fn get_limit_remaining(&self) -> SqliteResult<Vec<(String, i32, f64)>> {
+        let mut remaining: Vec<(String, i32, f64)> = self
+            .get_all_limit_status()?
+            .into_iter()
+            .filter(|(_, limit_minutes, _, _)| *limit_minutes > 0)
+            .map(|(app_name, limit_minutes, used_seconds, _)| {
+                let limit_seconds = (limit_minutes as i64) * 60;
+                let remaining_minutes = ((limit_seconds - used_seconds) / 60) as i32;
+                let ratio = used_seconds as f64 / limit_seconds as f64;
+                (app_name, remaining_minutes, ratio)
+            })
+            .collect();
+        remaining.sort_by(|a, b| b.2.total_cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+        Ok(remaining)
+    }
+
+    /// Delete usage sessions older than the specified number of days.
+    /// Returns the number of deleted rows.
+    pub fn cleanup_old_data(&self, retention_days: i64) -> SqliteResult<usize> {
+        let cutoff = Utc::now().timestamp() - (retention_days * 24 * 60 * 60);
+
+        let deleted = self.execute_write(
+            "DELETE FROM usage_sessions WHERE end_time < ?1",
+            rusqlite::params![cutoff],
+        )?;
+
+        // Space is only reclaimed by `optimize`, which can be slow
+
+        self.invalidate_daily_summary();
+
+        Ok(deleted)
+    }
+
+    /// Delete sessions older than their app category's retention period.
+    /// `retention_days` maps category names to days kept; a category mapped
+    /// to zero or fewer days is kept forever. Uncategorized apps and
+    /// categories missing from the map use `default_days`, where zero or
+    /// fewer likewise keeps them forever. Runs in one transaction.
+    pub fn cleanup_old_data_by_category(
+        &mut self,
+        retention_days: &HashMap<String, i64>,
+        default_days: i64,
+    ) -> SqliteResult<usize> {
+        let now = Utc::now().timestamp();
+        let configured = serde_json::to_string(&retention_days.keys().collect::<Vec<_>>())
+            .unwrap_or_else(|_| "[]".to_string());
+
+        let deleted = with_busy_retry(|| {
+            let tx = self.conn.transaction()?;
+            let mut deleted = 0;
+
+            for (category, &days) in retention_days {
+                if days <= 0 {
+                    continue;
+                }
+                deleted += tx.execute(
+                    "DELETE FROM usage_sessions
+                     WHERE end_time < ?1
+                       AND app_id IN (SELECT id FROM apps WHERE category = ?2)",
+                    rusqlite::params![now - days * 24 * 60 * 60, category],
+                )?;
+            }
+
+            if default_days > 0 {
+                deleted += tx.execute(
+                    "DELETE FROM usage_sessions
+                     WHERE end_time < ?1
+                       AND app_id IN (
+                           SELECT id FROM apps
+                           WHERE category IS NULL
+                              OR category NOT IN (SELECT value FROM json_each(?2))
+                       )",
+                    rusqlite::params![now - default_days * 24 * 60 * 60, configured],
+                )?;
+            }
+
+            tx.commit()?;
+            Ok(deleted)
+        })?;
+
+        self.invalidate_daily_summary();
+
+        Ok(deleted)
+    }
+
+    /// Size of the database file in bytes, from its page count
+    fn database_size(&self) -> SqliteResult<i64> {
+        let page_size: i64 = self
+            .conn
+            .pragma_query_value(None, "page_size", |row| row.get(0))?;
+        let page_count: i64 = self
+            .conn
+            .pragma_query_value(None, "page_count", |row| row.get(0))?;
+        Ok(page_size * page_count)
+    }
+
+    /// Rebuild the database file with VACUUM and refresh query planner
+    /// statistics. Returns the number of bytes reclaimed.
+    pub fn optimize(&self) -> SqliteResult<i64> {
+        let before = self.database_size()?;
+        with_busy_retry(|| self.conn.execute_batch("VACUUM; PRAGMA optimize;"))?;
+        let after = self.database_size()?;
+        Ok((before - after).max(0))
+    }
+
+    /// Copy the whole database to `path` with SQLite's online backup API
+    pub fn backup_to(&self, path: &std::path::Path) -> SqliteResult<()> {
+        self.conn.backup(rusqlite::DatabaseName::Main, path, None)
+    }
+
+    /// Replace the database with a backup made by `backup_to`. The source is
+    /// checked for the core tables first, and migrations are re-run afterwards
+    /// so older backups are brought up to the current schema.
+    pub fn restore_from(&mut self, path: &std::path::Path) -> SqliteResult<()> {
+        let source = Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let missing: Vec<&str> = ["apps", "usage_sessions", "app_limits"]
+            .into_iter()
+            .filter(|table| {
+                source
+                    .query_row(
+                        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                        [table],
+                        |_| Ok(()),
+                    )
+                    .optional()
+                    .ok()
+                    .flatten()
+                    .is_none()
+            })
+            .collect();
+        drop(source);
+
+        if !missing.is_empty() {
+            return Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_NOTADB),
+                Some(format!(
+                    "Not a wellbeing database, missing tables: {}",
+                    missing.join(", ")
+                )),
+            ));
+        }
+
+        self.conn.restore(
+            rusqlite::DatabaseName::Main,
+            path,
+            None::<fn(rusqlite::backup::Progress)>,
+        )?;
+        self.invalidate_daily_summary();
+        self.init_schema()?;
+        self.migrations_applied = self.run_migrations()?;
+        self.ensure_schema_columns()?;
+        Ok(())
+    }
+
+    /// Get the count of usage sessions and approximate database size info
+    pub fn get_storage_stats(&self) -> SqliteResult<(i64, i64, Option<String>)> {
+        let session_count: i64 =
+            self.conn
+                .query_row("SELECT COUNT(*) FROM usage_sessions", [], |row| row.get(0))?;
+
+        let oldest_timestamp: Option<i64> = self
+            .conn
+            .query_row("SELECT MIN(start_time) FROM usage_sessions", [], |row| {
+                row.get(0)
+            })
+            .optional()?
+            .flatten();
+
+        let oldest_date = oldest_timestamp.map(|ts| {
+            chrono::DateTime::from_timestamp(ts, 0)
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "Unknown".to_string())
+        });
+
+        Ok((session_count, oldest_timestamp.unwrap_or(0), oldest_date))
+    }
+
+    /// Export usage data within a date range
+    /// Returns: Vec of (date, app_name, category, duration_seconds, session_count)
+    pub fn export_usage_data(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> SqliteResult<Vec<ExportRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT 
+                date(us.start_time, 'unixepoch', 'localtime') as date,
+                a.name as app_name,
+                COALESCE(a.category, 'Uncategorized') as category,
+                SUM(us.duration_seconds) as total_seconds,
+                COUNT(us.id) as session_count
+             FROM usage_sessions us
+             JOIN apps a ON us.app_id = a.id
+             WHERE us.start_time >= ?1 AND us.start_time <= ?2
+             GROUP BY date, a.id
+             ORDER BY date DESC, total_seconds DESC",
+        )?;
+
+        let rows = stmt.query_map(rusqlite::params![start_timestamp, end_timestamp], |row| {
+            Ok(ExportRecord {
+                date: row.get(0)?,
+                app_name: row.get(1)?,
+                category: row.get(2)?,
+                duration_seconds: row.get(3)?,
+                session_count: row.get(4)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Individual sessions starting between the given timestamps (inclusive),
+    /// oldest first, for a raw per-session export
+    pub fn export_sessions_in_range(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> SqliteResult<Vec<UsageSession>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT us.id, us.app_id, a.name, a.category, us.start_time, us.end_time,
+                    us.duration_seconds
+             FROM usage_sessions us
+             JOIN apps a ON us.app_id = a.id
+             WHERE us.start_time >= ?1 AND us.start_time <= ?2
+             ORDER BY us.start_time ASC, us.id ASC",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![start_timestamp, end_timestamp], |row| {
+            Ok(UsageSession {
+                id: row.get(0)?,
+                app_id: row.get(1)?,
+                app_name: row.get(2)?,
+                category: row.get(3)?,
+                start_time: row.get(4)?,
+                end_time: row.get(5)?,
+                duration_seconds: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Get daily totals within a date range for historical analysis
+    /// Returns: Vec of (date_string, total_seconds)
+    pub fn get_daily_totals_in_range(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> SqliteResult<Vec<(String, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT 
+                date(us.start_time, 'unixepoch', 'localtime') as date,
+                SUM(us.duration_seconds) as total_seconds
+             FROM usage_sessions us
+             WHERE us.start_time >= ?1 AND us.start_time <= ?2
+             GROUP BY date
+             ORDER BY date ASC",
+        )?;
+
+        let rows = stmt.query_map(rusqlite::params![start_timestamp, end_timestamp], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Average seconds per day that had usage between two timestamps, and the
+    /// number of those days. Days without usage don't count toward the average.
+    pub fn get_average_daily_seconds(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> SqliteResult<(f64, i64)> {
+        let (total_seconds, active_days): (i64, i64) = self.conn.query_row(
+            "SELECT COALESCE(SUM(duration_seconds), 0),
+                    COUNT(DISTINCT date(start_time, 'unixepoch', 'localtime'))
+             FROM usage_sessions
+             WHERE start_time >= ?1 AND start_time <= ?2",
+            rusqlite::params![start_timestamp, end_timestamp],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        if active_days == 0 {
+            return Ok((0.0, 0));
+        }
+        Ok((total_seconds as f64 / active_days as f64, active_days))
+    }
+
+    /// The longest session ever recorded
+    pub fn get_longest_session(&self) -> SqliteResult<Option<SessionRecord>> {
+        self.conn
+            .query_row(
+                "SELECT us.id, us.app_id, a.name, us.start_time, us.end_time, us.duration_seconds,
+                        date(us.start_time, 'unixepoch', 'localtime'), a.category
+                 FROM usage_sessions us
+                 JOIN apps a ON us.app_id = a.id
+                 ORDER BY us.duration_seconds DESC, us.start_time ASC
+                 LIMIT 1",
+                [],
+                |row| {
+                    Ok(SessionRecord {
+                        session: UsageSession {
+                            id: row.get(0)?,
+                            app_id: row.get(1)?,
+                            app_name: row.get(2)?,
+                            category: row.get(7)?,
+                            start_time: row.get(3)?,
+                            end_time: row.get(4)?,
+                            duration_seconds: row.get(5)?,
+                        },
+                        date: row.get(6)?,
+                    })
+                },
+            )
+            .optional()
+    }
+
+    /// Longest session, busiest day and most-used app across all history
+    pub fn get_records(&self) -> SqliteResult<PersonalRecords> {
+        let busiest_day = self
+            .conn
+            .query_row(
+                "SELECT date(start_time, 'unixepoch', 'localtime') as day,
+                        SUM(duration_seconds) as total
+                 FROM usage_sessions
+                 GROUP BY day
+                 ORDER BY total DESC, day ASC
+                 LIMIT 1",
+                [],
+                |row| {
+                    Ok(DayRecord {
+                        date: row.get(0)?,
+                        total_seconds: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+
+        let most_used_app = self
+            .conn
+            .query_row(
+                "SELECT a.name, SUM(us.duration_seconds) as total
+                 FROM usage_sessions us
+                 JOIN apps a ON us.app_id = a.id
+                 GROUP BY a.id
+                 ORDER BY total DESC, a.name ASC
+                 LIMIT 1",
+                [],
+                |row| {
+                    Ok(AppRecord {
+                        app_name: row.get(0)?,
+                        total_seconds: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(PersonalRecords {
+            longest_session: self.get_longest_session()?,
+            busiest_day,
+            most_used_app,
+        })
+    }
+
+    /// Get app usage breakdown within a date range for historical analysis
+    /// Returns: Vec of AppUsage with totals for the entire range
+    pub fn get_app_usage_in_range(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> SqliteResult<Vec<AppUsage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT 
+                a.name,
+                COALESCE(SUM(us.duration_seconds), 0) as total_seconds,
+                COUNT(us.id) as session_count,
+                a.category,
+                COALESCE(a.pinned, 0),
+                COALESCE(a.custom_icon, a.icon_path)
+             FROM usage_sessions us
+             JOIN apps a ON us.app_id = a.id
+             WHERE us.start_time >= ?1 AND us.start_time <= ?2
+             GROUP BY a.id
+             ORDER BY total_seconds DESC",
+        )?;
+
+        let rows = stmt.query_map(rusqlite::params![start_timestamp, end_timestamp], |row| {
+            Ok(AppUsage {
+                app_name: row.get(0)?,
+                duration_seconds: row.get(1)?,
+                session_count: row.get(2)?,
+                category: row.get(3)?,
+                pinned: row.get::<_, i32>(4)? != 0,
+                icon: row.get(5)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Get category usage within a date range for historical analysis
+    pub fn get_category_usage_in_range(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> SqliteResult<Vec<CategoryUsage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT 
+                COALESCE(a.category, 'Uncategorized') as category,
+                SUM(us.duration_seconds) as total_seconds,
+                COUNT(DISTINCT a.id) as app_count
+             FROM usage_sessions us
+             JOIN apps a ON us.app_id = a.id
+             WHERE us.start_time >= ?1 AND us.start_time <= ?2
+             GROUP BY COALESCE(a.category, 'Uncategorized')
+             ORDER BY total_seconds DESC",
+        )?;
+
+        let rows = stmt.query_map(rusqlite::params![start_timestamp, end_timestamp], |row| {
+            Ok(CategoryUsage {
+                category: row.get(0)?,
+                total_seconds: row.get(1)?,
+                app_count: row.get(2)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Get all blocked apps in a single query (fixes N+1 query problem)
+    /// Returns app names where block_when_exceeded is true AND usage exceeds
+    /// limit, then the apps in a category whose blocking limit is reached
+    pub fn get_blocked_apps(&self) -> SqliteResult<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.name
+             FROM apps a
+             JOIN app_limits al ON a.id = al.app_id
+             LEFT JOIN todays_limit_bonuses tb ON tb.app_name = a.name
+             LEFT JOIN usage_sessions us ON a.id = us.app_id 
+                AND date(us.start_time, 'unixepoch', 'localtime') = date('now', 'localtime')
+             WHERE al.block_when_exceeded = 1
+             GROUP BY a.id
+             HAVING COALESCE(SUM(
+                 CASE WHEN us.duration_seconds = 0 AND us.end_time = us.start_time
+                      THEN MAX(strftime('%s','now') - us.start_time, 0)
+                      ELSE us.duration_seconds
+                 END
+             ), 0) >= ((CASE WHEN ?1 THEN COALESCE(al.weekend_limit_minutes, al.daily_limit_minutes)
+                             ELSE al.daily_limit_minutes END
+                        + COALESCE(tb.minutes, 0)) * 60)",
+        )?;
+
+        let weekend = is_weekend_day(chrono::Local::now().weekday());
+        let rows = stmt.query_map([weekend], |row| row.get(0))?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+
+        let blocked_categories: Vec<String> = self
+            .get_category_limit_status()?
+            .into_iter()
+            .filter(|status| status.block_when_exceeded && status.exceeded)
+            .map(|status| status.category)
+            .collect();
+        let mut stmt = self.conn.prepare(
+            "SELECT name FROM apps WHERE COALESCE(category, 'Uncategorized') = ?1 ORDER BY name",
+        )?;
+        for category in &blocked_categories {
+            for name in stmt.query_map([category], |row| row.get::<_, String>(0))? {
+                let name = name?;
+                if !result.contains(&name) {
+                    result.push(name);
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{test_db, TempPath};
+
+    #[test]
+    fn test_toggle_limit_blocking() {
+        let db = test_db();
+        db.set_limit_with_block("Firefox", 60, false).unwrap();
+        assert_eq!(db.get_limit_blocking("Firefox").unwrap(), Some(false));
+
+        assert!(db.set_limit_blocking("Firefox", true).unwrap());
+        assert_eq!(db.get_limit_blocking("Firefox").unwrap(), Some(true));
+
+        // The limit minutes are left untouched
+        assert_eq!(db.get_limit("Firefox").unwrap(), Some(60));
+    }
+
+    #[test]
+    fn test_limit_blocking_without_limit() {
+        let db = test_db();
+        db.get_or_create_app("Discord", None).unwrap();
+
+        assert!(!db.set_limit_blocking("Discord", true).unwrap());
+        assert!(!db.set_limit_blocking("Unknown", true).unwrap());
+        assert_eq!(db.get_limit_blocking("Discord").unwrap(), None);
+    }
+
+    #[test]
+    fn test_daily_summary_is_cached() {
+        let db = test_db();
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+        let now = Utc::now().timestamp();
+        let session = db.start_session(app_id, now - 120).unwrap();
+        db.end_session(session, now - 60).unwrap();
+
+        let first = db.get_daily_summary().unwrap();
+        assert_eq!(first.total_seconds, 60);
+
+        // A write that bypasses the Database API isn't seen until the cache expires
+        db.conn
+            .execute(
+                "INSERT INTO usage_sessions (app_id, start_time, end_time, duration_seconds)
+                 VALUES (?1, ?2, ?3, 30)",
+                rusqlite::params![app_id, now - 40, now - 10],
+            )
+            .unwrap();
+        assert_eq!(db.get_daily_summary().unwrap().total_seconds, 60);
+    }
+
+    #[test]
+    fn test_daily_summary_invalidated_by_new_session() {
+        let db = test_db();
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+        let now = Utc::now().timestamp();
+        let session = db.start_session(app_id, now - 120).unwrap();
+        db.end_session(session, now - 60).unwrap();
+        assert_eq!(db.get_daily_summary().unwrap().total_seconds, 60);
+
+        let code_id = db.get_or_create_app("Code", None).unwrap();
+        let session = db.start_session(code_id, now - 50).unwrap();
+        db.end_session(session, now - 20).unwrap();
+
+        let summary = db.get_daily_summary().unwrap();
+        assert_eq!(summary.total_seconds, 90);
+        assert_eq!(summary.apps.len(), 2);
+        assert_eq!(summary.categories.len(), 1);
+    }
+
+    #[test]
+    fn test_app_belongs_to_one_group() {
+        let mut db = test_db();
+        db.set_app_group("Browsers", &["Firefox".into(), "Chrome".into()])
+            .unwrap();
+        db.set_app_group("Work", &["Chrome".into(), "Slack".into()])
+            .unwrap();
+
+        let groups = db.get_app_groups().unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "Browsers");
+        assert_eq!(groups[0].members, vec!["Firefox"]);
+        assert_eq!(groups[1].members, vec!["Chrome", "Slack"]);
+
+        db.remove_app_group("Work").unwrap();
+        assert_eq!(db.get_app_groups().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_pinned_apps_sort_first() {
+        let db = test_db();
+        let now = Utc::now().timestamp();
+        for (name, seconds) in [("Firefox", 600), ("Code", 300), ("Slack", 60)] {
+            let app_id = db.get_or_create_app(name, None).unwrap();
+            let session = db.start_session(app_id, now - seconds - 10).unwrap();
+            db.end_session(session, now - 10).unwrap();
+        }
+
+        assert!(db.set_app_pinned("Slack", true).unwrap());
+        assert!(!db.set_app_pinned("Unknown", true).unwrap());
+
+        let usage = db.get_daily_usage().unwrap();
+        let names: Vec<&str> = usage.iter().map(|a| a.app_name.as_str()).collect();
+        assert_eq!(names, vec!["Slack", "Firefox", "Code"]);
+        assert!(usage[0].pinned);
+
+        // Pinning is presentation only
+        assert_eq!(db.get_daily_summary().unwrap().total_seconds, 960);
+
+        let apps = db.get_all_apps().unwrap();
+        assert_eq!(apps[0].name, "Slack");
+    }
+
+    /// Insert a finished session starting at local noon on `date`
+    /// Timestamp of a local wall-clock time on `date`
+    fn local_time(date: chrono::NaiveDate, hour: u32, minute: u32) -> i64 {
+        timestamp_in(&chrono::Local, date.and_hms_opt(hour, minute, 0).unwrap())
+    }
+
+    fn insert_session_on(db: &Database, app_name: &str, date: chrono::NaiveDate, seconds: i64) {
+        let app_id = db.get_or_create_app(app_name, None).unwrap();
+        let start = local_time(date, 12, 0);
+        let session = db.start_session(app_id, start).unwrap();
+        db.end_session(session, start + seconds).unwrap();
+    }
+
+    #[test]
+    fn test_week_start_is_local_midnight_six_days_back() {
+        // 2024-03-31 is the EU spring-forward day; model the clocks on either
+        // side of it with the two fixed offsets involved
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 4, 2).unwrap();
+        let winter = chrono::FixedOffset::east_opt(3600).unwrap();
+        let summer = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+
+        let expected = chrono::NaiveDate::from_ymd_opt(2024, 3, 27)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(
+            week_start_in(&winter, today),
+            expected.and_utc().timestamp() - 3600
+        );
+        assert_eq!(
+            week_start_in(&summer, today),
+            expected.and_utc().timestamp() - 2 * 3600
+        );
+
+        // A fixed 168 hour window taken at 00:30 summer time on `today` would
+        // start at 23:30 on 2024-03-25 winter time, before the window's first day
+        let now = today.and_hms_opt(0, 30, 0).unwrap().and_utc().timestamp() - 2 * 3600;
+        assert!(now - 7 * 24 * 3600 < week_start_in(&winter, today));
+    }
+
+    #[test]
+    fn test_weekly_stats_covers_exactly_seven_local_days() {
+        let db = test_db();
+        let today = chrono::Local::now().date_naive();
+        let first_day = today - chrono::Duration::days(6);
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+
+        // Late on the day before the window and early on its first day
+        let before = local_time(first_day - chrono::Duration::days(1), 23, 30);
+        let session = db.start_session(app_id, before).unwrap();
+        db.end_session(session, before + 600).unwrap();
+        let inside = local_time(first_day, 0, 30);
+        let session = db.start_session(app_id, inside).unwrap();
+        db.end_session(session, inside + 300).unwrap();
+        insert_session_on(&db, "Firefox", today, 120);
+
+        let stats = db.get_weekly_stats_for(today).unwrap();
+        assert_eq!(
+            stats,
+            vec![
+                (
+                    day_timestamp(&first_day.format("%Y-%m-%d").to_string()),
+                    300
+                ),
+                (day_timestamp(&today.format("%Y-%m-%d").to_string()), 120),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_apps_moves_sessions_and_limit() {
+        let mut db = test_db();
+        let today = chrono::Local::now().date_naive();
+        insert_session_on(&db, "Code", today, 600);
+        insert_session_on(&db, "Code", today - chrono::Duration::days(1), 300);
+        insert_session_on(&db, "Visual Studio Code", today, 900);
+        db.set_limit("Code", 60).unwrap();
+        db.set_app_category("Code", "Development").unwrap();
+        db.set_app_pinned("Code", true).unwrap();
+        db.set_app_cap_excluded("Code", true).unwrap();
+        db.set_app_group("Editors", &["Code".to_string()]).unwrap();
+        db.add_excluded_app("Code").unwrap();
+        let date = today.format("%Y-%m-%d").to_string();
+        db.conn
+            .execute(
+                "INSERT INTO limit_bonuses (app_name, date, minutes, reason, granted_at)
+                 VALUES ('Code', ?1, 15, 'focus_reward', 0)",
+                [&date],
+            )
+            .unwrap();
+
+        assert!(db.merge_apps("Code", "Visual Studio Code").unwrap());
+
+        let merged = db
+            .get_all_apps()
+            .unwrap()
+            .into_iter()
+            .find(|a| a.name == "Visual Studio Code")
+            .unwrap();
+        assert_eq!(merged.category.as_deref(), Some("Development"));
+        assert!(merged.pinned);
+        assert_eq!(
+            db.get_cap_excluded_apps().unwrap(),
+            vec!["Visual Studio Code"]
+        );
+        assert_eq!(
+            db.get_app_groups().unwrap()[0].members,
+            vec!["Visual Studio Code"]
+        );
+        assert_eq!(
+            db.get_limit_bonuses(&date).unwrap()[0].app_name,
+            "Visual Studio Code"
+        );
+        assert_eq!(db.get_excluded_apps().unwrap(), vec!["Visual Studio Code"]);
+
+        let totals: HashMap<String, i64> = db.get_app_totals().unwrap().into_iter().collect();
+        assert_eq!(totals.get("Visual Studio Code"), Some(&1800));
+        assert!(!totals.contains_key("Code"));
+        assert!(db.get_all_apps().unwrap().iter().all(|a| a.name != "Code"));
+        assert_eq!(db.get_limit("Visual Studio Code").unwrap(), Some(60));
+
+        // Merging into a new name creates it; a missing source is reported
+        assert!(db.merge_apps("Visual Studio Code", "VS Code").unwrap());
+        assert_eq!(db.get_usage_today("VS Code").unwrap(), 1500);
+        assert!(!db.merge_apps("Code", "VS Code").unwrap());
+    }
+
+    #[test]
+    fn test_average_daily_seconds_counts_active_days_only() {
+        let db = test_db();
+        let today = chrono::Local::now().date_naive();
+        // 3 active days in a 10-day range
+        insert_session_on(&db, "Firefox", today, 600);
+        insert_session_on(&db, "Slack", today, 300);
+        insert_session_on(&db, "Firefox", today - chrono::Duration::days(4), 1200);
+        insert_session_on(&db, "Firefox", today - chrono::Duration::days(9), 900);
+        // Outside the range
+        insert_session_on(&db, "Firefox", today - chrono::Duration::days(20), 9000);
+
+        let start = chrono::Local::now().timestamp() - 10 * 86400;
+        let end = chrono::Local::now().timestamp() + 86400;
+        assert_eq!(
+            db.get_average_daily_seconds(start, end).unwrap(),
+            (1000.0, 3)
+        );
+
+        assert_eq!(db.get_average_daily_seconds(0, 1).unwrap(), (0.0, 0));
+    }
+
+    #[test]
+    fn test_usage_highlights_span_all_days() {
+        let db = test_db();
+        let today = chrono::Local::now().date_naive();
+        insert_session_on(&db, "Firefox", today, 600);
+        insert_session_on(&db, "Firefox", today - chrono::Duration::days(40), 3000);
+        insert_session_on(&db, "Chrome", today, 1200);
+        insert_session_on(&db, "Code", today - chrono::Duration::days(3), 2000);
+        insert_session_on(&db, "Zed", today, 500);
+        insert_session_on(&db, "Notes", today, 100);
+        db.set_app_category("Firefox", "Browsers").unwrap();
+        db.set_app_category("Chrome", "Browsers").unwrap();
+        db.set_app_category("Code", "Development").unwrap();
+        db.set_app_category("Zed", "Development").unwrap();
+
+        assert_eq!(
+            db.get_all_time_top_app().unwrap(),
+            Some(("Firefox".to_string(), 3600))
+        );
+        assert_eq!(
+            db.get_top_app_per_category().unwrap(),
+            vec![
+                ("Browsers".to_string(), "Firefox".to_string(), 3600),
+                ("Development".to_string(), "Code".to_string(), 2000),
+                ("Uncategorized".to_string(), "Notes".to_string(), 100),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_peak_hour_and_weekday() {
+        let db = test_db();
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+        let add = |date: chrono::NaiveDate, hour: u32, seconds: i64| {
+            let start = local_time(date, hour, 10);
+            let session = db.start_session(app_id, start).unwrap();
+            db.end_session(session, start + seconds).unwrap();
+            start
+        };
+
+        // 2026-03-01 is a Sunday; evenings dominate overall
+        let sunday = chrono::NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        add(sunday, 20, 1800);
+        add(sunday + chrono::Duration::days(7), 20, 1800);
+        add(sunday + chrono::Duration::days(2), 9, 2400);
+        let late = add(sunday + chrono::Duration::days(10), 9, 600);
+
+        assert_eq!(db.get_peak_hour(None).unwrap(), Some((20, 3600)));
+        assert_eq!(db.get_peak_weekday(None).unwrap(), Some((0, 3600)));
+
+        // Only the Wednesday morning session falls in this range
+        let second_week = Some((late - 86400, late + 86400));
+        assert_eq!(db.get_peak_hour(second_week).unwrap(), Some((9, 600)));
+        assert_eq!(db.get_peak_weekday(second_week).unwrap(), Some((3, 600)));
+
+        assert_eq!(db.get_peak_hour(Some((0, 1))).unwrap(), None);
+    }
+
+    #[test]
+    fn test_usage_highlights_empty() {
+        let db = test_db();
+        assert_eq!(db.get_all_time_top_app().unwrap(), None);
+        assert!(db.get_top_app_per_category().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_close_orphaned_sessions() {
+        let db = test_db();
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+        let now = 1_700_000_000;
+
+        // Crashed before its first flush, long ago and just before the restart
+        let orphan = db.start_session(app_id, now - 3600).unwrap();
+        let recent = db.start_session(app_id, now - 10).unwrap();
+        // Flushed before the crash: ends at its last flush
+        let flushed = db.start_session(app_id, now - 7200).unwrap();
+        db.update_session_duration(flushed, now - 7000).unwrap();
+
+        assert_eq!(db.close_orphaned_sessions(now - 300, 30).unwrap(), 1);
+
+        let duration = |id: i64| -> Option<(i64, i64)> {
+            db.conn
+                .query_row(
+                    "SELECT end_time - start_time, duration_seconds FROM usage_sessions WHERE id = ?1",
+                    [id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()
+                .unwrap()
+        };
+        // Capped rather than counted up to now
+        assert_eq!(duration(orphan), Some((30, 30)));
+        // Too recent to tell from a session another instance still holds
+        assert_eq!(duration(recent), Some((0, 0)));
+        assert_eq!(duration(flushed), Some((200, 200)));
+
+        // Nothing left to repair
+        assert_eq!(db.close_orphaned_sessions(now - 300, 30).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_monthly_stats_cover_last_30_days() {
+        let db = test_db();
+        let today = chrono::Local::now().date_naive();
+        for days_ago in 0..40 {
+            insert_session_on(&db, "Firefox", today - chrono::Duration::days(days_ago), 60);
+        }
+
+        let stats = db.get_monthly_stats().unwrap();
+        assert_eq!(stats.len(), 30);
+        let first = chrono::DateTime::from_timestamp(stats[0].0, 0)
+            .unwrap()
+            .date_naive();
+        assert_eq!(first, today - chrono::Duration::days(29));
+        assert!(stats.iter().all(|(_, seconds)| *seconds == 60));
+    }
+
+    #[test]
+    fn test_week_comparison_splits_at_seven_days() {
+        let db = test_db();
+        let today = chrono::Local::now().date_naive();
+        // This week: today and 6 days ago
+        insert_session_on(&db, "Firefox", today, 600);
+        insert_session_on(&db, "Firefox", today - chrono::Duration::days(6), 300);
+        // Last week: 7 and 13 days ago
+        insert_session_on(&db, "Firefox", today - chrono::Duration::days(7), 1000);
+        insert_session_on(&db, "Firefox", today - chrono::Duration::days(13), 200);
+        // Outside both windows
+        insert_session_on(&db, "Firefox", today - chrono::Duration::days(14), 5000);
+
+        assert_eq!(db.get_week_comparison().unwrap(), (900, 1200));
+        assert_eq!(crate::percent_change(900, 1200), Some(-25.0));
+        assert_eq!(crate::percent_change(900, 0), None);
+    }
+
+    #[test]
+    fn test_hourly_usage_for_past_date() {
+        let db = test_db();
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 5, 14).unwrap();
+        for (start, seconds) in [
+            (local_time(day, 8, 0), 600),
+            (local_time(day, 8, 30), 300),
+            (local_time(day, 13, 15), 1200),
+            (local_time(day, 23, 50), 300),
+            (local_time(day.succ_opt().unwrap(), 8, 0), 900),
+        ] {
+            let session = db.start_session(app_id, start).unwrap();
+            db.end_session(session, start + seconds).unwrap();
+        }
+
+        let hours: Vec<(i32, i64)> = db
+            .get_hourly_usage_for(day)
+            .unwrap()
+            .into_iter()
+            .map(|h| (h.hour, h.total_seconds))
+            .collect();
+        assert_eq!(hours, vec![(8, 900), (13, 1200), (23, 300)]);
+    }
+
+    #[test]
+    fn test_usage_until_time_of_day() {
+        let db = test_db();
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 5, 14).unwrap();
+        let next_day = day.succ_opt().unwrap();
+        for (start, seconds) in [
+            (local_time(day, 9, 0), 3600),
+            (local_time(day, 14, 0), 3600),
+            (local_time(next_day, 8, 0), 7200),
+        ] {
+            let session = db.start_session(app_id, start).unwrap();
+            db.end_session(session, start + seconds).unwrap();
+        }
+
+        let half_past_nine = 9 * 3600 + 30 * 60;
+        assert_eq!(
+            db.get_usage_until_time_of_day(day, half_past_nine).unwrap(),
+            1800
+        );
+        assert_eq!(
+            db.get_usage_until_time_of_day(day, 13 * 3600).unwrap(),
+            3600
+        );
+        assert_eq!(
+            db.get_usage_until_time_of_day(day, 24 * 3600).unwrap(),
+            7200
+        );
+        assert_eq!(
+            db.get_usage_until_time_of_day(next_day, half_past_nine)
+                .unwrap(),
+            5400
+        );
+        assert_eq!(
+            db.get_usage_until_time_of_day(next_day, 7 * 3600).unwrap(),
+            0
+        );
+    }
+
+    fn count_rows(db: &Database, table: &str) -> i64 {
+        db.conn
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_optimize_reclaims_deleted_sessions() {
+        let db = test_db();
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+        for i in 0..2000 {
+            let session = db.start_session(app_id, 1_000_000 + i * 100).unwrap();
+            db.end_session(session, 1_000_000 + i * 100 + 60).unwrap();
+        }
+        db.cleanup_old_data(1).unwrap();
+        assert_eq!(count_rows(&db, "usage_sessions"), 0);
+
+        assert!(db.optimize().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_upsert_and_delete_category() {
+        let mut db = test_db();
+        let seeded = db.get_categories().unwrap();
+        assert!(seeded
+            .iter()
+            .any(|c| c.name == "Development" && c.is_productive));
+
+        let reading = Category {
+            name: "Reading".to_string(),
+            color: "#112233".to_string(),
+            is_productive: false,
+        };
+        db.upsert_category(&reading).unwrap();
+        db.upsert_category(&Category {
+            is_productive: true,
+            ..reading.clone()
+        })
+        .unwrap();
+        let categories = db.get_categories().unwrap();
+        assert_eq!(categories.len(), seeded.len() + 1);
+        assert!(categories
+            .iter()
+            .any(|c| c.name == "Reading" && c.is_productive));
+
+        db.get_or_create_app("Kindle", None).unwrap();
+        db.set_app_category("Kindle", "Reading").unwrap();
+        assert!(db.delete_category("Reading").unwrap());
+        assert!(!db.delete_category("Reading").unwrap());
+        assert_eq!(db.get_categories().unwrap().len(), seeded.len());
+
+        let category: Option<String> = db
+            .conn
+            .query_row(
+                "SELECT category FROM apps WHERE name = 'Kindle'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(category, None);
+    }
+
+    #[test]
+    fn test_breaks_taken_are_counted_per_day() {
+        let db = test_db();
+        let day = chrono::NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let next_day = day.succ_opt().unwrap();
+
+        db.record_break(day).unwrap();
+        db.record_break(day).unwrap();
+        db.record_break(next_day).unwrap();
+
+        assert_eq!(db.breaks_taken_on(day).unwrap(), 2);
+        assert_eq!(db.breaks_taken_on(next_day).unwrap(), 1);
+        assert_eq!(db.breaks_taken_on(day.pred_opt().unwrap()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_cleanup_old_data_by_category() {
+        let mut db = test_db();
+        let today = chrono::Local::now().date_naive();
+        let days_ago = |days| today - chrono::Duration::days(days);
+        for (app, category) in [("Code", "Development"), ("YouTube", "Entertainment")] {
+            db.get_or_create_app(app, None).unwrap();
+            db.set_app_category(app, category).unwrap();
+        }
+        for app in ["Code", "YouTube", "Firefox"] {
+            insert_session_on(&db, app, days_ago(400), 60);
+            insert_session_on(&db, app, days_ago(60), 60);
+            insert_session_on(&db, app, days_ago(1), 60);
+        }
+
+        let retention = HashMap::from([
+            ("Development".to_string(), 0),
+            ("Entertainment".to_string(), 30),
+        ]);
+        assert_eq!(db.cleanup_old_data_by_category(&retention, 90).unwrap(), 3);
+
+        let sessions = |app: &str| -> i64 {
+            db.conn
+                .query_row(
+                    "SELECT COUNT(*) FROM usage_sessions us JOIN apps a ON us.app_id = a.id
+                     WHERE a.name = ?1",
+                    [app],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+        // Development is kept forever
+        assert_eq!(sessions("Code"), 3);
+        // Entertainment keeps 30 days
+        assert_eq!(sessions("YouTube"), 1);
+        // Uncategorized uses the 90 day default
+        assert_eq!(sessions("Firefox"), 2);
+
+        // A default of zero days keeps the rest forever too
+        assert_eq!(db.cleanup_old_data_by_category(&retention, 0).unwrap(), 0);
+        assert_eq!(sessions("Firefox"), 2);
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let db = test_db();
+        let today = chrono::Local::now().date_naive();
+        for days_ago in 0..5 {
+            insert_session_on(&db, "Firefox", today - chrono::Duration::days(days_ago), 60);
+        }
+        insert_session_on(&db, "Code", today, 300);
+        db.set_limit("Firefox", 30).unwrap();
+
+        let path = TempPath::new("backup.db");
+        db.backup_to(&path).unwrap();
+
+        let mut restored = test_db();
+        restored.restore_from(&path).unwrap();
+        for table in ["apps", "usage_sessions", "app_limits"] {
+            assert_eq!(count_rows(&restored, table), count_rows(&db, table));
+        }
+        assert_eq!(restored.get_limit("Firefox").unwrap(), Some(30));
+    }
+
+    #[test]
+    fn test_restore_rejects_other_databases() {
+        let path = TempPath::new("not-a-backup.db");
+        Connection::open(&path)
+            .unwrap()
+            .execute("CREATE TABLE notes (body TEXT)", [])
+            .unwrap();
+
+        let mut db = test_db();
+        insert_session_on(&db, "Firefox", chrono::Local::now().date_naive(), 60);
+        assert!(db.restore_from(&path).is_err());
+        // The current data is untouched
+        assert_eq!(count_rows(&db, "usage_sessions"), 1);
+    }
+
+    #[test]
+    fn test_title_breakdown() {
+        let db = test_db();
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+        let now = Utc::now().timestamp();
+        for (title, seconds) in [
+            (Some("Docs"), 300),
+            (Some("News"), 60),
+            (Some("Docs"), 120),
+            (None, 30),
+        ] {
+            let session = db
+                .start_session_with_title(app_id, now - 1000, title)
+                .unwrap();
+            db.end_session(session, now - 1000 + seconds).unwrap();
+        }
+
+        let today = chrono::DateTime::from_timestamp(now - 1000, 0)
+            .unwrap()
+            .with_timezone(&chrono::Local)
+            .date_naive();
+        let breakdown = db.get_title_breakdown("Firefox", today).unwrap();
+        assert_eq!(
+            breakdown,
+            vec![("Docs".to_string(), 420), ("News".to_string(), 60)]
+        );
+    }
+
+    #[test]
+    fn test_sessions_for_date() {
+        let db = test_db();
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+        insert_session_on(&db, "Firefox", day, 600);
+        insert_session_on(&db, "Code", day, 300);
+        insert_session_on(&db, "Firefox", day.succ_opt().unwrap(), 120);
+
+        let sessions = db.get_sessions_for_date("2024-03-09").unwrap();
+        assert_eq!(sessions.len(), 2);
+        let mut names: Vec<&str> = sessions.iter().map(|s| s.app_name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["Code", "Firefox"]);
+        assert!(sessions.iter().all(|s| s.duration_seconds >= 300));
+
+        assert!(db.get_sessions_for_date("2024-03-11").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_usage_for_yesterday() {
+        let db = test_db();
+        let today = chrono::Local::now().date_naive();
+        let yesterday = today.pred_opt().unwrap();
+        insert_session_on(&db, "Firefox", yesterday, 600);
+        insert_session_on(
+            &db,
+            "Code",
+            today.pred_opt().unwrap().pred_opt().unwrap(),
+            300,
+        );
+
+        let usage = db.get_daily_usage_for(yesterday).unwrap();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].app_name, "Firefox");
+        assert_eq!(usage[0].duration_seconds, 600);
+    }
+
+    #[test]
+    fn test_usage_for_past_date() {
+        let db = test_db();
+        let date = chrono::NaiveDate::from_ymd_opt(2025, 6, 15).unwrap();
+        insert_session_on(&db, "Firefox", date, 600);
+        insert_session_on(&db, "Firefox", date, 120);
+        insert_session_on(&db, "Slack", date.succ_opt().unwrap(), 300);
+
+        let usage = db.get_daily_usage_for(date).unwrap();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].duration_seconds, 720);
+        assert_eq!(usage[0].session_count, 2);
+        assert!(db
+            .get_daily_usage_for(date.pred_opt().unwrap())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_idle_ratio_by_reason() {
+        let db = test_db();
+        let date = chrono::NaiveDate::from_ymd_opt(2025, 6, 15).unwrap();
+        insert_session_on(&db, "Firefox", date, 3600);
+
+        let morning = local_time(date, 9, 0);
+        db.record_idle_period(IdleReason::Suspended, morning, morning + 1800)
+            .unwrap();
+        let away = db
+            .start_idle_period(IdleReason::Away, morning + 3600)
+            .unwrap();
+        db.end_idle_period(away, morning + 4200).unwrap();
+        db.record_idle_period(IdleReason::Locked, morning + 5000, morning + 5600)
+            .unwrap();
+        // Another day's idle time isn't counted
+        db.record_idle_period(IdleReason::Away, morning - 86400, morning - 80000)
+            .unwrap();
+
+        let ratio = db.get_idle_ratio_for(date).unwrap();
+        assert_eq!(ratio.active_seconds, 3600);
+        assert_eq!(ratio.idle_seconds, 3000);
+        assert!((ratio.idle_ratio - 3000.0 / 6600.0).abs() < 1e-9);
+        assert_eq!(ratio.by_reason[0].reason, IdleReason::Suspended);
+        assert_eq!(ratio.by_reason[0].seconds, 1800);
+        assert_eq!(ratio.by_reason.len(), 3);
+
+        let empty = db.get_idle_ratio_for(date.succ_opt().unwrap()).unwrap();
+        assert_eq!(empty.idle_ratio, 0.0);
+    }
+
+    #[test]
+    fn test_open_idle_periods_closed_at_startup() {
+        let db = test_db();
+        let now = chrono::Local::now().timestamp();
+        let today = chrono::Local::now().date_naive();
+
+        // A zero-length period is closed, not open
+        db.record_idle_period(IdleReason::Locked, now, now).unwrap();
+        let open = db.start_idle_period(IdleReason::Away, now - 120).unwrap();
+        // Open periods count up to now
+        assert!(db.get_idle_ratio_for(today).unwrap().idle_seconds >= 120);
+
+        assert_eq!(db.close_open_idle_periods().unwrap(), 1);
+        assert_eq!(db.close_open_idle_periods().unwrap(), 0);
+        assert_eq!(db.get_idle_ratio_for(today).unwrap().idle_seconds, 0);
+
+        // A closed period isn't reopened or moved by a late end
+        db.end_idle_period(open, now).unwrap();
+        assert_eq!(db.get_idle_ratio_for(today).unwrap().idle_seconds, 0);
+    }
+
+    #[test]
+    fn test_delete_expired_emergency_grants() {
+        let db = test_db();
+        let grant = |granted_at: i64, expires_at: i64| EmergencyGrant {
+            app_name: "Firefox".to_string(),
+            granted_at,
+            expires_at,
+        };
+        db.record_emergency_grant(&grant(1_000, 1_600)).unwrap();
+        db.record_emergency_grant(&grant(5_000, 5_600)).unwrap();
+
+        assert_eq!(db.delete_emergency_grants_expired_before(5_000).unwrap(), 1);
+        assert_eq!(
+            db.get_emergency_grants_since(0).unwrap(),
+            vec![grant(5_000, 5_600)]
+        );
+    }
+
+    fn busy_error() -> rusqlite::Error {
+        rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY), None)
+    }
+
+    #[test]
+    fn test_busy_write_is_retried() {
+        let mut calls = 0;
+        let result = with_busy_retry(|| {
+            calls += 1;
+            if calls < BUSY_RETRY_ATTEMPTS {
+                Err(busy_error())
+            } else {
+                Ok(calls)
+            }
+        });
+
+        assert_eq!(result.unwrap(), BUSY_RETRY_ATTEMPTS);
+    }
+
+    #[test]
+    fn test_stuck_database_still_errors() {
+        let mut calls = 0;
+        let result: SqliteResult<()> = with_busy_retry(|| {
+            calls += 1;
+            Err(busy_error())
+        });
+
+        assert!(result.is_err_and(|e| is_busy_error(&e)));
+        assert_eq!(calls, BUSY_RETRY_ATTEMPTS);
+    }
+
+    #[test]
+    fn test_other_errors_are_not_retried() {
+        let mut calls = 0;
+        let result: SqliteResult<()> = with_busy_retry(|| {
+            calls += 1;
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_write_waits_out_a_lock_held_by_another_connection() {
+        let path = TempPath::new("busy.db");
+        let db = Database::new(path.to_path_buf()).unwrap();
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+
+        let other = Connection::open(&path).unwrap();
+        other.execute_batch("BEGIN IMMEDIATE").unwrap();
+        let holder = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            other.execute_batch("COMMIT").unwrap();
+        });
+
+        assert!(db.start_session(app_id, 1_700_000_000).is_ok());
+        holder.join().unwrap();
+    }
+
+    #[test]
+    fn test_two_connections_read_and_write_concurrently() {
+        let path = TempPath::new("wal.db");
+        let db = Database::new(path.to_path_buf()).unwrap();
+        let journal_mode: String = db
+            .conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(journal_mode.to_lowercase(), "wal");
+
+        // Like the background tracker and the GUI, each with its own connection
+        let handles: Vec<_> = ["Firefox", "Slack"]
+            .into_iter()
+            .map(|app| {
+                let path = path.to_path_buf();
+                std::thread::spawn(move || {
+                    let db = Database::new(path).unwrap();
+                    let app_id = db.get_or_create_app(app, None).unwrap();
+                    for i in 0..50 {
+                        let start = 1_700_000_000 + i * 60;
+                        let session = db.start_session(app_id, start).unwrap();
+                        db.end_session(session, start + 30).unwrap();
+                        db.get_all_apps().unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let total: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM usage_sessions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(total, 100);
+    }
+
+    /// Insert a finished session starting just after local midnight today
+    fn insert_session_today(db: &Database, app_name: &str, seconds: i64) {
+        let app_id = db.get_or_create_app(app_name, None).unwrap();
+        let start = chrono::Local::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 1)
+            .unwrap()
+            .and_local_timezone(chrono::Local)
+            .earliest()
+            .unwrap()
+            .timestamp();
+        let session = db.start_session(app_id, start).unwrap();
+        db.end_session(session, start + seconds).unwrap();
+    }
+
+    #[test]
+    fn test_usage_in_overnight_hour_window() {
+        let db = test_db();
+        let day = chrono::Local::now().date_naive() - chrono::Duration::days(3);
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+        let record = |start: i64, seconds: i64| {
+            let session = db.start_session(app_id, start).unwrap();
+            db.end_session(session, start + seconds).unwrap();
+        };
+
+        // 05:30-06:30: half inside the early part of the window
+        record(local_time(day, 5, 30), 3600);
+        // 12:00-13:00: outside
+        record(local_time(day, 12, 0), 3600);
+        // 22:45-23:15: the last 15 minutes are inside
+        record(local_time(day, 22, 45), 1800);
+        // 23:50 until 00:20 the next day: only the 10 minutes before midnight count
+        record(local_time(day, 23, 50), 1800);
+
+        assert_eq!(
+            db.get_usage_in_hour_window(day, 23, 6).unwrap(),
+            30 * 60 + 15 * 60 + 10 * 60
+        );
+        // The next day sees the 20 minutes after midnight
+        assert_eq!(
+            db.get_usage_in_hour_window(day + chrono::Duration::days(1), 23, 6)
+                .unwrap(),
+            20 * 60
+        );
+        // A daytime window
+        assert_eq!(db.get_usage_in_hour_window(day, 12, 13).unwrap(), 3600);
+        assert_eq!(db.get_usage_in_hour_window(day, 14, 22).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_limit_remaining_sorted_by_usage() {
+        let db = test_db();
+        for app in ["Firefox", "Slack", "Reddit"] {
+            db.set_limit(app, 10).unwrap();
+        }
+        insert_session_today(&db, "Firefox", 5 * 60);
+        insert_session_today(&db, "Slack", 9 * 60);
+        insert_session_today(&db, "Reddit", 12 * 60);
+
+        let remaining = db.get_limit_remaining().unwrap();
+        assert_eq!(
+            remaining,
+            vec![
+                ("Reddit".to_string(), -2, 1.2),
+                ("Slack".to_string(), 1, 0.9),
+                ("Firefox".to_string(), 5, 0.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_excluded_apps_and_purge() {
+        let db = test_db();
+        insert_session_today(&db, "KeePassXC", 300);
+        insert_session_today(&db, "Firefox", 600);
+
+        db.add_excluded_app("KeePassXC").unwrap();
+        db.add_excluded_app("KeePassXC").unwrap();
+        assert_eq!(db.get_excluded_apps().unwrap(), vec!["KeePassXC"]);
+        assert!(db.is_app_excluded("KeePassXC").unwrap());
+        assert!(!db.is_app_excluded("Firefox").unwrap());
+
+        assert_eq!(db.purge_app_sessions("KeePassXC").unwrap(), 1);
+        assert_eq!(db.get_daily_summary().unwrap().total_seconds, 600);
+
+        assert!(db.remove_excluded_app("KeePassXC").unwrap());
+        assert!(!db.remove_excluded_app("KeePassXC").unwrap());
+        assert!(db.get_excluded_apps().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_global_cap_ignores_excluded_apps() {
+        let db = test_db();
+        db.set_global_cap(60, true).unwrap();
+        db.set_app_cap_excluded("Terminal", true).unwrap();
+        insert_session_today(&db, "Terminal", 7200);
+        insert_session_today(&db, "Firefox", 1800);
+
+        let cap = db.get_global_cap_status().unwrap().unwrap();
+        assert_eq!(cap.used_seconds, 1800);
+        assert!(!cap.exceeded);
+        assert_eq!(cap.excluded_apps, vec!["Terminal"]);
+        assert!(!db.is_app_blocked("Firefox").unwrap());
+
+        insert_session_today(&db, "Slack", 2400);
+        let cap = db.get_global_cap_status().unwrap().unwrap();
+        assert_eq!(cap.used_seconds, 4200);
+        assert!(cap.exceeded);
+        assert!(db.is_app_blocked("Firefox").unwrap());
+        assert!(!db.is_app_blocked("Terminal").unwrap());
+    }
+
+    #[test]
+    fn test_global_cap_without_blocking() {
+        let db = test_db();
+        db.set_global_cap(1, false).unwrap();
+        insert_session_today(&db, "Firefox", 600);
+
+        assert!(db.get_global_cap_status().unwrap().unwrap().exceeded);
+        assert!(!db.is_app_blocked("Firefox").unwrap());
+
+        db.remove_global_cap().unwrap();
+        assert!(db.get_global_cap_status().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_focus_history_round_trip() {
+        let db = test_db();
+        let entries = [
+            FocusHistoryEntry {
+                start_time: 2_000,
+                end_time: 3_500,
+                planned_minutes: Some(25),
+                completed: true,
+                schedule_name: None,
+            },
+            FocusHistoryEntry {
+                start_time: 1_000,
+                end_time: 1_300,
+                planned_minutes: None,
+                completed: false,
+                schedule_name: Some("Mornings".to_string()),
+            },
+        ];
+        for entry in &entries {
+            db.record_focus_session(entry).unwrap();
+        }
+
+        let history = db.get_focus_history().unwrap();
+        assert_eq!(history.len(), 2);
+        let in_range = db.get_focus_history_in_range(1_500, 2_500).unwrap();
+        assert_eq!(in_range.len(), 1);
+        assert_eq!(in_range[0].start_time, 2_000);
+        assert_eq!(history[0].schedule_name.as_deref(), Some("Mornings"));
+        assert!(!history[0].completed);
+        assert_eq!(history[1].planned_minutes, Some(25));
+        assert!(history[1].completed);
+    }
+
+    #[test]
+    fn test_custom_icon_overrides_scanned_icon() {
+        let db = test_db();
+        let date = chrono::Local::now().date_naive();
+        insert_session_on(&db, "Firefox", date, 60);
+        insert_session_on(&db, "Terminal", date, 60);
+        db.conn
+            .execute(
+                "UPDATE apps SET icon_path = '/usr/share/icons/firefox.png' WHERE name = 'Firefox'",
+                [],
+            )
+            .unwrap();
+
+        let icon_of = |db: &Database, name: &str| {
+            let app = db
+                .get_all_apps()
+                .unwrap()
+                .into_iter()
+                .find(|a| a.name == name)
+                .unwrap();
+            let usage = db
+                .get_daily_usage_for(date)
+                .unwrap()
+                .into_iter()
+                .find(|a| a.app_name == name)
+                .unwrap();
+            assert_eq!(app.icon_path, usage.icon);
+            (app.icon_path, app.has_custom_icon)
+        };
+
+        assert_eq!(icon_of(&db, "Terminal"), (None, false));
+        assert_eq!(
+            icon_of(&db, "Firefox"),
+            (Some("/usr/share/icons/firefox.png".to_string()), false)
+        );
+
+        assert!(db
+            .set_app_icon("Firefox", Some("data:image/png;base64,AAAA"))
+            .unwrap());
+        assert_eq!(
+            icon_of(&db, "Firefox"),
+            (Some("data:image/png;base64,AAAA".to_string()), true)
+        );
+
+        assert!(db.set_app_icon("Firefox", None).unwrap());
+        assert_eq!(
+            icon_of(&db, "Firefox"),
+            (Some("/usr/share/icons/firefox.png".to_string()), false)
+        );
+        assert!(!db.set_app_icon("Missing", None).unwrap());
+    }
+
+    #[test]
+    fn test_set_app_categories_creates_missing_apps() {
+        let mut db = test_db();
+        insert_session_today(&db, "Firefox", 60);
+
+        db.set_app_categories(&[
+            ("Firefox".to_string(), "Productivity".to_string()),
+            ("Obsidian".to_string(), "Notes".to_string()),
+        ])
+        .unwrap();
+
+        let categories: Vec<(String, Option<String>)> = db
+            .conn
+            .prepare("SELECT name, category FROM apps ORDER BY name")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<SqliteResult<_>>()
+            .unwrap();
+        assert_eq!(
+            categories,
+            vec![
+                ("Firefox".to_string(), Some("Productivity".to_string())),
+                ("Obsidian".to_string(), Some("Notes".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_records_on_empty_database() {
+        let db = test_db();
+        assert!(db.get_longest_session().unwrap().is_none());
+
+        let records = db.get_records().unwrap();
+        assert!(records.longest_session.is_none());
+        assert!(records.busiest_day.is_none());
+        assert!(records.most_used_app.is_none());
+    }
+
+    #[test]
+    fn test_records_pick_the_largest_values() {
+        let db = test_db();
+        let day = 86_400;
+        // Noon ten days ago, so a day's sessions can't straddle midnight
+        let base = (chrono::Local::now() - chrono::Duration::days(10))
+            .date_naive()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_local_timezone(chrono::Local)
+            .earliest()
+            .unwrap()
+            .timestamp();
+        let add = |app: &str, start: i64, seconds: i64| {
+            let app_id = db.get_or_create_app(app, None).unwrap();
+            let session = db.start_session(app_id, start).unwrap();
+            db.end_session(session, start + seconds).unwrap();
+        };
+
+        let local_date = |timestamp: i64| {
+            chrono::DateTime::from_timestamp(timestamp, 0)
+                .unwrap()
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d")
+                .to_string()
+        };
+
+        add("Firefox", base, 1_800);
+        add("Firefox", base + 3_600, 1_500);
+        add("Code", base + day, 2_700);
+        add("Code", base + 2 * day, 900);
+        add("Slack", base + 2 * day + 3_600, 300);
+
+        let longest = db.get_longest_session().unwrap().unwrap();
+        assert_eq!(longest.session.app_name, "Code");
+        assert_eq!(longest.session.duration_seconds, 2_700);
+        assert_eq!(longest.date, local_date(base + day));
+
+        let records = db.get_records().unwrap();
+        let busiest = records.busiest_day.unwrap();
+        assert_eq!(busiest.total_seconds, 3_300);
+        assert_eq!(busiest.date, local_date(base));
+        let most_used = records.most_used_app.unwrap();
+        assert_eq!(most_used.app_name, "Code");
+        assert_eq!(most_used.total_seconds, 3_600);
+    }
+
+    #[test]
+    fn test_meta_round_trip() {
+        let db = test_db();
+        assert_eq!(db.get_meta("last_auto_export_date").unwrap(), None);
+
+        db.set_meta("last_auto_export_date", "2024-03-09").unwrap();
+        db.set_meta("last_auto_export_date", "2024-03-10").unwrap();
+
+        assert_eq!(
+            db.get_meta("last_auto_export_date").unwrap().as_deref(),
+            Some("2024-03-10")
+        );
+    }
+
+    #[test]
+    fn test_weekend_limit_applies_on_weekends() {
+        use chrono::Weekday;
+        let db = test_db();
+        db.set_limit_with_schedule("Reddit", 30, Some(120), true)
+            .unwrap();
+        db.set_limit_with_block("YouTube", 45, true).unwrap();
+        insert_session_today(&db, "Reddit", 60 * 60);
+
+        let limit_on = |weekday| -> HashMap<String, i32> {
+            db.get_all_limit_status_on(weekday)
+                .unwrap()
+                .into_iter()
+                .map(|(name, limit, _, _)| (name, limit))
+                .collect()
+        };
+        assert_eq!(limit_on(Weekday::Mon)["Reddit"], 30);
+        assert_eq!(limit_on(Weekday::Sat)["Reddit"], 120);
+        // No weekend limit: the daily limit applies every day
+        assert_eq!(limit_on(Weekday::Mon)["YouTube"], 45);
+        assert_eq!(limit_on(Weekday::Sun)["YouTube"], 45);
+
+        assert!(db.is_app_blocked_on("Reddit", Weekday::Mon).unwrap());
+        assert!(!db.is_app_blocked_on("Reddit", Weekday::Sat).unwrap());
+
+        let limits = db.get_all_limits().unwrap();
+        let reddit = limits.iter().find(|l| l.app_name == "Reddit").unwrap();
+        assert_eq!(reddit.weekend_limit_minutes, Some(120));
+    }
+
+    #[test]
+    fn test_category_limit_blocks_every_app_in_category() {
+        let db = test_db();
+        for (app, category) in [
+            ("Reddit", "Social"),
+            ("Twitter", "Social"),
+            ("Code", "Development"),
+        ] {
+            db.get_or_create_app(app, None).unwrap();
+            db.set_app_category(app, category).unwrap();
+        }
+        db.set_category_limit("Social", 60, true).unwrap();
+        db.set_category_limit("Uncategorized", 30, false).unwrap();
+
+        insert_session_today(&db, "Reddit", 40 * 60);
+        insert_session_today(&db, "Code", 90 * 60);
+        assert!(!db.is_app_blocked("Reddit").unwrap());
+
+        // Together the category crosses its hour
+        insert_session_today(&db, "Twitter", 20 * 60);
+        assert!(db.is_app_blocked("Reddit").unwrap());
+        assert!(db.is_app_blocked("Twitter").unwrap());
+        assert!(!db.is_app_blocked("Code").unwrap());
+        assert_eq!(db.get_blocked_apps().unwrap(), vec!["Reddit", "Twitter"]);
+
+        insert_session_today(&db, "Notes", 45 * 60);
+        let status = db.get_category_limit_status().unwrap();
+        assert_eq!(status.len(), 2);
+        assert_eq!(status[0].category, "Social");
+        assert_eq!(status[0].used_seconds, 60 * 60);
+        assert!(status[0].exceeded);
+        // Over the limit, but it doesn't block
+        assert!(status[1].exceeded);
+        assert!(!db.is_app_blocked("Notes").unwrap());
+
+        db.remove_category_limit("Social").unwrap();
+        assert!(!db.is_app_blocked("Reddit").unwrap());
+        assert!(db.get_blocked_apps().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delete_category_removes_its_limit() {
+        let mut db = test_db();
+        db.upsert_category(&Category {
+            name: "Social".to_string(),
+            color: "#ff0000".to_string(),
+            is_productive: false,
+        })
+        .unwrap();
+        db.set_category_limit("Social", 60, true).unwrap();
+        db.set_category_limit("Games", 30, true).unwrap();
+
+        assert!(db.delete_category("Social").unwrap());
+
+        let remaining: Vec<String> = db
+            .get_category_limit_status()
+            .unwrap()
+            .into_iter()
+            .map(|status| status.category)
+            .collect();
+        assert_eq!(remaining, vec!["Games"]);
+    }
+
+    #[test]
+    fn test_focus_reward_raises_effective_limit_for_today() {
+        let mut db = test_db();
+        db.set_limit_with_block("YouTube", 30, true).unwrap();
+        db.set_limit_with_block("Steam", 60, true).unwrap();
+        db.set_app_category("Steam", "Gaming").unwrap();
+        db.set_limit_with_block("Code", 120, false).unwrap();
+        insert_session_today(&db, "YouTube", 35 * 60);
+        assert!(db.is_app_blocked("YouTube").unwrap());
+
+        let reward = FocusReward {
+            enabled: true,
+            bonus_minutes: 15,
+            apps: vec!["YouTube".to_string()],
+            categories: vec!["Gaming".to_string()],
+        };
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let granted = db.grant_focus_reward(&reward, &today).unwrap();
+        assert_eq!(granted, vec!["Steam".to_string(), "YouTube".to_string()]);
+
+        let limits: std::collections::HashMap<String, i32> = db
+            .get_all_limit_status()
+            .unwrap()
+            .into_iter()
+            .map(|(name, limit, _, _)| (name, limit))
+            .collect();
+        assert_eq!(limits["YouTube"], 45);
+        assert_eq!(limits["Steam"], 75);
+        assert_eq!(limits["Code"], 120);
+        assert!(!db.is_app_blocked("YouTube").unwrap());
+        assert!(db.get_blocked_apps().unwrap().is_empty());
+
+        let audit = db.get_limit_bonuses(&today).unwrap();
+        assert_eq!(audit.len(), 2);
+        assert!(audit
+            .iter()
+            .all(|b| b.reason == "focus_reward" && b.minutes == 15));
+    }
+
+    #[test]
+    fn test_focus_reward_resets_at_rollover() {
+        let mut db = test_db();
+        db.set_limit_with_block("YouTube", 30, true).unwrap();
+        let reward = FocusReward {
+            enabled: true,
+            bonus_minutes: 15,
+            apps: vec!["YouTube".to_string()],
+            categories: vec![],
+        };
+        let yesterday = (chrono::Local::now() - chrono::Duration::days(1))
+            .format("%Y-%m-%d")
+            .to_string();
+        db.grant_focus_reward(&reward, &yesterday).unwrap();
+
+        let status = db.get_all_limit_status().unwrap();
+        assert_eq!(status[0].1, 30);
+        assert_eq!(db.get_limit_bonuses(&yesterday).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_category_app_breakdown_nests_apps() {
+        let db = test_db();
+        insert_session_today(&db, "Code", 3_000);
+        insert_session_today(&db, "Terminal", 600);
+        insert_session_today(&db, "Code", 400);
+        insert_session_today(&db, "Slack", 900);
+        insert_session_today(&db, "Mystery", 100);
+        db.set_app_category("Code", "Development").unwrap();
+        db.set_app_category("Terminal", "Development").unwrap();
+        db.set_app_category("Slack", "Communication").unwrap();
+
+        let breakdown = db
+            .get_category_app_breakdown(chrono::Local::now().date_naive())
+            .unwrap();
+
+        let summary: Vec<(&str, i64, Vec<(&str, i64)>)> = breakdown
+            .iter()
+            .map(|c| {
+                (
+                    c.category.as_str(),
+                    c.total_seconds,
+                    c.apps
+                        .iter()
+                        .map(|a| (a.app_name.as_str(), a.duration_seconds))
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "Development",
+                    4_000,
+                    vec![("Code", 3_400), ("Terminal", 600)]
+                ),
+                ("Communication", 900, vec![("Slack", 900)]),
+                ("Uncategorized", 100, vec![("Mystery", 100)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_daily_streak_over_known_pattern() {
+        let db = test_db();
+        let today = chrono::Local::now().date_naive();
+        let first = today - chrono::Duration::days(9);
+        let start = first
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_local_timezone(chrono::Local)
+            .earliest()
+            .unwrap()
+            .timestamp();
+        let app_id = db.get_or_create_app("Code", None).unwrap();
+        let session = db.start_session(app_id, start).unwrap();
+        db.end_session(session, start + 60).unwrap();
+
+        // Days 0..=9 from the first day: a run of 4, a gap, then 3 up to yesterday
+        let pattern = [
+            true, true, true, true, false, false, true, true, true, false,
+        ];
+        let qualifies = |day: chrono::NaiveDate| pattern[(day - first).num_days() as usize];
+
+        let streak = db.compute_daily_streak(today, qualifies).unwrap();
+        assert_eq!(
+            streak,
+            DailyStreak {
+                current: 3,
+                longest: 4
+            }
+        );
+
+        // Once today qualifies it extends the current streak
+        let streak = db.compute_daily_streak(today, |_| true).unwrap();
+        assert_eq!(
+            streak,
+            DailyStreak {
+                current: 10,
+                longest: 10
+            }
+        );
+
+        // Missing yesterday restarts the current streak at today
+        let streak = db
+            .compute_daily_streak(today, |day| day != today - chrono::Duration::days(1))
+            .unwrap();
+        assert_eq!(
+            streak,
+            DailyStreak {
+                current: 1,
+                longest: 8
+            }
+        );
+    }
+
+    #[test]
+    fn test_compute_daily_streak_without_history() {
+        let db = test_db();
+        let streak = db
+            .compute_daily_streak(chrono::Local::now().date_naive(), |_| true)
+            .unwrap();
+        assert_eq!(streak, DailyStreak::default());
+    }
+
+    #[test]
+    fn test_save_goals_state_drops_removed_goals() {
+        let mut db = test_db();
+        let mut state = GoalsState::new();
+        for id in ["first", "second"] {
+            state.add_goal(Goal {
+                id: id.to_string(),
+                name: id.to_string(),
+                goal_type: crate::goals::GoalType::DailyLimit,
+                target_minutes: 60,
+                days: vec![],
+                enabled: true,
+                created_at: "2024-03-01".to_string(),
+                start_date: None,
+                end_date: None,
+                count_weekends: None,
+                period: crate::goals::GoalPeriod::Daily,
+                notify: false,
+            });
+        }
+        db.save_goals_state(&state).unwrap();
+
+        state.goals.retain(|goal| goal.id == "second");
+        db.save_goals_state(&state).unwrap();
+        // Saving unchanged state again is a no-op
+        db.save_goals_state(&state).unwrap();
+
+        let loaded = db.load_goals_state().unwrap();
+        let ids: Vec<&str> = loaded.goals.iter().map(|g| g.id.as_str()).collect();
+        assert_eq!(ids, ["second"]);
+    }
+
+    #[test]
+    fn test_goals_state_survives_restart() {
+        use crate::goals::{GoalType, WeekStart};
+
+        let path = TempPath::new("goals.db");
+        let mut db = Database::new(path.to_path_buf()).unwrap();
+        assert!(db.load_goals_state().unwrap().goals.is_empty());
+
+        let mut state = GoalsState::new();
+        state.add_goal(Goal {
+            id: "limit".to_string(),
+            name: "Under 2 hours".to_string(),
+            goal_type: GoalType::DailyLimit,
+            target_minutes: 120,
+            days: vec![],
+            enabled: true,
+            created_at: "2024-03-01".to_string(),
+            start_date: None,
+            end_date: None,
+            count_weekends: None,
+            period: crate::goals::GoalPeriod::Daily,
+            notify: false,
+        });
+        state.current_streak = 4;
+        state.longest_streak = 9;
+        state.total_goals_met = 20;
+        state.focus_sessions_completed = 3;
+        state.weekends_count = false;
+        state.week_start = WeekStart::Sunday;
+        db.save_goals_state(&state).unwrap();
+        drop(db);
+
+        let db = Database::new(path.to_path_buf()).unwrap();
+        let loaded = db.load_goals_state().unwrap();
+        assert_eq!(loaded.goals.len(), 1);
+        assert_eq!(loaded.goals[0].id, "limit");
+        assert!(!loaded.goals[0].notify);
+        assert_eq!(loaded.current_streak, 4);
+        assert_eq!(loaded.longest_streak, 9);
+        assert_eq!(loaded.total_goals_met, 20);
+        assert_eq!(loaded.focus_sessions_completed, 3);
+        assert!(!loaded.weekends_count);
+        assert_eq!(loaded.week_start, WeekStart::Sunday);
+        assert!(loaded.achievements["first_goal"].earned_at.is_some());
     }
 }
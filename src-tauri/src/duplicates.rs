@@ -0,0 +1,173 @@
+//! Detecting tracked apps that are probably the same program
+//!
+//! The same app can end up tracked under several names, e.g. "chrome" from an
+//! X11 class and "google-chrome" from a Wayland app id. Names are grouped when
+//! they normalize to the same string, are known aliases in `APP_MAPPINGS`, or
+//! are within a small edit distance of each other. Nothing is changed; the
+//! groups are only reported.
+
+use crate::window_tracker::mapped_display_name;
+use serde::{Deserialize, Serialize};
+
+/// Names may differ by at most one edit per this many characters
+const CHARS_PER_EDIT: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateApp {
+    pub name: String,
+    pub total_seconds: i64,
+}
+
+/// Names that likely refer to one program, most used first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub apps: Vec<DuplicateApp>,
+    pub total_seconds: i64,
+}
+
+/// Lowercase alphanumerics only, so "Google Chrome" and "google-chrome" match
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+fn likely_same(a: &str, b: &str) -> bool {
+    let (norm_a, norm_b) = (normalize(a), normalize(b));
+    if norm_a.is_empty() || norm_b.is_empty() {
+        return false;
+    }
+    if norm_a == norm_b {
+        return true;
+    }
+    if let (Some(alias_a), Some(alias_b)) = (mapped_display_name(a), mapped_display_name(b)) {
+        if alias_a == alias_b {
+            return true;
+        }
+    }
+
+    let allowed = norm_a.chars().count().min(norm_b.chars().count()) / CHARS_PER_EDIT;
+    allowed > 0 && edit_distance(&norm_a, &norm_b) <= allowed
+}
+
+fn find_root(parents: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[i] = root;
+    root
+}
+
+/// Group apps (name, total seconds) that likely refer to the same program.
+/// Apps with no likely duplicate are left out. Largest groups by usage first.
+pub fn find_duplicate_groups(apps: &[(String, i64)]) -> Vec<DuplicateGroup> {
+    let mut parents: Vec<usize> = (0..apps.len()).collect();
+    for (i, (name_a, _)) in apps.iter().enumerate() {
+        for (j, (name_b, _)) in apps.iter().enumerate().skip(i + 1) {
+            if likely_same(name_a, name_b) {
+                let (root_i, root_j) = (find_root(&mut parents, i), find_root(&mut parents, j));
+                parents[root_j] = root_i;
+            }
+        }
+    }
+
+    let mut groups: std::collections::HashMap<usize, Vec<DuplicateApp>> =
+        std::collections::HashMap::new();
+    for (i, (name, total_seconds)) in apps.iter().enumerate() {
+        let root = find_root(&mut parents, i);
+        groups.entry(root).or_default().push(DuplicateApp {
+            name: name.clone(),
+            total_seconds: *total_seconds,
+        });
+    }
+
+    let mut result: Vec<DuplicateGroup> = groups
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .map(|mut members| {
+            members.sort_by(|a, b| {
+                b.total_seconds
+                    .cmp(&a.total_seconds)
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+            DuplicateGroup {
+                total_seconds: members.iter().map(|app| app.total_seconds).sum(),
+                apps: members,
+            }
+        })
+        .collect();
+    result.sort_by(|a, b| {
+        b.total_seconds
+            .cmp(&a.total_seconds)
+            .then_with(|| a.apps[0].name.cmp(&b.apps[0].name))
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apps(list: &[(&str, i64)]) -> Vec<(String, i64)> {
+        list.iter()
+            .map(|(name, seconds)| (name.to_string(), *seconds))
+            .collect()
+    }
+
+    fn names(group: &DuplicateGroup) -> Vec<&str> {
+        group.apps.iter().map(|app| app.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_chrome_variants_are_grouped() {
+        let groups = find_duplicate_groups(&apps(&[
+            ("chrome", 600),
+            ("Firefox", 5_000),
+            ("Chrome", 3_000),
+            ("Slack", 1_200),
+            ("google-chrome", 900),
+        ]));
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(names(&groups[0]), vec!["Chrome", "google-chrome", "chrome"]);
+        assert_eq!(groups[0].total_seconds, 4_500);
+    }
+
+    #[test]
+    fn test_unrelated_apps_are_not_grouped() {
+        let groups = find_duplicate_groups(&apps(&[
+            ("Firefox", 100),
+            ("Slack", 100),
+            ("Chromium", 100),
+            ("Chrome", 100),
+            ("Zed", 100),
+            ("Ted", 100),
+        ]));
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn test_small_typos_in_long_names_are_grouped() {
+        let groups =
+            find_duplicate_groups(&apps(&[("Logseq Desktop", 100), ("logseq-desktp", 50)]));
+        assert_eq!(groups.len(), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
@@ -14,15 +14,12 @@ pub enum WellbeingError {
     InvalidAppName(String),
 
     #[error("App not found: {0}")]
-    #[allow(dead_code)]
     AppNotFound(String),
 
     #[error("Limit not found for app: {0}")]
-    #[allow(dead_code)]
     LimitNotFound(String),
 
     #[error("Configuration error: {0}")]
-    #[allow(dead_code)]
     Config(String),
 
     #[error("Window tracker error: {0}")]
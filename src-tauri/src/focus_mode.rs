@@ -1,8 +1,13 @@
-use chrono::{Datelike, Local, NaiveTime};
+use crate::settings_store;
+use crate::settings_validation::{self, SettingsRepair};
+use crate::settings_version::VersionedSettings;
+use chrono::{Datelike, Local, NaiveDate, NaiveTime, TimeZone};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use tauri::AppHandle;
 use tokio::sync::Mutex;
 
 /// Focus mode settings
@@ -20,6 +25,22 @@ pub struct FocusSettings {
     pub block_notifications: bool,
     /// Scheduled focus sessions
     pub schedules: Vec<FocusSchedule>,
+    /// Bonus limit minutes granted for completing a session
+    #[serde(default)]
+    pub reward: FocusReward,
+    /// Whether manual sessions block `blocked_apps` or everything but `allowed_apps`
+    #[serde(default)]
+    pub mode: FocusMode,
+    /// Apps left usable by manual sessions in allow-list mode
+    #[serde(default)]
+    pub allowed_apps: Vec<String>,
+    /// Settings format version, see `settings_version`
+    #[serde(default = "current_settings_version")]
+    pub version: u32,
+}
+
+fn current_settings_version() -> u32 {
+    <FocusSettings as VersionedSettings>::CURRENT_VERSION
 }
 
 impl Default for FocusSettings {
@@ -31,6 +52,32 @@ impl Default for FocusSettings {
             notify_on_end: true,
             block_notifications: true,
             schedules: vec![],
+            reward: FocusReward::default(),
+            mode: FocusMode::BlockList,
+            allowed_apps: vec![],
+            version: current_settings_version(),
+        }
+    }
+}
+
+/// Opt-in reward for completing a focus session: extra minutes on the daily
+/// limits of the chosen apps and categories, for the rest of the day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusReward {
+    pub enabled: bool,
+    pub bonus_minutes: u32,
+    pub apps: Vec<String>,
+    /// Every app in these categories gets the bonus
+    pub categories: Vec<String>,
+}
+
+impl Default for FocusReward {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bonus_minutes: 10,
+            apps: vec![],
+            categories: vec![],
         }
     }
 }
@@ -114,6 +161,22 @@ pub struct FocusSession {
     pub is_scheduled: bool,
     /// The schedule name if this is a scheduled session
     pub schedule_name: Option<String>,
+    /// Strict sessions can't be stopped before their end time
+    #[serde(default)]
+    pub strict: bool,
+    /// Whether `blocked_apps` or everything but `allowed_apps` is blocked
+    #[serde(default)]
+    pub mode: FocusMode,
+    /// Apps left usable in allow-list mode
+    #[serde(default)]
+    pub allowed_apps: Vec<String>,
+}
+
+impl FocusSession {
+    /// Whether this is a strict session that hasn't reached its end time yet
+    pub fn is_strict_locked(&self, now: i64) -> bool {
+        self.is_active && self.strict && self.end_time.is_some_and(|end| now < end)
+    }
 }
 
 impl Default for FocusSession {
@@ -127,7 +190,76 @@ impl Default for FocusSession {
             blocked_apps: vec![],
             is_scheduled: false,
             schedule_name: None,
+            strict: false,
+            mode: FocusMode::BlockList,
+            allowed_apps: vec![],
+        }
+    }
+}
+
+/// How a focus session decides which apps to block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusMode {
+    /// Block the listed apps
+    #[default]
+    BlockList,
+    /// Block everything except the listed apps
+    AllowList,
+}
+
+/// Our own windows, which focus mode never blocks
+pub const ALWAYS_ALLOWED_APPS: [&str; 2] = ["Digital Wellbeing", "limit-popup"];
+
+/// Whether focus mode blocks `app_name`, given the mode and its app list
+pub fn focus_blocks_app(mode: FocusMode, apps: &[String], app_name: &str) -> bool {
+    if ALWAYS_ALLOWED_APPS.contains(&app_name) {
+        return false;
+    }
+    let listed = apps.iter().any(|app| app.eq_ignore_ascii_case(app_name));
+    match mode {
+        FocusMode::BlockList => listed,
+        FocusMode::AllowList => !listed,
+    }
+}
+
+/// Which of the running apps a focus session would block
+pub fn preview_blocked_apps(mode: FocusMode, apps: &[String], running: &[String]) -> Vec<String> {
+    running
+        .iter()
+        .filter(|app| focus_blocks_app(mode, apps, app))
+        .cloned()
+        .collect()
+}
+
+/// File in the config directory that focus settings and the running session
+/// are saved to
+const FOCUS_STATE_FILE: &str = "focus_settings.json";
+
+/// Focus settings and session as saved between launches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedFocusState {
+    pub settings: FocusSettings,
+    #[serde(default)]
+    pub session: Option<FocusSession>,
+}
+
+impl SavedFocusState {
+    /// Parse a saved state, upgrading settings written by older versions
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let mut value: serde_json::Value = serde_json::from_str(json)?;
+        if let Some(settings) = value.get_mut("settings") {
+            crate::settings_version::upgrade_settings::<FocusSettings>(settings);
         }
+        serde_json::from_value(value)
+    }
+
+    /// The session to resume at `now`: only a manual timed session that
+    /// hasn't ended. Schedules restart on their own at the next tick.
+    pub fn resumable_session(&self, now: i64) -> Option<FocusSession> {
+        self.session
+            .clone()
+            .filter(|s| s.is_active && !s.is_scheduled && s.end_time.is_some_and(|end| end > now))
     }
 }
 
@@ -138,24 +270,97 @@ pub struct FocusManager {
     session: Arc<Mutex<FocusSession>>,
     /// Track apps that were blocked by schedule (to restore when schedule ends)
     schedule_blocked_apps: Arc<Mutex<HashSet<String>>>,
+    /// File settings and the session are saved to; None keeps them in memory
+    state_path: Option<PathBuf>,
+    /// Set once the GUI is up; notifications then go through the plugin
+    app_handle: OnceLock<AppHandle>,
 }
 
 impl FocusManager {
-    pub fn new() -> Self {
+    /// Restore settings and any unfinished timed session saved by the last run
+    pub fn load() -> Self {
+        Self::with_state_path(settings_store::settings_path(FOCUS_STATE_FILE))
+    }
+
+    /// Manager saving to `state_path`; None keeps everything in memory
+    pub(crate) fn with_state_path(state_path: Option<PathBuf>) -> Self {
+        let saved = state_path
+            .as_ref()
+            .filter(|path| path.exists())
+            .and_then(|path| match std::fs::read_to_string(path) {
+                Ok(json) => SavedFocusState::from_json(&json)
+                    .map_err(|e| tracing::warn!(error = %e, "Ignoring unreadable focus settings"))
+                    .ok(),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to read focus settings");
+                    None
+                }
+            });
+
+        let (settings, session) = match saved {
+            Some(saved) => {
+                let session = saved.resumable_session(chrono::Utc::now().timestamp());
+                if session.is_some() {
+                    tracing::info!("Resuming focus session from the last run");
+                }
+                (saved.settings, session)
+            }
+            None => (FocusSettings::default(), None),
+        };
+
         Self {
-            settings: Arc::new(Mutex::new(FocusSettings::default())),
-            is_active: AtomicBool::new(false),
-            session: Arc::new(Mutex::new(FocusSession::default())),
+            settings: Arc::new(Mutex::new(settings)),
+            is_active: AtomicBool::new(session.is_some()),
+            session: Arc::new(Mutex::new(session.unwrap_or_default())),
             schedule_blocked_apps: Arc::new(Mutex::new(HashSet::new())),
+            state_path,
+            app_handle: OnceLock::new(),
+        }
+    }
+
+    /// Send notifications through the notification plugin from now on
+    pub fn set_app_handle(&self, handle: AppHandle) {
+        let _ = self.app_handle.set(handle);
+    }
+
+    /// Write settings and session to the state file, if there is one
+    fn persist(&self, settings: &FocusSettings, session: &FocusSession) {
+        let Some(path) = &self.state_path else {
+            return;
+        };
+        let state = SavedFocusState {
+            settings: settings.clone(),
+            session: session.is_active.then(|| session.clone()),
+        };
+        if let Err(e) = settings_store::save(path, &state) {
+            tracing::error!(error = %e, "Failed to save focus settings");
         }
     }
 
+    /// Persist the current state; must be called without holding either lock
+    async fn persist_current(&self) {
+        let settings = self.settings.lock().await;
+        let session = self.session.lock().await;
+        self.persist(&settings, &session);
+    }
+
     pub async fn get_settings(&self) -> FocusSettings {
         self.settings.lock().await.clone()
     }
 
-    pub async fn update_settings(&self, settings: FocusSettings) {
+    pub async fn update_settings(&self, mut settings: FocusSettings) {
+        let repairs = settings_validation::validate_focus_settings(&mut settings);
+        settings_validation::log_repairs(&repairs);
         *self.settings.lock().await = settings;
+        self.persist_current().await;
+    }
+
+    /// Validate the current settings in place, returning any repairs made
+    pub async fn validate_settings(&self) -> Vec<SettingsRepair> {
+        let mut settings = self.settings.lock().await;
+        let repairs = settings_validation::validate_focus_settings(&mut settings);
+        settings_validation::log_repairs(&repairs);
+        repairs
     }
 
     pub fn is_active(&self) -> bool {
@@ -175,15 +380,25 @@ impl FocusManager {
         session
     }
 
-    /// Start a focus session
+    /// Start a focus session. A strict session can't be stopped until its
+    /// timer ends; strict has no effect on sessions without a duration.
     pub async fn start_session(
         &self,
         duration_minutes: Option<u32>,
         blocked_apps: Option<Vec<String>>,
+        strict: bool,
     ) -> FocusSession {
         let settings = self.settings.lock().await;
         let now = chrono::Utc::now().timestamp();
 
+        {
+            let current = self.session.lock().await;
+            if current.is_strict_locked(now) {
+                tracing::info!("Refusing to replace strict focus session before it ends");
+                return current.clone();
+            }
+        }
+
         // Determine which apps to block
         let apps_to_block = blocked_apps.unwrap_or_else(|| settings.blocked_apps.clone());
 
@@ -203,10 +418,14 @@ impl FocusManager {
             blocked_apps: apps_to_block.clone(),
             is_scheduled: false,
             schedule_name: None,
+            strict: strict && duration.is_some(),
+            mode: settings.mode,
+            allowed_apps: settings.allowed_apps.clone(),
         };
 
         self.is_active.store(true, Ordering::SeqCst);
         *self.session.lock().await = session.clone();
+        self.persist(&settings, &session);
 
         // Send notification if enabled
         if settings.notify_on_start {
@@ -216,14 +435,28 @@ impl FocusManager {
         session
     }
 
-    /// Stop the current focus session
-    pub async fn stop_session(&self) -> FocusSession {
+    /// Stop the current focus session and return the session that ended.
+    /// Returns None when no session was running, so a session stopped twice
+    /// at once ends only once. A strict session that hasn't reached its end
+    /// time is left running and None is returned.
+    pub async fn stop_session(&self) -> Option<FocusSession> {
         let settings = self.settings.lock().await;
 
-        self.is_active.store(false, Ordering::SeqCst);
         let session = FocusSession::default();
-        *self.session.lock().await = session.clone();
+        let ended = {
+            let mut current = self.session.lock().await;
+            if !current.is_active {
+                return None;
+            }
+            if current.is_strict_locked(chrono::Utc::now().timestamp()) {
+                tracing::info!("Refusing to stop strict focus session before it ends");
+                return None;
+            }
+            self.is_active.store(false, Ordering::SeqCst);
+            std::mem::replace(&mut *current, session.clone())
+        };
         *self.schedule_blocked_apps.lock().await = HashSet::new();
+        self.persist(&settings, &session);
 
         // Send notification if enabled
         if settings.notify_on_end {
@@ -233,27 +466,42 @@ impl FocusManager {
             );
         }
 
-        session
+        Some(ended)
     }
 
-    /// Extend the current session by the specified minutes
+    /// Extend the current session by the specified minutes. An indefinite
+    /// session becomes a timed one ending that many minutes from now.
+    /// Scheduled sessions follow their schedule and are returned unchanged.
     pub async fn extend_session(&self, additional_minutes: u32) -> Option<FocusSession> {
         if !self.is_active() {
             return None;
         }
 
-        let mut session = self.session.lock().await;
+        let extended = {
+            let mut session = self.session.lock().await;
+            if session.is_scheduled {
+                return Some(session.clone());
+            }
 
-        if let Some(current_end) = session.end_time {
-            let new_end = current_end + (additional_minutes as i64 * 60);
-            session.end_time = Some(new_end);
+            let additional_seconds = additional_minutes as i64 * 60;
+            match session.end_time {
+                Some(current_end) => {
+                    session.end_time = Some(current_end + additional_seconds);
 
-            if let Some(current_duration) = session.duration_minutes {
-                session.duration_minutes = Some(current_duration + additional_minutes);
+                    if let Some(current_duration) = session.duration_minutes {
+                        session.duration_minutes = Some(current_duration + additional_minutes);
+                    }
+                }
+                None => {
+                    session.end_time = Some(chrono::Utc::now().timestamp() + additional_seconds);
+                    session.duration_minutes = Some(additional_minutes);
+                }
             }
-        }
+            session.clone()
+        };
+        self.persist_current().await;
 
-        Some(session.clone())
+        Some(extended)
     }
 
     /// Check if a specific app should be blocked during focus mode
@@ -263,47 +511,30 @@ impl FocusManager {
         }
 
         let session = self.session.lock().await;
-        session
-            .blocked_apps
-            .iter()
-            .any(|blocked| blocked.eq_ignore_ascii_case(app_name))
+        let apps = match session.mode {
+            FocusMode::BlockList => &session.blocked_apps,
+            FocusMode::AllowList => &session.allowed_apps,
+        };
+        focus_blocks_app(session.mode, apps, app_name)
     }
 
     /// Called every minute to check schedules and session expiry
     pub async fn tick(&self) -> Option<FocusEvent> {
         let now = Local::now();
 
-        // First, collect schedule info we need
-        let (schedules, current_session) = {
+        let (settings, current_session) = {
             let settings = self.settings.lock().await;
             let session = self.session.lock().await;
-            (settings.schedules.clone(), session.clone())
+            (settings.clone(), session.clone())
         };
 
-        // Check if any schedule should start
-        for schedule in &schedules {
-            if schedule.is_active_at(now) {
-                // If not currently in a scheduled session for this schedule, start it
-                if !current_session.is_active
-                    || current_session.schedule_name.as_ref() != Some(&schedule.name)
-                {
-                    return Some(FocusEvent::ScheduleStarted(schedule.clone()));
-                }
-            }
-        }
-
-        // Check if current scheduled session should end
-        if current_session.is_active && current_session.is_scheduled {
-            if let Some(schedule_name) = &current_session.schedule_name {
-                // Find the schedule
-                let schedule = schedules.iter().find(|s| &s.name == schedule_name);
-
-                if let Some(schedule) = schedule {
-                    if !schedule.is_active_at(now) {
-                        return Some(FocusEvent::ScheduleEnded);
-                    }
-                }
-            }
+        if let Some(event) = plan_schedule_event(
+            &current_session,
+            &settings.schedules,
+            &settings.blocked_apps,
+            now,
+        ) {
+            return Some(event);
         }
 
         // Check if timed session has expired
@@ -319,12 +550,20 @@ impl FocusManager {
         None
     }
 
-    /// Start a scheduled focus session
-    pub async fn start_scheduled_session(&self, schedule: &FocusSchedule) {
+    /// Start a scheduled focus session, returning the session it replaced so
+    /// it can be recorded in focus history. A strict session that hasn't
+    /// reached its end time is left running.
+    pub async fn start_scheduled_session(&self, schedule: &FocusSchedule) -> Option<FocusSession> {
         let settings = self.settings.lock().await;
 
-        // Determine which apps to block
-        let apps_to_block = schedule.get_blocked_apps(&settings.blocked_apps);
+        // Block the union of every schedule active right now, so overlapping
+        // schedules compose instead of replacing each other
+        let active = active_schedules(&settings.schedules, Local::now());
+        let apps_to_block = if active.iter().any(|s| s.id == schedule.id) {
+            scheduled_blocklist(&active, &settings.blocked_apps)
+        } else {
+            schedule.get_blocked_apps(&settings.blocked_apps)
+        };
 
         let now = chrono::Utc::now().timestamp();
 
@@ -337,10 +576,18 @@ impl FocusManager {
             blocked_apps: apps_to_block,
             is_scheduled: true,
             schedule_name: Some(schedule.name.clone()),
+            ..Default::default()
         };
 
+        let replaced = {
+            let mut current = self.session.lock().await;
+            if current.is_strict_locked(now) {
+                return None;
+            }
+            std::mem::replace(&mut *current, session.clone())
+        };
         self.is_active.store(true, Ordering::SeqCst);
-        *self.session.lock().await = session.clone();
+        self.persist(&settings, &session);
 
         // Send notification
         if settings.notify_on_start {
@@ -349,20 +596,44 @@ impl FocusManager {
                 &format!("{} has started. Stay focused!", schedule.name),
             );
         }
+
+        replaced.is_active.then_some(replaced)
+    }
+
+    /// Update the running scheduled session after the set of active schedules
+    /// changed, keeping its start time
+    pub async fn update_scheduled_session(&self, schedule_name: String, blocked_apps: Vec<String>) {
+        let mut session = self.session.lock().await;
+        if session.is_active && session.is_scheduled {
+            session.schedule_name = Some(schedule_name);
+            session.blocked_apps = blocked_apps;
+        }
     }
 
     /// Add an app to the blocked list for the current session
     pub async fn add_blocked_app(&self, app_name: String) {
-        let mut session = self.session.lock().await;
-        if !session.blocked_apps.contains(&app_name) {
+        {
+            let mut session = self.session.lock().await;
+            if session.blocked_apps.contains(&app_name) {
+                return;
+            }
             session.blocked_apps.push(app_name);
         }
+        self.persist_current().await;
     }
 
-    /// Remove an app from the blocked list for the current session
+    /// Remove an app from the blocked list for the current session. The list
+    /// of a strict session that hasn't reached its end time is left unchanged.
     pub async fn remove_blocked_app(&self, app_name: &str) {
-        let mut session = self.session.lock().await;
-        session.blocked_apps.retain(|a| a != app_name);
+        {
+            let mut session = self.session.lock().await;
+            if session.is_strict_locked(chrono::Utc::now().timestamp()) {
+                tracing::info!(app = %app_name, "Refusing to unblock app during strict focus session");
+                return;
+            }
+            session.blocked_apps.retain(|a| a != app_name);
+        }
+        self.persist_current().await;
     }
 
     fn get_start_message(&self, session: &FocusSession) -> String {
@@ -380,24 +651,230 @@ impl FocusManager {
     }
 
     fn send_notification(&self, title: &str, message: &str) {
-        crate::notifications::send_notification(title, message);
+        crate::notifications::notify(self.app_handle.get(), title, message, "normal");
     }
 }
 
+/// Schedules active at `now`, in precedence order: earliest start time first,
+/// then by name so the order is stable between ticks
+pub fn active_schedules(
+    schedules: &[FocusSchedule],
+    now: chrono::DateTime<Local>,
+) -> Vec<&FocusSchedule> {
+    let mut active: Vec<&FocusSchedule> =
+        schedules.iter().filter(|s| s.is_active_at(now)).collect();
+    active.sort_by(|a, b| {
+        let a_start = NaiveTime::parse_from_str(&a.start_time, "%H:%M").ok();
+        let b_start = NaiveTime::parse_from_str(&b.start_time, "%H:%M").ok();
+        a_start.cmp(&b_start).then_with(|| a.name.cmp(&b.name))
+    });
+    active
+}
+
+/// Union of the blocked apps of all given schedules, without duplicates
+pub fn scheduled_blocklist(schedules: &[&FocusSchedule], default_apps: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    schedules
+        .iter()
+        .flat_map(|s| s.get_blocked_apps(default_apps))
+        .filter(|app| seen.insert(app.clone()))
+        .collect()
+}
+
+/// Decide which schedule event (if any) a tick should produce.
+///
+/// The highest-precedence active schedule names the session, and the
+/// blocklist is the union of all active schedules. A running scheduled
+/// session is updated in place rather than restarted when that set changes.
+/// A locked strict session runs to its end before any schedule takes over.
+pub fn plan_schedule_event(
+    session: &FocusSession,
+    schedules: &[FocusSchedule],
+    default_apps: &[String],
+    now: chrono::DateTime<Local>,
+) -> Option<FocusEvent> {
+    if session.is_strict_locked(now.timestamp()) {
+        return None;
+    }
+    let active = active_schedules(schedules, now);
+
+    let Some(primary) = active.first() else {
+        return (session.is_active && session.is_scheduled).then_some(FocusEvent::ScheduleEnded);
+    };
+
+    if !session.is_active || !session.is_scheduled {
+        return Some(FocusEvent::ScheduleStarted((*primary).clone()));
+    }
+
+    let blocked_apps = scheduled_blocklist(&active, default_apps);
+    let current: HashSet<&String> = session.blocked_apps.iter().collect();
+    let wanted: HashSet<&String> = blocked_apps.iter().collect();
+
+    if session.schedule_name.as_ref() != Some(&primary.name) || current != wanted {
+        return Some(FocusEvent::SchedulesChanged {
+            schedule_name: primary.name.clone(),
+            blocked_apps,
+        });
+    }
+
+    None
+}
+
 /// Events that can occur during focus mode
 #[derive(Debug, Clone)]
 pub enum FocusEvent {
     /// A scheduled focus session should start
     ScheduleStarted(FocusSchedule),
+    /// The set of active schedules changed while a scheduled session is running
+    SchedulesChanged {
+        /// Name of the schedule that now takes precedence
+        schedule_name: String,
+        /// Union of the active schedules' blocked apps
+        blocked_apps: Vec<String>,
+    },
     /// A scheduled focus session has ended
     ScheduleEnded,
     /// A timed manual session has expired
     SessionExpired,
 }
 
+/// A finished focus session, as stored in focus history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusHistoryEntry {
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Planned length; None for indefinite and scheduled sessions
+    pub planned_minutes: Option<u32>,
+    /// False when the session was stopped before it was due to end
+    pub completed: bool,
+    pub schedule_name: Option<String>,
+}
+
+impl FocusHistoryEntry {
+    /// Build the history entry for `session` ending at `ended_at`.
+    /// `natural_end` is true when the timer ran out or the schedule ended.
+    /// Only a timed manual session can be completed, by running to its end or
+    /// being stopped once the planned time has passed. Scheduled and
+    /// indefinite sessions just end.
+    pub fn from_session(session: &FocusSession, ended_at: i64, natural_end: bool) -> Option<Self> {
+        if !session.is_active {
+            return None;
+        }
+        let start_time = session.start_time?;
+
+        let completed = !session.is_scheduled
+            && session
+                .end_time
+                .is_some_and(|end_time| natural_end || ended_at >= end_time);
+
+        Some(Self {
+            start_time,
+            end_time: ended_at.max(start_time),
+            planned_minutes: session.duration_minutes,
+            completed,
+            schedule_name: session.schedule_name.clone(),
+        })
+    }
+
+    pub fn actual_minutes(&self) -> i64 {
+        (self.end_time - self.start_time) / 60
+    }
+
+    /// Local date the session started on
+    pub fn local_date(&self) -> NaiveDate {
+        Local
+            .timestamp_opt(self.start_time, 0)
+            .single()
+            .map(|dt| dt.date_naive())
+            .unwrap_or_default()
+    }
+}
+
+/// Summary of focus history for the focus page
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FocusDashboard {
+    pub total_sessions: u32,
+    pub completed_sessions: u32,
+    pub aborted_sessions: u32,
+    /// Completed share of all sessions, 0.0 when there are none
+    pub completion_rate: f64,
+    /// Average planned length of sessions that had one
+    pub average_planned_minutes: Option<f64>,
+    pub average_actual_minutes: f64,
+    /// Focus time in sessions started this calendar week (Monday first)
+    pub focus_minutes_this_week: i64,
+    /// Focus time in sessions started this calendar month
+    pub focus_minutes_this_month: i64,
+    /// Most consecutive days with at least one completed session
+    pub longest_streak_days: u32,
+}
+
+/// Summarize focus history as of `today`
+pub fn focus_dashboard(history: &[FocusHistoryEntry], today: NaiveDate) -> FocusDashboard {
+    if history.is_empty() {
+        return FocusDashboard::default();
+    }
+
+    let total = history.len() as u32;
+    let completed = history.iter().filter(|e| e.completed).count() as u32;
+
+    let planned: Vec<u32> = history.iter().filter_map(|e| e.planned_minutes).collect();
+    let average_planned_minutes = (!planned.is_empty())
+        .then(|| planned.iter().map(|&m| m as f64).sum::<f64>() / planned.len() as f64);
+    let average_actual_minutes = history
+        .iter()
+        .map(|e| e.actual_minutes() as f64)
+        .sum::<f64>()
+        / total as f64;
+
+    let week_start = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+    let mut focus_minutes_this_week = 0;
+    let mut focus_minutes_this_month = 0;
+    for entry in history {
+        let date = entry.local_date();
+        if date >= week_start && date <= today {
+            focus_minutes_this_week += entry.actual_minutes();
+        }
+        if date.year() == today.year() && date.month() == today.month() {
+            focus_minutes_this_month += entry.actual_minutes();
+        }
+    }
+
+    let mut days: Vec<NaiveDate> = history
+        .iter()
+        .filter(|e| e.completed)
+        .map(|e| e.local_date())
+        .collect();
+    days.sort();
+    days.dedup();
+    let mut longest_streak_days = 0;
+    let mut streak = 0;
+    for (i, day) in days.iter().enumerate() {
+        streak = if i > 0 && days[i - 1].succ_opt() == Some(*day) {
+            streak + 1
+        } else {
+            1
+        };
+        longest_streak_days = longest_streak_days.max(streak);
+    }
+
+    FocusDashboard {
+        total_sessions: total,
+        completed_sessions: completed,
+        aborted_sessions: total - completed,
+        completion_rate: completed as f64 / total as f64,
+        average_planned_minutes,
+        average_actual_minutes,
+        focus_minutes_this_week,
+        focus_minutes_this_month,
+        longest_streak_days,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::TempPath;
     use chrono::Timelike;
 
     #[test]
@@ -501,4 +978,511 @@ mod tests {
 
         assert_eq!(blocked, default_apps);
     }
+
+    fn schedule(name: &str, start: &str, end: &str, apps: &[&str]) -> FocusSchedule {
+        FocusSchedule {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            days: vec![0, 1, 2, 3, 4, 5, 6],
+            start_time: start.to_string(),
+            end_time: end.to_string(),
+            blocked_apps: apps.iter().map(|a| a.to_string()).collect(),
+            enabled: true,
+        }
+    }
+
+    fn at(hour: u32, minute: u32) -> chrono::DateTime<Local> {
+        use chrono::TimeZone;
+        Local
+            .with_ymd_and_hms(2026, 1, 13, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn scheduled_session(name: &str, apps: &[&str]) -> FocusSession {
+        FocusSession {
+            is_active: true,
+            is_scheduled: true,
+            schedule_name: Some(name.to_string()),
+            blocked_apps: apps.iter().map(|a| a.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_overlapping_schedules_block_union() {
+        let schedules = vec![
+            schedule("Afternoon", "10:00", "14:00", &["Slack", "Discord"]),
+            schedule("Morning", "09:00", "12:00", &["Discord", "YouTube"]),
+        ];
+
+        // Both active: earliest start takes precedence, apps are the union
+        let event = plan_schedule_event(&FocusSession::default(), &schedules, &[], at(11, 0));
+        match event {
+            Some(FocusEvent::ScheduleStarted(s)) => assert_eq!(s.name, "Morning"),
+            other => panic!("expected ScheduleStarted, got {:?}", other),
+        }
+
+        let active = active_schedules(&schedules, at(11, 0));
+        assert_eq!(
+            scheduled_blocklist(&active, &[]),
+            vec!["Discord", "YouTube", "Slack"]
+        );
+    }
+
+    #[test]
+    fn test_schedule_waits_for_a_strict_session() {
+        let schedules = vec![schedule("Morning", "09:00", "12:00", &["YouTube"])];
+        let strict = FocusSession {
+            is_active: true,
+            start_time: Some(at(10, 0).timestamp()),
+            end_time: Some(at(11, 0).timestamp()),
+            duration_minutes: Some(60),
+            strict: true,
+            ..Default::default()
+        };
+
+        assert!(plan_schedule_event(&strict, &schedules, &[], at(10, 30)).is_none());
+        // Once its timer has run out the schedule takes over
+        assert!(matches!(
+            plan_schedule_event(&strict, &schedules, &[], at(11, 0)),
+            Some(FocusEvent::ScheduleStarted(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_session_returns_the_replaced_session() {
+        let quiet = || FocusSettings {
+            notify_on_start: false,
+            ..Default::default()
+        };
+        let morning = schedule("Morning", "00:00", "23:59", &["YouTube"]);
+
+        let manager = FocusManager::with_state_path(None);
+        manager.update_settings(quiet()).await;
+        manager.start_session(Some(30), None, false).await;
+        let replaced = manager.start_scheduled_session(&morning).await.unwrap();
+        assert_eq!(replaced.duration_minutes, Some(30));
+        assert!(manager.get_session().await.is_scheduled);
+
+        // A strict session is neither replaced nor returned
+        let manager = FocusManager::with_state_path(None);
+        manager.update_settings(quiet()).await;
+        manager.start_session(Some(30), None, true).await;
+        assert!(manager.start_scheduled_session(&morning).await.is_none());
+        assert!(manager.get_session().await.strict);
+    }
+
+    #[test]
+    fn test_overlapping_schedules_do_not_flap() {
+        let schedules = vec![
+            schedule("Afternoon", "10:00", "14:00", &["Slack"]),
+            schedule("Morning", "09:00", "12:00", &["YouTube"]),
+        ];
+        let session = scheduled_session("Morning", &["YouTube", "Slack"]);
+
+        // Repeated ticks while both are active produce no events
+        assert!(plan_schedule_event(&session, &schedules, &[], at(11, 0)).is_none());
+        assert!(plan_schedule_event(&session, &schedules, &[], at(11, 30)).is_none());
+    }
+
+    #[test]
+    fn test_schedule_ending_updates_session_in_place() {
+        let schedules = vec![
+            schedule("Afternoon", "10:00", "14:00", &["Slack"]),
+            schedule("Morning", "09:00", "12:00", &["YouTube"]),
+        ];
+        let session = scheduled_session("Morning", &["YouTube", "Slack"]);
+
+        // Morning ends at noon; Afternoon keeps going with only its own apps
+        match plan_schedule_event(&session, &schedules, &[], at(12, 30)) {
+            Some(FocusEvent::SchedulesChanged {
+                schedule_name,
+                blocked_apps,
+            }) => {
+                assert_eq!(schedule_name, "Afternoon");
+                assert_eq!(blocked_apps, vec!["Slack"]);
+            }
+            other => panic!("expected SchedulesChanged, got {:?}", other),
+        }
+
+        // Once nothing is active the session ends
+        let session = scheduled_session("Afternoon", &["Slack"]);
+        assert!(matches!(
+            plan_schedule_event(&session, &schedules, &[], at(15, 0)),
+            Some(FocusEvent::ScheduleEnded)
+        ));
+    }
+
+    fn history_entry(
+        date: NaiveDate,
+        planned: Option<u32>,
+        actual: i64,
+        completed: bool,
+    ) -> FocusHistoryEntry {
+        let start_time = Local
+            .from_local_datetime(&date.and_hms_opt(10, 0, 0).unwrap())
+            .earliest()
+            .unwrap()
+            .timestamp();
+        FocusHistoryEntry {
+            start_time,
+            end_time: start_time + actual * 60,
+            planned_minutes: planned,
+            completed,
+            schedule_name: None,
+        }
+    }
+
+    #[test]
+    fn test_focus_dashboard_rates() {
+        // Wednesday
+        let today = NaiveDate::from_ymd_opt(2026, 3, 18).unwrap();
+        let day = |offset: i64| today - chrono::Duration::days(offset);
+        let history = vec![
+            history_entry(day(20), Some(25), 25, true),
+            history_entry(day(19), Some(25), 10, false),
+            history_entry(day(3), Some(50), 50, true),
+            history_entry(day(2), None, 40, true),
+            history_entry(day(1), Some(25), 25, true),
+            history_entry(day(0), Some(60), 15, false),
+        ];
+
+        let dashboard = focus_dashboard(&history, today);
+
+        assert_eq!(dashboard.total_sessions, 6);
+        assert_eq!(dashboard.completed_sessions, 4);
+        assert_eq!(dashboard.aborted_sessions, 2);
+        assert!((dashboard.completion_rate - 4.0 / 6.0).abs() < 1e-9);
+        assert_eq!(dashboard.average_planned_minutes, Some(37.0));
+        assert!((dashboard.average_actual_minutes - 165.0 / 6.0).abs() < 1e-9);
+        // Monday 16th onwards
+        assert_eq!(dashboard.focus_minutes_this_week, 80);
+        // March only; the first two sessions were in February
+        assert_eq!(dashboard.focus_minutes_this_month, 130);
+        // Sunday to Tuesday; today's session was aborted
+        assert_eq!(dashboard.longest_streak_days, 3);
+    }
+
+    #[test]
+    fn test_empty_focus_dashboard() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 18).unwrap();
+        let dashboard = focus_dashboard(&[], today);
+        assert_eq!(dashboard.total_sessions, 0);
+        assert_eq!(dashboard.completion_rate, 0.0);
+        assert_eq!(dashboard.average_planned_minutes, None);
+    }
+
+    #[test]
+    fn test_history_entry_completion() {
+        let session = FocusSession {
+            is_active: true,
+            start_time: Some(1_000),
+            end_time: Some(1_000 + 25 * 60),
+            duration_minutes: Some(25),
+            ..Default::default()
+        };
+
+        assert!(
+            !FocusHistoryEntry::from_session(&session, 1_600, false)
+                .unwrap()
+                .completed
+        );
+        assert!(
+            FocusHistoryEntry::from_session(&session, 2_500, false)
+                .unwrap()
+                .completed
+        );
+        assert!(
+            FocusHistoryEntry::from_session(&session, 1_600, true)
+                .unwrap()
+                .completed
+        );
+        assert!(FocusHistoryEntry::from_session(&FocusSession::default(), 1_600, true).is_none());
+
+        let indefinite = FocusSession {
+            end_time: None,
+            duration_minutes: None,
+            ..session.clone()
+        };
+        assert!(
+            !FocusHistoryEntry::from_session(&indefinite, 1_600, false)
+                .unwrap()
+                .completed
+        );
+        let scheduled = FocusSession {
+            is_scheduled: true,
+            ..session
+        };
+        assert!(
+            !FocusHistoryEntry::from_session(&scheduled, 2_500, true)
+                .unwrap()
+                .completed
+        );
+    }
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_blocklist_preview() {
+        let running = names(&["Firefox", "Slack", "Digital Wellbeing", "Terminal"]);
+        let blocked = names(&["slack", "Discord"]);
+
+        assert_eq!(
+            preview_blocked_apps(FocusMode::BlockList, &blocked, &running),
+            names(&["Slack"])
+        );
+    }
+
+    #[test]
+    fn test_allowlist_preview() {
+        let running = names(&["Firefox", "Slack", "Digital Wellbeing", "Terminal"]);
+        let allowed = names(&["terminal"]);
+
+        assert_eq!(
+            preview_blocked_apps(FocusMode::AllowList, &allowed, &running),
+            names(&["Firefox", "Slack"])
+        );
+        assert!(!focus_blocks_app(FocusMode::AllowList, &[], "limit-popup"));
+    }
+
+    fn saved_schedule() -> FocusSchedule {
+        FocusSchedule {
+            id: "work".to_string(),
+            name: "Work hours".to_string(),
+            enabled: true,
+            days: vec![1, 2, 3, 4, 5],
+            start_time: "09:00".to_string(),
+            end_time: "17:00".to_string(),
+            blocked_apps: vec!["Steam".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_saved_focus_state_round_trip() {
+        let state = SavedFocusState {
+            settings: FocusSettings {
+                blocked_apps: vec!["Discord".to_string()],
+                schedules: vec![saved_schedule()],
+                ..Default::default()
+            },
+            session: Some(FocusSession {
+                is_active: true,
+                start_time: Some(1_000),
+                end_time: Some(2_800),
+                duration_minutes: Some(30),
+                minutes_remaining: Some(30),
+                blocked_apps: vec!["Discord".to_string()],
+                is_scheduled: false,
+                schedule_name: None,
+                ..Default::default()
+            }),
+        };
+
+        let json = serde_json::to_string(&state).unwrap();
+        let loaded = SavedFocusState::from_json(&json).unwrap();
+
+        assert_eq!(loaded.settings.blocked_apps, vec!["Discord"]);
+        assert_eq!(loaded.settings.schedules.len(), 1);
+        assert_eq!(loaded.settings.schedules[0].name, "Work hours");
+        assert_eq!(loaded.settings.schedules[0].days, vec![1, 2, 3, 4, 5]);
+        assert_eq!(loaded.session.as_ref().unwrap().end_time, Some(2_800));
+
+        // Only resumed while it still has time left
+        assert!(loaded.resumable_session(2_000).is_some());
+        assert!(loaded.resumable_session(2_800).is_none());
+    }
+
+    #[test]
+    fn test_scheduled_and_indefinite_sessions_are_not_resumed() {
+        let session = FocusSession {
+            is_active: true,
+            start_time: Some(1_000),
+            ..Default::default()
+        };
+        let indefinite = SavedFocusState {
+            settings: FocusSettings::default(),
+            session: Some(session.clone()),
+        };
+        assert!(indefinite.resumable_session(1_500).is_none());
+
+        let scheduled = SavedFocusState {
+            settings: FocusSettings::default(),
+            session: Some(FocusSession {
+                end_time: Some(5_000),
+                is_scheduled: true,
+                schedule_name: Some("Work hours".to_string()),
+                ..session
+            }),
+        };
+        assert!(scheduled.resumable_session(1_500).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_focus_manager_restores_saved_state() {
+        let path = TempPath::new("focus.json");
+
+        let manager = FocusManager::with_state_path(Some(path.to_path_buf()));
+        assert!(!manager.is_active());
+        manager
+            .update_settings(FocusSettings {
+                blocked_apps: vec!["Discord".to_string()],
+                notify_on_start: false,
+                schedules: vec![saved_schedule()],
+                ..Default::default()
+            })
+            .await;
+        manager.start_session(Some(30), None, false).await;
+
+        let reopened = FocusManager::with_state_path(Some(path.to_path_buf()));
+        assert!(reopened.is_active());
+        assert_eq!(reopened.get_settings().await.schedules.len(), 1);
+        let session = reopened.get_session().await;
+        assert_eq!(session.blocked_apps, vec!["Discord"]);
+        assert_eq!(session.duration_minutes, Some(30));
+        assert!(reopened.should_block_app("Discord").await);
+    }
+
+    #[tokio::test]
+    async fn test_strict_session_cannot_stop_early() {
+        let manager = FocusManager::with_state_path(None);
+        manager.start_session(Some(30), None, true).await;
+
+        assert!(manager.stop_session().await.is_none());
+        assert!(manager.get_session().await.strict);
+        assert!(manager.is_active());
+
+        // Extending still works
+        let extended = manager.extend_session(10).await.unwrap();
+        assert_eq!(extended.duration_minutes, Some(40));
+        assert!(manager.is_active());
+    }
+
+    #[tokio::test]
+    async fn test_strict_session_cannot_be_replaced() {
+        let manager = FocusManager::with_state_path(None);
+        manager
+            .start_session(Some(30), Some(vec!["Discord".to_string()]), true)
+            .await;
+
+        let session = manager.start_session(None, Some(vec![]), false).await;
+        assert!(session.strict);
+        assert_eq!(session.duration_minutes, Some(30));
+        assert_eq!(session.blocked_apps, vec!["Discord"]);
+    }
+
+    #[tokio::test]
+    async fn test_strict_session_keeps_its_blocked_apps() {
+        let manager = FocusManager::with_state_path(None);
+        manager
+            .start_session(Some(30), Some(vec!["Discord".to_string()]), true)
+            .await;
+
+        manager.remove_blocked_app("Discord").await;
+        assert_eq!(manager.get_session().await.blocked_apps, vec!["Discord"]);
+
+        // Once the timer has run out the list can change again
+        manager.session.lock().await.end_time = Some(chrono::Utc::now().timestamp() - 1);
+        manager.remove_blocked_app("Discord").await;
+        assert!(manager.get_session().await.blocked_apps.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_extend_indefinite_session_makes_it_timed() {
+        let manager = FocusManager::with_state_path(None);
+        manager.start_session(None, None, false).await;
+        assert!(manager.get_session().await.end_time.is_none());
+
+        let before = chrono::Utc::now().timestamp();
+        let extended = manager.extend_session(30).await.unwrap();
+        let end = extended.end_time.unwrap();
+        assert!(end >= before + 30 * 60 && end <= chrono::Utc::now().timestamp() + 30 * 60);
+        assert_eq!(extended.duration_minutes, Some(30));
+        let remaining = manager.get_session().await.minutes_remaining.unwrap();
+        assert!((29..=30).contains(&remaining));
+    }
+
+    #[tokio::test]
+    async fn test_extend_scheduled_session_is_a_no_op() {
+        let manager = FocusManager::with_state_path(None);
+        manager.start_session(None, None, false).await;
+        manager.session.lock().await.is_scheduled = true;
+
+        let extended = manager.extend_session(30).await.unwrap();
+        assert!(extended.is_scheduled);
+        assert!(extended.end_time.is_none());
+        assert!(extended.duration_minutes.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_strict_session_stops_after_expiry() {
+        let manager = FocusManager::with_state_path(None);
+        manager.start_session(Some(30), None, true).await;
+        manager.session.lock().await.end_time = Some(chrono::Utc::now().timestamp() - 1);
+
+        let ended = manager.stop_session().await.unwrap();
+        assert!(ended.strict);
+        assert!(!manager.is_active());
+    }
+
+    #[tokio::test]
+    async fn test_strict_requires_a_duration() {
+        let manager = FocusManager::with_state_path(None);
+        let session = manager.start_session(None, None, true).await;
+        assert!(!session.strict);
+        assert!(manager.stop_session().await.is_some());
+        assert!(!manager.is_active());
+    }
+
+    #[tokio::test]
+    async fn test_session_stops_only_once() {
+        let manager = FocusManager::with_state_path(None);
+        manager.start_session(Some(30), None, false).await;
+
+        let ended = manager.stop_session().await.unwrap();
+        assert_eq!(ended.duration_minutes, Some(30));
+        assert!(manager.stop_session().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_block_list_session_blocks_listed_apps() {
+        let manager = FocusManager::with_state_path(None);
+        manager
+            .update_settings(FocusSettings {
+                blocked_apps: vec!["Discord".to_string()],
+                allowed_apps: vec!["Code".to_string()],
+                notify_on_start: false,
+                ..Default::default()
+            })
+            .await;
+        manager.start_session(Some(25), None, false).await;
+
+        assert!(manager.should_block_app("Discord").await);
+        assert!(!manager.should_block_app("Code").await);
+        assert!(!manager.should_block_app("Firefox").await);
+    }
+
+    #[tokio::test]
+    async fn test_allow_list_session_blocks_everything_else() {
+        let manager = FocusManager::with_state_path(None);
+        manager
+            .update_settings(FocusSettings {
+                mode: FocusMode::AllowList,
+                allowed_apps: vec!["Code".to_string(), "Terminal".to_string()],
+                notify_on_start: false,
+                ..Default::default()
+            })
+            .await;
+        assert!(!manager.should_block_app("Discord").await);
+
+        manager.start_session(Some(25), None, false).await;
+        assert!(manager.should_block_app("Discord").await);
+        assert!(manager.should_block_app("Firefox").await);
+        assert!(!manager.should_block_app("code").await);
+        assert!(!manager.should_block_app("Terminal").await);
+        // Our own windows stay usable
+        assert!(!manager.should_block_app("Digital Wellbeing").await);
+        assert!(!manager.should_block_app("limit-popup").await);
+    }
 }
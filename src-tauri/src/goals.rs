@@ -1,6 +1,8 @@
-use chrono::{Datelike, Local, NaiveDate};
+use crate::focus_mode::FocusHistoryEntry;
+use crate::settings_version::VersionedSettings;
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, Timelike, Weekday};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// A user-defined screen time goal
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +21,123 @@ pub struct Goal {
     pub enabled: bool,
     /// Date the goal was created
     pub created_at: String,
+    /// First day (YYYY-MM-DD) the goal applies; None = no lower bound
+    #[serde(default)]
+    pub start_date: Option<String>,
+    /// Last day (YYYY-MM-DD) the goal applies; None = no upper bound.
+    /// A goal with an end date is a challenge and is archived once it expires.
+    #[serde(default)]
+    pub end_date: Option<String>,
+    /// Per-goal override of whether weekends are evaluated; None = use the global setting
+    #[serde(default)]
+    pub count_weekends: Option<bool>,
+    /// Whether the target applies to each day or to the whole week
+    #[serde(default)]
+    pub period: GoalPeriod,
+    /// Whether status changes (warning, exceeded, achieved) send a notification
+    #[serde(default = "default_notify")]
+    pub notify: bool,
+}
+
+/// Time span a goal's target covers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalPeriod {
+    /// Evaluated against a single day's usage
+    #[default]
+    Daily,
+    /// Evaluated against usage since the start of the current week
+    Weekly,
+}
+
+fn default_notify() -> bool {
+    true
+}
+
+/// Whether a day falls on Saturday or Sunday
+pub fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+fn default_weekends_count() -> bool {
+    true
+}
+
+impl Goal {
+    /// An enabled daily goal for every day, without dates or overrides
+    #[cfg(test)]
+    pub(crate) fn new(id: &str, name: &str, goal_type: GoalType, target_minutes: i32) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            goal_type,
+            target_minutes,
+            days: vec![],
+            enabled: true,
+            created_at: "2024-01-01".to_string(),
+            start_date: None,
+            end_date: None,
+            count_weekends: None,
+            period: GoalPeriod::Daily,
+            notify: true,
+        }
+    }
+
+    fn parse_date(date: &Option<String>) -> Option<NaiveDate> {
+        date.as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+    }
+
+    /// Whether the goal's date window includes the given day
+    pub fn is_within_dates(&self, date: NaiveDate) -> bool {
+        let after_start = Self::parse_date(&self.start_date).is_none_or(|start| date >= start);
+        let before_end = Self::parse_date(&self.end_date).is_none_or(|end| date <= end);
+        after_start && before_end
+    }
+
+    /// Whether this is a date-bounded challenge
+    pub fn is_challenge(&self) -> bool {
+        Self::parse_date(&self.end_date).is_some()
+    }
+
+    /// Whether the challenge has ended before the given day
+    pub fn is_expired(&self, date: NaiveDate) -> bool {
+        Self::parse_date(&self.end_date).is_some_and(|end| date > end)
+    }
+
+    /// Whether the goal is evaluated on weekends, given the global setting
+    pub fn counts_weekends(&self, global: bool) -> bool {
+        self.count_weekends.unwrap_or(global)
+    }
+
+    /// Days left in the challenge including the given day (None for unbounded goals)
+    pub fn days_remaining(&self, date: NaiveDate) -> Option<i64> {
+        Self::parse_date(&self.end_date).map(|end| ((end - date).num_days() + 1).max(0))
+    }
+
+    /// Whether progress is measured over the whole week. Breaks and curfews
+    /// are only counted per day, so those goals stay daily.
+    pub fn is_weekly(&self) -> bool {
+        self.period == GoalPeriod::Weekly
+            && !matches!(
+                self.goal_type,
+                GoalType::MinimumBreaks { .. } | GoalType::CurfewLimit { .. }
+            )
+    }
+}
+
+/// A challenge goal that has run past its end date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletedChallenge {
+    pub goal: Goal,
+    /// Number of days the goal was met
+    pub days_met: i32,
+    /// Number of days the goal was evaluated
+    pub days_tracked: i32,
+    /// Percentage of tracked days the goal was met (0-100)
+    pub success_rate: f64,
+    /// Date the challenge was archived
+    pub completed_at: String,
 }
 
 /// Types of goals users can set
@@ -33,6 +152,37 @@ pub enum GoalType {
     CategoryLimit { category: String },
     /// Minimum productive time (e.g., "at least 2 hours on Development")
     MinimumProductive { category: String },
+    /// Minimum number of breaks taken per day. Progress is counted in
+    /// breaks rather than minutes, and the goal is always evaluated daily.
+    MinimumBreaks { count: i32 },
+    /// Little or no use between two local hours (e.g., "no screen time after
+    /// 11pm"). Progress is the minutes used in that window today; the target
+    /// is the allowance, usually 0. A window may wrap past midnight, like 23
+    /// to 6, and is always evaluated daily.
+    CurfewLimit { start_hour: u32, end_hour: u32 },
+}
+
+impl GoalType {
+    /// Whether more is better: the goal is met by reaching its target
+    /// rather than staying under it
+    pub fn is_minimum(&self) -> bool {
+        matches!(
+            self,
+            GoalType::MinimumProductive { .. } | GoalType::MinimumBreaks { .. }
+        )
+    }
+}
+
+/// The usage a goal's progress is measured against, for a day or for a week
+/// so far
+#[derive(Debug, Clone, Default)]
+pub struct GoalUsage {
+    pub total_minutes: i32,
+    pub app_minutes: HashMap<String, i32>,
+    pub category_minutes: HashMap<String, i32>,
+    pub breaks_taken: i32,
+    /// Minutes used in each curfew goal's window, keyed by goal id
+    pub curfew_minutes: HashMap<String, i32>,
 }
 
 /// Progress toward a goal
@@ -49,6 +199,9 @@ pub struct GoalProgress {
     pub is_met: bool,
     /// Status message for the user
     pub status: GoalStatus,
+    /// Days left for challenge goals, or in the week for weekly goals if
+    /// fewer (None for unbounded daily goals)
+    pub days_remaining: Option<i64>,
 }
 
 /// Status of goal completion
@@ -158,7 +311,7 @@ pub fn get_available_achievements() -> Vec<Achievement> {
 }
 
 /// Goals manager state
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GoalsState {
     pub goals: Vec<Goal>,
     pub achievements: HashMap<String, Achievement>,
@@ -166,6 +319,170 @@ pub struct GoalsState {
     pub longest_streak: i32,
     pub total_goals_met: i32,
     pub focus_sessions_completed: i32,
+    /// Per-challenge daily results: goal id -> (date -> met)
+    #[serde(default)]
+    pub challenge_results: HashMap<String, BTreeMap<String, bool>>,
+    /// Challenges that have ended, with their final success rate
+    #[serde(default)]
+    pub completed_challenges: Vec<CompletedChallenge>,
+    /// Whether weekend days count towards streaks and averages
+    #[serde(default = "default_weekends_count")]
+    pub weekends_count: bool,
+    /// First day of the week for weekly goals
+    #[serde(default)]
+    pub week_start: WeekStart,
+    /// Weekly focus-time target, if set
+    #[serde(default)]
+    pub weekly_focus_goal: Option<WeeklyFocusGoal>,
+    /// Settings format version, see `settings_version`
+    #[serde(default = "current_settings_version")]
+    pub version: u32,
+    /// Each goal's status at the last check today, to detect transitions
+    #[serde(skip)]
+    goal_statuses: HashMap<String, GoalStatus>,
+    #[serde(skip)]
+    goal_statuses_date: Option<NaiveDate>,
+    /// Week start and whether the weekly focus goal was met at the last check
+    #[serde(skip)]
+    weekly_focus_status: Option<(NaiveDate, bool)>,
+    /// Achievements earned today that have already been notified
+    #[serde(skip)]
+    notified_achievements: HashSet<String>,
+}
+
+fn current_settings_version() -> u32 {
+    <GoalsState as VersionedSettings>::CURRENT_VERSION
+}
+
+/// A goal status change worth telling the user about
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalNotification {
+    pub goal_id: String,
+    pub title: String,
+    pub body: String,
+}
+
+/// Notification for a goal whose status changed to the one in `progress`.
+/// Only moving into Exceeded, Achieved, or Warning on a limit goal notifies.
+pub fn goal_notification(
+    goal: &Goal,
+    previous: &GoalStatus,
+    progress: &GoalProgress,
+) -> Option<GoalNotification> {
+    if !goal.notify || *previous == progress.status {
+        return None;
+    }
+
+    let is_limit_goal = !goal.goal_type.is_minimum();
+    let (title, body) = match progress.status {
+        GoalStatus::Warning if is_limit_goal => (
+            "Goal almost reached",
+            format!(
+                "{}: {} of {} minutes used",
+                goal.name, progress.current_minutes, progress.target_minutes
+            ),
+        ),
+        GoalStatus::Exceeded => (
+            "Goal exceeded",
+            format!(
+                "{}: {} minutes, over the {} minute target",
+                goal.name, progress.current_minutes, progress.target_minutes
+            ),
+        ),
+        GoalStatus::Achieved => (
+            "Goal achieved",
+            match goal.goal_type {
+                GoalType::MinimumBreaks { count } => {
+                    format!("{}: {} breaks taken", goal.name, count)
+                }
+                _ => format!("{}: {} minutes reached", goal.name, progress.target_minutes),
+            },
+        ),
+        _ => return None,
+    };
+
+    Some(GoalNotification {
+        goal_id: goal.id.clone(),
+        title: title.to_string(),
+        body,
+    })
+}
+
+/// First day of the week for weekly goals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    /// First day of the week containing `date`
+    pub fn week_containing(self, date: NaiveDate) -> NaiveDate {
+        let offset = match self {
+            WeekStart::Monday => date.weekday().num_days_from_monday(),
+            WeekStart::Sunday => date.weekday().num_days_from_sunday(),
+        };
+        date - chrono::Duration::days(offset as i64)
+    }
+}
+
+/// Target amount of focus time per week
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeeklyFocusGoal {
+    pub target_minutes: u32,
+    #[serde(default = "default_notify")]
+    pub notify: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyFocusGoalProgress {
+    /// First day of the current week (YYYY-MM-DD)
+    pub week_start: String,
+    pub target_minutes: u32,
+    pub focus_minutes: i64,
+    /// Progress as percentage (0-100+)
+    pub progress_percent: i32,
+    pub is_met: bool,
+    /// Days left in the week after today
+    pub days_remaining: i64,
+}
+
+/// Progress towards `goal` from focus sessions started this week up to `today`
+pub fn weekly_focus_goal_progress(
+    goal: &WeeklyFocusGoal,
+    history: &[FocusHistoryEntry],
+    today: NaiveDate,
+    week_start: WeekStart,
+) -> WeeklyFocusGoalProgress {
+    let start = week_start.week_containing(today);
+    let focus_minutes: i64 = history
+        .iter()
+        .filter(|entry| (start..=today).contains(&entry.local_date()))
+        .map(FocusHistoryEntry::actual_minutes)
+        .sum();
+
+    let progress_percent = if goal.target_minutes > 0 {
+        (focus_minutes * 100 / goal.target_minutes as i64) as i32
+    } else {
+        100
+    };
+
+    WeeklyFocusGoalProgress {
+        week_start: start.format("%Y-%m-%d").to_string(),
+        target_minutes: goal.target_minutes,
+        focus_minutes,
+        progress_percent,
+        is_met: focus_minutes >= goal.target_minutes as i64,
+        days_remaining: 6 - (today - start).num_days(),
+    }
+}
+
+impl Default for GoalsState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GoalsState {
@@ -182,9 +499,76 @@ impl GoalsState {
             longest_streak: 0,
             total_goals_met: 0,
             focus_sessions_completed: 0,
+            challenge_results: HashMap::new(),
+            completed_challenges: vec![],
+            weekends_count: default_weekends_count(),
+            week_start: WeekStart::default(),
+            weekly_focus_goal: None,
+            version: current_settings_version(),
+            goal_statuses: HashMap::new(),
+            goal_statuses_date: None,
+            weekly_focus_status: None,
+            notified_achievements: HashSet::new(),
         }
     }
 
+    /// Record today's goal statuses and return notifications for goals whose
+    /// status changed since the last check. The first check of a day only sets
+    /// the baseline. Goals with `notify` off are tracked but never notify.
+    pub fn goal_status_changes(
+        &mut self,
+        today: NaiveDate,
+        progress: &[GoalProgress],
+    ) -> Vec<GoalNotification> {
+        if self.goal_statuses_date != Some(today) {
+            self.goal_statuses.clear();
+            self.goal_statuses_date = Some(today);
+        }
+
+        let mut notifications = Vec::new();
+        for current in progress {
+            let previous = self
+                .goal_statuses
+                .insert(current.goal_id.clone(), current.status.clone());
+            let Some(previous) = previous else {
+                continue;
+            };
+            let Some(goal) = self.goals.iter().find(|g| g.id == current.goal_id) else {
+                continue;
+            };
+            notifications.extend(goal_notification(goal, &previous, current));
+        }
+        notifications
+    }
+
+    /// Record the weekly focus goal's progress and return a notification when
+    /// it has just been met. As with daily goals, the first check only sets the
+    /// baseline; a new week starts unmet.
+    pub fn weekly_focus_goal_change(
+        &mut self,
+        progress: &WeeklyFocusGoalProgress,
+    ) -> Option<GoalNotification> {
+        let week = NaiveDate::parse_from_str(&progress.week_start, "%Y-%m-%d").ok()?;
+        let previous = self.weekly_focus_status.replace((week, progress.is_met));
+        let was_met = match previous {
+            None => return None,
+            Some((previous_week, was_met)) => previous_week == week && was_met,
+        };
+
+        let notify = self.weekly_focus_goal.as_ref().is_some_and(|g| g.notify);
+        if !notify || was_met || !progress.is_met {
+            return None;
+        }
+        Some(GoalNotification {
+            goal_id: "weekly_focus".to_string(),
+            title: "Weekly focus goal met".to_string(),
+            body: format!(
+                "{} minutes of focus this week, {} minute target reached",
+                progress.focus_minutes, progress.target_minutes
+            ),
+        })
+    }
+
     /// Add a new goal
     pub fn add_goal(&mut self, goal: Goal) {
         // Check for first goal achievement
@@ -217,9 +601,68 @@ impl GoalsState {
         self.goals
             .iter()
             .filter(|g| g.enabled && (g.days.is_empty() || g.days.contains(&weekday)))
+            .filter(|g| g.is_within_dates(date))
+            .filter(|g| !is_weekend(date) || g.counts_weekends(self.weekends_count))
             .collect()
     }
 
+    /// Record whether a challenge goal was met on a day.
+    /// Recording the same day again overwrites the earlier result.
+    /// Returns whether the recorded result changed.
+    pub fn record_challenge_day(&mut self, goal_id: &str, date: NaiveDate, met: bool) -> bool {
+        self.challenge_results
+            .entry(goal_id.to_string())
+            .or_default()
+            .insert(date.format("%Y-%m-%d").to_string(), met)
+            != Some(met)
+    }
+
+    /// Record a challenge's result for the period containing `date`. Weekly
+    /// goals keep one result per week, keyed by the week's first day, so
+    /// their success rate counts weeks rather than days.
+    pub fn record_challenge_result(&mut self, goal_id: &str, date: NaiveDate, met: bool) -> bool {
+        let weekly = self.goals.iter().any(|g| g.id == goal_id && g.is_weekly());
+        let period_start = if weekly {
+            self.week_start.week_containing(date)
+        } else {
+            date
+        };
+        self.record_challenge_day(goal_id, period_start, met)
+    }
+
+    /// Move challenges whose end date is before `today` out of the active goals
+    /// and into `completed_challenges`. Returns the newly archived challenges.
+    pub fn archive_expired_challenges(&mut self, today: NaiveDate) -> Vec<CompletedChallenge> {
+        let (expired, active): (Vec<Goal>, Vec<Goal>) =
+            self.goals.drain(..).partition(|g| g.is_expired(today));
+        self.goals = active;
+
+        let completed_at = today.format("%Y-%m-%d").to_string();
+        let archived: Vec<CompletedChallenge> = expired
+            .into_iter()
+            .map(|goal| {
+                let results = self.challenge_results.remove(&goal.id).unwrap_or_default();
+                let days_tracked = results.len() as i32;
+                let days_met = results.values().filter(|met| **met).count() as i32;
+                let success_rate = if days_tracked > 0 {
+                    days_met as f64 / days_tracked as f64 * 100.0
+                } else {
+                    0.0
+                };
+                CompletedChallenge {
+                    goal,
+                    days_met,
+                    days_tracked,
+                    success_rate,
+                    completed_at: completed_at.clone(),
+                }
+            })
+            .collect();
+
+        self.completed_challenges.extend(archived.iter().cloned());
+        archived
+    }
+
     /// Record that a focus session was completed
     pub fn record_focus_session(&mut self) {
         self.focus_sessions_completed += 1;
@@ -240,8 +683,18 @@ impl GoalsState {
         }
     }
 
-    /// Record that all goals were met for a day
+    /// Record that all goals were met today
     pub fn record_goals_met(&mut self, all_met: bool) {
+        self.record_goals_met_on(Local::now().date_naive(), all_met);
+    }
+
+    /// Record whether all goals were met on a day. Weekend days are ignored
+    /// (neither extend nor break the streak) when weekends don't count.
+    pub fn record_goals_met_on(&mut self, date: NaiveDate, all_met: bool) {
+        if !self.weekends_count && is_weekend(date) {
+            return;
+        }
+
         if all_met {
             self.current_streak += 1;
             self.total_goals_met += 1;
@@ -251,7 +704,7 @@ impl GoalsState {
             }
 
             // Update streak achievements
-            let today = Local::now().format("%Y-%m-%d").to_string();
+            let today = date.format("%Y-%m-%d").to_string();
 
             if let Some(achievement) = self.achievements.get_mut("streak_3") {
                 achievement.progress = self.current_streak.min(3);
@@ -291,6 +744,37 @@ impl GoalsState {
         self.achievements.values().cloned().collect()
     }
 
+    /// Progress an achievement would have from the current counters alone
+    fn derived_progress(&self, achievement: &Achievement) -> i32 {
+        let counter = match achievement.id.as_str() {
+            "first_goal" => self.goals.len() as i32,
+            "streak_3" | "streak_7" | "streak_30" => self.current_streak,
+            "focus_5" | "focus_25" => self.focus_sessions_completed,
+            "under_limit_10" => self.total_goals_met,
+            _ => 0,
+        };
+        counter.min(achievement.target)
+    }
+
+    /// Clear an earned achievement. Progress is recomputed from the current
+    /// counters, but the achievement is only earned again on the next
+    /// qualifying event. Returns the reset achievement, or None if unknown.
+    pub fn reset_achievement(&mut self, achievement_id: &str) -> Option<Achievement> {
+        let progress = self.derived_progress(self.achievements.get(achievement_id)?);
+        let achievement = self.achievements.get_mut(achievement_id)?;
+        achievement.earned_at = None;
+        achievement.progress = progress;
+        Some(achievement.clone())
+    }
+
+    /// Clear every earned achievement, keeping usage counters intact
+    pub fn reset_all_achievements(&mut self) {
+        let ids: Vec<String> = self.achievements.keys().cloned().collect();
+        for id in ids {
+            self.reset_achievement(&id);
+        }
+    }
+
     /// Get newly earned achievements (for notifications)
     pub fn get_newly_earned(&self) -> Vec<&Achievement> {
         let today = Local::now().format("%Y-%m-%d").to_string();
@@ -299,43 +783,153 @@ impl GoalsState {
             .filter(|a| a.earned_at.as_ref() == Some(&today))
             .collect()
     }
+
+    /// Notifications for achievements earned today that haven't been
+    /// notified yet. Each achievement notifies once; one that is reset and
+    /// earned again notifies again.
+    pub fn achievement_notifications(&mut self) -> Vec<GoalNotification> {
+        let earned: Vec<(String, String, String)> = self
+            .get_newly_earned()
+            .into_iter()
+            .map(|a| (a.id.clone(), a.name.clone(), a.description.clone()))
+            .collect();
+        self.notified_achievements
+            .retain(|id| earned.iter().any(|(earned_id, _, _)| earned_id == id));
+
+        earned
+            .into_iter()
+            .filter(|(id, _, _)| self.notified_achievements.insert(id.clone()))
+            .map(|(id, name, description)| GoalNotification {
+                goal_id: id,
+                title: format!("🏆 Achievement Unlocked: {}", name),
+                body: description,
+            })
+            .collect()
+    }
+}
+
+/// Estimated time a goal's target will be hit at today's pace
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "direction", rename_all = "snake_case")]
+pub enum GoalProjection {
+    /// Minimum goals: when the target will be reached
+    Completion {
+        /// Target already reached today
+        reached: bool,
+        /// Estimated local time (HH:MM); None if it won't be reached today
+        estimated_at: Option<String>,
+    },
+    /// Limit goals: when the limit will be used up
+    Exhaustion {
+        /// Limit already used up today
+        exceeded: bool,
+        /// Estimated local time (HH:MM); None if the limit will last the day
+        estimated_at: Option<String>,
+    },
+}
+
+/// Fraction of the day elapsed at `now` (0.0 at midnight, approaching 1.0 at 23:59)
+pub fn current_day_progress(now: NaiveDateTime) -> f64 {
+    now.time().num_seconds_from_midnight() as f64 / 86_400.0
+}
+
+/// Project when a goal's target will be hit if usage continues at the
+/// average pace seen so far today
+pub fn project_goal_completion(progress: &GoalProgress, now: NaiveDateTime) -> GoalProjection {
+    let day_start = now.date().and_time(chrono::NaiveTime::MIN);
+    project_in_period(progress, now, day_start, 1, "%H:%M")
+}
+
+/// Project when a weekly goal's target will be hit if usage continues at the
+/// average pace seen since the week began on `week_start`. The estimate is
+/// a weekday and time (e.g. "Thu 14:30"); None if it falls after the week.
+pub fn project_weekly_goal_completion(
+    progress: &GoalProgress,
+    now: NaiveDateTime,
+    week_start: NaiveDate,
+) -> GoalProjection {
+    let period_start = week_start.and_time(chrono::NaiveTime::MIN);
+    project_in_period(progress, now, period_start, 7, "%a %H:%M")
+}
+
+/// Extrapolate the pace between `period_start` and `now` over the rest of a
+/// period lasting `period_days`, formatting an estimate inside it with `format`
+fn project_in_period(
+    progress: &GoalProgress,
+    now: NaiveDateTime,
+    period_start: NaiveDateTime,
+    period_days: i64,
+    format: &str,
+) -> GoalProjection {
+    let is_minimum = progress.goal_type.is_minimum();
+    let reached = progress.current_minutes >= progress.target_minutes;
+
+    let elapsed_minutes = (now - period_start).num_seconds() as f64 / 60.0;
+    let period_end = period_start + chrono::Duration::days(period_days);
+    let estimated_at = if reached || elapsed_minutes <= 0.0 || progress.current_minutes <= 0 {
+        None
+    } else {
+        // remaining / (current / elapsed), arranged to avoid rounding drift
+        let remaining = (progress.target_minutes - progress.current_minutes) as f64;
+        let minutes_needed = remaining * elapsed_minutes / progress.current_minutes as f64;
+        let eta = now + chrono::Duration::minutes(minutes_needed.ceil() as i64);
+        (eta < period_end).then(|| eta.format(format).to_string())
+    };
+
+    if is_minimum {
+        GoalProjection::Completion {
+            reached,
+            estimated_at,
+        }
+    } else {
+        GoalProjection::Exhaustion {
+            exceeded: reached,
+            estimated_at,
+        }
+    }
 }
 
 /// Calculate goal progress based on usage data
-pub fn calculate_goal_progress(
-    goal: &Goal,
-    total_daily_minutes: i32,
-    app_usage: &HashMap<String, i32>,
-    category_usage: &HashMap<String, i32>,
-) -> GoalProgress {
+pub fn calculate_goal_progress(goal: &Goal, usage: &GoalUsage) -> GoalProgress {
+    // Break goals count breaks; their target is the break count
+    let target = match goal.goal_type {
+        GoalType::MinimumBreaks { count } => count,
+        _ => goal.target_minutes,
+    };
+
     let (current_minutes, is_limit_goal) = match &goal.goal_type {
-        GoalType::DailyLimit => (total_daily_minutes, true),
+        GoalType::DailyLimit => (usage.total_minutes, true),
         GoalType::AppLimit { app_name } => {
-            let minutes = app_usage.get(app_name).copied().unwrap_or(0);
+            let minutes = usage.app_minutes.get(app_name).copied().unwrap_or(0);
             (minutes, true)
         }
         GoalType::CategoryLimit { category } => {
-            let minutes = category_usage.get(category).copied().unwrap_or(0);
+            let minutes = usage.category_minutes.get(category).copied().unwrap_or(0);
             (minutes, true)
         }
         GoalType::MinimumProductive { category } => {
-            let minutes = category_usage.get(category).copied().unwrap_or(0);
+            let minutes = usage.category_minutes.get(category).copied().unwrap_or(0);
             (minutes, false)
         }
+        GoalType::MinimumBreaks { .. } => (usage.breaks_taken, false),
+        GoalType::CurfewLimit { .. } => {
+            let minutes = usage.curfew_minutes.get(&goal.id).copied().unwrap_or(0);
+            (minutes, true)
+        }
     };
 
-    let progress_percent = if goal.target_minutes > 0 {
-        ((current_minutes as f64 / goal.target_minutes as f64) * 100.0) as i32
+    let progress_percent = if target > 0 {
+        ((current_minutes as f64 / target as f64) * 100.0) as i32
     } else {
         0
     };
 
     let (is_met, status) = if is_limit_goal {
         // For limit goals, lower is better
-        let is_met = current_minutes <= goal.target_minutes;
+        let is_met = current_minutes <= target;
         let status = if current_minutes == 0 {
             GoalStatus::NotStarted
-        } else if progress_percent >= 100 {
+        } else if progress_percent >= 100 || current_minutes > target {
             GoalStatus::Exceeded
         } else if progress_percent >= 80 {
             GoalStatus::Warning
@@ -345,7 +939,7 @@ pub fn calculate_goal_progress(
         (is_met, status)
     } else {
         // For minimum goals, higher is better
-        let is_met = current_minutes >= goal.target_minutes;
+        let is_met = current_minutes >= target;
         let status = if is_met {
             GoalStatus::Achieved
         } else if progress_percent >= 50 {
@@ -362,18 +956,151 @@ pub fn calculate_goal_progress(
         goal_id: goal.id.clone(),
         goal_name: goal.name.clone(),
         goal_type: goal.goal_type.clone(),
-        target_minutes: goal.target_minutes,
+        target_minutes: target,
         current_minutes,
         progress_percent,
         is_met,
         status,
+        days_remaining: None,
     }
 }
 
+/// Calculate progress of a weekly goal on `date` from usage totals for its
+/// week so far. The thresholds are the same as for daily goals, applied to
+/// the whole week's target, and `days_remaining` counts the days left in the
+/// week (or in the challenge, if it ends sooner). Breaks and curfews are only
+/// counted per day, so `week_usage` has none.
+pub fn calculate_weekly_goal_progress(
+    goal: &Goal,
+    week_usage: &GoalUsage,
+    date: NaiveDate,
+    week_start: WeekStart,
+) -> GoalProgress {
+    let mut progress = calculate_goal_progress(goal, week_usage);
+
+    let week_days_left = 7 - (date - week_start.week_containing(date)).num_days();
+    progress.days_remaining = Some(
+        goal.days_remaining(date)
+            .map_or(week_days_left, |days| days.min(week_days_left)),
+    );
+    progress
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_weekly_category_limit_half_consumed() {
+        let goal = Goal {
+            id: "social".to_string(),
+            name: "Under 10 hours of social media this week".to_string(),
+            goal_type: GoalType::CategoryLimit {
+                category: "Social".to_string(),
+            },
+            target_minutes: 600,
+            days: vec![],
+            enabled: true,
+            created_at: "2026-01-13".to_string(),
+            start_date: None,
+            end_date: None,
+            count_weekends: None,
+            period: GoalPeriod::Weekly,
+            notify: true,
+        };
+        let week_categories = HashMap::from([("Social".to_string(), 300)]);
+
+        // Tuesday 2026-01-13: Tuesday through Sunday are left
+        let progress = calculate_weekly_goal_progress(
+            &goal,
+            &GoalUsage {
+                total_minutes: 420,
+                category_minutes: week_categories,
+                ..Default::default()
+            },
+            date("2026-01-13"),
+            WeekStart::Monday,
+        );
+        assert_eq!(progress.current_minutes, 300);
+        assert_eq!(progress.progress_percent, 50);
+        assert!(progress.is_met);
+        assert_eq!(progress.status, GoalStatus::OnTrack);
+        assert_eq!(progress.days_remaining, Some(6));
+
+        // 300 minutes in the 36 hours since Monday runs out 36 hours later
+        assert_eq!(
+            project_weekly_goal_completion(&progress, at("12:00"), date("2026-01-12")),
+            GoalProjection::Exhaustion {
+                exceeded: false,
+                estimated_at: Some("Thu 00:00".to_string()),
+            }
+        );
+        // At the same pace late in the week the limit lasts until next week
+        let progress = calculate_weekly_goal_progress(
+            &goal,
+            &GoalUsage {
+                total_minutes: 420,
+                category_minutes: HashMap::from([("Social".to_string(), 400)]),
+                ..Default::default()
+            },
+            date("2026-01-17"),
+            WeekStart::Monday,
+        );
+        assert_eq!(progress.days_remaining, Some(2));
+        assert_eq!(
+            project_weekly_goal_completion(
+                &progress,
+                date("2026-01-17").and_hms_opt(12, 0, 0).unwrap(),
+                date("2026-01-12"),
+            ),
+            GoalProjection::Exhaustion {
+                exceeded: false,
+                estimated_at: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_weekly_challenge_results_are_keyed_by_week() {
+        let mut state = GoalsState::default();
+        state.goals.push(Goal {
+            id: "weekly".to_string(),
+            name: "Weekly social limit".to_string(),
+            goal_type: GoalType::CategoryLimit {
+                category: "Social".to_string(),
+            },
+            target_minutes: 600,
+            days: vec![],
+            enabled: true,
+            created_at: "2026-01-01".to_string(),
+            start_date: None,
+            end_date: Some("2026-01-31".to_string()),
+            count_weekends: None,
+            period: GoalPeriod::Weekly,
+            notify: true,
+        });
+
+        assert!(state.record_challenge_result("weekly", date("2026-01-13"), true));
+        // Later days in the same week overwrite that week's result
+        assert!(state.record_challenge_result("weekly", date("2026-01-16"), false));
+        state.record_challenge_result("weekly", date("2026-01-19"), true);
+
+        let results = &state.challenge_results["weekly"];
+        assert_eq!(results.len(), 2);
+        assert!(!results["2026-01-12"]);
+        assert!(results["2026-01-19"]);
+    }
+
+    #[test]
+    fn test_goal_period_defaults_to_daily() {
+        let json = r#"{
+            "id": "g", "name": "Limit", "goal_type": "daily_limit", "target_minutes": 60,
+            "days": [], "enabled": true, "created_at": "2026-01-13"
+        }"#;
+        let goal: Goal = serde_json::from_str(json).unwrap();
+        assert_eq!(goal.period, GoalPeriod::Daily);
+    }
+
     #[test]
     fn test_daily_limit_goal_progress() {
         let goal = Goal {
@@ -384,9 +1111,20 @@ mod tests {
             days: vec![],
             enabled: true,
             created_at: "2026-01-13".to_string(),
+            start_date: None,
+            end_date: None,
+            count_weekends: None,
+            period: GoalPeriod::Daily,
+            notify: true,
         };
 
-        let progress = calculate_goal_progress(&goal, 120, &HashMap::new(), &HashMap::new());
+        let progress = calculate_goal_progress(
+            &goal,
+            &GoalUsage {
+                total_minutes: 120,
+                ..Default::default()
+            },
+        );
         assert_eq!(progress.progress_percent, 50);
         assert!(progress.is_met);
         assert_eq!(progress.status, GoalStatus::OnTrack);
@@ -402,9 +1140,20 @@ mod tests {
             days: vec![],
             enabled: true,
             created_at: "2026-01-13".to_string(),
+            start_date: None,
+            end_date: None,
+            count_weekends: None,
+            period: GoalPeriod::Daily,
+            notify: true,
         };
 
-        let progress = calculate_goal_progress(&goal, 300, &HashMap::new(), &HashMap::new());
+        let progress = calculate_goal_progress(
+            &goal,
+            &GoalUsage {
+                total_minutes: 300,
+                ..Default::default()
+            },
+        );
         assert_eq!(progress.progress_percent, 125);
         assert!(!progress.is_met);
         assert_eq!(progress.status, GoalStatus::Exceeded);
@@ -422,17 +1171,113 @@ mod tests {
             days: vec![],
             enabled: true,
             created_at: "2026-01-13".to_string(),
+            start_date: None,
+            end_date: None,
+            count_weekends: None,
+            period: GoalPeriod::Daily,
+            notify: true,
         };
 
         let mut category_usage = HashMap::new();
         category_usage.insert("Development".to_string(), 150);
 
-        let progress = calculate_goal_progress(&goal, 300, &HashMap::new(), &category_usage);
+        let progress = calculate_goal_progress(
+            &goal,
+            &GoalUsage {
+                total_minutes: 300,
+                category_minutes: category_usage,
+                ..Default::default()
+            },
+        );
         assert_eq!(progress.progress_percent, 125);
         assert!(progress.is_met);
         assert_eq!(progress.status, GoalStatus::Achieved);
     }
 
+    #[test]
+    fn test_curfew_goal() {
+        let goal = Goal {
+            id: "curfew".to_string(),
+            name: "No screens after 11pm".to_string(),
+            goal_type: GoalType::CurfewLimit {
+                start_hour: 23,
+                end_hour: 6,
+            },
+            target_minutes: 0,
+            days: vec![],
+            enabled: true,
+            created_at: "2026-01-13".to_string(),
+            start_date: None,
+            end_date: None,
+            count_weekends: None,
+            period: GoalPeriod::Daily,
+            notify: true,
+        };
+        let progress_with = |minutes: i32| {
+            let curfew_usage = HashMap::from([("curfew".to_string(), minutes)]);
+            calculate_goal_progress(
+                &goal,
+                &GoalUsage {
+                    total_minutes: 300,
+                    curfew_minutes: curfew_usage,
+                    ..Default::default()
+                },
+            )
+        };
+
+        let clean = progress_with(0);
+        assert!(clean.is_met);
+        assert_eq!(clean.current_minutes, 0);
+        assert!(!goal.goal_type.is_minimum());
+
+        // Any use in the window breaks a curfew with no allowance
+        let broken = progress_with(1);
+        assert!(!broken.is_met);
+        assert_eq!(broken.status, GoalStatus::Exceeded);
+    }
+
+    #[test]
+    fn test_minimum_breaks_goal() {
+        let goal = Goal {
+            id: "breaks".to_string(),
+            name: "Take breaks".to_string(),
+            goal_type: GoalType::MinimumBreaks { count: 4 },
+            target_minutes: 0,
+            days: vec![],
+            enabled: true,
+            created_at: "2026-01-13".to_string(),
+            start_date: None,
+            end_date: None,
+            count_weekends: None,
+            period: GoalPeriod::Daily,
+            notify: true,
+        };
+        let progress_with = |breaks| {
+            calculate_goal_progress(
+                &goal,
+                &GoalUsage {
+                    total_minutes: 300,
+                    breaks_taken: breaks,
+                    ..Default::default()
+                },
+            )
+        };
+
+        let none = progress_with(0);
+        assert_eq!(none.target_minutes, 4);
+        assert!(!none.is_met);
+        assert_eq!(none.status, GoalStatus::NotStarted);
+
+        let half = progress_with(2);
+        assert_eq!(half.progress_percent, 50);
+        assert_eq!(half.status, GoalStatus::OnTrack);
+
+        let done = progress_with(4);
+        assert!(done.is_met);
+        assert_eq!(done.status, GoalStatus::Achieved);
+        assert_eq!(done.current_minutes, 4);
+    }
+
     #[test]
     fn test_goals_state_streak() {
         let mut state = GoalsState::new();
@@ -465,6 +1310,11 @@ mod tests {
             days: vec![],
             enabled: true,
             created_at: "2026-01-13".to_string(),
+            start_date: None,
+            end_date: None,
+            count_weekends: None,
+            period: GoalPeriod::Daily,
+            notify: true,
         });
 
         assert!(state
@@ -474,4 +1324,423 @@ mod tests {
             .earned_at
             .is_some());
     }
+
+    fn challenge_goal(start: &str, end: &str) -> Goal {
+        Goal {
+            id: "challenge".to_string(),
+            name: "30-day challenge".to_string(),
+            goal_type: GoalType::DailyLimit,
+            target_minutes: 120,
+            days: vec![],
+            enabled: true,
+            created_at: start.to_string(),
+            start_date: Some(start.to_string()),
+            end_date: Some(end.to_string()),
+            count_weekends: None,
+            period: GoalPeriod::Daily,
+            notify: true,
+        }
+    }
+
+    #[test]
+    fn test_challenge_only_active_within_dates() {
+        let mut state = GoalsState::new();
+        state.add_goal(challenge_goal("2026-03-01", "2026-03-30"));
+
+        assert!(state.get_goals_for_day(date("2026-02-28")).is_empty());
+        assert_eq!(state.get_goals_for_day(date("2026-03-01")).len(), 1);
+        assert_eq!(state.get_goals_for_day(date("2026-03-30")).len(), 1);
+        assert!(state.get_goals_for_day(date("2026-03-31")).is_empty());
+
+        let goal = &state.goals[0];
+        assert_eq!(goal.days_remaining(date("2026-03-01")), Some(30));
+        assert_eq!(goal.days_remaining(date("2026-03-30")), Some(1));
+    }
+
+    #[test]
+    fn test_expired_challenge_is_archived_with_success_rate() {
+        let mut state = GoalsState::new();
+        state.add_goal(challenge_goal("2026-03-01", "2026-03-04"));
+        state.add_goal(Goal {
+            id: "unbounded".to_string(),
+            ..challenge_goal("2026-03-01", "2026-03-04")
+        });
+        state.goals[1].start_date = None;
+        state.goals[1].end_date = None;
+
+        assert!(state.record_challenge_day("challenge", date("2026-03-01"), true));
+        assert!(!state.record_challenge_day("challenge", date("2026-03-01"), true));
+        state.record_challenge_day("challenge", date("2026-03-02"), false);
+        // Re-recording a day overwrites the earlier result
+        state.record_challenge_day("challenge", date("2026-03-02"), true);
+        state.record_challenge_day("challenge", date("2026-03-03"), false);
+        state.record_challenge_day("challenge", date("2026-03-04"), true);
+
+        assert!(state
+            .archive_expired_challenges(date("2026-03-04"))
+            .is_empty());
+
+        let archived = state.archive_expired_challenges(date("2026-03-05"));
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].days_tracked, 4);
+        assert_eq!(archived[0].days_met, 3);
+        assert_eq!(archived[0].success_rate, 75.0);
+
+        assert_eq!(state.goals.len(), 1);
+        assert_eq!(state.goals[0].id, "unbounded");
+        assert_eq!(state.completed_challenges.len(), 1);
+        assert!(!state.challenge_results.contains_key("challenge"));
+    }
+
+    #[test]
+    fn test_reset_earned_achievement() {
+        let mut state = GoalsState::new();
+        for _ in 0..5 {
+            state.record_focus_session();
+        }
+        assert!(state.achievements["focus_5"].earned_at.is_some());
+
+        let reset = state.reset_achievement("focus_5").unwrap();
+        assert!(reset.earned_at.is_none());
+        assert!(state.achievements["focus_5"].earned_at.is_none());
+
+        // Usage counters are kept
+        assert_eq!(state.focus_sessions_completed, 5);
+        assert!(state.reset_achievement("unknown").is_none());
+    }
+
+    #[test]
+    fn test_streak_achievement_can_be_re_earned() {
+        let mut state = GoalsState::new();
+        for _ in 0..3 {
+            state.record_goals_met(true);
+        }
+        assert!(state.achievements["streak_3"].earned_at.is_some());
+
+        state.reset_all_achievements();
+        assert!(state.achievements.values().all(|a| a.earned_at.is_none()));
+        assert_eq!(state.achievements["streak_3"].progress, 3);
+
+        // Not re-earned from existing data until the next qualifying day
+        assert!(state.achievements["streak_3"].earned_at.is_none());
+        state.record_goals_met(true);
+        assert!(state.achievements["streak_3"].earned_at.is_some());
+    }
+
+    #[test]
+    fn test_streak_with_and_without_weekends() {
+        // Thursday 2026-01-01 through Tuesday 2026-01-06, missing only the weekend
+        let start = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let days: Vec<(NaiveDate, bool)> = (0..6)
+            .map(|offset| {
+                let date = start + chrono::Duration::days(offset);
+                (date, !is_weekend(date))
+            })
+            .collect();
+
+        let mut included = GoalsState::new();
+        for (date, met) in &days {
+            included.record_goals_met_on(*date, *met);
+        }
+        assert_eq!(included.current_streak, 2);
+
+        let mut excluded = GoalsState::new();
+        excluded.weekends_count = false;
+        for (date, met) in &days {
+            excluded.record_goals_met_on(*date, *met);
+        }
+        assert_eq!(excluded.current_streak, 4);
+    }
+
+    #[test]
+    fn test_goal_weekend_override() {
+        let saturday = NaiveDate::from_ymd_opt(2026, 1, 3).unwrap();
+        let mut state = GoalsState::new();
+        state.weekends_count = false;
+
+        let mut goal = challenge_goal("2026-01-01", "2026-01-31");
+        state.goals.push(goal.clone());
+        assert!(state.get_goals_for_day(saturday).is_empty());
+
+        goal.count_weekends = Some(true);
+        state.update_goal(goal);
+        assert_eq!(state.get_goals_for_day(saturday).len(), 1);
+    }
+
+    fn at(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("2026-01-13 {}", time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn date(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_minimum_goal_on_pace() {
+        let goal = Goal {
+            id: "g1".to_string(),
+            name: "Deep work".to_string(),
+            goal_type: GoalType::MinimumProductive {
+                category: "Development".to_string(),
+            },
+            target_minutes: 120,
+            days: vec![],
+            enabled: true,
+            created_at: "2026-01-13".to_string(),
+            start_date: None,
+            end_date: None,
+            count_weekends: None,
+            period: GoalPeriod::Daily,
+            notify: true,
+        };
+        let mut category_usage = HashMap::new();
+        category_usage.insert("Development".to_string(), 60);
+        let progress = calculate_goal_progress(
+            &goal,
+            &GoalUsage {
+                total_minutes: 60,
+                category_minutes: category_usage,
+                ..Default::default()
+            },
+        );
+
+        // 60 minutes by noon: another 60 minutes takes 12 hours at that pace
+        assert_eq!(
+            project_goal_completion(&progress, at("12:00")),
+            GoalProjection::Completion {
+                reached: false,
+                estimated_at: None,
+            }
+        );
+
+        // 60 minutes by 06:00 reaches 120 minutes by 12:00
+        assert_eq!(
+            project_goal_completion(&progress, at("06:00")),
+            GoalProjection::Completion {
+                reached: false,
+                estimated_at: Some("12:00".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_limit_goal_about_to_be_exceeded() {
+        let goal = Goal {
+            id: "g2".to_string(),
+            name: "Screen time".to_string(),
+            goal_type: GoalType::DailyLimit,
+            target_minutes: 240,
+            days: vec![],
+            enabled: true,
+            created_at: "2026-01-13".to_string(),
+            start_date: None,
+            end_date: None,
+            count_weekends: None,
+            period: GoalPeriod::Daily,
+            notify: true,
+        };
+
+        // 225 minutes used by 15:00 (15 min/hour): 15 minutes left lasts one hour
+        let progress = calculate_goal_progress(
+            &goal,
+            &GoalUsage {
+                total_minutes: 225,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            project_goal_completion(&progress, at("15:00")),
+            GoalProjection::Exhaustion {
+                exceeded: false,
+                estimated_at: Some("16:00".to_string()),
+            }
+        );
+
+        let progress = calculate_goal_progress(
+            &goal,
+            &GoalUsage {
+                total_minutes: 300,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            project_goal_completion(&progress, at("15:00")),
+            GoalProjection::Exhaustion {
+                exceeded: true,
+                estimated_at: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_current_day_progress() {
+        assert_eq!(current_day_progress(at("00:00")), 0.0);
+        assert_eq!(current_day_progress(at("12:00")), 0.5);
+    }
+
+    #[test]
+    fn test_goal_status_change_respects_notify() {
+        let goal = |id: &str, notify: bool| Goal {
+            id: id.to_string(),
+            name: id.to_string(),
+            goal_type: GoalType::DailyLimit,
+            target_minutes: 100,
+            days: vec![],
+            enabled: true,
+            created_at: "2026-01-13".to_string(),
+            start_date: None,
+            end_date: None,
+            count_weekends: None,
+            period: GoalPeriod::Daily,
+            notify,
+        };
+        let mut state = GoalsState::new();
+        state.goals = vec![goal("loud", true), goal("silent", false)];
+        let today = NaiveDate::from_ymd_opt(2026, 1, 13).unwrap();
+
+        let progress_at = |state: &GoalsState, minutes: i32| -> Vec<GoalProgress> {
+            state
+                .goals
+                .iter()
+                .map(|g| {
+                    calculate_goal_progress(
+                        g,
+                        &GoalUsage {
+                            total_minutes: minutes,
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect()
+        };
+
+        // First check sets the baseline
+        let on_track = progress_at(&state, 50);
+        assert!(state.goal_status_changes(today, &on_track).is_empty());
+
+        let exceeded = progress_at(&state, 120);
+        let notifications = state.goal_status_changes(today, &exceeded);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].goal_id, "loud");
+        assert_eq!(notifications[0].title, "Goal exceeded");
+
+        // No repeat while the status stays the same
+        assert!(state.goal_status_changes(today, &exceeded).is_empty());
+    }
+
+    #[test]
+    fn test_notify_defaults_to_true_for_saved_goals() {
+        let json = r#"{
+            "id": "g1",
+            "name": "Limit",
+            "goal_type": "daily_limit",
+            "target_minutes": 240,
+            "days": [],
+            "enabled": true,
+            "created_at": "2026-01-13"
+        }"#;
+        let goal: Goal = serde_json::from_str(json).unwrap();
+        assert!(goal.notify);
+    }
+
+    fn focus_entry(date: NaiveDate, minutes: i64) -> FocusHistoryEntry {
+        use chrono::TimeZone;
+        let start_time = Local
+            .from_local_datetime(&date.and_hms_opt(12, 0, 0).unwrap())
+            .earliest()
+            .unwrap()
+            .timestamp();
+        FocusHistoryEntry {
+            start_time,
+            end_time: start_time + minutes * 60,
+            planned_minutes: Some(minutes as u32),
+            completed: true,
+            schedule_name: None,
+        }
+    }
+
+    #[test]
+    fn test_week_start() {
+        // Wednesday
+        let date = NaiveDate::from_ymd_opt(2026, 3, 18).unwrap();
+        assert_eq!(
+            WeekStart::Monday.week_containing(date),
+            NaiveDate::from_ymd_opt(2026, 3, 16).unwrap()
+        );
+        assert_eq!(
+            WeekStart::Sunday.week_containing(date),
+            NaiveDate::from_ymd_opt(2026, 3, 15).unwrap()
+        );
+        let sunday = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+        assert_eq!(WeekStart::Sunday.week_containing(sunday), sunday);
+        assert_eq!(
+            WeekStart::Monday.week_containing(sunday),
+            NaiveDate::from_ymd_opt(2026, 3, 9).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_achievement_notifies_once() {
+        let mut state = GoalsState::new();
+        assert!(state.achievement_notifications().is_empty());
+
+        for _ in 0..5 {
+            state.record_focus_session();
+        }
+        let notifications = state.achievement_notifications();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].goal_id, "focus_5");
+        assert!(notifications[0].title.contains("Achievement Unlocked"));
+
+        // Checking again, or more sessions that earn nothing new, stay silent
+        assert!(state.achievement_notifications().is_empty());
+        state.record_focus_session();
+        assert!(state.achievement_notifications().is_empty());
+    }
+
+    #[test]
+    fn test_weekly_focus_goal_accumulates_and_notifies_once() {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        let mut state = GoalsState::new();
+        let goal = WeeklyFocusGoal {
+            target_minutes: 600,
+            notify: true,
+        };
+        state.weekly_focus_goal = Some(goal.clone());
+
+        // Sunday 15th belongs to the previous Monday-based week
+        let mut history = vec![focus_entry(day(15), 300), focus_entry(day(16), 240)];
+        let progress = weekly_focus_goal_progress(&goal, &history, day(17), WeekStart::Monday);
+        assert_eq!(progress.week_start, "2026-03-16");
+        assert_eq!(progress.focus_minutes, 240);
+        assert_eq!(progress.progress_percent, 40);
+        assert_eq!(progress.days_remaining, 5);
+        assert!(!progress.is_met);
+        assert!(state.weekly_focus_goal_change(&progress).is_none());
+
+        // With a Sunday start both sessions count
+        let sunday_week = weekly_focus_goal_progress(&goal, &history, day(17), WeekStart::Sunday);
+        assert_eq!(sunday_week.focus_minutes, 540);
+
+        history.push(focus_entry(day(18), 200));
+        let progress = weekly_focus_goal_progress(&goal, &history, day(18), WeekStart::Monday);
+        assert!(!progress.is_met);
+        assert!(state.weekly_focus_goal_change(&progress).is_none());
+
+        history.push(focus_entry(day(19), 180));
+        let progress = weekly_focus_goal_progress(&goal, &history, day(19), WeekStart::Monday);
+        assert_eq!(progress.focus_minutes, 620);
+        assert_eq!(progress.progress_percent, 103);
+        assert!(progress.is_met);
+        let notification = state.weekly_focus_goal_change(&progress).unwrap();
+        assert_eq!(notification.title, "Weekly focus goal met");
+
+        // Staying met doesn't notify again
+        assert!(state.weekly_focus_goal_change(&progress).is_none());
+
+        // Next week starts over
+        let progress = weekly_focus_goal_progress(&goal, &history, day(23), WeekStart::Monday);
+        assert_eq!(progress.focus_minutes, 0);
+        assert!(state.weekly_focus_goal_change(&progress).is_none());
+    }
 }
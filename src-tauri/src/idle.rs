@@ -0,0 +1,181 @@
+//! Classifying why the user is away from the computer
+//!
+//! The tracker stops recording app usage once the user goes idle. Idle periods
+//! are recorded separately with a reason so the day can be interpreted later:
+//! a locked screen, no input with the screen on, or the machine being
+//! suspended (detected as a gap between tracker ticks).
+
+use serde::{Deserialize, Serialize};
+
+/// A gap between tracker ticks longer than this means the machine was asleep
+pub const SUSPEND_GAP_SECONDS: i64 = 60;
+
+/// No input for this long counts as away, unless configured otherwise
+pub const DEFAULT_IDLE_THRESHOLD_SECONDS: u64 = 300;
+/// Bounds for the configurable idle threshold
+pub const MIN_IDLE_THRESHOLD_SECONDS: u64 = 30;
+pub const MAX_IDLE_THRESHOLD_SECONDS: u64 = 2 * 60 * 60;
+/// app_meta key holding the saved idle threshold in seconds
+pub const THRESHOLD_KEY: &str = "idle_threshold_seconds";
+
+/// Where the tracker reads input idleness from, so tests can simulate it
+pub trait IdleProvider: Send + Sync {
+    /// Seconds since the last user input
+    fn idle_seconds(&self) -> u64;
+
+    /// Whether the session is locked
+    fn is_locked(&self) -> bool {
+        is_session_locked()
+    }
+}
+
+/// Why the user was idle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdleReason {
+    /// The session was locked
+    Locked,
+    /// No input for longer than the idle threshold, screen still on
+    Away,
+    /// The machine was suspended
+    Suspended,
+}
+
+impl IdleReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IdleReason::Locked => "locked",
+            IdleReason::Away => "away",
+            IdleReason::Suspended => "suspended",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "locked" => Some(IdleReason::Locked),
+            "away" => Some(IdleReason::Away),
+            "suspended" => Some(IdleReason::Suspended),
+            _ => None,
+        }
+    }
+}
+
+/// What the tracker observed on a tick
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdleSignals {
+    /// Seconds since the last user input
+    pub idle_seconds: u64,
+    /// Whether the session is locked
+    pub locked: bool,
+    /// Wall-clock seconds since the previous tick
+    pub wake_gap_seconds: i64,
+}
+
+/// Decide whether the user is idle and why. Returns None when active.
+pub fn classify_idle(signals: &IdleSignals, idle_threshold_seconds: u64) -> Option<IdleReason> {
+    if signals.wake_gap_seconds >= SUSPEND_GAP_SECONDS {
+        Some(IdleReason::Suspended)
+    } else if signals.locked {
+        Some(IdleReason::Locked)
+    } else if signals.idle_seconds >= idle_threshold_seconds {
+        Some(IdleReason::Away)
+    } else {
+        None
+    }
+}
+
+/// Idle time for one reason
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdleReasonTotal {
+    pub reason: IdleReason,
+    pub seconds: i64,
+}
+
+/// How a day split between tracked usage and idle time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdleRatio {
+    /// Local date (YYYY-MM-DD)
+    pub date: String,
+    pub active_seconds: i64,
+    pub idle_seconds: i64,
+    /// Idle share of active + idle time, 0.0 when nothing was recorded
+    pub idle_ratio: f64,
+    /// Idle time by reason, longest first
+    pub by_reason: Vec<IdleReasonTotal>,
+}
+
+/// Whether the current login session is locked.
+/// Only logind is queried; other platforms report unlocked.
+pub fn is_session_locked() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        std::process::Command::new("loginctl")
+            .args(["show-session", "self", "-p", "LockedHint", "--value"])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "yes")
+            .unwrap_or(false)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLD: u64 = 300;
+
+    #[test]
+    fn test_locked_session_is_locked() {
+        let signals = IdleSignals {
+            idle_seconds: 10,
+            locked: true,
+            wake_gap_seconds: 1,
+        };
+        assert_eq!(classify_idle(&signals, THRESHOLD), Some(IdleReason::Locked));
+    }
+
+    #[test]
+    fn test_large_wake_gap_is_suspend() {
+        let signals = IdleSignals {
+            idle_seconds: 0,
+            locked: true,
+            wake_gap_seconds: 3600,
+        };
+        assert_eq!(
+            classify_idle(&signals, THRESHOLD),
+            Some(IdleReason::Suspended)
+        );
+    }
+
+    #[test]
+    fn test_idle_timeout_is_away() {
+        let signals = IdleSignals {
+            idle_seconds: THRESHOLD,
+            locked: false,
+            wake_gap_seconds: 5,
+        };
+        assert_eq!(classify_idle(&signals, THRESHOLD), Some(IdleReason::Away));
+    }
+
+    #[test]
+    fn test_recent_input_is_active() {
+        let signals = IdleSignals {
+            idle_seconds: THRESHOLD - 1,
+            locked: false,
+            wake_gap_seconds: 1,
+        };
+        assert_eq!(classify_idle(&signals, THRESHOLD), None);
+    }
+
+    #[test]
+    fn test_reason_round_trips_through_storage() {
+        for reason in [IdleReason::Locked, IdleReason::Away, IdleReason::Suspended] {
+            assert_eq!(IdleReason::parse(reason.as_str()), Some(reason));
+        }
+        assert_eq!(IdleReason::parse("unknown"), None);
+    }
+}
@@ -1,38 +1,75 @@
+mod app_groups;
 mod app_scanner;
+mod auto_export;
 mod autostart;
+mod blocking;
 mod break_reminder;
+mod category_import;
 mod commands;
+mod config_transfer;
 mod database;
+mod duplicates;
 mod error;
 mod focus_mode;
 mod goals;
+mod idle;
 mod limit_popup;
 mod migrations;
 mod notification_settings;
 mod notifications;
+mod onboarding;
+mod power;
+mod settings_store;
+mod settings_validation;
+mod settings_version;
+#[cfg(test)]
+mod test_support;
 mod theme;
 mod tracker;
 mod tray;
 mod window_tracker;
 
-use app_scanner::InstalledApp;
+use app_groups::AppGroup;
+use app_scanner::{AppSearchResult, CategoryAssignmentSummary, InstalledApp};
+use auto_export::AutoExportSettings;
 use autostart::AutostartStatus;
+use blocking::{BlockMethod, BlockResult, BlockSettings};
 use break_reminder::{BreakReminder, BreakSettings};
-use commands::{DailyStats, DayStats, WeeklyStats};
-use database::{AppLimit, AppUsage, CategoryUsage, Database, ExportRecord, HourlyUsage};
+use category_import::CategoryImportSummary;
+use commands::{
+    AppTotal, CategoryChampion, CurrentActivity, DailyStats, DayStats, DisplaySettings,
+    MonthlyStats, OverLimitApp, Pacing, UsageHighlights, UsagePatterns, UsagePeak, WeekComparison,
+    WeeklyStats,
+};
+use config_transfer::{ConfigBundle, ConfigImportReport, GoalsConfig};
+use database::{
+    AppLimit, AppUsage, Category, CategoryBreakdown, CategoryLimitStatus, CategoryUsage,
+    DailyStreak, DailySummary, Database, ExportRecord, GlobalCapStatus, HourlyUsage, LimitBonus,
+    MigrationHistory, PersonalRecords,
+};
+use duplicates::DuplicateGroup;
 use error::WellbeingError;
-use focus_mode::{FocusManager, FocusSession, FocusSettings};
-use goals::{Achievement, Goal, GoalProgress, GoalsState};
-use limit_popup::EmergencyAccessManager;
+use focus_mode::{
+    FocusDashboard, FocusHistoryEntry, FocusManager, FocusMode, FocusSession, FocusSettings,
+};
+use goals::{
+    Achievement, CompletedChallenge, Goal, GoalProgress, GoalProjection, GoalType, GoalUsage,
+    GoalsState, WeekStart, WeeklyFocusGoal, WeeklyFocusGoalProgress,
+};
+use idle::IdleRatio;
+use limit_popup::{EmergencyAccessManager, EmergencyGrant, EmergencySettings};
 use notification_settings::{NotificationManager, NotificationSettings};
+use onboarding::{OnboardingFacts, OnboardingStatus};
+use power::EcoMode;
+use settings_validation::SettingsReport;
 use std::collections::HashMap;
-use std::process::Command;
 use std::sync::Arc;
 use tauri::{Emitter, Manager, State};
 use tauri_plugin_updater::UpdaterExt;
 use theme::{Theme, ThemeLoader};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 use tracker::UsageTracker;
+use window_tracker::TitleAwareApp;
 
 type CmdResult<T> = Result<T, WellbeingError>;
 
@@ -43,6 +80,21 @@ pub struct AppState {
     pub focus_manager: Arc<FocusManager>,
     pub goals_state: Arc<Mutex<GoalsState>>,
     pub emergency_access: Arc<EmergencyAccessManager>,
+    /// Shared with both trackers so changes apply immediately
+    pub block_settings: Arc<RwLock<BlockSettings>>,
+    /// Read by the background tracker on every tick
+    pub eco_mode: Arc<RwLock<EcoMode>>,
+    pub display_settings: Arc<RwLock<DisplaySettings>>,
+    /// Read by the background tracker when naming each session
+    pub title_aware_apps: Arc<RwLock<Vec<TitleAwareApp>>>,
+    /// Seconds without input before the background tracker stops counting
+    pub idle_threshold_seconds: Arc<RwLock<u64>>,
+    /// Seconds between the background tracker's commits of the open session
+    pub commit_seconds: Arc<RwLock<u64>>,
+    /// Persisted in the database; read by the auto-export task every minute
+    pub auto_export: Arc<RwLock<AutoExportSettings>>,
+    /// Where the database was opened from
+    pub db_path: std::path::PathBuf,
     pub tracker: Arc<Mutex<UsageTracker>>,
     /// The background tracker instance, used for graceful shutdown
     pub background_tracker: Arc<Mutex<Option<Arc<UsageTracker>>>>,
@@ -50,10 +102,11 @@ pub struct AppState {
 
 #[tauri::command]
 async fn get_daily_usage(state: State<'_, AppState>) -> CmdResult<DailyStats> {
+    let min_app_seconds = state.display_settings.read().await.min_app_seconds;
     let db = state.db.lock().await;
-    let apps = db.get_daily_usage()?;
-
-    let total_seconds: i64 = apps.iter().map(|a| a.duration_seconds).sum();
+    let summary = db.get_daily_summary()?;
+    let apps = summary.apps;
+    let total_seconds = summary.total_seconds;
 
     tracing::info!(
         app_count = apps.len(),
@@ -62,38 +115,239 @@ async fn get_daily_usage(state: State<'_, AppState>) -> CmdResult<DailyStats> {
         "get_daily_usage called by frontend"
     );
 
-    Ok(DailyStats {
+    Ok(DailyStats::for_display(
+        apps,
         total_seconds,
+        min_app_seconds,
+    ))
+}
+
+#[tauri::command]
+async fn get_usage_for_date(state: State<'_, AppState>, date: String) -> CmdResult<DailyStats> {
+    let date = parse_date(&date)?;
+    daily_stats_for(&state, date).await
+}
+
+/// Time per window title for an app on a `YYYY-MM-DD` date (default today)
+#[tauri::command]
+async fn get_app_titles(
+    state: State<'_, AppState>,
+    app_name: String,
+    date: Option<String>,
+) -> CmdResult<Vec<commands::TitleUsage>> {
+    if !is_valid_app_name(&app_name) {
+        return Err(WellbeingError::InvalidAppName(app_name));
+    }
+    let date = parse_date_or_today(date)?;
+
+    let db = state.db.lock().await;
+    Ok(db
+        .get_title_breakdown(&app_name, date)?
+        .into_iter()
+        .map(|(title, total_seconds)| commands::TitleUsage {
+            title,
+            total_seconds,
+        })
+        .collect())
+}
+
+/// Raw usage sessions that started on a `YYYY-MM-DD` date, for debugging
+#[tauri::command]
+async fn get_sessions_for_date(
+    state: State<'_, AppState>,
+    date: String,
+) -> CmdResult<Vec<database::UsageSession>> {
+    parse_date(&date)?;
+    let db = state.db.lock().await;
+    Ok(db.get_sessions_for_date(&date)?)
+}
+
+#[tauri::command]
+async fn get_yesterday_usage(state: State<'_, AppState>) -> CmdResult<DailyStats> {
+    let yesterday = chrono::Local::now()
+        .date_naive()
+        .pred_opt()
+        .ok_or_else(|| WellbeingError::Other("Invalid date".into()))?;
+    daily_stats_for(&state, yesterday).await
+}
+
+/// Active vs idle time for a day (defaults to today), with idle time by reason
+#[tauri::command]
+async fn get_idle_ratio(state: State<'_, AppState>, date: Option<String>) -> CmdResult<IdleRatio> {
+    let date = parse_date_or_today(date)?;
+    let db = state.db.lock().await;
+    Ok(db.get_idle_ratio_for(date)?)
+}
+
+/// Usage for a day (defaults to today) by category, with each category's apps
+#[tauri::command]
+async fn get_category_app_breakdown(
+    state: State<'_, AppState>,
+    date: Option<String>,
+) -> CmdResult<Vec<CategoryBreakdown>> {
+    let date = parse_date_or_today(date)?;
+    let db = state.db.lock().await;
+    Ok(db.get_category_app_breakdown(date)?)
+}
+
+/// All-time personal records: longest session, busiest day, most-used app
+#[tauri::command]
+async fn get_records(state: State<'_, AppState>) -> CmdResult<PersonalRecords> {
+    let db = state.db.lock().await;
+    Ok(db.get_records()?)
+}
+
+async fn daily_stats_for(state: &AppState, date: chrono::NaiveDate) -> CmdResult<DailyStats> {
+    let min_app_seconds = state.display_settings.read().await.min_app_seconds;
+    let db = state.db.lock().await;
+    let apps = db.get_daily_usage_for(date)?;
+    let total_seconds = apps.iter().map(|a| a.duration_seconds).sum();
+    Ok(DailyStats::for_display(
         apps,
-    })
+        total_seconds,
+        min_app_seconds,
+    ))
 }
 
 #[tauri::command]
 async fn get_weekly_stats(state: State<'_, AppState>) -> CmdResult<WeeklyStats> {
     let db = state.db.lock().await;
-    let raw_stats = db.get_weekly_stats()?;
+    let days = day_stats(&db.get_weekly_stats()?);
+
+    let total_seconds: i64 = days.iter().map(|d| d.total_seconds).sum();
+    let weekends_count = state.goals_state.lock().await.weekends_count;
+
+    Ok(WeeklyStats {
+        average_daily_seconds: commands::average_daily_seconds(&days, weekends_count),
+        days,
+        total_seconds,
+    })
+}
+
+/// Daily totals for the last 30 days; days without usage are omitted
+#[tauri::command]
+async fn get_monthly_stats(state: State<'_, AppState>) -> CmdResult<MonthlyStats> {
+    let db = state.db.lock().await;
+    let days = day_stats(&db.get_monthly_stats()?);
+    let total_seconds = days.iter().map(|d| d.total_seconds).sum();
+
+    Ok(MonthlyStats {
+        days,
+        total_seconds,
+    })
+}
+
+/// Busiest hour of day and day of week, over a `YYYY-MM-DD` range or all history
+#[tauri::command]
+async fn get_usage_patterns(
+    state: State<'_, AppState>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> CmdResult<UsagePatterns> {
+    let range = match (start_date, end_date) {
+        (Some(start), Some(end)) => Some(day_range_timestamps(&start, &end)?),
+        (None, None) => None,
+        _ => {
+            return Err(WellbeingError::Other(
+                "Both start and end dates are needed for a range".to_string(),
+            ))
+        }
+    };
+
+    let db = state.db.lock().await;
+    let peak = |(value, total_seconds): (u32, i64)| UsagePeak {
+        value,
+        total_seconds,
+    };
+    Ok(UsagePatterns {
+        peak_hour: db.get_peak_hour(range)?.map(peak),
+        peak_weekday: db.get_peak_weekday(range)?.map(peak),
+    })
+}
+
+/// The most-used app ever, and the most-used app of each category
+#[tauri::command]
+async fn get_usage_highlights(state: State<'_, AppState>) -> CmdResult<UsageHighlights> {
+    let db = state.db.lock().await;
+    let top_app = db
+        .get_all_time_top_app()?
+        .map(|(app_name, total_seconds)| AppTotal {
+            app_name,
+            total_seconds,
+        });
+    let category_champions = db
+        .get_top_app_per_category()?
+        .into_iter()
+        .map(|(category, app_name, total_seconds)| CategoryChampion {
+            category,
+            app_name,
+            total_seconds,
+        })
+        .collect();
+
+    Ok(UsageHighlights {
+        top_app,
+        category_champions,
+    })
+}
+
+/// This week's usage against last week's
+#[tauri::command]
+async fn get_week_comparison(state: State<'_, AppState>) -> CmdResult<WeekComparison> {
+    let (this_week_seconds, last_week_seconds) = state.db.lock().await.get_week_comparison()?;
+
+    Ok(WeekComparison {
+        this_week_seconds,
+        last_week_seconds,
+        percent_change: percent_change(this_week_seconds, last_week_seconds),
+    })
+}
+
+#[tauri::command]
+async fn get_pacing(state: State<'_, AppState>) -> CmdResult<Pacing> {
+    use chrono::Timelike;
+
+    let now = chrono::Local::now();
+    let today = now.date_naive();
+    let yesterday = today.pred_opt().unwrap_or(today);
+    let seconds_into_day = now.num_seconds_from_midnight() as i64;
+
+    let db = state.db.lock().await;
+    let today_seconds = db.get_usage_until_time_of_day(today, seconds_into_day)?;
+    let yesterday_seconds = db.get_usage_until_time_of_day(yesterday, seconds_into_day)?;
+
+    Ok(Pacing {
+        today_seconds,
+        yesterday_seconds,
+        ahead_of_yesterday: today_seconds > yesterday_seconds,
+    })
+}
+
+/// Percent change from `previous` to `current`; None when `previous` is 0,
+/// where any change is unbounded
+fn percent_change(current: i64, previous: i64) -> Option<f64> {
+    if previous == 0 {
+        return None;
+    }
+    Some((current - previous) as f64 / previous as f64 * 100.0)
+}
 
-    let days: Vec<commands::DayStats> = raw_stats
+/// Convert `(timestamp, seconds)` rows from the database into `DayStats`
+fn day_stats(raw_stats: &[(i64, i64)]) -> Vec<DayStats> {
+    raw_stats
         .iter()
         .map(|(timestamp, seconds)| {
             let date = chrono::DateTime::from_timestamp(*timestamp, 0)
                 .map(|dt| dt.format("%Y-%m-%d").to_string())
                 .unwrap_or_else(|| "1970-01-01".to_string());
 
-            commands::DayStats {
+            DayStats {
                 date,
                 timestamp: *timestamp,
                 total_seconds: *seconds,
             }
         })
-        .collect();
-
-    let total_seconds: i64 = days.iter().map(|d| d.total_seconds).sum();
-
-    Ok(WeeklyStats {
-        days,
-        total_seconds,
-    })
+        .collect()
 }
 
 #[tauri::command]
@@ -102,10 +356,11 @@ async fn set_app_limit(
     app_name: String,
     minutes: i32,
     block_when_exceeded: Option<bool>,
+    weekend_minutes: Option<i32>,
 ) -> CmdResult<()> {
     let db = state.db.lock().await;
     let block = block_when_exceeded.unwrap_or(false);
-    db.set_limit_with_block(&app_name, minutes, block)?;
+    db.set_limit_with_schedule(&app_name, minutes, weekend_minutes, block)?;
     Ok(())
 }
 
@@ -116,201 +371,901 @@ async fn get_app_limits(state: State<'_, AppState>) -> CmdResult<Vec<AppLimit>>
 }
 
 #[tauri::command]
-async fn remove_app_limit(state: State<'_, AppState>, app_name: String) -> CmdResult<()> {
+async fn set_limit_blocking(
+    state: State<'_, AppState>,
+    app_name: String,
+    block: bool,
+) -> CmdResult<()> {
     let db = state.db.lock().await;
-    db.remove_limit(&app_name)?;
-    Ok(())
+    if db.set_limit_blocking(&app_name, block)? {
+        Ok(())
+    } else {
+        Err(WellbeingError::LimitNotFound(app_name))
+    }
 }
 
 #[tauri::command]
-fn get_theme() -> Theme {
-    ThemeLoader::load()
+async fn get_limit_blocking(state: State<'_, AppState>, app_name: String) -> CmdResult<bool> {
+    let db = state.db.lock().await;
+    db.get_limit_blocking(&app_name)?
+        .ok_or(WellbeingError::LimitNotFound(app_name))
 }
 
 #[tauri::command]
-fn get_theme_path() -> Option<String> {
-    ThemeLoader::get_theme_path().map(|p| p.to_string_lossy().to_string())
+async fn remove_app_limit(state: State<'_, AppState>, app_name: String) -> CmdResult<()> {
+    let db = state.db.lock().await;
+    db.remove_limit(&app_name)?;
+    Ok(())
 }
 
 #[tauri::command]
-async fn get_all_apps(state: State<'_, AppState>) -> CmdResult<Vec<database::App>> {
+async fn get_global_cap(state: State<'_, AppState>) -> CmdResult<Option<GlobalCapStatus>> {
     let db = state.db.lock().await;
-    Ok(db.get_all_apps()?)
+    Ok(db.get_global_cap_status()?)
 }
 
 #[tauri::command]
-async fn record_usage(
+async fn set_global_cap(
     state: State<'_, AppState>,
-    app_name: String,
-    duration_seconds: i64,
+    minutes: i32,
+    block_when_exceeded: Option<bool>,
 ) -> CmdResult<()> {
-    let mut db = state.db.lock().await;
-    db.record_usage_atomic(&app_name, duration_seconds)?;
+    if minutes <= 0 {
+        return Err(WellbeingError::Other(
+            "Daily cap must be at least one minute".into(),
+        ));
+    }
+    let db = state.db.lock().await;
+    db.set_global_cap(minutes, block_when_exceeded.unwrap_or(false))?;
     Ok(())
 }
 
 #[tauri::command]
-async fn get_hourly_usage(state: State<'_, AppState>) -> CmdResult<Vec<HourlyUsage>> {
+async fn remove_global_cap(state: State<'_, AppState>) -> CmdResult<()> {
     let db = state.db.lock().await;
-    Ok(db.get_hourly_usage()?)
+    db.remove_global_cap()?;
+    Ok(())
 }
 
 #[tauri::command]
-async fn get_category_usage(state: State<'_, AppState>) -> CmdResult<Vec<CategoryUsage>> {
+async fn get_categories(state: State<'_, AppState>) -> CmdResult<Vec<Category>> {
     let db = state.db.lock().await;
-    Ok(db.get_category_usage()?)
+    Ok(db.get_categories()?)
 }
 
 #[tauri::command]
-async fn set_app_category(
-    state: State<'_, AppState>,
-    app_name: String,
-    category: String,
-) -> CmdResult<()> {
+async fn upsert_category(state: State<'_, AppState>, category: Category) -> CmdResult<()> {
+    if category.name.trim().is_empty() {
+        return Err(WellbeingError::Other("Category name can't be empty".into()));
+    }
+    if !is_valid_hex_color(&category.color) {
+        return Err(WellbeingError::Other(format!(
+            "Invalid color: {} (expected #RRGGBB)",
+            category.color
+        )));
+    }
     let db = state.db.lock().await;
-    db.set_app_category(&app_name, &category)?;
+    db.upsert_category(&category)?;
     Ok(())
 }
 
-/// Validates an app name to prevent command injection
-/// Only allows alphanumeric characters, spaces, hyphens, underscores, and dots
-fn is_valid_app_name(name: &str) -> bool {
-    !name.is_empty()
-        && name.len() <= 256
-        && name
-            .chars()
-            .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' || c == '.')
+/// Delete a category; its apps become Uncategorized
+#[tauri::command]
+async fn delete_category(state: State<'_, AppState>, name: String) -> CmdResult<bool> {
+    let mut db = state.db.lock().await;
+    Ok(db.delete_category(&name)?)
 }
 
-#[tauri::command]
-async fn check_app_blocked(state: State<'_, AppState>, app_name: String) -> CmdResult<bool> {
-    if !is_valid_app_name(&app_name) {
-        return Err(WellbeingError::InvalidAppName(app_name));
-    }
-    let db = state.db.lock().await;
-    Ok(db.is_app_blocked(&app_name)?)
+/// Whether `color` is a `#RRGGBB` hex color
+fn is_valid_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
 }
 
+/// Today's productivity score, see `compute_productivity_score`
 #[tauri::command]
-fn block_app(app_name: String) -> CmdResult<()> {
-    // Validate app name to prevent command injection
-    if !is_valid_app_name(&app_name) {
-        return Err(WellbeingError::InvalidAppName(app_name));
-    }
-
-    // On Linux, use wmctrl or xdotool to close app windows
-    #[cfg(target_os = "linux")]
-    {
-        // Try to close windows of the app using wmctrl
-        let _ = Command::new("wmctrl").args(["-c", &app_name]).output();
+async fn get_productivity_score(state: State<'_, AppState>) -> CmdResult<u8> {
+    let db = state.db.lock().await;
+    let productive: std::collections::HashSet<String> = db
+        .get_categories()?
+        .into_iter()
+        .filter(|category| category.is_productive)
+        .map(|category| category.name)
+        .collect();
+    Ok(compute_productivity_score(
+        &db.get_category_usage()?,
+        &productive,
+    ))
+}
 
-        // Also try to kill the process (less aggressive approach - send SIGTERM)
-        // Using exact match with -x flag to avoid partial matches
-        let _ = Command::new("pkill").args(["-x", &app_name]).output();
+/// A 0-100 score for how productive a day's usage was.
+///
+/// Time in a productive category counts +1, time in any other category -1
+/// and Uncategorized time 0. The weighted average over all time, which lies
+/// in -1..=1, is mapped onto 0..=100:
+///
+/// `score = 50 + 50 * (productive - distracting) / total`
+///
+/// A day with no usage scores a neutral 50.
+fn compute_productivity_score(
+    category_usage: &[CategoryUsage],
+    productive_categories: &std::collections::HashSet<String>,
+) -> u8 {
+    let mut balance = 0i64;
+    let mut total = 0i64;
+    for usage in category_usage {
+        let seconds = usage.total_seconds.max(0);
+        total += seconds;
+        if productive_categories.contains(&usage.category) {
+            balance += seconds;
+        } else if usage.category != "Uncategorized" {
+            balance -= seconds;
+        }
     }
 
-    // On Windows, use taskkill to terminate the app
-    #[cfg(target_os = "windows")]
-    {
-        let _ = Command::new("taskkill")
-            .args(["/IM", &format!("{}.exe", app_name), "/F"])
-            .output();
+    if total == 0 {
+        return 50;
     }
-
-    Ok(())
+    (50.0 + 50.0 * balance as f64 / total as f64).round() as u8
 }
 
 #[tauri::command]
-async fn get_blocked_apps(state: State<'_, AppState>) -> CmdResult<Vec<String>> {
+async fn get_category_limits(state: State<'_, AppState>) -> CmdResult<Vec<CategoryLimitStatus>> {
     let db = state.db.lock().await;
-    Ok(db.get_blocked_apps()?)
+    Ok(db.get_category_limit_status()?)
 }
 
-// Emergency access commands for limit popup
 #[tauri::command]
-async fn grant_emergency_access(state: State<'_, AppState>, app_name: String) -> CmdResult<i64> {
-    if !is_valid_app_name(&app_name) {
-        return Err(WellbeingError::InvalidAppName(app_name));
+async fn set_category_limit(
+    state: State<'_, AppState>,
+    category: String,
+    minutes: i32,
+    block_when_exceeded: Option<bool>,
+) -> CmdResult<()> {
+    if category.trim().is_empty() {
+        return Err(WellbeingError::Other("Category name can't be empty".into()));
+    }
+    if minutes <= 0 {
+        return Err(WellbeingError::Other(
+            "Category limit must be at least one minute".into(),
+        ));
     }
-    let expiry = state.emergency_access.grant_access(&app_name).await;
+    let db = state.db.lock().await;
+    db.set_category_limit(&category, minutes, block_when_exceeded.unwrap_or(false))?;
+    Ok(())
+}
 
-    // Close the limit popup window
-    let tracker = state.tracker.lock().await;
-    tracker.close_limit_popup();
+#[tauri::command]
+async fn remove_category_limit(state: State<'_, AppState>, category: String) -> CmdResult<()> {
+    let db = state.db.lock().await;
+    db.remove_category_limit(&category)?;
+    Ok(())
+}
 
-    Ok(expiry)
+#[tauri::command]
+async fn get_cap_excluded_apps(state: State<'_, AppState>) -> CmdResult<Vec<String>> {
+    let db = state.db.lock().await;
+    Ok(db.get_cap_excluded_apps()?)
 }
 
+/// Exclude an app from the global cap. It is still tracked and shown.
 #[tauri::command]
-async fn get_emergency_access_remaining(
+async fn set_app_cap_excluded(
     state: State<'_, AppState>,
     app_name: String,
-) -> CmdResult<i64> {
+    excluded: bool,
+) -> CmdResult<()> {
     if !is_valid_app_name(&app_name) {
         return Err(WellbeingError::InvalidAppName(app_name));
     }
-    Ok(state.emergency_access.get_remaining_time(&app_name).await)
+    let db = state.db.lock().await;
+    db.set_app_cap_excluded(&app_name, excluded)?;
+    Ok(())
 }
 
 #[tauri::command]
-async fn has_emergency_access(state: State<'_, AppState>, app_name: String) -> CmdResult<bool> {
-    if !is_valid_app_name(&app_name) {
-        return Err(WellbeingError::InvalidAppName(app_name));
-    }
-    Ok(state.emergency_access.has_active_access(&app_name).await)
+async fn get_excluded_apps(state: State<'_, AppState>) -> CmdResult<Vec<String>> {
+    let db = state.db.lock().await;
+    Ok(db.get_excluded_apps()?)
 }
 
+/// Stop tracking an app entirely. With `purge`, its recorded sessions are
+/// deleted as well.
 #[tauri::command]
-async fn quit_blocked_app(state: State<'_, AppState>, app_name: String) -> CmdResult<()> {
+async fn add_excluded_app(
+    state: State<'_, AppState>,
+    app_name: String,
+    purge: bool,
+) -> CmdResult<()> {
     if !is_valid_app_name(&app_name) {
         return Err(WellbeingError::InvalidAppName(app_name));
     }
+    state.db.lock().await.add_excluded_app(&app_name)?;
 
-    // Close the limit popup window first
-    let tracker = state.tracker.lock().await;
-    tracker.close_limit_popup();
-    tracker.block_app(&app_name);
+    // Otherwise a session already open for the app keeps recording until
+    // the user switches away from it
+    let background_tracker = state.background_tracker.lock().await.clone();
+    if let Some(tracker) = background_tracker {
+        tracker.end_session_of(&app_name).await;
+    }
 
+    if purge {
+        let db = state.db.lock().await;
+        let purged = db.purge_app_sessions(&app_name)?;
+        tracing::info!(app = %app_name, purged, "Purged sessions of excluded app");
+    }
     Ok(())
 }
 
 #[tauri::command]
-fn get_installed_apps() -> Vec<InstalledApp> {
-    app_scanner::get_installed_apps()
+async fn remove_excluded_app(state: State<'_, AppState>, app_name: String) -> CmdResult<bool> {
+    let removed = state.db.lock().await.remove_excluded_app(&app_name)?;
+
+    // Otherwise the app isn't recorded until the user switches away from it
+    // and back
+    let background_tracker = state.background_tracker.lock().await.clone();
+    if let Some(tracker) = background_tracker {
+        tracker.end_session_of(&app_name).await;
+    }
+    Ok(removed)
 }
 
 #[tauri::command]
-fn resolve_app_icon(icon_name: String) -> Option<String> {
-    app_scanner::resolve_icon_path(&icon_name)
+fn get_theme() -> Theme {
+    ThemeLoader::load()
 }
 
 #[tauri::command]
-fn send_test_notification() -> CmdResult<()> {
-    if notifications::send_notification(
-        "Digital Wellbeing",
-        "Notifications are working! You will receive alerts when approaching or exceeding app limits.",
-    ) {
-        Ok(())
-    } else {
-        Err(WellbeingError::Notification(
-            "Failed to send test notification. Check that your system notification service is available.".into(),
-        ))
-    }
+fn get_theme_path() -> Option<String> {
+    ThemeLoader::get_theme_path().map(|p| p.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-fn enable_autostart() -> CmdResult<String> {
-    autostart::install_autostart().map_err(WellbeingError::Autostart)
+async fn get_all_apps(state: State<'_, AppState>) -> CmdResult<Vec<database::App>> {
+    let db = state.db.lock().await;
+    Ok(db.get_all_apps()?)
 }
 
 #[tauri::command]
-fn disable_autostart() -> CmdResult<String> {
-    autostart::uninstall_autostart().map_err(WellbeingError::Autostart)
+async fn record_usage(
+    state: State<'_, AppState>,
+    app_name: String,
+    duration_seconds: i64,
+) -> CmdResult<()> {
+    let mut db = state.db.lock().await;
+    db.record_usage_atomic(&app_name, duration_seconds)?;
+    Ok(())
 }
 
 #[tauri::command]
-fn get_autostart_status() -> AutostartStatus {
-    autostart::get_autostart_status()
+async fn get_hourly_usage(state: State<'_, AppState>) -> CmdResult<Vec<HourlyUsage>> {
+    let db = state.db.lock().await;
+    Ok(db.get_hourly_usage()?)
+}
+
+#[tauri::command]
+async fn get_hourly_usage_for_date(
+    state: State<'_, AppState>,
+    date: String,
+) -> CmdResult<Vec<HourlyUsage>> {
+    let date = parse_date(&date)?;
+    let db = state.db.lock().await;
+    Ok(db.get_hourly_usage_for(date)?)
+}
+
+#[tauri::command]
+async fn get_category_usage(state: State<'_, AppState>) -> CmdResult<Vec<CategoryUsage>> {
+    let db = state.db.lock().await;
+    Ok(db.get_daily_summary()?.categories)
+}
+
+#[tauri::command]
+async fn get_daily_summary(state: State<'_, AppState>) -> CmdResult<DailySummary> {
+    let db = state.db.lock().await;
+    Ok(db.get_daily_summary()?)
+}
+
+#[tauri::command]
+async fn get_daily_usage_grouped(state: State<'_, AppState>) -> CmdResult<DailyStats> {
+    let min_app_seconds = state.display_settings.read().await.min_app_seconds;
+    let db = state.db.lock().await;
+    let summary = db.get_daily_summary()?;
+    let membership = app_groups::membership(&db.get_app_groups()?);
+
+    Ok(DailyStats::for_display(
+        app_groups::group_app_usage(summary.apps, &membership),
+        summary.total_seconds,
+        min_app_seconds,
+    ))
+}
+
+#[tauri::command]
+async fn get_display_settings(state: State<'_, AppState>) -> CmdResult<DisplaySettings> {
+    Ok(state.display_settings.read().await.clone())
+}
+
+#[tauri::command]
+async fn set_display_settings(
+    state: State<'_, AppState>,
+    mut settings: DisplaySettings,
+) -> CmdResult<()> {
+    settings.min_app_seconds = settings.min_app_seconds.max(0);
+    let db = state.db.lock().await;
+    save_meta_settings(&db, commands::DISPLAY_SETTINGS_KEY, &settings)?;
+    *state.display_settings.write().await = settings;
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_app_groups(state: State<'_, AppState>) -> CmdResult<Vec<AppGroup>> {
+    let db = state.db.lock().await;
+    Ok(db.get_app_groups()?)
+}
+
+#[tauri::command]
+async fn set_app_group(
+    state: State<'_, AppState>,
+    group_name: String,
+    members: Vec<String>,
+) -> CmdResult<()> {
+    let group_name = group_name.trim().to_string();
+    if group_name.is_empty() {
+        return Err(WellbeingError::Other("Group name cannot be empty".into()));
+    }
+    if let Some(invalid) = members.iter().find(|m| !is_valid_app_name(m)) {
+        return Err(WellbeingError::InvalidAppName(invalid.clone()));
+    }
+
+    let mut db = state.db.lock().await;
+    db.set_app_group(&group_name, &members)?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn remove_app_group(state: State<'_, AppState>, group_name: String) -> CmdResult<()> {
+    let db = state.db.lock().await;
+    db.remove_app_group(&group_name)?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_app_pinned(
+    state: State<'_, AppState>,
+    app_name: String,
+    pinned: bool,
+) -> CmdResult<()> {
+    if !is_valid_app_name(&app_name) {
+        return Err(WellbeingError::InvalidAppName(app_name));
+    }
+    let db = state.db.lock().await;
+    if !db.set_app_pinned(&app_name, pinned)? {
+        return Err(WellbeingError::AppNotFound(app_name));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_app_category(
+    state: State<'_, AppState>,
+    app_name: String,
+    category: String,
+) -> CmdResult<()> {
+    let db = state.db.lock().await;
+    db.set_app_category(&app_name, &category)?;
+    Ok(())
+}
+
+/// Groups of tracked app names that probably refer to the same program, so
+/// the user can decide which to merge. Read-only.
+#[tauri::command]
+async fn find_possible_duplicate_apps(
+    state: State<'_, AppState>,
+) -> CmdResult<Vec<DuplicateGroup>> {
+    let db = state.db.lock().await;
+    Ok(duplicates::find_duplicate_groups(&db.get_app_totals()?))
+}
+
+/// Assign categories from CSV rows of `app_name,category`. Malformed rows are
+/// skipped and reported; the rest are applied together.
+#[tauri::command]
+async fn import_categories_csv(
+    state: State<'_, AppState>,
+    csv: String,
+) -> CmdResult<CategoryImportSummary> {
+    let (assignments, skipped) = category_import::parse_category_csv(&csv);
+    let mut db = state.db.lock().await;
+    db.set_app_categories(&assignments)?;
+
+    Ok(CategoryImportSummary {
+        applied: assignments.len(),
+        custom_categories: category_import::custom_categories(&assignments),
+        skipped,
+    })
+}
+
+/// Fold all of `from_name`'s tracked data into `to_name`
+#[tauri::command]
+async fn merge_apps(
+    state: State<'_, AppState>,
+    from_name: String,
+    to_name: String,
+) -> CmdResult<()> {
+    for name in [&from_name, &to_name] {
+        if !is_valid_app_name(name) {
+            return Err(WellbeingError::InvalidAppName(name.clone()));
+        }
+    }
+    let mut db = state.db.lock().await;
+    if !db.merge_apps(&from_name, &to_name)? {
+        return Err(WellbeingError::AppNotFound(from_name));
+    }
+    Ok(())
+}
+
+/// Validates an app name to prevent command injection
+/// Only allows alphanumeric characters, spaces, hyphens, underscores, and dots
+fn is_valid_app_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 256
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' || c == '.')
+}
+
+#[tauri::command]
+async fn check_app_blocked(state: State<'_, AppState>, app_name: String) -> CmdResult<bool> {
+    if !is_valid_app_name(&app_name) {
+        return Err(WellbeingError::InvalidAppName(app_name));
+    }
+    let db = state.db.lock().await;
+    Ok(db.is_app_blocked(&app_name)?)
+}
+
+#[tauri::command]
+async fn block_app(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    app_name: String,
+) -> CmdResult<BlockResult> {
+    // Validate app name to prevent command injection
+    if !is_valid_app_name(&app_name) {
+        return Err(WellbeingError::InvalidAppName(app_name));
+    }
+
+    let settings = state.block_settings.read().await.clone();
+    let method = blocking::kill_app(&app_name, &settings).await;
+    match method {
+        BlockMethod::DryRun => {
+            notifications::notify(
+                Some(&app),
+                &format!("{} would be blocked", app_name),
+                "Dry run is on, so the app stays open.",
+                "normal",
+            );
+            Ok(BlockResult {
+                attempted: false,
+                succeeded: false,
+                method,
+            })
+        }
+        BlockMethod::Unsupported => Ok(BlockResult {
+            attempted: false,
+            succeeded: false,
+            method,
+        }),
+        _ => Ok(BlockResult {
+            attempted: true,
+            succeeded: blocking::verify_block(&app_name).await,
+            method,
+        }),
+    }
+}
+
+#[tauri::command]
+async fn get_blocked_apps(state: State<'_, AppState>) -> CmdResult<Vec<String>> {
+    let db = state.db.lock().await;
+    Ok(db.get_blocked_apps()?)
+}
+
+/// Apps whose usage today has gone past their limit, most over first
+#[tauri::command]
+async fn get_over_limit_apps(state: State<'_, AppState>) -> CmdResult<Vec<OverLimitApp>> {
+    let db = state.db.lock().await;
+    Ok(commands::over_limit_apps(db.get_all_limit_status()?))
+}
+
+/// Remaining minutes and fraction used for every limited app, most used first
+#[tauri::command]
+async fn get_limits_remaining(state: State<'_, AppState>) -> CmdResult<Vec<(String, i32, f64)>> {
+    let db = state.db.lock().await;
+    Ok(db.get_limit_remaining()?)
+}
+
+// Emergency access commands for limit popup
+#[tauri::command]
+async fn grant_emergency_access(
+    state: State<'_, AppState>,
+    app_name: String,
+    duration_seconds: Option<i64>,
+) -> CmdResult<i64> {
+    grant_limit_access(&state, app_name, duration_seconds).await
+}
+
+/// Allow an over-limit app for `minutes` (1 to 240), after which it's
+/// blocked again
+#[tauri::command]
+async fn grant_timed_access(
+    state: State<'_, AppState>,
+    app_name: String,
+    minutes: u32,
+) -> CmdResult<i64> {
+    grant_limit_access(&state, app_name, Some(minutes as i64 * 60)).await
+}
+
+/// Grant emergency access for `duration_seconds`, or the configured duration,
+/// persist it and close the limit popup. Returns the expiry timestamp.
+/// Every grant command comes through here, so they share one duration check.
+async fn grant_limit_access(
+    state: &AppState,
+    app_name: String,
+    duration_seconds: Option<i64>,
+) -> CmdResult<i64> {
+    if !is_valid_app_name(&app_name) {
+        return Err(WellbeingError::InvalidAppName(app_name));
+    }
+    if let Some(seconds) = duration_seconds {
+        EmergencySettings::validate_duration(seconds).map_err(WellbeingError::Other)?;
+    }
+    let granted_at = chrono::Utc::now().timestamp();
+    let expiry = match duration_seconds {
+        Some(seconds) => {
+            state
+                .emergency_access
+                .grant_access_with_duration(&app_name, seconds)
+                .await
+        }
+        None => state.emergency_access.grant_access(&app_name).await,
+    }
+    .map_err(WellbeingError::Other)?;
+
+    // Persist the grant so it outlasts a restart and counts toward the cap
+    let grant = EmergencyGrant {
+        app_name: app_name.clone(),
+        granted_at,
+        expires_at: expiry,
+    };
+    if let Err(e) = state.db.lock().await.record_emergency_grant(&grant) {
+        tracing::error!(app = %app_name, error = %e, "Failed to save emergency grant");
+    }
+
+    // Close the limit popup window
+    let tracker = state.tracker.lock().await;
+    tracker.close_limit_popup();
+
+    Ok(expiry)
+}
+
+#[tauri::command]
+async fn get_emergency_access_remaining(
+    state: State<'_, AppState>,
+    app_name: String,
+) -> CmdResult<i64> {
+    if !is_valid_app_name(&app_name) {
+        return Err(WellbeingError::InvalidAppName(app_name));
+    }
+    Ok(state.emergency_access.get_remaining_time(&app_name).await)
+}
+
+#[tauri::command]
+async fn has_emergency_access(state: State<'_, AppState>, app_name: String) -> CmdResult<bool> {
+    if !is_valid_app_name(&app_name) {
+        return Err(WellbeingError::InvalidAppName(app_name));
+    }
+    Ok(state.emergency_access.has_active_access(&app_name).await)
+}
+
+#[tauri::command]
+async fn quit_blocked_app(state: State<'_, AppState>, app_name: String) -> CmdResult<BlockResult> {
+    if !is_valid_app_name(&app_name) {
+        return Err(WellbeingError::InvalidAppName(app_name));
+    }
+
+    // Close the limit popup window first, then let go of the tracker while
+    // the app is closed and checked
+    let blocker = {
+        let tracker = state.tracker.lock().await;
+        tracker.close_limit_popup();
+        tracker.blocker()
+    };
+    Ok(blocker.block_app(&app_name).await)
+}
+
+/// The running background tracker, once setup has started it
+async fn running_tracker(state: &AppState) -> CmdResult<Arc<UsageTracker>> {
+    state
+        .background_tracker
+        .lock()
+        .await
+        .clone()
+        .ok_or_else(|| WellbeingError::Other("Tracking has not started yet".to_string()))
+}
+
+#[tauri::command]
+async fn pause_tracking(app: tauri::AppHandle, state: State<'_, AppState>) -> CmdResult<()> {
+    running_tracker(&state).await?.pause_tracking().await;
+    tray::refresh_tray(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn resume_tracking(app: tauri::AppHandle, state: State<'_, AppState>) -> CmdResult<()> {
+    running_tracker(&state).await?.resume_tracking().await;
+    tray::refresh_tray(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn is_tracking_paused(state: State<'_, AppState>) -> CmdResult<bool> {
+    Ok(running_tracker(&state).await?.is_tracking_paused())
+}
+
+#[tauri::command]
+async fn get_current_activity(state: State<'_, AppState>) -> CmdResult<CurrentActivity> {
+    let (app_name, session_seconds) = running_tracker(&state)
+        .await?
+        .current_activity_at(chrono::Utc::now().timestamp())
+        .await;
+    Ok(CurrentActivity {
+        app_name,
+        session_seconds,
+    })
+}
+
+#[tauri::command]
+async fn get_emergency_settings(state: State<'_, AppState>) -> CmdResult<EmergencySettings> {
+    Ok(state.emergency_access.get_settings().await)
+}
+
+#[tauri::command]
+async fn set_emergency_settings(
+    state: State<'_, AppState>,
+    settings: EmergencySettings,
+) -> CmdResult<()> {
+    settings.validate().map_err(WellbeingError::Config)?;
+
+    let db = state.db.lock().await;
+    save_meta_settings(&db, limit_popup::SETTINGS_KEY, &settings)?;
+    state.emergency_access.update_settings(settings).await;
+    Ok(())
+}
+
+/// Settings saved in app_meta under `key`, or the defaults if there are none
+fn load_meta_settings<T: serde::de::DeserializeOwned + Default>(db: &Database, key: &str) -> T {
+    db.get_meta(key)
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Save settings to app_meta under `key`
+fn save_meta_settings<T: serde::Serialize>(
+    db: &Database,
+    key: &str,
+    settings: &T,
+) -> CmdResult<()> {
+    let json = serde_json::to_string(settings)
+        .map_err(|e| WellbeingError::Config(format!("Failed to save settings: {}", e)))?;
+    db.set_meta(key, &json)?;
+    Ok(())
+}
+
+/// Emergency access manager with saved settings and today's grants
+fn load_emergency_access(db: &Database) -> EmergencyAccessManager {
+    let (settings, grants) = load_emergency_state(db);
+    EmergencyAccessManager::restore(settings, grants)
+}
+
+/// Saved emergency access settings and today's grants
+fn load_emergency_state(db: &Database) -> (EmergencySettings, Vec<EmergencyGrant>) {
+    let settings: EmergencySettings = load_meta_settings(db, limit_popup::SETTINGS_KEY);
+    let today_start = local_day_start(chrono::Local::now().date_naive());
+    // Grants that ran out before today no longer count toward anything
+    if let Err(e) = db.delete_emergency_grants_expired_before(today_start) {
+        tracing::error!(error = %e, "Failed to delete expired emergency grants");
+    }
+    let grants = db
+        .get_emergency_grants_since(today_start)
+        .unwrap_or_else(|e| {
+            tracing::error!(error = %e, "Failed to load emergency grants");
+            Vec::new()
+        });
+    (settings, grants)
+}
+
+#[tauri::command]
+async fn get_block_settings(state: State<'_, AppState>) -> CmdResult<BlockSettings> {
+    Ok(state.block_settings.read().await.clone())
+}
+
+#[tauri::command]
+async fn set_block_settings(state: State<'_, AppState>, settings: BlockSettings) -> CmdResult<()> {
+    let db = state.db.lock().await;
+    save_meta_settings(&db, blocking::SETTINGS_KEY, &settings)?;
+    *state.block_settings.write().await = settings;
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_eco_mode(state: State<'_, AppState>) -> CmdResult<EcoMode> {
+    Ok(*state.eco_mode.read().await)
+}
+
+#[tauri::command]
+async fn set_eco_mode(state: State<'_, AppState>, mode: EcoMode) -> CmdResult<()> {
+    tracing::info!(mode = ?mode, "Eco mode changed");
+    let db = state.db.lock().await;
+    save_meta_settings(&db, power::SETTINGS_KEY, &mode)?;
+    *state.eco_mode.write().await = mode;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_installed_apps() -> Vec<InstalledApp> {
+    app_scanner::cached_installed_apps()
+}
+
+/// Installed apps matching `query`, best match first
+#[tauri::command]
+fn search_installed_apps(query: String) -> Vec<InstalledApp> {
+    app_scanner::search_installed_apps(&query)
+}
+
+/// Rescan installed apps on the next search
+#[tauri::command]
+fn refresh_installed_apps() {
+    app_scanner::refresh_installed_apps();
+}
+
+/// Default number of results returned by `search_apps`
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// Categorize every tracked app that has no category yet, using the
+/// categories of a matching installed app
+#[tauri::command]
+async fn apply_installed_categories(
+    state: State<'_, AppState>,
+) -> CmdResult<CategoryAssignmentSummary> {
+    let installed = app_scanner::cached_installed_apps();
+
+    let db = state.db.lock().await;
+    let uncategorized: Vec<String> = db
+        .get_all_apps()?
+        .into_iter()
+        .filter(|app| app.category.as_deref().is_none_or(|c| c.trim().is_empty()))
+        .map(|app| app.name)
+        .collect();
+
+    let (assignments, unmatched) =
+        app_scanner::match_installed_categories(&uncategorized, &installed);
+    for (app_name, category) in &assignments {
+        db.set_app_category(app_name, category)?;
+    }
+
+    tracing::info!(
+        categorized = assignments.len(),
+        unmatched,
+        "Applied categories from installed apps"
+    );
+
+    Ok(CategoryAssignmentSummary {
+        categorized: assignments.len(),
+        unmatched,
+    })
+}
+
+#[tauri::command]
+async fn search_apps(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<usize>,
+) -> CmdResult<Vec<AppSearchResult>> {
+    let tracked: Vec<String> = {
+        let db = state.db.lock().await;
+        db.get_all_apps()?.into_iter().map(|a| a.name).collect()
+    };
+    let installed: Vec<String> = app_scanner::cached_installed_apps()
+        .into_iter()
+        .map(|a| a.name)
+        .collect();
+
+    Ok(app_scanner::search_apps(
+        &query,
+        &installed,
+        &tracked,
+        limit.unwrap_or(DEFAULT_SEARCH_LIMIT),
+    ))
+}
+
+/// Use a custom icon for an app: an image file path or an image data URL
+#[tauri::command]
+async fn set_app_icon(state: State<'_, AppState>, app_name: String, icon: String) -> CmdResult<()> {
+    app_scanner::validate_custom_icon(&icon).map_err(WellbeingError::Other)?;
+    let db = state.db.lock().await;
+    if !db.set_app_icon(&app_name, Some(&icon))? {
+        return Err(WellbeingError::AppNotFound(app_name));
+    }
+    Ok(())
+}
+
+/// Go back to the scanned icon (or none)
+#[tauri::command]
+async fn clear_app_icon(state: State<'_, AppState>, app_name: String) -> CmdResult<()> {
+    let db = state.db.lock().await;
+    if !db.set_app_icon(&app_name, None)? {
+        return Err(WellbeingError::AppNotFound(app_name));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn resolve_app_icon(icon_name: String) -> Option<String> {
+    app_scanner::resolve_icon_path(&icon_name)
+}
+
+#[tauri::command]
+fn send_test_notification(app: tauri::AppHandle) -> CmdResult<()> {
+    if notifications::notify(
+        Some(&app),
+        "Digital Wellbeing",
+        "Notifications are working! You will receive alerts when approaching or exceeding app limits.",
+        "normal",
+    ) {
+        Ok(())
+    } else {
+        Err(WellbeingError::Notification(
+            "Failed to send test notification. Check that your system notification service is available.".into(),
+        ))
+    }
+}
+
+#[tauri::command]
+fn enable_autostart(state: State<'_, AppState>) -> CmdResult<String> {
+    // The background process must open the same database as this one
+    let db_path = Some(state.db_path.as_path()).filter(|path| *path != default_db_path());
+    autostart::install_autostart(db_path).map_err(WellbeingError::Autostart)
+}
+
+#[tauri::command]
+fn disable_autostart() -> CmdResult<String> {
+    autostart::uninstall_autostart().map_err(WellbeingError::Autostart)
+}
+
+#[tauri::command]
+fn get_autostart_status() -> AutostartStatus {
+    autostart::get_autostart_status()
+}
+
+#[tauri::command]
+fn check_tracking_permissions() -> bool {
+    window_tracker::check_tracking_permissions()
+}
+
+/// Setup checklist for new installs
+#[tauri::command]
+async fn get_onboarding_status(state: State<'_, AppState>) -> CmdResult<OnboardingStatus> {
+    let (limit_count, session_count) = {
+        let db = state.db.lock().await;
+        (db.get_all_limits()?.len(), db.get_storage_stats()?.0)
+    };
+    let goal_count = state.goals_state.lock().await.goals.len();
+
+    Ok(onboarding::onboarding_status(OnboardingFacts {
+        tracking_permitted: window_tracker::check_tracking_permissions(),
+        autostart_enabled: autostart::get_autostart_status().enabled,
+        limit_count,
+        goal_count,
+        session_count,
+    }))
 }
 
 /// Default data retention period in days
@@ -323,35 +1278,452 @@ async fn cleanup_old_data(state: State<'_, AppState>, days: Option<i64>) -> CmdR
     Ok(db.cleanup_old_data(retention_days)?)
 }
 
-#[tauri::command]
-async fn get_storage_stats(state: State<'_, AppState>) -> CmdResult<(i64, i64, Option<String>)> {
-    let db = state.db.lock().await;
-    Ok(db.get_storage_stats()?)
+/// Delete old sessions with a retention period per category. Categories not
+/// in `retention_days`, and uncategorized apps, keep `default_days`. Zero or
+/// fewer days keeps data forever.
+#[tauri::command]
+async fn cleanup_old_data_by_category(
+    state: State<'_, AppState>,
+    retention_days: HashMap<String, i64>,
+    default_days: Option<i64>,
+) -> CmdResult<usize> {
+    let default_days = default_days.unwrap_or(DEFAULT_RETENTION_DAYS);
+    let mut db = state.db.lock().await;
+    Ok(db.cleanup_old_data_by_category(&retention_days, default_days)?)
+}
+
+/// Shrink the database file after a cleanup. Returns the bytes reclaimed.
+/// VACUUM needs the database to itself, which holding the lock guarantees.
+#[tauri::command]
+async fn optimize_database(state: State<'_, AppState>) -> CmdResult<i64> {
+    let db = state.db.lock().await;
+    let reclaimed = db.optimize()?;
+    tracing::info!(bytes = reclaimed, "Database optimized");
+    Ok(reclaimed)
+}
+
+/// Copy the database to `dest_path`, e.g. to move history to another machine
+#[tauri::command]
+async fn backup_database(state: State<'_, AppState>, dest_path: String) -> CmdResult<()> {
+    let db = state.db.lock().await;
+    db.backup_to(std::path::Path::new(&dest_path))?;
+    tracing::info!(path = %dest_path, "Database backed up");
+    Ok(())
+}
+
+/// Replace the database with a backup made by `backup_database`
+#[tauri::command]
+async fn restore_database(state: State<'_, AppState>, src_path: String) -> CmdResult<()> {
+    let restore = async {
+        let mut db = state.db.lock().await;
+        db.restore_from(std::path::Path::new(&src_path))?;
+        reload_saved_state(&state, &db).await
+    };
+
+    // The open session and idle period are rows of the database being
+    // replaced; later flushes would overwrite unrelated restored rows
+    let background_tracker = state.background_tracker.lock().await.clone();
+    match background_tracker {
+        Some(tracker) => tracker.end_session_during(restore).await?,
+        None => restore.await?,
+    }
+    tracing::info!(path = %src_path, "Database restored");
+    Ok(())
+}
+
+/// Replace the state cached in memory with what `db` has saved, after the
+/// database was restored from a backup
+async fn reload_saved_state(state: &AppState, db: &Database) -> CmdResult<()> {
+    *state.goals_state.lock().await = db.load_goals_state()?;
+
+    let (emergency_settings, grants) = load_emergency_state(db);
+    state
+        .emergency_access
+        .reload(emergency_settings, grants)
+        .await;
+    *state.auto_export.write().await = load_meta_settings(db, auto_export::SETTINGS_KEY);
+    *state.block_settings.write().await = load_meta_settings(db, blocking::SETTINGS_KEY);
+    *state.eco_mode.write().await = load_meta_settings(db, power::SETTINGS_KEY);
+    *state.display_settings.write().await = load_meta_settings(db, commands::DISPLAY_SETTINGS_KEY);
+    *state.title_aware_apps.write().await =
+        load_meta_settings(db, window_tracker::TITLE_AWARE_APPS_KEY);
+    *state.idle_threshold_seconds.write().await = load_idle_threshold(db);
+    *state.commit_seconds.write().await = load_commit_seconds(db);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_migration_history(state: State<'_, AppState>) -> CmdResult<MigrationHistory> {
+    let db = state.db.lock().await;
+    Ok(db.get_migration_history()?)
+}
+
+#[tauri::command]
+async fn get_storage_stats(state: State<'_, AppState>) -> CmdResult<(i64, i64, Option<String>)> {
+    let db = state.db.lock().await;
+    Ok(db.get_storage_stats()?)
+}
+
+#[tauri::command]
+async fn export_usage_data(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    anonymize: Option<bool>,
+) -> CmdResult<Vec<ExportRecord>> {
+    let (start_timestamp, end_timestamp) = export_range_timestamps(&start_date, &end_date)?;
+
+    let db = state.db.lock().await;
+    let records = db.export_usage_data(start_timestamp, end_timestamp)?;
+
+    if anonymize.unwrap_or(false) {
+        Ok(anonymize_export_records(records))
+    } else {
+        Ok(records)
+    }
+}
+
+#[tauri::command]
+async fn export_sessions(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+) -> CmdResult<Vec<database::UsageSession>> {
+    let (start_timestamp, end_timestamp) = export_range_timestamps(&start_date, &end_date)?;
+
+    let db = state.db.lock().await;
+    Ok(db.export_sessions_in_range(start_timestamp, end_timestamp)?)
+}
+
+/// Replace app names and categories with pseudonyms ("App 1", "Category 1").
+/// Each distinct name gets the same pseudonym everywhere in the export, in
+/// order of first appearance. Dates, durations and session counts are kept.
+fn anonymize_export_records(records: Vec<ExportRecord>) -> Vec<ExportRecord> {
+    let mut apps: HashMap<String, String> = HashMap::new();
+    let mut categories: HashMap<String, String> = HashMap::new();
+
+    records
+        .into_iter()
+        .map(|record| {
+            let next_app = apps.len() + 1;
+            let app_name = apps
+                .entry(record.app_name)
+                .or_insert_with(|| format!("App {}", next_app))
+                .clone();
+            let next_category = categories.len() + 1;
+            let category = categories
+                .entry(record.category)
+                .or_insert_with(|| format!("Category {}", next_category))
+                .clone();
+
+            ExportRecord {
+                app_name,
+                category,
+                ..record
+            }
+        })
+        .collect()
+}
+
+/// Convert an inclusive YYYY-MM-DD export range into timestamps using local day
+/// boundaries, matching the local dates the export records are bucketed by.
+fn export_range_timestamps(start_date: &str, end_date: &str) -> CmdResult<(i64, i64)> {
+    day_range_timestamps(start_date, end_date).map_err(|e| match e {
+        WellbeingError::Other(message) => WellbeingError::Export(message),
+        e => e,
+    })
+}
+
+/// Convert an inclusive YYYY-MM-DD range into timestamps from the start of
+/// the first local day to the last second of the last one
+fn day_range_timestamps(start_date: &str, end_date: &str) -> CmdResult<(i64, i64)> {
+    let start = parse_date(start_date)?;
+    let day_after_end = parse_date(end_date)?
+        .succ_opt()
+        .ok_or_else(|| WellbeingError::Other(format!("Invalid end date: {}", end_date)))?;
+
+    Ok((local_day_start(start), local_day_start(day_after_end) - 1))
+}
+
+/// Parse a `YYYY-MM-DD` date passed in from the frontend
+fn parse_date(date: &str) -> CmdResult<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|e| WellbeingError::Other(format!("Invalid date: {}", e)))
+}
+
+/// Parse an optional `YYYY-MM-DD` date, defaulting to today
+fn parse_date_or_today(date: Option<String>) -> CmdResult<chrono::NaiveDate> {
+    match date {
+        Some(date) => parse_date(&date),
+        None => Ok(chrono::Local::now().date_naive()),
+    }
+}
+
+/// Timestamp of the first local instant of `date`. When a DST change skips
+/// midnight, the first valid time after it is used instead.
+fn local_day_start(date: chrono::NaiveDate) -> i64 {
+    database::timestamp_in(&chrono::Local, date.and_time(chrono::NaiveTime::MIN))
+}
+
+#[tauri::command]
+async fn get_title_aware_apps(state: State<'_, AppState>) -> CmdResult<Vec<TitleAwareApp>> {
+    Ok(state.title_aware_apps.read().await.clone())
+}
+
+/// Set the apps whose usage is split by window title. Blank and duplicate
+/// app names are dropped.
+#[tauri::command]
+async fn set_title_aware_apps(
+    state: State<'_, AppState>,
+    apps: Vec<TitleAwareApp>,
+) -> CmdResult<()> {
+    let mut seen = std::collections::HashSet::new();
+    let apps: Vec<TitleAwareApp> = apps
+        .into_iter()
+        .map(|app| TitleAwareApp {
+            app_name: app.app_name.trim().to_string(),
+            ..app
+        })
+        .filter(|app| !app.app_name.is_empty() && seen.insert(app.app_name.to_lowercase()))
+        .collect();
+    let db = state.db.lock().await;
+    save_meta_settings(&db, window_tracker::TITLE_AWARE_APPS_KEY, &apps)?;
+    *state.title_aware_apps.write().await = apps;
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_idle_threshold(state: State<'_, AppState>) -> CmdResult<u64> {
+    Ok(*state.idle_threshold_seconds.read().await)
+}
+
+/// Set how many seconds without input count as away. Returns the value
+/// applied, clamped to the supported range.
+#[tauri::command]
+async fn set_idle_threshold(state: State<'_, AppState>, seconds: u64) -> CmdResult<u64> {
+    let seconds = seconds.clamp(
+        idle::MIN_IDLE_THRESHOLD_SECONDS,
+        idle::MAX_IDLE_THRESHOLD_SECONDS,
+    );
+    let db = state.db.lock().await;
+    db.set_meta(idle::THRESHOLD_KEY, &seconds.to_string())?;
+    *state.idle_threshold_seconds.write().await = seconds;
+    Ok(seconds)
+}
+
+#[tauri::command]
+async fn get_flush_interval(state: State<'_, AppState>) -> CmdResult<u64> {
+    Ok(*state.commit_seconds.read().await)
+}
+
+/// Set how many seconds pass between commits of the open session to the
+/// database, i.e. how much a crash can lose. Returns the value applied,
+/// clamped to the supported range.
+#[tauri::command]
+async fn set_flush_interval(state: State<'_, AppState>, seconds: u64) -> CmdResult<u64> {
+    let seconds = seconds.clamp(power::MIN_COMMIT_SECONDS, power::MAX_COMMIT_SECONDS);
+    let db = state.db.lock().await;
+    db.set_meta(power::COMMIT_SECONDS_KEY, &seconds.to_string())?;
+    *state.commit_seconds.write().await = seconds;
+    Ok(seconds)
+}
+
+/// The saved session commit interval, or the default if none was saved
+fn load_commit_seconds(db: &Database) -> u64 {
+    db.get_meta(power::COMMIT_SECONDS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|seconds| seconds.parse::<u64>().ok())
+        .map(|seconds| seconds.clamp(power::MIN_COMMIT_SECONDS, power::MAX_COMMIT_SECONDS))
+        .unwrap_or(power::NORMAL_COMMIT_SECONDS)
+}
+
+/// The saved idle threshold, or the default if none was saved
+fn load_idle_threshold(db: &Database) -> u64 {
+    db.get_meta(idle::THRESHOLD_KEY)
+        .ok()
+        .flatten()
+        .and_then(|seconds| seconds.parse::<u64>().ok())
+        .map(|seconds| {
+            seconds.clamp(
+                idle::MIN_IDLE_THRESHOLD_SECONDS,
+                idle::MAX_IDLE_THRESHOLD_SECONDS,
+            )
+        })
+        .unwrap_or(idle::DEFAULT_IDLE_THRESHOLD_SECONDS)
+}
+
+#[tauri::command]
+async fn get_auto_export_settings(state: State<'_, AppState>) -> CmdResult<AutoExportSettings> {
+    Ok(state.auto_export.read().await.clone())
+}
+
+#[tauri::command]
+async fn set_auto_export_settings(
+    state: State<'_, AppState>,
+    settings: AutoExportSettings,
+) -> CmdResult<()> {
+    settings.validate().map_err(WellbeingError::Config)?;
+
+    let db = state.db.lock().await;
+    save_meta_settings(&db, auto_export::SETTINGS_KEY, &settings)?;
+    *state.auto_export.write().await = settings;
+    Ok(())
+}
+
+/// Settings and definitions as a JSON bundle for sharing, without usage data
+#[tauri::command]
+async fn export_config(state: State<'_, AppState>) -> CmdResult<String> {
+    let mut bundle = ConfigBundle::new(chrono::Utc::now().to_rfc3339());
+    bundle.definitions = state.db.lock().await.get_config_definitions()?;
+    bundle.focus = Some(state.focus_manager.get_settings().await);
+    bundle.breaks = Some(state.break_reminder.get_settings().await);
+    bundle.notifications = Some(state.notification_manager.get_settings().await);
+    {
+        let goals_state = state.goals_state.lock().await;
+        bundle.goals = Some(GoalsConfig {
+            goals: goals_state.goals.clone(),
+            weekends_count: goals_state.weekends_count,
+            week_start: goals_state.week_start,
+            weekly_focus_goal: goals_state.weekly_focus_goal.clone(),
+        });
+    }
+    bundle.block_settings = Some(state.block_settings.read().await.clone());
+    bundle.display = Some(state.display_settings.read().await.clone());
+    bundle.title_aware_apps = Some(state.title_aware_apps.read().await.clone());
+    bundle.auto_export = Some(state.auto_export.read().await.clone());
+
+    serde_json::to_string_pretty(&bundle)
+        .map_err(|e| WellbeingError::Config(format!("Failed to export config: {}", e)))
+}
+
+/// Apply a bundle from `export_config`. Each section is validated separately;
+/// invalid sections are skipped and replaced values reported as conflicts.
+#[tauri::command]
+async fn import_config(state: State<'_, AppState>, json: String) -> CmdResult<ConfigImportReport> {
+    let mut bundle: ConfigBundle = serde_json::from_str(&json)
+        .map_err(|e| WellbeingError::Config(format!("Invalid config: {}", e)))?;
+    if bundle.format_version > config_transfer::CONFIG_FORMAT_VERSION {
+        return Err(WellbeingError::Config(format!(
+            "Config format {} is newer than this version supports",
+            bundle.format_version
+        )));
+    }
+
+    let mut report = config_transfer::validate_bundle(&mut bundle);
+    settings_validation::log_repairs(&report.repairs);
+    report.applied = bundle.sections();
+
+    {
+        let mut db = state.db.lock().await;
+        let current = db.get_config_definitions()?;
+        report.conflicts = config_transfer::definition_conflicts(&current, &bundle.definitions);
+        db.apply_config_definitions(&bundle.definitions)?;
+        if let Some(settings) = &bundle.auto_export {
+            save_meta_settings(&db, auto_export::SETTINGS_KEY, settings)?;
+        }
+        if let Some(settings) = &bundle.block_settings {
+            save_meta_settings(&db, blocking::SETTINGS_KEY, settings)?;
+        }
+        if let Some(settings) = &bundle.display {
+            save_meta_settings(&db, commands::DISPLAY_SETTINGS_KEY, settings)?;
+        }
+        if let Some(apps) = &bundle.title_aware_apps {
+            save_meta_settings(&db, window_tracker::TITLE_AWARE_APPS_KEY, apps)?;
+        }
+    }
+
+    if let Some(settings) = bundle.focus {
+        state.focus_manager.update_settings(settings).await;
+    }
+    if let Some(settings) = bundle.breaks {
+        state.break_reminder.update_settings(settings).await;
+    }
+    if let Some(settings) = bundle.notifications {
+        state.notification_manager.update_settings(settings).await;
+    }
+    if let Some(config) = bundle.goals {
+        let mut db = state.db.lock().await;
+        let mut goals_state = state.goals_state.lock().await;
+        for goal in config.goals {
+            if goals_state.goals.iter().any(|g| g.id == goal.id) {
+                report.conflicts.push(config_transfer::ConfigConflict {
+                    section: "goals".to_string(),
+                    item: goal.id.clone(),
+                    message: format!("goal '{}' replaced", goal.name),
+                });
+                goals_state.update_goal(goal);
+            } else {
+                goals_state.add_goal(goal);
+            }
+        }
+        goals_state.weekends_count = config.weekends_count;
+        goals_state.week_start = config.week_start;
+        goals_state.weekly_focus_goal = config.weekly_focus_goal;
+        db.save_goals_state(&goals_state)?;
+    }
+    if let Some(settings) = bundle.block_settings {
+        *state.block_settings.write().await = settings;
+    }
+    if let Some(settings) = bundle.display {
+        *state.display_settings.write().await = settings;
+    }
+    if let Some(apps) = bundle.title_aware_apps {
+        *state.title_aware_apps.write().await = apps;
+    }
+    if let Some(settings) = bundle.auto_export {
+        *state.auto_export.write().await = settings;
+    }
+
+    tracing::info!(
+        applied = report.applied.len(),
+        rejected = report.rejected.len(),
+        conflicts = report.conflicts.len(),
+        "Imported config"
+    );
+    Ok(report)
 }
 
-#[tauri::command]
-async fn export_usage_data(
-    state: State<'_, AppState>,
-    start_date: String,
-    end_date: String,
+/// All retained history up to `today`, for an automatic export
+fn auto_export_records(
+    db: &Database,
+    settings: &AutoExportSettings,
+    today: chrono::NaiveDate,
 ) -> CmdResult<Vec<ExportRecord>> {
-    // Parse dates (YYYY-MM-DD format) and convert to timestamps
-    let start_timestamp = chrono::NaiveDate::parse_from_str(&start_date, "%Y-%m-%d")
-        .map_err(|e| WellbeingError::Export(format!("Invalid start date: {}", e)))?
-        .and_hms_opt(0, 0, 0)
-        .ok_or_else(|| WellbeingError::Export("Invalid start time".into()))?
-        .and_utc()
-        .timestamp();
-
-    let end_timestamp = chrono::NaiveDate::parse_from_str(&end_date, "%Y-%m-%d")
-        .map_err(|e| WellbeingError::Export(format!("Invalid end date: {}", e)))?
-        .and_hms_opt(23, 59, 59)
-        .ok_or_else(|| WellbeingError::Export("Invalid end time".into()))?
-        .and_utc()
-        .timestamp();
+    let start = today - chrono::Duration::days(DEFAULT_RETENTION_DAYS);
+    let end = today
+        .succ_opt()
+        .ok_or_else(|| WellbeingError::Export("Invalid export date".into()))?;
+    let records = db.export_usage_data(local_day_start(start), local_day_start(end) - 1)?;
+    Ok(if settings.anonymize {
+        anonymize_export_records(records)
+    } else {
+        records
+    })
+}
 
-    let db = state.db.lock().await;
-    Ok(db.export_usage_data(start_timestamp, end_timestamp)?)
+/// Write `records` to a timestamped file in the configured directory, then
+/// prune automatic exports beyond the retention count
+fn write_auto_export(
+    records: Vec<ExportRecord>,
+    settings: &AutoExportSettings,
+    now: chrono::NaiveDateTime,
+) -> CmdResult<std::path::PathBuf> {
+    let contents = match settings.format {
+        auto_export::AutoExportFormat::Csv => format_export_csv(records),
+        auto_export::AutoExportFormat::Json => format_export_json(records)?,
+    };
+
+    let dir = settings.export_dir();
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(auto_export::export_file_name(now, settings.format));
+    std::fs::write(&path, contents)?;
+
+    let pruned = auto_export::prune_old_exports(&dir, settings.retention_count)?;
+    if pruned > 0 {
+        tracing::info!(pruned, "Removed old automatic exports");
+    }
+    Ok(path)
 }
 
 #[tauri::command]
@@ -386,12 +1758,98 @@ fn format_export_csv(records: Vec<ExportRecord>) -> String {
     csv
 }
 
+#[tauri::command]
+fn format_export_sessions_csv(sessions: Vec<database::UsageSession>) -> String {
+    let mut csv =
+        String::from("session_id,app_name,category,start_time_iso,end_time_iso,duration_seconds\n");
+
+    for session in sessions {
+        let app_name = escape_csv_field(&session.app_name);
+        let category = escape_csv_field(session.category.as_deref().unwrap_or("Uncategorized"));
+
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            session.id,
+            app_name,
+            category,
+            local_iso_timestamp(session.start_time),
+            local_iso_timestamp(session.end_time),
+            session.duration_seconds
+        ));
+    }
+
+    csv
+}
+
+/// ISO 8601 rendering of a unix timestamp in local time, with its UTC offset
+fn local_iso_timestamp(timestamp: i64) -> String {
+    use chrono::TimeZone;
+
+    chrono::Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|dt| dt.to_rfc3339_opts(chrono::SecondsFormat::Secs, false))
+        .unwrap_or_default()
+}
+
 #[tauri::command]
 fn format_export_json(records: Vec<ExportRecord>) -> CmdResult<String> {
     serde_json::to_string_pretty(&records)
         .map_err(|e| WellbeingError::Export(format!("JSON serialization error: {}", e)))
 }
 
+/// Sessions still unflushed after this long can't belong to a running tracker,
+/// which flushes at least every `power::MAX_COMMIT_SECONDS`
+const ORPHANED_SESSION_AGE_SECONDS: i64 = 2 * power::MAX_COMMIT_SECONDS as i64;
+
+/// Close sessions and idle periods a crash or power loss left open, before
+/// tracking starts
+fn close_orphaned_sessions(db: &Database) {
+    let older_than = chrono::Utc::now().timestamp() - ORPHANED_SESSION_AGE_SECONDS;
+    // The longest an open session can go without a flush
+    let max_seconds = load_commit_seconds(db).max(power::ECO_COMMIT_SECONDS) as i64;
+    match db.close_orphaned_sessions(older_than, max_seconds) {
+        Ok(0) => {}
+        Ok(closed) => tracing::warn!(closed, "Closed sessions left open by an unclean shutdown"),
+        Err(e) => tracing::error!(error = %e, "Failed to close orphaned sessions"),
+    }
+    match db.close_open_idle_periods() {
+        Ok(0) => {}
+        Ok(closed) => tracing::warn!(
+            closed,
+            "Closed idle periods left open by an unclean shutdown"
+        ),
+        Err(e) => tracing::error!(error = %e, "Failed to close open idle periods"),
+    }
+}
+
+/// Average usage per active day over a date range
+#[derive(serde::Serialize)]
+struct AverageDailyUsage {
+    average_seconds: f64,
+    /// Days in the range that had any usage; the average's divisor
+    active_days: i64,
+}
+
+#[tauri::command]
+async fn get_average_daily_usage(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+) -> CmdResult<AverageDailyUsage> {
+    let (start_timestamp, end_timestamp) = day_range_timestamps(&start_date, &end_date)?;
+    let (average_seconds, active_days) = state
+        .db
+        .lock()
+        .await
+        .get_average_daily_seconds(start_timestamp, end_timestamp)?;
+
+    Ok(AverageDailyUsage {
+        average_seconds,
+        active_days,
+    })
+}
+
 /// Historical data response containing daily totals and app breakdown
 #[derive(serde::Serialize)]
 struct HistoricalData {
@@ -399,6 +1857,7 @@ struct HistoricalData {
     app_usage: Vec<AppUsage>,
     category_usage: Vec<CategoryUsage>,
     total_seconds: i64,
+    average_daily_seconds: i64,
 }
 
 #[tauri::command]
@@ -407,20 +1866,7 @@ async fn get_historical_data(
     start_date: String,
     end_date: String,
 ) -> CmdResult<HistoricalData> {
-    // Parse dates (YYYY-MM-DD format) and convert to timestamps
-    let start_timestamp = chrono::NaiveDate::parse_from_str(&start_date, "%Y-%m-%d")
-        .map_err(|e| WellbeingError::Export(format!("Invalid start date: {}", e)))?
-        .and_hms_opt(0, 0, 0)
-        .ok_or_else(|| WellbeingError::Export("Invalid start time".into()))?
-        .and_utc()
-        .timestamp();
-
-    let end_timestamp = chrono::NaiveDate::parse_from_str(&end_date, "%Y-%m-%d")
-        .map_err(|e| WellbeingError::Export(format!("Invalid end date: {}", e)))?
-        .and_hms_opt(23, 59, 59)
-        .ok_or_else(|| WellbeingError::Export("Invalid end time".into()))?
-        .and_utc()
-        .timestamp();
+    let (start_timestamp, end_timestamp) = day_range_timestamps(&start_date, &end_date)?;
 
     let db = state.db.lock().await;
 
@@ -451,8 +1897,11 @@ async fn get_historical_data(
 
     // Calculate total
     let total_seconds: i64 = daily_totals.iter().map(|d| d.total_seconds).sum();
+    let weekends_count = state.goals_state.lock().await.weekends_count;
+    let average_daily_seconds = commands::average_daily_seconds(&daily_totals, weekends_count);
 
     Ok(HistoricalData {
+        average_daily_seconds,
         daily_totals,
         app_usage,
         category_usage,
@@ -517,6 +1966,8 @@ async fn get_break_status(state: State<'_, AppState>) -> CmdResult<BreakStatus>
 #[tauri::command]
 async fn start_break(state: State<'_, AppState>) -> CmdResult<()> {
     state.break_reminder.start_break().await;
+    let db = state.db.lock().await;
+    db.record_break(chrono::Local::now().date_naive())?;
     Ok(())
 }
 
@@ -532,6 +1983,13 @@ async fn reset_break_timer(state: State<'_, AppState>) -> CmdResult<()> {
     Ok(())
 }
 
+/// Dismiss a due break and remind again in `minutes`
+#[tauri::command]
+async fn snooze_break(state: State<'_, AppState>, minutes: u32) -> CmdResult<()> {
+    state.break_reminder.snooze(minutes).await;
+    Ok(())
+}
+
 // Notification settings commands
 #[tauri::command]
 async fn get_notification_settings(state: State<'_, AppState>) -> CmdResult<NotificationSettings> {
@@ -564,6 +2022,18 @@ async fn is_notifications_muted(state: State<'_, AppState>) -> CmdResult<bool> {
     Ok(state.notification_manager.is_muted())
 }
 
+#[tauri::command]
+async fn validate_settings(state: State<'_, AppState>) -> CmdResult<SettingsReport> {
+    let db = state.db.lock().await;
+    Ok(settings_validation::validate_settings(
+        &state.focus_manager,
+        &state.break_reminder,
+        &state.notification_manager,
+        &db,
+    )
+    .await)
+}
+
 // Focus mode commands
 #[tauri::command]
 async fn get_focus_settings(state: State<'_, AppState>) -> CmdResult<FocusSettings> {
@@ -576,135 +2046,666 @@ async fn set_focus_settings(state: State<'_, AppState>, settings: FocusSettings)
     Ok(())
 }
 
-#[tauri::command]
-async fn get_focus_session(state: State<'_, AppState>) -> CmdResult<FocusSession> {
-    Ok(state.focus_manager.get_session().await)
+#[tauri::command]
+async fn get_focus_session(state: State<'_, AppState>) -> CmdResult<FocusSession> {
+    Ok(state.focus_manager.get_session().await)
+}
+
+#[tauri::command]
+async fn start_focus_session(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    duration_minutes: Option<u32>,
+    blocked_apps: Option<Vec<String>>,
+    strict: Option<bool>,
+) -> CmdResult<FocusSession> {
+    check_not_strict_locked(&state.focus_manager).await?;
+    let session = state
+        .focus_manager
+        .start_session(duration_minutes, blocked_apps, strict.unwrap_or(false))
+        .await;
+    tray::refresh_tray(&app).await;
+    Ok(session)
+}
+
+#[tauri::command]
+async fn stop_focus_session(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> CmdResult<FocusSession> {
+    check_not_strict_locked(&state.focus_manager).await?;
+    if let Some(ended) = state.focus_manager.stop_session().await {
+        record_focus_history(
+            &state.db,
+            &state.focus_manager,
+            &state.goals_state,
+            &ended,
+            false,
+        )
+        .await;
+    }
+    tray::refresh_tray(&app).await;
+    Ok(state.focus_manager.get_session().await)
+}
+
+/// Refuse to change a strict focus session before its timer ends
+async fn check_not_strict_locked(focus_manager: &FocusManager) -> CmdResult<()> {
+    if focus_manager
+        .get_session()
+        .await
+        .is_strict_locked(chrono::Utc::now().timestamp())
+    {
+        return Err(WellbeingError::Other(
+            "Strict focus session can't be changed before its timer ends".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Store a session that just ended in focus history. If it was completed (a
+/// timed session that ran to its end), count it toward the focus achievements
+/// and grant the focus reward when enabled.
+async fn record_focus_history(
+    db: &Mutex<Database>,
+    focus_manager: &FocusManager,
+    goals_state: &Mutex<GoalsState>,
+    ended: &FocusSession,
+    natural_end: bool,
+) {
+    let now = chrono::Utc::now().timestamp();
+    let Some(entry) = FocusHistoryEntry::from_session(ended, now, natural_end) else {
+        return;
+    };
+    let mut db = db.lock().await;
+    if let Err(e) = db.record_focus_session(&entry) {
+        tracing::error!(error = %e, "Failed to record focus session");
+    }
+
+    if !entry.completed {
+        return;
+    }
+    let mut goals_state = goals_state.lock().await;
+    goals_state.record_focus_session();
+    if let Err(e) = db.save_goals_state(&goals_state) {
+        tracing::error!(error = %e, "Failed to save goals after focus session");
+    }
+    drop(goals_state);
+
+    let reward = focus_manager.get_settings().await.reward;
+    if !reward.enabled || reward.bonus_minutes == 0 {
+        return;
+    }
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    match db.grant_focus_reward(&reward, &today) {
+        Ok(apps) => {
+            tracing::info!(
+                apps = ?apps,
+                bonus_minutes = reward.bonus_minutes,
+                "Granted focus reward"
+            );
+        }
+        Err(e) => tracing::error!(error = %e, "Failed to grant focus reward"),
+    }
+}
+
+/// Consecutive days with at least one completed focus session
+#[tauri::command]
+async fn get_focus_streak(state: State<'_, AppState>) -> CmdResult<DailyStreak> {
+    let db = state.db.lock().await;
+    focus_streak(&db, chrono::Local::now().date_naive())
+}
+
+/// The focus streak up to `today`, from the recorded focus history
+fn focus_streak(db: &Database, today: chrono::NaiveDate) -> CmdResult<DailyStreak> {
+    let focus_days: std::collections::HashSet<chrono::NaiveDate> = db
+        .get_focus_history()?
+        .iter()
+        .filter(|entry| entry.completed)
+        .map(FocusHistoryEntry::local_date)
+        .collect();
+    Ok(db.compute_daily_streak(today, |day| focus_days.contains(&day))?)
+}
+
+/// Consecutive days within the global daily cap; None when no cap is set
+#[tauri::command]
+async fn get_under_cap_streak(state: State<'_, AppState>) -> CmdResult<Option<DailyStreak>> {
+    let db = state.db.lock().await;
+    let Some(cap) = db.get_global_cap_status()? else {
+        return Ok(None);
+    };
+    let limit_seconds = cap.limit_minutes as i64 * 60;
+    let usage = db.get_cap_usage_by_day()?;
+    let streak = db.compute_daily_streak(chrono::Local::now().date_naive(), |day| {
+        let date = day.format("%Y-%m-%d").to_string();
+        usage.get(&date).copied().unwrap_or(0) <= limit_seconds
+    })?;
+    Ok(Some(streak))
+}
+
+/// Limit bonuses granted for a day (defaults to today)
+#[tauri::command]
+async fn get_limit_bonuses(
+    state: State<'_, AppState>,
+    date: Option<String>,
+) -> CmdResult<Vec<LimitBonus>> {
+    let date = parse_date_or_today(date)?;
+    let db = state.db.lock().await;
+    Ok(db.get_limit_bonuses(&date.format("%Y-%m-%d").to_string())?)
+}
+
+/// Focus sessions started within an inclusive `YYYY-MM-DD` date range
+#[tauri::command]
+async fn get_focus_history(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+) -> CmdResult<Vec<FocusHistoryEntry>> {
+    let db = state.db.lock().await;
+    focus_history_between(&db, &start_date, &end_date)
+}
+
+/// Focus sessions started between the local days `start_date` and `end_date`
+fn focus_history_between(
+    db: &Database,
+    start_date: &str,
+    end_date: &str,
+) -> CmdResult<Vec<FocusHistoryEntry>> {
+    let (start_timestamp, end_timestamp) = day_range_timestamps(start_date, end_date)?;
+    Ok(db.get_focus_history_in_range(start_timestamp, end_timestamp)?)
+}
+
+#[tauri::command]
+async fn get_focus_dashboard(state: State<'_, AppState>) -> CmdResult<FocusDashboard> {
+    let db = state.db.lock().await;
+    let history = db.get_focus_history()?;
+    Ok(focus_mode::focus_dashboard(
+        &history,
+        chrono::Local::now().date_naive(),
+    ))
+}
+
+#[tauri::command]
+async fn extend_focus_session(
+    state: State<'_, AppState>,
+    additional_minutes: u32,
+) -> CmdResult<Option<FocusSession>> {
+    Ok(state.focus_manager.extend_session(additional_minutes).await)
+}
+
+#[tauri::command]
+async fn is_focus_mode_active(state: State<'_, AppState>) -> CmdResult<bool> {
+    Ok(state.focus_manager.is_active())
+}
+
+#[tauri::command]
+async fn should_block_app_focus(state: State<'_, AppState>, app_name: String) -> CmdResult<bool> {
+    Ok(state.focus_manager.should_block_app(&app_name).await)
+}
+
+/// Which running apps a focus session would block, without starting one.
+/// `blocked_apps` is the session's app list (the allowed apps in allow-list
+/// mode). Both default to the configured mode and its list.
+#[tauri::command]
+async fn preview_focus_block(
+    state: State<'_, AppState>,
+    blocked_apps: Option<Vec<String>>,
+    mode: Option<FocusMode>,
+) -> CmdResult<Vec<String>> {
+    let settings = state.focus_manager.get_settings().await;
+    let mode = mode.unwrap_or(settings.mode);
+    let apps = match blocked_apps {
+        Some(apps) => apps,
+        None => match mode {
+            FocusMode::BlockList => settings.blocked_apps,
+            FocusMode::AllowList => settings.allowed_apps,
+        },
+    };
+    let running = tokio::task::spawn_blocking(window_tracker::get_running_apps)
+        .await
+        .map_err(|e| WellbeingError::Other(format!("Failed to list running apps: {}", e)))?;
+    Ok(focus_mode::preview_blocked_apps(mode, &apps, &running))
+}
+
+#[tauri::command]
+async fn add_focus_blocked_app(state: State<'_, AppState>, app_name: String) -> CmdResult<()> {
+    state.focus_manager.add_blocked_app(app_name).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn remove_focus_blocked_app(state: State<'_, AppState>, app_name: String) -> CmdResult<()> {
+    check_not_strict_locked(&state.focus_manager).await?;
+    state.focus_manager.remove_blocked_app(&app_name).await;
+    Ok(())
+}
+
+// Goals commands
+#[tauri::command]
+async fn get_goals(state: State<'_, AppState>) -> CmdResult<Vec<Goal>> {
+    let goals_state = state.goals_state.lock().await;
+    Ok(goals_state.goals.clone())
+}
+
+#[tauri::command]
+async fn add_goal(state: State<'_, AppState>, goal: Goal) -> CmdResult<()> {
+    check_curfew_hours(&goal)?;
+    let mut db = state.db.lock().await;
+    let mut goals_state = state.goals_state.lock().await;
+    goals_state.add_goal(goal);
+    db.save_goals_state(&goals_state)?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn update_goal(state: State<'_, AppState>, goal: Goal) -> CmdResult<()> {
+    check_curfew_hours(&goal)?;
+    let mut db = state.db.lock().await;
+    let mut goals_state = state.goals_state.lock().await;
+    goals_state.update_goal(goal);
+    db.save_goals_state(&goals_state)?;
+    Ok(())
+}
+
+/// Curfew hours must be hours of the day
+fn check_curfew_hours(goal: &Goal) -> CmdResult<()> {
+    match goal.goal_type {
+        GoalType::CurfewLimit {
+            start_hour,
+            end_hour,
+        } if start_hour > 23 || end_hour > 23 => Err(WellbeingError::Other(format!(
+            "Curfew hours must be between 0 and 23, got {} to {}",
+            start_hour, end_hour
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[tauri::command]
+async fn remove_goal(state: State<'_, AppState>, goal_id: String) -> CmdResult<()> {
+    let mut db = state.db.lock().await;
+    let mut goals_state = state.goals_state.lock().await;
+    goals_state.remove_goal(&goal_id);
+    db.save_goals_state(&goals_state)?;
+    Ok(())
+}
+
+/// Today's total, per-app and per-category minutes in the shape goals expect.
+/// Breaks and curfew minutes are left for the caller to fill in.
+fn goal_usage_from_summary(summary: &DailySummary) -> GoalUsage {
+    GoalUsage {
+        total_minutes: (summary.total_seconds / 60) as i32,
+        app_minutes: summary
+            .apps
+            .iter()
+            .map(|a| (a.app_name.clone(), (a.duration_seconds / 60) as i32))
+            .collect(),
+        category_minutes: summary
+            .categories
+            .iter()
+            .map(|c| (c.category.clone(), (c.total_seconds / 60) as i32))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// Goal usage like `goal_usage_from_summary`, for sessions started between two timestamps
+fn goal_usage_in_range(
+    db: &Database,
+    start_timestamp: i64,
+    end_timestamp: i64,
+) -> CmdResult<GoalUsage> {
+    Ok(goal_usage_from(
+        db.get_app_usage_in_range(start_timestamp, end_timestamp)?,
+        db.get_category_usage_in_range(start_timestamp, end_timestamp)?,
+    ))
+}
+
+/// Goal usage like `goal_usage_from_summary`, for a single local calendar day
+fn goal_usage_for_date(db: &Database, date: chrono::NaiveDate) -> CmdResult<GoalUsage> {
+    Ok(goal_usage_from(
+        db.get_daily_usage_for(date)?,
+        db.get_category_usage_for(date)?,
+    ))
+}
+
+fn goal_usage_from(apps: Vec<AppUsage>, categories: Vec<CategoryUsage>) -> GoalUsage {
+    GoalUsage {
+        total_minutes: (apps.iter().map(|a| a.duration_seconds).sum::<i64>() / 60) as i32,
+        app_minutes: apps
+            .into_iter()
+            .map(|a| (a.app_name, (a.duration_seconds / 60) as i32))
+            .collect(),
+        category_minutes: categories
+            .into_iter()
+            .map(|c| (c.category, (c.total_seconds / 60) as i32))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[tauri::command]
+async fn project_goal_completion(
+    state: State<'_, AppState>,
+    goal_id: String,
+) -> CmdResult<GoalProjection> {
+    let db = state.db.lock().await;
+    let goals_state = state.goals_state.lock().await;
+
+    let goal = goals_state
+        .goals
+        .iter()
+        .find(|g| g.id == goal_id)
+        .ok_or_else(|| WellbeingError::Other(format!("Goal not found: {}", goal_id)))?;
+
+    let now = chrono::Local::now();
+    let today = now.date_naive();
+
+    // Weekly goals are projected from the week's usage so far to its end
+    if goal.is_weekly() {
+        let week_start = goals_state.week_start.week_containing(today);
+        let week_usage = goal_usage_in_range(&db, local_day_start(week_start), now.timestamp())?;
+        let progress =
+            goals::calculate_weekly_goal_progress(goal, &week_usage, today, goals_state.week_start);
+        return Ok(goals::project_weekly_goal_completion(
+            &progress,
+            now.naive_local(),
+            week_start,
+        ));
+    }
+
+    let usage = GoalUsage {
+        breaks_taken: db.breaks_taken_on(today)? as i32,
+        curfew_minutes: curfew_usage_on(&db, [goal], today)?,
+        ..goal_usage_from_summary(&db.get_daily_summary()?)
+    };
+    let progress = goals::calculate_goal_progress(goal, &usage);
+
+    Ok(goals::project_goal_completion(&progress, now.naive_local()))
+}
+
+#[tauri::command]
+async fn get_goals_progress(state: State<'_, AppState>) -> CmdResult<Vec<GoalProgress>> {
+    let mut db = state.db.lock().await;
+    let mut goals_state = state.goals_state.lock().await;
+    goals_progress_for_today(&mut db, &mut goals_state)
+}
+
+#[tauri::command]
+async fn get_goals_progress_for_date(
+    state: State<'_, AppState>,
+    date: String,
+) -> CmdResult<Vec<GoalProgress>> {
+    let date = parse_date(&date)?;
+    if date > chrono::Local::now().date_naive() {
+        return Err(WellbeingError::Other(format!(
+            "Date is in the future: {}",
+            date
+        )));
+    }
+
+    let db = state.db.lock().await;
+    let goals_state = state.goals_state.lock().await;
+    let next_day = date.succ_opt().unwrap_or(date);
+    let day_usage = GoalUsage {
+        breaks_taken: db.breaks_taken_on(date)? as i32,
+        ..goal_usage_for_date(&db, date)?
+    };
+    goals_progress_on(
+        &db,
+        &goals_state,
+        date,
+        day_usage,
+        local_day_start(next_day) - 1,
+    )
+}
+
+/// Minutes used in each curfew goal's window on `date`, by goal id
+fn curfew_usage_on<'a>(
+    db: &Database,
+    goals: impl IntoIterator<Item = &'a Goal>,
+    date: chrono::NaiveDate,
+) -> CmdResult<HashMap<String, i32>> {
+    let mut usage = HashMap::new();
+    for goal in goals {
+        if let GoalType::CurfewLimit {
+            start_hour,
+            end_hour,
+        } = goal.goal_type
+        {
+            let seconds = db.get_usage_in_hour_window(date, start_hour, end_hour)?;
+            usage.insert(goal.id.clone(), (seconds / 60) as i32);
+        }
+    }
+    Ok(usage)
+}
+
+/// Progress of the goals that applied on `date`, given that day's usage and
+/// breaks taken. Weekly goals use usage from the start of that week up to
+/// `week_end`; break and curfew goals are always evaluated for the day.
+fn goals_progress_on(
+    db: &Database,
+    goals_state: &GoalsState,
+    date: chrono::NaiveDate,
+    day_usage: GoalUsage,
+    week_end: i64,
+) -> CmdResult<Vec<GoalProgress>> {
+    // The week's usage, only queried when a weekly goal needs it
+    let goals_for_day = goals_state.get_goals_for_day(date);
+    let week_usage = if goals_for_day.iter().any(|goal| goal.is_weekly()) {
+        let week_start = goals_state.week_start.week_containing(date);
+        Some(goal_usage_in_range(
+            db,
+            local_day_start(week_start),
+            week_end,
+        )?)
+    } else {
+        None
+    };
+    let day_usage = GoalUsage {
+        curfew_minutes: curfew_usage_on(db, goals_for_day.iter().copied(), date)?,
+        ..day_usage
+    };
+
+    Ok(goals_for_day
+        .iter()
+        .map(|goal| match &week_usage {
+            Some(week_usage) if goal.is_weekly() => goals::calculate_weekly_goal_progress(
+                goal,
+                week_usage,
+                date,
+                goals_state.week_start,
+            ),
+            _ => {
+                let mut progress = goals::calculate_goal_progress(goal, &day_usage);
+                progress.days_remaining = goal.days_remaining(date);
+                progress
+            }
+        })
+        .collect())
+}
+
+/// Progress of today's goals. Also archives expired challenges and records
+/// today's result for each running challenge, saving the updated state.
+fn goals_progress_for_today(
+    db: &mut Database,
+    goals_state: &mut GoalsState,
+) -> CmdResult<Vec<GoalProgress>> {
+    let today = chrono::Local::now().date_naive();
+    let mut changed = !goals_state.archive_expired_challenges(today).is_empty();
+
+    let day_usage = GoalUsage {
+        breaks_taken: db.breaks_taken_on(today)? as i32,
+        ..goal_usage_from_summary(&db.get_daily_summary()?)
+    };
+    let progress = goals_progress_on(
+        db,
+        goals_state,
+        today,
+        day_usage,
+        chrono::Utc::now().timestamp(),
+    )?;
+
+    let challenge_days: Vec<(String, bool)> = progress
+        .iter()
+        .filter(|p| {
+            goals_state
+                .goals
+                .iter()
+                .any(|g| g.id == p.goal_id && g.is_challenge())
+        })
+        .map(|p| (p.goal_id.clone(), p.is_met))
+        .collect();
+
+    // Keep each challenge's result for today (or this week, for weekly
+    // goals) up to date for its final success rate
+    for (goal_id, met) in challenge_days {
+        changed |= goals_state.record_challenge_result(&goal_id, today, met);
+    }
+    // This runs every minute; only write when something actually changed
+    if changed {
+        db.save_goals_state(goals_state)?;
+    }
+
+    Ok(progress)
 }
 
 #[tauri::command]
-async fn start_focus_session(
+async fn get_completed_challenges(
     state: State<'_, AppState>,
-    duration_minutes: Option<u32>,
-    blocked_apps: Option<Vec<String>>,
-) -> CmdResult<FocusSession> {
-    Ok(state
-        .focus_manager
-        .start_session(duration_minutes, blocked_apps)
-        .await)
+) -> CmdResult<Vec<CompletedChallenge>> {
+    let mut db = state.db.lock().await;
+    let mut goals_state = state.goals_state.lock().await;
+    if !goals_state
+        .archive_expired_challenges(chrono::Local::now().date_naive())
+        .is_empty()
+    {
+        db.save_goals_state(&goals_state)?;
+    }
+    Ok(goals_state.completed_challenges.clone())
 }
 
 #[tauri::command]
-async fn stop_focus_session(state: State<'_, AppState>) -> CmdResult<FocusSession> {
-    Ok(state.focus_manager.stop_session().await)
+async fn get_achievements(state: State<'_, AppState>) -> CmdResult<Vec<Achievement>> {
+    let goals_state = state.goals_state.lock().await;
+    Ok(goals_state.get_achievements())
 }
 
 #[tauri::command]
-async fn extend_focus_session(
+async fn reset_achievement(
     state: State<'_, AppState>,
-    additional_minutes: u32,
-) -> CmdResult<Option<FocusSession>> {
-    Ok(state.focus_manager.extend_session(additional_minutes).await)
-}
-
-#[tauri::command]
-async fn is_focus_mode_active(state: State<'_, AppState>) -> CmdResult<bool> {
-    Ok(state.focus_manager.is_active())
+    achievement_id: String,
+) -> CmdResult<Achievement> {
+    let mut db = state.db.lock().await;
+    let mut goals_state = state.goals_state.lock().await;
+    let achievement = goals_state
+        .reset_achievement(&achievement_id)
+        .ok_or_else(|| {
+            WellbeingError::Other(format!("Achievement not found: {}", achievement_id))
+        })?;
+    db.save_goals_state(&goals_state)?;
+    Ok(achievement)
 }
 
 #[tauri::command]
-async fn should_block_app_focus(state: State<'_, AppState>, app_name: String) -> CmdResult<bool> {
-    Ok(state.focus_manager.should_block_app(&app_name).await)
+async fn reset_all_achievements(state: State<'_, AppState>) -> CmdResult<()> {
+    let mut db = state.db.lock().await;
+    let mut goals_state = state.goals_state.lock().await;
+    goals_state.reset_all_achievements();
+    db.save_goals_state(&goals_state)?;
+    Ok(())
 }
 
 #[tauri::command]
-async fn add_focus_blocked_app(state: State<'_, AppState>, app_name: String) -> CmdResult<()> {
-    state.focus_manager.add_blocked_app(app_name).await;
-    Ok(())
+async fn get_weekends_count(state: State<'_, AppState>) -> CmdResult<bool> {
+    Ok(state.goals_state.lock().await.weekends_count)
 }
 
 #[tauri::command]
-async fn remove_focus_blocked_app(state: State<'_, AppState>, app_name: String) -> CmdResult<()> {
-    state.focus_manager.remove_blocked_app(&app_name).await;
+async fn set_weekends_count(state: State<'_, AppState>, enabled: bool) -> CmdResult<()> {
+    let mut db = state.db.lock().await;
+    let mut goals_state = state.goals_state.lock().await;
+    goals_state.weekends_count = enabled;
+    db.save_goals_state(&goals_state)?;
     Ok(())
 }
 
-// Goals commands
 #[tauri::command]
-async fn get_goals(state: State<'_, AppState>) -> CmdResult<Vec<Goal>> {
-    let goals_state = state.goals_state.lock().await;
-    Ok(goals_state.goals.clone())
+async fn get_week_start(state: State<'_, AppState>) -> CmdResult<WeekStart> {
+    Ok(state.goals_state.lock().await.week_start)
 }
 
 #[tauri::command]
-async fn add_goal(state: State<'_, AppState>, goal: Goal) -> CmdResult<()> {
+async fn set_week_start(state: State<'_, AppState>, week_start: WeekStart) -> CmdResult<()> {
+    let mut db = state.db.lock().await;
     let mut goals_state = state.goals_state.lock().await;
-    goals_state.add_goal(goal);
+    goals_state.week_start = week_start;
+    db.save_goals_state(&goals_state)?;
     Ok(())
 }
 
 #[tauri::command]
-async fn update_goal(state: State<'_, AppState>, goal: Goal) -> CmdResult<()> {
-    let mut goals_state = state.goals_state.lock().await;
-    goals_state.update_goal(goal);
-    Ok(())
+async fn get_weekly_focus_goal(state: State<'_, AppState>) -> CmdResult<Option<WeeklyFocusGoal>> {
+    Ok(state.goals_state.lock().await.weekly_focus_goal.clone())
 }
 
+/// Set or clear (None) the weekly focus-time target
 #[tauri::command]
-async fn remove_goal(state: State<'_, AppState>, goal_id: String) -> CmdResult<()> {
+async fn set_weekly_focus_goal(
+    state: State<'_, AppState>,
+    goal: Option<WeeklyFocusGoal>,
+) -> CmdResult<()> {
+    if goal.as_ref().is_some_and(|g| g.target_minutes == 0) {
+        return Err(WellbeingError::Other(
+            "Weekly focus target must be at least one minute".to_string(),
+        ));
+    }
+    let mut db = state.db.lock().await;
     let mut goals_state = state.goals_state.lock().await;
-    goals_state.remove_goal(&goal_id);
+    goals_state.weekly_focus_goal = goal;
+    db.save_goals_state(&goals_state)?;
     Ok(())
 }
 
-#[tauri::command]
-async fn get_goals_progress(state: State<'_, AppState>) -> CmdResult<Vec<GoalProgress>> {
-    let db = state.db.lock().await;
-    let goals_state = state.goals_state.lock().await;
-
-    // Get today's usage data
-    let apps = db.get_daily_usage()?;
-    let categories = db.get_category_usage()?;
-
-    let total_daily_minutes = (apps.iter().map(|a| a.duration_seconds).sum::<i64>() / 60) as i32;
-
-    // Build usage maps
-    let app_usage: HashMap<String, i32> = apps
-        .iter()
-        .map(|a| (a.app_name.clone(), (a.duration_seconds / 60) as i32))
-        .collect();
-
-    let category_usage: HashMap<String, i32> = categories
-        .iter()
-        .map(|c| (c.category.clone(), (c.total_seconds / 60) as i32))
-        .collect();
-
-    // Calculate progress for each goal
-    let today = chrono::Local::now().date_naive();
-    let progress: Vec<GoalProgress> = goals_state
-        .get_goals_for_day(today)
-        .iter()
-        .map(|goal| {
-            goals::calculate_goal_progress(goal, total_daily_minutes, &app_usage, &category_usage)
-        })
-        .collect();
-
-    Ok(progress)
+/// Focus time this week against the weekly target; None when no target is set
+fn weekly_focus_progress_for_today(
+    db: &Database,
+    goals_state: &GoalsState,
+) -> CmdResult<Option<WeeklyFocusGoalProgress>> {
+    let Some(goal) = &goals_state.weekly_focus_goal else {
+        return Ok(None);
+    };
+    let history = db.get_focus_history()?;
+    Ok(Some(goals::weekly_focus_goal_progress(
+        goal,
+        &history,
+        chrono::Local::now().date_naive(),
+        goals_state.week_start,
+    )))
 }
 
 #[tauri::command]
-async fn get_achievements(state: State<'_, AppState>) -> CmdResult<Vec<Achievement>> {
+async fn get_weekly_focus_goal_progress(
+    state: State<'_, AppState>,
+) -> CmdResult<Option<WeeklyFocusGoalProgress>> {
+    let db = state.db.lock().await;
     let goals_state = state.goals_state.lock().await;
-    Ok(goals_state.get_achievements())
+    weekly_focus_progress_for_today(&db, &goals_state)
 }
 
 #[tauri::command]
 async fn get_goals_stats(state: State<'_, AppState>) -> CmdResult<GoalsStats> {
+    let focus_streak = {
+        let db = state.db.lock().await;
+        focus_streak(&db, chrono::Local::now().date_naive())?
+    };
     let goals_state = state.goals_state.lock().await;
     Ok(GoalsStats {
         current_streak: goals_state.current_streak,
         longest_streak: goals_state.longest_streak,
         total_goals_met: goals_state.total_goals_met,
         focus_sessions_completed: goals_state.focus_sessions_completed,
+        focus_current_streak: focus_streak.current,
+        focus_longest_streak: focus_streak.longest,
     })
 }
 
@@ -714,6 +2715,8 @@ struct GoalsStats {
     longest_streak: i32,
     total_goals_met: i32,
     focus_sessions_completed: i32,
+    focus_current_streak: u32,
+    focus_longest_streak: u32,
 }
 
 #[derive(serde::Serialize)]
@@ -799,39 +2802,71 @@ async fn install_update(app: tauri::AppHandle) -> Result<(), String> {
     app.restart();
 }
 
-/// Run the app in headless background mode (no GUI window)
-/// This is used by the autostart service to track usage silently
-pub fn run_background() {
-    // Initialize tracing subscriber for background mode
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::INFO.into()),
-        )
-        .init();
-
-    tracing::info!("Starting Digital Wellbeing in background mode...");
+/// Environment variable that overrides the database location
+pub const DB_PATH_ENV: &str = "WELLBEING_DB_PATH";
+
+/// Where the database lives. A `--db-path` argument wins over the
+/// `WELLBEING_DB_PATH` environment variable; blank values are ignored. Without
+/// either, it is `wellbeing/wellbeing.db` in the user's data directory.
+pub fn resolve_db_path(cli_path: Option<&str>, env_path: Option<&str>) -> std::path::PathBuf {
+    cli_path
+        .or(env_path)
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(default_db_path)
+}
 
-    let db_path = dirs::data_dir()
+/// `wellbeing/wellbeing.db` in the user's data directory
+pub fn default_db_path() -> std::path::PathBuf {
+    dirs::data_dir()
         .unwrap_or_else(|| std::path::PathBuf::from("."))
         .join("wellbeing")
-        .join("wellbeing.db");
+        .join("wellbeing.db")
+}
 
-    if let Some(parent) = db_path.parent() {
+/// Open the database at `db_path`, creating its directory if needed
+fn open_database(db_path: std::path::PathBuf) -> Database {
+    if let Some(parent) = db_path.parent().filter(|p| !p.as_os_str().is_empty()) {
         std::fs::create_dir_all(parent).expect("Failed to create data directory");
     }
 
-    let db = Database::new(db_path).expect("Failed to initialize database");
-    let db = Arc::new(Mutex::new(db));
+    tracing::info!(path = %db_path.display(), "Opening database");
+    Database::new(db_path).expect("Failed to initialize database")
+}
+
+/// Run the app in headless background mode (no GUI window)
+/// This is used by the autostart service to track usage silently
+pub fn run_background(db_path: std::path::PathBuf) {
+    // Initialize tracing subscriber for background mode
+    init_tracing();
 
-    // Create emergency access manager (limited functionality in background mode)
-    let emergency_access = Arc::new(EmergencyAccessManager::new());
+    tracing::info!("Starting Digital Wellbeing in background mode...");
+
+    let db = open_database(db_path);
+    close_orphaned_sessions(&db);
+
+    // Emergency access manager with grants still active from the GUI
+    let emergency_access = Arc::new(load_emergency_access(&db));
+    let block_settings: BlockSettings = load_meta_settings(&db, blocking::SETTINGS_KEY);
+    let eco_mode: EcoMode = load_meta_settings(&db, power::SETTINGS_KEY);
+    let title_aware_apps: Vec<TitleAwareApp> =
+        load_meta_settings(&db, window_tracker::TITLE_AWARE_APPS_KEY);
+    let idle_threshold_seconds = load_idle_threshold(&db);
+    let commit_seconds = load_commit_seconds(&db);
+    let db = Arc::new(Mutex::new(db));
 
     // Create tokio runtime for async operations
     let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
 
     rt.block_on(async {
-        let tracker = Arc::new(UsageTracker::new(db, emergency_access));
+        let mut tracker = UsageTracker::new(db, emergency_access);
+        tracker.set_block_settings(Arc::new(RwLock::new(block_settings)));
+        tracker.set_eco_mode(Arc::new(RwLock::new(eco_mode)));
+        tracker.set_title_aware_apps(Arc::new(RwLock::new(title_aware_apps)));
+        tracker.set_idle_threshold(Arc::new(RwLock::new(idle_threshold_seconds)));
+        tracker.set_commit_seconds(Arc::new(RwLock::new(commit_seconds)));
+        let tracker = Arc::new(tracker);
         let tracker_for_shutdown = Arc::clone(&tracker);
 
         tracing::info!("Background tracker started. Press Ctrl+C to stop.");
@@ -872,62 +2907,118 @@ pub fn run_background() {
     });
 }
 
+/// Environment variable selecting the log output format ("json" or "text")
+const LOG_FORMAT_ENV: &str = "WELLBEING_LOG_FORMAT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogFormat {
+    /// Human-readable line-oriented output (default)
+    Text,
+    /// One JSON object per event, for log aggregators
+    Json,
+}
+
+impl LogFormat {
+    fn from_env_value(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_ascii_lowercase()) {
+            Some(v) if v == "json" => LogFormat::Json,
+            _ => LogFormat::Text,
+        }
+    }
+}
+
 /// Initialize the tracing subscriber for the application
 fn init_tracing() {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::INFO.into()),
-        )
-        .init();
+    let filter = tracing_subscriber::EnvFilter::from_default_env()
+        .add_directive(tracing::Level::INFO.into());
+    let format = LogFormat::from_env_value(std::env::var(LOG_FORMAT_ENV).ok().as_deref());
+
+    match format {
+        LogFormat::Json => tracing_subscriber::fmt()
+            .json()
+            .with_current_span(false)
+            .with_env_filter(filter)
+            .init(),
+        LogFormat::Text => tracing_subscriber::fmt().with_env_filter(filter).init(),
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let env_path = std::env::var(DB_PATH_ENV).ok();
+    run_with_db_path(resolve_db_path(None, env_path.as_deref()));
+}
+
+/// Run the GUI app with the database at `db_path`
+pub fn run_with_db_path(db_path: std::path::PathBuf) {
     // Initialize tracing for structured logging
     init_tracing();
 
-    let db_path = dirs::data_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("wellbeing")
-        .join("wellbeing.db");
-
-    if let Some(parent) = db_path.parent() {
-        std::fs::create_dir_all(parent).expect("Failed to create data directory");
-    }
-
-    let db = Database::new(db_path).expect("Failed to initialize database");
+    let db = open_database(db_path.clone());
+    close_orphaned_sessions(&db);
+    let saved_goals_state = db.load_goals_state().unwrap_or_else(|e| {
+        tracing::error!(error = %e, "Failed to load saved goals, starting fresh");
+        GoalsState::new()
+    });
+    let auto_export_settings: AutoExportSettings =
+        load_meta_settings(&db, auto_export::SETTINGS_KEY);
+    let block_settings: BlockSettings = load_meta_settings(&db, blocking::SETTINGS_KEY);
+    let eco_mode: EcoMode = load_meta_settings(&db, power::SETTINGS_KEY);
+    let display_settings: DisplaySettings = load_meta_settings(&db, commands::DISPLAY_SETTINGS_KEY);
+    let idle_threshold_seconds = load_idle_threshold(&db);
+    let commit_seconds = load_commit_seconds(&db);
+    let title_aware_apps: Vec<TitleAwareApp> =
+        load_meta_settings(&db, window_tracker::TITLE_AWARE_APPS_KEY);
+    let emergency_access = Arc::new(load_emergency_access(&db));
     let db = Arc::new(Mutex::new(db));
 
-    // Create break reminder
-    let break_reminder = Arc::new(BreakReminder::new());
-
-    // Create notification manager
-    let notification_manager = Arc::new(NotificationManager::new());
+    // Break reminder and notification manager with the settings from the last run
+    let break_reminder = Arc::new(BreakReminder::load());
+    let notification_manager = Arc::new(NotificationManager::load());
 
-    // Create focus manager
-    let focus_manager = Arc::new(FocusManager::new());
+    // Focus manager with settings and any running session from the last run
+    let focus_manager = Arc::new(FocusManager::load());
 
-    // Create goals state
-    let goals_state = Arc::new(Mutex::new(GoalsState::new()));
-
-    // Create emergency access manager
-    let emergency_access = Arc::new(EmergencyAccessManager::new());
+    // Goals state saved on the last run
+    let goals_state = Arc::new(Mutex::new(saved_goals_state));
 
     // Clone for background tasks
     let tracker_db = Arc::clone(&db);
     let cleanup_db = Arc::clone(&db);
+    let focus_db = Arc::clone(&db);
     let tracker_emergency = Arc::clone(&emergency_access);
     let break_reminder_clone = Arc::clone(&break_reminder);
     let focus_manager_clone = Arc::clone(&focus_manager);
+    let focus_manager_for_tracker = Arc::clone(&focus_manager);
     let notification_manager_clone = Arc::clone(&notification_manager);
+    let validation_db = Arc::clone(&db);
+    let validation_focus = Arc::clone(&focus_manager);
+    let validation_break = Arc::clone(&break_reminder);
+    let validation_notifications = Arc::clone(&notification_manager);
+    let goal_notify_db = Arc::clone(&db);
+    let goal_notify_state = Arc::clone(&goals_state);
+    let focus_goals_state = Arc::clone(&goals_state);
+    let goal_notify_manager = Arc::clone(&notification_manager);
+
+    let block_settings = Arc::new(RwLock::new(block_settings));
+    let block_settings_for_tracker = Arc::clone(&block_settings);
+    let eco_mode = Arc::new(RwLock::new(eco_mode));
+    let eco_mode_for_tracker = Arc::clone(&eco_mode);
+    let title_aware_apps = Arc::new(RwLock::new(title_aware_apps));
+    let title_aware_apps_for_tracker = Arc::clone(&title_aware_apps);
+    let idle_threshold_seconds = Arc::new(RwLock::new(idle_threshold_seconds));
+    let idle_threshold_for_tracker = Arc::clone(&idle_threshold_seconds);
+    let commit_seconds = Arc::new(RwLock::new(commit_seconds));
+    let commit_seconds_for_tracker = Arc::clone(&commit_seconds);
+    let auto_export = Arc::new(RwLock::new(auto_export_settings));
+    let auto_export_for_task = Arc::clone(&auto_export);
+    let auto_export_db = Arc::clone(&db);
 
     // Create tracker (will be set with app handle in setup)
     // This tracker is used for state management (emergency access commands)
-    let tracker = Arc::new(Mutex::new(UsageTracker::new(
-        Arc::clone(&db),
-        Arc::clone(&emergency_access),
-    )));
+    let mut state_tracker = UsageTracker::new(Arc::clone(&db), Arc::clone(&emergency_access));
+    state_tracker.set_block_settings(Arc::clone(&block_settings));
+    let tracker = Arc::new(Mutex::new(state_tracker));
     let tracker_for_state = Arc::clone(&tracker);
 
     // Shared slot for the background tracker, filled during setup()
@@ -948,6 +3039,14 @@ pub fn run() {
             focus_manager,
             goals_state,
             emergency_access: Arc::clone(&tracker_emergency),
+            block_settings,
+            eco_mode,
+            display_settings: Arc::new(RwLock::new(display_settings)),
+            title_aware_apps,
+            idle_threshold_seconds,
+            commit_seconds,
+            auto_export,
+            db_path,
             tracker: tracker_for_state,
             background_tracker: background_tracker_for_state,
         })
@@ -1012,11 +3111,22 @@ pub fn run() {
             let emergency_for_tracker = Arc::clone(&tracker_emergency);
             let notification_manager_for_tracker = Arc::clone(&notification_manager_clone);
 
+            // Notifications go through the notification plugin while the GUI runs
+            notification_manager_clone.set_app_handle(handle.clone());
+            focus_manager_clone.set_app_handle(handle.clone());
+            let break_handle = handle.clone();
+
             // Create the background tracker as an Arc so we can share it for shutdown
             let mut background_tracker =
                 UsageTracker::new(tracker_db, emergency_for_tracker);
             background_tracker.set_app_handle(handle.clone());
             background_tracker.set_notification_manager(notification_manager_for_tracker);
+            background_tracker.set_focus_manager(focus_manager_for_tracker);
+            background_tracker.set_block_settings(block_settings_for_tracker);
+            background_tracker.set_eco_mode(eco_mode_for_tracker);
+            background_tracker.set_title_aware_apps(title_aware_apps_for_tracker);
+            background_tracker.set_idle_threshold(idle_threshold_for_tracker);
+            background_tracker.set_commit_seconds(commit_seconds_for_tracker);
             let background_tracker = Arc::new(background_tracker);
 
             // Store the background tracker for graceful shutdown
@@ -1044,19 +3154,113 @@ pub fn run() {
                 }
             });
 
+            // Run the scheduled export once a day after its configured time
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+                loop {
+                    interval.tick().await;
+                    let settings = auto_export_for_task.read().await.clone();
+                    let now = chrono::Local::now().naive_local();
+                    // Query under the lock, but write the file without holding it
+                    let records = {
+                        let db = auto_export_db.lock().await;
+                        let last_run = db
+                            .get_meta(auto_export::LAST_RUN_KEY)
+                            .ok()
+                            .flatten()
+                            .and_then(|date| {
+                                chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()
+                            });
+                        if !auto_export::should_run(&settings, now, last_run) {
+                            continue;
+                        }
+                        auto_export_records(&db, &settings, now.date())
+                    };
+
+                    match records.and_then(|records| write_auto_export(records, &settings, now)) {
+                        Ok(path) => {
+                            tracing::info!(path = %path.display(), "Automatic export written");
+                        }
+                        Err(e) => {
+                            tracing::error!(error = %e, "Automatic export failed");
+                        }
+                    }
+                    // Recorded even on failure so a broken destination isn't retried every minute
+                    let today = now.date().format("%Y-%m-%d").to_string();
+                    let db = auto_export_db.lock().await;
+                    if let Err(e) = db.set_meta(auto_export::LAST_RUN_KEY, &today) {
+                        tracing::error!(error = %e, "Failed to record automatic export date");
+                    }
+                }
+            });
+
+            // Notify when a goal's status changes (warning, exceeded, achieved)
+            // or an achievement is unlocked
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+                loop {
+                    interval.tick().await;
+                    let notifications = {
+                        let mut db = goal_notify_db.lock().await;
+                        let mut goals_state = goal_notify_state.lock().await;
+                        let mut notifications = match goals_progress_for_today(&mut db, &mut goals_state) {
+                            Ok(progress) => goals_state
+                                .goal_status_changes(chrono::Local::now().date_naive(), &progress),
+                            Err(e) => {
+                                tracing::error!(error = %e, "Failed to check goal progress");
+                                continue;
+                            }
+                        };
+                        notifications.extend(goals_state.achievement_notifications());
+                        match weekly_focus_progress_for_today(&db, &goals_state) {
+                            Ok(Some(progress)) => {
+                                notifications.extend(goals_state.weekly_focus_goal_change(&progress))
+                            }
+                            Ok(None) => {}
+                            Err(e) => {
+                                tracing::error!(error = %e, "Failed to check weekly focus goal");
+                            }
+                        }
+                        notifications
+                    };
+                    for notification in notifications {
+                        if let Err(e) = goal_notify_manager
+                            .send_notification(&notification.title, &notification.body, "normal")
+                            .await
+                        {
+                            tracing::debug!(error = %e, goal = %notification.goal_id, "Goal notification suppressed or failed");
+                        }
+                    }
+                }
+            });
+
+            // Validate and repair settings on startup
+            tauri::async_runtime::spawn(async move {
+                let db = validation_db.lock().await;
+                settings_validation::validate_settings(
+                    &validation_focus,
+                    &validation_break,
+                    &validation_notifications,
+                    &db,
+                )
+                .await;
+            });
+
             // Start break reminder background task
             tauri::async_runtime::spawn(async move {
                 let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
                 loop {
                     interval.tick().await;
                     if let Some(notification) = break_reminder_clone.tick().await {
-                        notification.send();
+                        notification.send(Some(&break_handle));
                         tracing::info!("Break reminder notification sent");
                     }
                 }
             });
 
-            // Start focus mode background task (check schedules and session expiry)
+            // Start focus mode background task (check schedules and session expiry).
+            // Also refreshes the tray so it reflects focus/break state.
+            let tray_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
                 loop {
@@ -1065,18 +3269,57 @@ pub fn run() {
                         match event {
                             focus_mode::FocusEvent::ScheduleStarted(schedule) => {
                                 tracing::info!(schedule = %schedule.name, "Starting scheduled focus session");
-                                focus_manager_clone.start_scheduled_session(&schedule).await;
+                                if let Some(replaced) =
+                                    focus_manager_clone.start_scheduled_session(&schedule).await
+                                {
+                                    record_focus_history(
+                                        &focus_db,
+                                        &focus_manager_clone,
+                                        &focus_goals_state,
+                                        &replaced,
+                                        false,
+                                    )
+                                    .await;
+                                }
+                            }
+                            focus_mode::FocusEvent::SchedulesChanged {
+                                schedule_name,
+                                blocked_apps,
+                            } => {
+                                tracing::info!(schedule = %schedule_name, "Active focus schedules changed");
+                                focus_manager_clone
+                                    .update_scheduled_session(schedule_name, blocked_apps)
+                                    .await;
                             }
                             focus_mode::FocusEvent::ScheduleEnded => {
                                 tracing::info!("Scheduled focus session ended");
-                                focus_manager_clone.stop_session().await;
+                                if let Some(ended) = focus_manager_clone.stop_session().await {
+                                    record_focus_history(
+                                        &focus_db,
+                                        &focus_manager_clone,
+                                        &focus_goals_state,
+                                        &ended,
+                                        true,
+                                    )
+                                    .await;
+                                }
                             }
                             focus_mode::FocusEvent::SessionExpired => {
                                 tracing::info!("Focus session expired");
-                                focus_manager_clone.stop_session().await;
+                                if let Some(ended) = focus_manager_clone.stop_session().await {
+                                    record_focus_history(
+                                        &focus_db,
+                                        &focus_manager_clone,
+                                        &focus_goals_state,
+                                        &ended,
+                                        true,
+                                    )
+                                    .await;
+                                }
                             }
                         }
                     }
+                    tray::refresh_tray(&tray_handle).await;
                 }
             });
 
@@ -1085,35 +3328,115 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             get_daily_usage,
             get_weekly_stats,
+            get_monthly_stats,
+            get_week_comparison,
+            get_pacing,
+            get_usage_highlights,
+            get_usage_patterns,
+            get_usage_for_date,
+            get_sessions_for_date,
+            get_app_titles,
+            get_yesterday_usage,
+            get_idle_ratio,
+            get_records,
+            get_category_app_breakdown,
             set_app_limit,
             get_app_limits,
             remove_app_limit,
+            get_global_cap,
+            set_global_cap,
+            remove_global_cap,
+            get_categories,
+            upsert_category,
+            delete_category,
+            get_productivity_score,
+            get_category_limits,
+            set_category_limit,
+            remove_category_limit,
+            get_cap_excluded_apps,
+            set_app_cap_excluded,
+            get_excluded_apps,
+            add_excluded_app,
+            remove_excluded_app,
+            set_limit_blocking,
+            get_limit_blocking,
             get_theme,
             get_theme_path,
             get_all_apps,
             record_usage,
             get_hourly_usage,
+            get_hourly_usage_for_date,
             get_category_usage,
+            get_daily_summary,
+            get_daily_usage_grouped,
+            get_display_settings,
+            set_display_settings,
+            get_title_aware_apps,
+            set_title_aware_apps,
+            get_idle_threshold,
+            set_idle_threshold,
+            get_flush_interval,
+            set_flush_interval,
+            get_auto_export_settings,
+            set_auto_export_settings,
+            export_config,
+            import_config,
+            get_app_groups,
+            set_app_group,
+            remove_app_group,
             set_app_category,
+            import_categories_csv,
+            find_possible_duplicate_apps,
+            set_app_pinned,
+            merge_apps,
             check_app_blocked,
             block_app,
             get_blocked_apps,
+            get_over_limit_apps,
+            get_limits_remaining,
             grant_emergency_access,
+            grant_timed_access,
             get_emergency_access_remaining,
             has_emergency_access,
+            get_emergency_settings,
+            set_emergency_settings,
+            pause_tracking,
+            resume_tracking,
+            is_tracking_paused,
+            get_current_activity,
             quit_blocked_app,
+            get_block_settings,
+            set_block_settings,
+            get_eco_mode,
+            set_eco_mode,
             get_installed_apps,
+            search_installed_apps,
+            refresh_installed_apps,
+            search_apps,
+            apply_installed_categories,
             resolve_app_icon,
+            set_app_icon,
+            clear_app_icon,
             send_test_notification,
             enable_autostart,
             disable_autostart,
             get_autostart_status,
+            check_tracking_permissions,
+            get_onboarding_status,
             cleanup_old_data,
+            cleanup_old_data_by_category,
             get_storage_stats,
+            optimize_database,
+            backup_database,
+            restore_database,
+            get_migration_history,
             export_usage_data,
+            export_sessions,
             format_export_csv,
+            format_export_sessions_csv,
             format_export_json,
             get_historical_data,
+            get_average_daily_usage,
             minimize_to_tray,
             show_window,
             get_break_settings,
@@ -1122,19 +3445,27 @@ pub fn run() {
             start_break,
             end_break,
             reset_break_timer,
+            snooze_break,
             get_notification_settings,
             set_notification_settings,
             mute_notifications,
             unmute_notifications,
             is_notifications_muted,
+            validate_settings,
             get_focus_settings,
             set_focus_settings,
             get_focus_session,
             start_focus_session,
             stop_focus_session,
+            get_focus_dashboard,
+            get_focus_history,
+            get_limit_bonuses,
+            get_focus_streak,
+            get_under_cap_streak,
             extend_focus_session,
             is_focus_mode_active,
             should_block_app_focus,
+            preview_focus_block,
             add_focus_blocked_app,
             remove_focus_blocked_app,
             get_goals,
@@ -1142,8 +3473,20 @@ pub fn run() {
             update_goal,
             remove_goal,
             get_goals_progress,
+            get_goals_progress_for_date,
+            project_goal_completion,
+            get_completed_challenges,
             get_achievements,
+            reset_achievement,
+            reset_all_achievements,
             get_goals_stats,
+            get_weekends_count,
+            set_weekends_count,
+            get_week_start,
+            set_week_start,
+            get_weekly_focus_goal,
+            set_weekly_focus_goal,
+            get_weekly_focus_goal_progress,
             check_for_update,
             install_update
         ])
@@ -1184,6 +3527,218 @@ mod tests {
     use super::*;
     use database::ExportRecord;
 
+    #[tokio::test]
+    async fn test_completed_timed_focus_session_counts_once() {
+        let db = Mutex::new(Database::new(std::path::PathBuf::from(":memory:")).unwrap());
+        let goals_state = Mutex::new(GoalsState::new());
+        let focus_manager = FocusManager::with_state_path(None);
+        let now = chrono::Utc::now().timestamp();
+
+        // A timed session that reached its end
+        let completed = FocusSession {
+            is_active: true,
+            start_time: Some(now - 25 * 60),
+            end_time: Some(now),
+            duration_minutes: Some(25),
+            ..Default::default()
+        };
+        record_focus_history(&db, &focus_manager, &goals_state, &completed, true).await;
+        assert_eq!(goals_state.lock().await.focus_sessions_completed, 1);
+
+        // Stopped early, scheduled and indefinite sessions don't count
+        let stopped_early = FocusSession {
+            end_time: Some(now + 600),
+            ..completed.clone()
+        };
+        record_focus_history(&db, &focus_manager, &goals_state, &stopped_early, false).await;
+        let scheduled = FocusSession {
+            end_time: None,
+            is_scheduled: true,
+            schedule_name: Some("Work hours".to_string()),
+            ..completed.clone()
+        };
+        record_focus_history(&db, &focus_manager, &goals_state, &scheduled, true).await;
+        let indefinite = FocusSession {
+            end_time: None,
+            duration_minutes: None,
+            ..completed
+        };
+        record_focus_history(&db, &focus_manager, &goals_state, &indefinite, false).await;
+
+        assert_eq!(goals_state.lock().await.focus_sessions_completed, 1);
+        assert_eq!(
+            db.lock()
+                .await
+                .load_goals_state()
+                .unwrap()
+                .focus_sessions_completed,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_focus_reward_needs_a_completed_session() {
+        let db = Mutex::new(Database::new(std::path::PathBuf::from(":memory:")).unwrap());
+        let goals_state = Mutex::new(GoalsState::new());
+        let focus_manager = FocusManager::with_state_path(None);
+        focus_manager
+            .update_settings(FocusSettings {
+                reward: focus_mode::FocusReward {
+                    enabled: true,
+                    bonus_minutes: 10,
+                    apps: vec!["Firefox".to_string()],
+                    categories: vec![],
+                },
+                ..Default::default()
+            })
+            .await;
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let now = chrono::Utc::now().timestamp();
+
+        // An indefinite session stopped after a second earns nothing
+        let indefinite = FocusSession {
+            is_active: true,
+            start_time: Some(now - 1),
+            ..Default::default()
+        };
+        record_focus_history(&db, &focus_manager, &goals_state, &indefinite, false).await;
+        assert!(db
+            .lock()
+            .await
+            .get_limit_bonuses(&today)
+            .unwrap()
+            .is_empty());
+
+        let timed = FocusSession {
+            start_time: Some(now - 25 * 60),
+            end_time: Some(now),
+            duration_minutes: Some(25),
+            ..indefinite
+        };
+        record_focus_history(&db, &focus_manager, &goals_state, &timed, true).await;
+        let bonuses = db.lock().await.get_limit_bonuses(&today).unwrap();
+        assert_eq!(bonuses.len(), 1);
+        assert_eq!(bonuses[0].app_name, "Firefox");
+    }
+
+    #[tokio::test]
+    async fn test_stopped_focus_session_recorded_once() {
+        let db = Mutex::new(Database::new(std::path::PathBuf::from(":memory:")).unwrap());
+        let goals_state = Mutex::new(GoalsState::new());
+        let focus_manager = FocusManager::with_state_path(None);
+
+        focus_manager.start_session(Some(25), None, false).await;
+        let ended = focus_manager.stop_session().await.unwrap();
+        record_focus_history(&db, &focus_manager, &goals_state, &ended, false).await;
+
+        let history = db.lock().await.get_focus_history().unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(!history[0].completed);
+        assert_eq!(history[0].planned_minutes, Some(25));
+    }
+
+    #[test]
+    fn test_usage_patterns_range_matches_local_hours() {
+        let db = Database::new(std::path::PathBuf::from(":memory:")).unwrap();
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+        let midnight = local_day_start(chrono::NaiveDate::from_ymd_opt(2024, 3, 10).unwrap());
+        let late = db.start_session(app_id, midnight - 600).unwrap();
+        db.end_session(late, midnight - 60).unwrap();
+        let next_day = db.start_session(app_id, midnight + 60).unwrap();
+        db.end_session(next_day, midnight + 3600).unwrap();
+
+        // Only the session from 23:50 falls on the local day
+        let range = day_range_timestamps("2024-03-09", "2024-03-09").unwrap();
+        assert_eq!(db.get_peak_hour(Some(range)).unwrap(), Some((23, 540)));
+    }
+
+    #[test]
+    fn test_focus_history_uses_local_days() {
+        let db = Database::new(std::path::PathBuf::from(":memory:")).unwrap();
+        let midnight = local_day_start(chrono::NaiveDate::from_ymd_opt(2024, 3, 10).unwrap());
+        for start_time in [midnight - 60, midnight + 60] {
+            db.record_focus_session(&FocusHistoryEntry {
+                start_time,
+                end_time: start_time + 30,
+                planned_minutes: None,
+                completed: true,
+                schedule_name: None,
+            })
+            .unwrap();
+        }
+
+        // Each session lands on the local day it started on
+        let history = focus_history_between(&db, "2024-03-09", "2024-03-09").unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].start_time, midnight - 60);
+        let history = focus_history_between(&db, "2024-03-10", "2024-03-10").unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].start_time, midnight + 60);
+        assert!(focus_history_between(&db, "2024-03-10", "bad").is_err());
+    }
+
+    #[test]
+    fn test_focus_streak_counts_days_with_a_completed_session() {
+        let db = Database::new(std::path::PathBuf::from(":memory:")).unwrap();
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let record = |offset: i64, completed: bool| {
+            let start_time = local_day_start(day + chrono::Duration::days(offset)) + 12 * 3600;
+            db.record_focus_session(&FocusHistoryEntry {
+                start_time,
+                end_time: start_time + 1500,
+                planned_minutes: Some(25),
+                completed,
+                schedule_name: None,
+            })
+            .unwrap();
+        };
+        record(0, true);
+        record(1, true);
+        record(1, true);
+        record(2, true);
+        // Stopped early, so the day doesn't count
+        record(3, false);
+
+        // The streak survives until the end of the next day
+        let streak = focus_streak(&db, day + chrono::Duration::days(3)).unwrap();
+        assert_eq!(
+            streak,
+            DailyStreak {
+                current: 3,
+                longest: 3
+            }
+        );
+
+        // A day with no completed session resets it
+        let streak = focus_streak(&db, day + chrono::Duration::days(4)).unwrap();
+        assert_eq!(
+            streak,
+            DailyStreak {
+                current: 0,
+                longest: 3
+            }
+        );
+
+        record(4, true);
+        let streak = focus_streak(&db, day + chrono::Duration::days(4)).unwrap();
+        assert_eq!(
+            streak,
+            DailyStreak {
+                current: 1,
+                longest: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_valid_hex_colors() {
+        assert!(is_valid_hex_color("#0EA5E9"));
+        assert!(is_valid_hex_color("#abcdef"));
+        assert!(!is_valid_hex_color("0EA5E9"));
+        assert!(!is_valid_hex_color("#FFF"));
+        assert!(!is_valid_hex_color("#GGGGGG"));
+    }
+
     #[test]
     fn test_valid_app_names() {
         assert!(is_valid_app_name("Firefox"));
@@ -1289,4 +3844,291 @@ mod tests {
         // Check second data row
         assert!(csv.contains("2026-01-12,Code,Development,120,2m,1"));
     }
+
+    fn category_usage(category: &str, minutes: i64) -> CategoryUsage {
+        CategoryUsage {
+            category: category.to_string(),
+            total_seconds: minutes * 60,
+            app_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_productivity_score() {
+        let productive: std::collections::HashSet<String> =
+            ["Development".to_string(), "Education".to_string()].into();
+
+        let all_productive = [
+            category_usage("Development", 120),
+            category_usage("Education", 30),
+        ];
+        assert_eq!(
+            compute_productivity_score(&all_productive, &productive),
+            100
+        );
+
+        let all_distracting = [
+            category_usage("Gaming", 90),
+            category_usage("Social Media", 45),
+        ];
+        assert_eq!(compute_productivity_score(&all_distracting, &productive), 0);
+
+        // 90 productive, 30 distracting, 60 neutral: 50 + 50 * 60 / 180
+        let mixed = [
+            category_usage("Development", 90),
+            category_usage("Gaming", 30),
+            category_usage("Uncategorized", 60),
+        ];
+        assert_eq!(compute_productivity_score(&mixed, &productive), 67);
+
+        let even = [
+            category_usage("Development", 30),
+            category_usage("Gaming", 30),
+        ];
+        assert_eq!(compute_productivity_score(&even, &productive), 50);
+        assert_eq!(compute_productivity_score(&[], &productive), 50);
+    }
+
+    #[test]
+    fn test_resolve_db_path() {
+        let default = resolve_db_path(None, None);
+        assert!(default.ends_with("wellbeing/wellbeing.db"));
+
+        assert_eq!(
+            resolve_db_path(None, Some("/tmp/env.db")),
+            std::path::PathBuf::from("/tmp/env.db")
+        );
+        assert_eq!(
+            resolve_db_path(Some("/tmp/cli.db"), None),
+            std::path::PathBuf::from("/tmp/cli.db")
+        );
+        // The argument wins over the environment
+        assert_eq!(
+            resolve_db_path(Some("/tmp/cli.db"), Some("/tmp/env.db")),
+            std::path::PathBuf::from("/tmp/cli.db")
+        );
+        // Blank values fall back to the default
+        assert_eq!(resolve_db_path(Some("  "), None), default);
+        assert_eq!(resolve_db_path(None, Some("")), default);
+    }
+
+    #[test]
+    fn test_format_export_sessions_csv() {
+        let start =
+            local_day_start(chrono::NaiveDate::from_ymd_opt(2026, 1, 12).unwrap()) + 9 * 3600;
+        let sessions = vec![
+            database::UsageSession {
+                id: 7,
+                app_id: 1,
+                app_name: "Firefox".to_string(),
+                category: Some("Productivity".to_string()),
+                start_time: start,
+                end_time: start + 90,
+                duration_seconds: 90,
+            },
+            database::UsageSession {
+                id: 8,
+                app_id: 2,
+                app_name: "Code, Insiders".to_string(),
+                category: None,
+                start_time: start + 120,
+                end_time: start + 3720,
+                duration_seconds: 3600,
+            },
+        ];
+
+        let csv = format_export_sessions_csv(sessions);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "session_id,app_name,category,start_time_iso,end_time_iso,duration_seconds"
+        );
+        assert_eq!(lines.len(), 3);
+
+        let first: Vec<&str> = lines[1].split(',').collect();
+        assert_eq!(&first[..3], &["7", "Firefox", "Productivity"]);
+        assert!(first[3].starts_with("2026-01-12T09:00:00"));
+        assert!(first[4].starts_with("2026-01-12T09:01:30"));
+        assert_eq!(first[5], "90");
+        let parsed = chrono::DateTime::parse_from_rfc3339(first[3]).unwrap();
+        assert_eq!(parsed.timestamp(), start);
+
+        assert!(lines[2].starts_with("8,\"Code, Insiders\",Uncategorized,2026-01-12T09:02:00"));
+        assert!(lines[2].ends_with(",3600"));
+    }
+
+    #[test]
+    fn test_export_range_uses_local_boundaries() {
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+        let next_day = day.succ_opt().unwrap();
+        let (start, end) = export_range_timestamps("2024-03-09", "2024-03-09").unwrap();
+
+        assert_eq!(start, local_day_start(day));
+        assert_eq!(end, local_day_start(next_day) - 1);
+        assert!(matches!(
+            export_range_timestamps("2024-13-01", "2024-03-09"),
+            Err(WellbeingError::Export(_))
+        ));
+        // Outside exports a bad date isn't an export error
+        assert_eq!(
+            day_range_timestamps("2024-03-09", "2024-03-09").unwrap(),
+            (start, end)
+        );
+        assert!(matches!(
+            day_range_timestamps("2024-03-09", "2024-03-32"),
+            Err(WellbeingError::Other(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_session_before_local_midnight() {
+        let db = Database::new(std::path::PathBuf::from(":memory:")).unwrap();
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+
+        // Session starting one minute before local midnight on 2024-03-09
+        let midnight = local_day_start(chrono::NaiveDate::from_ymd_opt(2024, 3, 10).unwrap());
+        let session = db.start_session(app_id, midnight - 60).unwrap();
+        db.end_session(session, midnight - 30).unwrap();
+
+        let (start, end) = export_range_timestamps("2024-03-09", "2024-03-09").unwrap();
+        let records = db.export_usage_data(start, end).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].date, "2024-03-09");
+
+        let (start, end) = export_range_timestamps("2024-03-10", "2024-03-10").unwrap();
+        assert!(db.export_usage_data(start, end).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_goals_progress_for_past_date() {
+        let db = Database::new(std::path::PathBuf::from(":memory:")).unwrap();
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 6).unwrap();
+        for (app, minutes) in [("Firefox", 90), ("Slack", 20)] {
+            let app_id = db.get_or_create_app(app, None).unwrap();
+            let start = local_day_start(date) + 12 * 3600;
+            let session = db.start_session(app_id, start).unwrap();
+            db.end_session(session, start + minutes * 60).unwrap();
+        }
+
+        let mut goals_state = GoalsState::new();
+        for (id, app) in [("firefox", "Firefox"), ("slack", "Slack")] {
+            goals_state.add_goal(Goal::new(
+                id,
+                app,
+                GoalType::AppLimit {
+                    app_name: app.to_string(),
+                },
+                60,
+            ));
+        }
+
+        let next_day = date.succ_opt().unwrap();
+        let progress = goals_progress_on(
+            &db,
+            &goals_state,
+            date,
+            goal_usage_for_date(&db, date).unwrap(),
+            local_day_start(next_day) - 1,
+        )
+        .unwrap();
+
+        let firefox = progress.iter().find(|p| p.goal_id == "firefox").unwrap();
+        assert_eq!(firefox.current_minutes, 90);
+        assert!(!firefox.is_met);
+        assert_eq!(firefox.status, goals::GoalStatus::Exceeded);
+
+        let slack = progress.iter().find(|p| p.goal_id == "slack").unwrap();
+        assert_eq!(slack.current_minutes, 20);
+        assert!(slack.is_met);
+
+        // The day after has no usage
+        let usage = goal_usage_for_date(&db, next_day).unwrap();
+        assert_eq!(usage.total_minutes, 0);
+    }
+
+    #[test]
+    fn test_curfew_goal_over_night_window() {
+        let db = Database::new(std::path::PathBuf::from(":memory:")).unwrap();
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 6).unwrap();
+        let app_id = db.get_or_create_app("Firefox", None).unwrap();
+        // 23:30 to 00:30: 30 minutes inside the window on each day
+        let start = local_day_start(date) + 23 * 3600 + 30 * 60;
+        let session = db.start_session(app_id, start).unwrap();
+        db.end_session(session, start + 3600).unwrap();
+
+        let mut goals_state = GoalsState::new();
+        for (id, allowance) in [("strict", 0), ("lenient", 45)] {
+            goals_state.add_goal(Goal::new(
+                id,
+                "No screens after 11pm",
+                GoalType::CurfewLimit {
+                    start_hour: 23,
+                    end_hour: 6,
+                },
+                allowance,
+            ));
+        }
+
+        let next_day = date.succ_opt().unwrap();
+        let progress = goals_progress_on(
+            &db,
+            &goals_state,
+            date,
+            goal_usage_for_date(&db, date).unwrap(),
+            local_day_start(next_day) - 1,
+        )
+        .unwrap();
+
+        let strict = progress.iter().find(|p| p.goal_id == "strict").unwrap();
+        assert_eq!(strict.current_minutes, 30);
+        assert!(!strict.is_met);
+        assert_eq!(strict.status, goals::GoalStatus::Exceeded);
+
+        let lenient = progress.iter().find(|p| p.goal_id == "lenient").unwrap();
+        assert_eq!(lenient.current_minutes, 30);
+        assert!(lenient.is_met);
+
+        // The morning after counts the part past midnight
+        let usage = curfew_usage_on(&db, &goals_state.goals, next_day).unwrap();
+        assert_eq!(usage["strict"], 30);
+    }
+
+    #[test]
+    fn test_log_format_from_env() {
+        assert_eq!(LogFormat::from_env_value(None), LogFormat::Text);
+        assert_eq!(LogFormat::from_env_value(Some("text")), LogFormat::Text);
+        assert_eq!(LogFormat::from_env_value(Some("JSON")), LogFormat::Json);
+        assert_eq!(LogFormat::from_env_value(Some(" json ")), LogFormat::Json);
+        assert_eq!(LogFormat::from_env_value(Some("yaml")), LogFormat::Text);
+    }
+
+    #[test]
+    fn test_anonymize_export_records() {
+        let record = |date: &str, app: &str, category: &str, seconds: i64| ExportRecord {
+            date: date.to_string(),
+            app_name: app.to_string(),
+            category: category.to_string(),
+            duration_seconds: seconds,
+            session_count: 2,
+        };
+        let records = vec![
+            record("2026-01-14", "Firefox", "Browser", 3600),
+            record("2026-01-14", "Slack", "Communication", 1200),
+            record("2026-01-13", "Firefox", "Browser", 600),
+        ];
+
+        let anonymized = anonymize_export_records(records.clone());
+
+        let names: Vec<&str> = anonymized.iter().map(|r| r.app_name.as_str()).collect();
+        assert_eq!(names, vec!["App 1", "App 2", "App 1"]);
+        let categories: Vec<&str> = anonymized.iter().map(|r| r.category.as_str()).collect();
+        assert_eq!(categories, vec!["Category 1", "Category 2", "Category 1"]);
+
+        for (original, anon) in records.iter().zip(&anonymized) {
+            assert_eq!(original.date, anon.date);
+            assert_eq!(original.duration_seconds, anon.duration_seconds);
+            assert_eq!(original.session_count, anon.session_count);
+        }
+    }
 }
@@ -1,27 +1,124 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 
-/// Duration of emergency access in seconds (10 minutes)
+/// Default duration of emergency access in seconds (10 minutes)
 pub const EMERGENCY_ACCESS_DURATION: i64 = 10 * 60;
 
+/// Meta key the emergency access settings are stored under
+pub const SETTINGS_KEY: &str = "emergency_access_settings";
+
+/// Emergency access settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmergencySettings {
+    /// How long a grant lasts when no duration is given
+    pub duration_seconds: i64,
+    /// Grants each app may receive per day; 0 means unlimited
+    pub daily_cap: u32,
+}
+
+impl Default for EmergencySettings {
+    fn default() -> Self {
+        Self {
+            duration_seconds: EMERGENCY_ACCESS_DURATION,
+            daily_cap: 3,
+        }
+    }
+}
+
+impl EmergencySettings {
+    /// Check the duration is between 1 minute and 4 hours
+    pub fn validate(&self) -> Result<(), String> {
+        Self::validate_duration(self.duration_seconds)
+    }
+
+    /// Check a grant duration is between 1 minute and 4 hours
+    pub fn validate_duration(seconds: i64) -> Result<(), String> {
+        if !(60..=4 * 60 * 60).contains(&seconds) {
+            return Err(format!(
+                "Emergency access duration must be between 60 and 14400 seconds, got {}",
+                seconds
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// An emergency access grant as stored in the database
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmergencyGrant {
+    pub app_name: String,
+    pub granted_at: i64,
+    pub expires_at: i64,
+}
+
 /// Manages emergency access grants for blocked apps
 pub struct EmergencyAccessManager {
     /// Map of app name to expiry timestamp (Unix timestamp)
     access_grants: Arc<Mutex<HashMap<String, i64>>>,
+    /// Number of grants each app received today, for the daily cap
+    grant_counts: Arc<Mutex<HashMap<String, u32>>>,
     /// The date we last reset grants (to reset daily)
     last_reset_date: Arc<Mutex<String>>,
+    settings: RwLock<EmergencySettings>,
 }
 
 impl EmergencyAccessManager {
     pub fn new() -> Self {
+        Self::restore(EmergencySettings::default(), Vec::new())
+    }
+
+    /// Create a manager with saved settings and today's grants from the
+    /// database, so active grants and the daily cap survive a restart
+    pub fn restore(settings: EmergencySettings, todays_grants: Vec<EmergencyGrant>) -> Self {
         let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let (access_grants, grant_counts) = Self::grant_maps(todays_grants);
+
         EmergencyAccessManager {
-            access_grants: Arc::new(Mutex::new(HashMap::new())),
+            access_grants: Arc::new(Mutex::new(access_grants)),
+            grant_counts: Arc::new(Mutex::new(grant_counts)),
             last_reset_date: Arc::new(Mutex::new(today)),
+            settings: RwLock::new(settings),
         }
     }
 
+    /// Replace the settings and grants in place, e.g. after the database
+    /// they were loaded from has been restored from a backup
+    pub async fn reload(&self, settings: EmergencySettings, todays_grants: Vec<EmergencyGrant>) {
+        let (access_grants, grant_counts) = Self::grant_maps(todays_grants);
+        *self.access_grants.lock().await = access_grants;
+        *self.grant_counts.lock().await = grant_counts;
+        *self.last_reset_date.lock().await = chrono::Local::now().format("%Y-%m-%d").to_string();
+        *self.settings.write().await = settings;
+    }
+
+    /// Expiry of each app's unexpired grants and the number of grants each
+    /// app received, from today's grants
+    fn grant_maps(
+        todays_grants: Vec<EmergencyGrant>,
+    ) -> (HashMap<String, i64>, HashMap<String, u32>) {
+        let now = chrono::Utc::now().timestamp();
+        let mut access_grants = HashMap::new();
+        let mut grant_counts = HashMap::new();
+        for grant in todays_grants {
+            *grant_counts.entry(grant.app_name.clone()).or_insert(0) += 1;
+            if grant.expires_at > now {
+                let expiry = access_grants.entry(grant.app_name).or_insert(0);
+                *expiry = grant.expires_at.max(*expiry);
+            }
+        }
+        (access_grants, grant_counts)
+    }
+
+    pub async fn get_settings(&self) -> EmergencySettings {
+        self.settings.read().await.clone()
+    }
+
+    pub async fn update_settings(&self, settings: EmergencySettings) {
+        *self.settings.write().await = settings;
+    }
+
     /// Reset grants if it's a new day
     async fn reset_if_new_day(&self) {
         let today = chrono::Local::now().format("%Y-%m-%d").to_string();
@@ -30,35 +127,72 @@ impl EmergencyAccessManager {
         if *last_reset != today {
             let mut grants = self.access_grants.lock().await;
             grants.clear();
+            self.grant_counts.lock().await.clear();
             *last_reset = today;
             tracing::info!("Reset emergency access grants for new day");
         }
     }
 
-    /// Grant emergency access for an app (10 minutes)
-    pub async fn grant_access(&self, app_name: &str) -> i64 {
+    /// Grant emergency access for an app for the configured duration
+    pub async fn grant_access(&self, app_name: &str) -> Result<i64, String> {
+        let duration = self.settings.read().await.duration_seconds;
+        self.grant_access_with_duration(app_name, duration).await
+    }
+
+    /// Grant emergency access for an app for `seconds`. Returns the expiry
+    /// timestamp, or an error once the app has used up today's grants.
+    pub async fn grant_access_with_duration(
+        &self,
+        app_name: &str,
+        seconds: i64,
+    ) -> Result<i64, String> {
         self.reset_if_new_day().await;
 
+        if seconds <= 0 {
+            return Err(format!(
+                "Emergency access duration must be positive, got {}",
+                seconds
+            ));
+        }
+
+        let daily_cap = self.settings.read().await.daily_cap;
+        let mut counts = self.grant_counts.lock().await;
+        let count = counts.entry(app_name.to_string()).or_insert(0);
+        if daily_cap > 0 && *count >= daily_cap {
+            return Err(format!(
+                "{} has already used its {} emergency grants today",
+                app_name, daily_cap
+            ));
+        }
         let now = chrono::Utc::now().timestamp();
-        let expiry = now + EMERGENCY_ACCESS_DURATION;
+        let expiry = now
+            .checked_add(seconds)
+            .ok_or_else(|| format!("Emergency access duration is too long: {}", seconds))?;
+        *count += 1;
 
         let mut grants = self.access_grants.lock().await;
         grants.insert(app_name.to_string(), expiry);
 
         tracing::info!(
             app = %app_name,
-            expiry_seconds = EMERGENCY_ACCESS_DURATION,
+            expiry_seconds = seconds,
+            grants_today = *count,
             "Granted emergency access"
         );
 
-        expiry
+        Ok(expiry)
     }
 
     /// Check if an app has active emergency access
     pub async fn has_active_access(&self, app_name: &str) -> bool {
+        self.has_active_access_at(app_name, chrono::Utc::now().timestamp())
+            .await
+    }
+
+    /// Check if an app's emergency access lasts past `now`
+    pub async fn has_active_access_at(&self, app_name: &str, now: i64) -> bool {
         self.reset_if_new_day().await;
 
-        let now = chrono::Utc::now().timestamp();
         let grants = self.access_grants.lock().await;
 
         if let Some(&expiry) = grants.get(app_name) {
@@ -128,7 +262,7 @@ mod tests {
         assert_eq!(manager.get_remaining_time("Firefox").await, 0);
 
         // Grant access
-        let expiry = manager.grant_access("Firefox").await;
+        let expiry = manager.grant_access("Firefox").await.unwrap();
         assert!(expiry > chrono::Utc::now().timestamp());
 
         // Now should have access
@@ -140,10 +274,99 @@ mod tests {
     async fn test_revoke_access() {
         let manager = EmergencyAccessManager::new();
 
-        manager.grant_access("Firefox").await;
+        manager.grant_access("Firefox").await.unwrap();
         assert!(manager.has_active_access("Firefox").await);
 
         manager.revoke_access("Firefox").await;
         assert!(!manager.has_active_access("Firefox").await);
     }
+
+    fn manager_with_cap(daily_cap: u32) -> EmergencyAccessManager {
+        EmergencyAccessManager::restore(
+            EmergencySettings {
+                daily_cap,
+                ..Default::default()
+            },
+            Vec::new(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_daily_cap_is_enforced_per_app() {
+        let manager = manager_with_cap(2);
+
+        assert!(manager.grant_access("Firefox").await.is_ok());
+        assert!(manager
+            .grant_access_with_duration("Firefox", 60)
+            .await
+            .is_ok());
+        assert!(manager.grant_access("Firefox").await.is_err());
+
+        // Other apps have their own allowance
+        assert!(manager.grant_access("Discord").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_zero_cap_is_unlimited() {
+        let manager = manager_with_cap(0);
+        for _ in 0..10 {
+            assert!(manager.grant_access("Firefox").await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restored_grants_count_toward_cap() {
+        let now = chrono::Utc::now().timestamp();
+        let manager = EmergencyAccessManager::restore(
+            EmergencySettings {
+                daily_cap: 2,
+                ..Default::default()
+            },
+            vec![
+                EmergencyGrant {
+                    app_name: "Firefox".to_string(),
+                    granted_at: now - 3600,
+                    expires_at: now - 3000,
+                },
+                EmergencyGrant {
+                    app_name: "Firefox".to_string(),
+                    granted_at: now - 60,
+                    expires_at: now + 540,
+                },
+            ],
+        );
+
+        // The unexpired grant is still active after the restart
+        assert!(manager.has_active_access("Firefox").await);
+        assert!(manager.get_remaining_time("Firefox").await > 500);
+        assert!(manager.grant_access("Firefox").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_custom_duration() {
+        let manager = EmergencyAccessManager::new();
+        let expiry = manager
+            .grant_access_with_duration("Firefox", 30 * 60)
+            .await
+            .unwrap();
+        assert!(expiry >= chrono::Utc::now().timestamp() + 29 * 60);
+        assert!(manager
+            .grant_access_with_duration("Firefox", 0)
+            .await
+            .is_err());
+        // An expiry past i64::MAX is rejected without using up a grant
+        assert!(manager
+            .grant_access_with_duration("Slack", i64::MAX)
+            .await
+            .is_err());
+        assert_eq!(manager.grant_counts.lock().await.get("Slack"), Some(&0));
+    }
+
+    #[test]
+    fn test_grant_duration_bounds() {
+        assert!(EmergencySettings::validate_duration(59).is_err());
+        assert!(EmergencySettings::validate_duration(60).is_ok());
+        assert!(EmergencySettings::validate_duration(4 * 60 * 60).is_ok());
+        assert!(EmergencySettings::validate_duration(4 * 60 * 60 + 1).is_err());
+    }
 }
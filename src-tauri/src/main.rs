@@ -3,13 +3,30 @@
 
 use std::env;
 
+/// Value of `--db-path <path>` or `--db-path=<path>`, if given. A value that
+/// looks like another flag (`--db-path --background`) is not a path.
+fn db_path_arg(args: &[String]) -> Option<&str> {
+    args.iter()
+        .enumerate()
+        .find_map(|(i, arg)| {
+            if arg == "--db-path" {
+                args.get(i + 1).map(String::as_str)
+            } else {
+                arg.strip_prefix("--db-path=")
+            }
+        })
+        .filter(|path| !path.starts_with("--"))
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
+    let env_db_path = env::var(wellbeing_lib::DB_PATH_ENV).ok();
+    let db_path = wellbeing_lib::resolve_db_path(db_path_arg(&args), env_db_path.as_deref());
 
     // Check for --background flag for headless mode
     if args.contains(&"--background".to_string()) || args.contains(&"-b".to_string()) {
-        wellbeing_lib::run_background();
+        wellbeing_lib::run_background(db_path);
     } else {
-        wellbeing_lib::run();
+        wellbeing_lib::run_with_db_path(db_path);
     }
 }
@@ -4,9 +4,10 @@
 //! schema changes and applies them incrementally.
 
 use rusqlite::{Connection, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
 
 /// Current schema version - increment this when adding new migrations
-pub const SCHEMA_VERSION: i64 = 2;
+pub const SCHEMA_VERSION: i64 = 20;
 
 /// Represents a single migration
 struct Migration {
@@ -15,6 +16,14 @@ struct Migration {
     sql: &'static str,
 }
 
+/// A migration that has been applied to the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationRecord {
+    pub version: i64,
+    pub applied_at: i64,
+    pub description: Option<String>,
+}
+
 /// Get all migrations in order
 fn get_migrations() -> Vec<Migration> {
     vec![
@@ -38,12 +47,233 @@ fn get_migrations() -> Vec<Migration> {
                 CREATE INDEX IF NOT EXISTS idx_sessions_date ON usage_sessions(start_time);
             ",
         },
-        // Future migrations go here:
-        // Migration {
-        //     version: 3,
-        //     description: "Add weekly goals table",
-        //     sql: "CREATE TABLE IF NOT EXISTS weekly_goals (...)",
-        // },
+        Migration {
+            version: 3,
+            description: "Add app display groups",
+            sql: "
+                CREATE TABLE IF NOT EXISTS app_group_members (
+                    app_name TEXT PRIMARY KEY,
+                    group_name TEXT NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_app_group_members_group ON app_group_members(group_name);
+            ",
+        },
+        Migration {
+            version: 4,
+            description: "Add pinned flag to apps",
+            sql: "
+                ALTER TABLE apps ADD COLUMN pinned INTEGER DEFAULT 0;
+            ",
+        },
+        Migration {
+            version: 5,
+            description: "Add idle periods with reason",
+            sql: "
+                CREATE TABLE IF NOT EXISTS idle_periods (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    start_time INTEGER NOT NULL,
+                    end_time INTEGER NOT NULL,
+                    reason TEXT NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_idle_periods_start ON idle_periods(start_time);
+            ",
+        },
+        Migration {
+            version: 6,
+            description: "Add global daily cap and per-app cap exclusion",
+            sql: "
+                ALTER TABLE apps ADD COLUMN excluded_from_cap INTEGER DEFAULT 0;
+                CREATE TABLE IF NOT EXISTS global_limit (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    daily_limit_minutes INTEGER NOT NULL,
+                    block_when_exceeded INTEGER DEFAULT 0
+                );
+            ",
+        },
+        Migration {
+            version: 7,
+            description: "Add focus session history",
+            sql: "
+                CREATE TABLE IF NOT EXISTS focus_sessions (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    start_time INTEGER NOT NULL,
+                    end_time INTEGER NOT NULL,
+                    planned_minutes INTEGER,
+                    completed INTEGER NOT NULL,
+                    schedule_name TEXT
+                );
+                CREATE INDEX IF NOT EXISTS idx_focus_sessions_start ON focus_sessions(start_time);
+            ",
+        },
+        Migration {
+            version: 8,
+            description: "Add user-chosen app icons",
+            sql: "
+                ALTER TABLE apps ADD COLUMN custom_icon TEXT;
+            ",
+        },
+        Migration {
+            version: 9,
+            description: "Add key/value table for app state",
+            sql: "
+                CREATE TABLE IF NOT EXISTS app_meta (
+                    key TEXT PRIMARY KEY,
+                    value TEXT NOT NULL
+                );
+            ",
+        },
+        Migration {
+            version: 10,
+            description: "Add per-day limit bonuses",
+            sql: "
+                CREATE TABLE IF NOT EXISTS limit_bonuses (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    app_name TEXT NOT NULL,
+                    date TEXT NOT NULL,
+                    minutes INTEGER NOT NULL,
+                    reason TEXT NOT NULL,
+                    granted_at INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_limit_bonuses_date ON limit_bonuses(date, app_name);
+            ",
+        },
+        Migration {
+            version: 11,
+            description: "Persist goals, achievements and goal streaks",
+            sql: "
+                CREATE TABLE IF NOT EXISTS goals (
+                    id TEXT PRIMARY KEY,
+                    position INTEGER NOT NULL,
+                    data TEXT NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS achievements (
+                    id TEXT PRIMARY KEY,
+                    progress INTEGER NOT NULL DEFAULT 0,
+                    earned_at TEXT
+                );
+                CREATE TABLE IF NOT EXISTS goals_state (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    current_streak INTEGER NOT NULL DEFAULT 0,
+                    longest_streak INTEGER NOT NULL DEFAULT 0,
+                    total_goals_met INTEGER NOT NULL DEFAULT 0,
+                    focus_sessions_completed INTEGER NOT NULL DEFAULT 0,
+                    weekends_count INTEGER NOT NULL DEFAULT 1,
+                    week_start TEXT,
+                    weekly_focus_goal TEXT,
+                    challenge_results TEXT,
+                    completed_challenges TEXT
+                );
+            ",
+        },
+        Migration {
+            version: 12,
+            description: "Persist emergency access grants",
+            sql: "
+                CREATE TABLE IF NOT EXISTS emergency_grants (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    app_name TEXT NOT NULL,
+                    granted_at INTEGER NOT NULL,
+                    expires_at INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_emergency_grants_granted_at ON emergency_grants(granted_at);
+            ",
+        },
+        Migration {
+            version: 13,
+            description: "Add window_title to usage_sessions",
+            sql: "
+                ALTER TABLE usage_sessions ADD COLUMN window_title TEXT;
+            ",
+        },
+        Migration {
+            version: 14,
+            description: "Add optional weekend limits",
+            sql: "
+                ALTER TABLE app_limits ADD COLUMN weekend_limit_minutes INTEGER;
+            ",
+        },
+        Migration {
+            version: 15,
+            description: "Add per-category limits",
+            sql: "
+                CREATE TABLE IF NOT EXISTS category_limits (
+                    category TEXT PRIMARY KEY,
+                    daily_limit_minutes INTEGER NOT NULL,
+                    block_when_exceeded INTEGER NOT NULL DEFAULT 0
+                );
+            ",
+        },
+        Migration {
+            version: 16,
+            description: "Add categories with colors and productivity flag",
+            sql: "
+                CREATE TABLE IF NOT EXISTS categories (
+                    name TEXT PRIMARY KEY,
+                    color TEXT NOT NULL,
+                    is_productive INTEGER NOT NULL DEFAULT 0
+                );
+                INSERT OR IGNORE INTO categories (name, color, is_productive) VALUES
+                    ('Productivity', '#4F46E5', 1),
+                    ('Development', '#0EA5E9', 1),
+                    ('Communication', '#10B981', 0),
+                    ('Entertainment', '#F43F5E', 0),
+                    ('Social Media', '#EC4899', 0),
+                    ('Gaming', '#F97316', 0),
+                    ('Utilities', '#64748B', 0),
+                    ('Education', '#8B5CF6', 1),
+                    ('Other', '#9CA3AF', 0);
+                INSERT OR IGNORE INTO categories (name, color, is_productive)
+                    SELECT DISTINCT category, '#9CA3AF', 0 FROM apps
+                    WHERE category IS NOT NULL AND category != '';
+            ",
+        },
+        Migration {
+            version: 17,
+            description: "Add apps excluded from tracking",
+            sql: "
+                CREATE TABLE IF NOT EXISTS excluded_apps (
+                    name TEXT PRIMARY KEY
+                );
+            ",
+        },
+        Migration {
+            version: 18,
+            description: "Mark open idle periods with a NULL end time",
+            sql: "
+                CREATE TABLE IF NOT EXISTS idle_periods_new (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    start_time INTEGER NOT NULL,
+                    end_time INTEGER,
+                    reason TEXT NOT NULL
+                );
+                INSERT INTO idle_periods_new (id, start_time, end_time, reason)
+                    SELECT id, start_time, NULLIF(end_time, start_time), reason FROM idle_periods;
+                DROP TABLE idle_periods;
+                ALTER TABLE idle_periods_new RENAME TO idle_periods;
+                CREATE INDEX IF NOT EXISTS idx_idle_periods_start ON idle_periods(start_time);
+            ",
+        },
+        Migration {
+            version: 19,
+            description: "Add a view of today's limit bonus minutes per app",
+            sql: "
+                CREATE VIEW IF NOT EXISTS todays_limit_bonuses AS
+                    SELECT app_name, SUM(minutes) AS minutes
+                    FROM limit_bonuses
+                    WHERE date = date('now', 'localtime')
+                    GROUP BY app_name;
+            ",
+        },
+        Migration {
+            version: 20,
+            description: "Add breaks taken per day",
+            sql: "
+                CREATE TABLE IF NOT EXISTS breaks_taken (
+                    date TEXT PRIMARY KEY,
+                    count INTEGER NOT NULL DEFAULT 0
+                );
+            ",
+        },
     ]
 }
 
@@ -78,6 +308,27 @@ fn record_migration(conn: &Connection, version: i64, description: &str) -> Sqlit
     Ok(())
 }
 
+/// Get every applied migration, oldest version first
+pub fn get_migration_history(conn: &Connection) -> SqliteResult<Vec<MigrationRecord>> {
+    init_version_table(conn)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT version, COALESCE(applied_at, 0), description
+         FROM schema_version
+         ORDER BY version ASC",
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok(MigrationRecord {
+            version: row.get(0)?,
+            applied_at: row.get(1)?,
+            description: row.get(2)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
 /// Run all pending migrations
 ///
 /// This function:
@@ -179,4 +430,29 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_migration_history_in_order() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE apps (id INTEGER PRIMARY KEY, name TEXT);
+             CREATE TABLE app_limits (id INTEGER PRIMARY KEY, app_id INTEGER);
+             CREATE TABLE usage_sessions (id INTEGER PRIMARY KEY, app_id INTEGER, start_time INTEGER);",
+        )
+        .unwrap();
+
+        let applied = run_migrations(&conn).unwrap();
+        let history = get_migration_history(&conn).unwrap();
+
+        assert_eq!(history.len(), applied as usize);
+        let versions: Vec<i64> = history.iter().map(|r| r.version).collect();
+        let expected: Vec<i64> = get_migrations().iter().map(|m| m.version).collect();
+        assert_eq!(versions, expected);
+        assert!(history
+            .iter()
+            .all(|r| r.description.is_some() && r.applied_at > 0));
+
+        // Running again applies nothing new
+        assert_eq!(run_migrations(&conn).unwrap(), 0);
+    }
 }
@@ -1,7 +1,26 @@
+use crate::settings_store;
+use crate::settings_validation::{self, SettingsRepair};
+use crate::settings_version::VersionedSettings;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tauri::AppHandle;
 use tokio::sync::RwLock;
 
+/// How the "approaching limit" warning is triggered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningMode {
+    /// Warn once usage reaches `warning_threshold` percent of the limit
+    #[default]
+    Percentage,
+    /// Warn once `warning_minutes` or fewer remain
+    AbsoluteMinutes,
+    /// Warn on whichever of the two happens first
+    Both,
+}
+
 /// Notification settings for the app
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationSettings {
@@ -11,6 +30,77 @@ pub struct NotificationSettings {
     pub dnd_enabled: bool,
     pub dnd_start_hour: u32, // 0-23
     pub dnd_end_hour: u32,   // 0-23
+    #[serde(default)]
+    pub dnd_start_minute: u32, // 0-59
+    #[serde(default)]
+    pub dnd_end_minute: u32, // 0-59
+    #[serde(default)]
+    pub warning_mode: WarningMode,
+    #[serde(default = "default_warning_minutes")]
+    pub warning_minutes: u32, // minutes remaining, used by AbsoluteMinutes/Both
+    #[serde(default = "default_limit_check_seconds")]
+    pub limit_check_seconds: u32, // how often usage is checked against limits
+    /// Settings format version, see `settings_version`
+    #[serde(default = "current_settings_version")]
+    pub version: u32,
+}
+
+fn default_warning_minutes() -> u32 {
+    10
+}
+
+fn default_limit_check_seconds() -> u32 {
+    crate::power::LIMIT_CHECK_SECONDS as u32
+}
+
+fn current_settings_version() -> u32 {
+    <NotificationSettings as VersionedSettings>::CURRENT_VERSION
+}
+
+impl NotificationSettings {
+    /// Whether a limit warning is due for the given usage
+    pub fn should_warn(&self, used_seconds: i64, limit_seconds: i64) -> bool {
+        if limit_seconds <= 0 {
+            return false;
+        }
+
+        let by_percentage = used_seconds * 100 >= limit_seconds * self.warning_threshold as i64;
+        let by_minutes = limit_seconds - used_seconds <= self.warning_minutes as i64 * 60;
+
+        match self.warning_mode {
+            WarningMode::Percentage => by_percentage,
+            WarningMode::AbsoluteMinutes => by_minutes,
+            WarningMode::Both => by_percentage || by_minutes,
+        }
+    }
+
+    /// Whether usage has reached `exceeded_threshold` percent of the limit
+    pub fn is_exceeded(&self, used_seconds: i64, limit_seconds: i64) -> bool {
+        limit_seconds > 0 && used_seconds * 100 >= limit_seconds * self.exceeded_threshold as i64
+    }
+
+    /// Whether `time` falls inside the Do Not Disturb window. The start is
+    /// inclusive and the end exclusive; a start after the end spans midnight.
+    pub fn is_dnd_at(&self, time: chrono::NaiveTime) -> bool {
+        if !self.dnd_enabled {
+            return false;
+        }
+
+        let (Some(start), Some(end)) = (
+            chrono::NaiveTime::from_hms_opt(self.dnd_start_hour, self.dnd_start_minute, 0),
+            chrono::NaiveTime::from_hms_opt(self.dnd_end_hour, self.dnd_end_minute, 0),
+        ) else {
+            return false;
+        };
+
+        if start > end {
+            // Overnight DND (e.g., 22:30 to 08:15)
+            time >= start || time < end
+        } else {
+            // Normal range (e.g., 09:00 to 17:00)
+            time >= start && time < end
+        }
+    }
 }
 
 impl Default for NotificationSettings {
@@ -22,31 +112,79 @@ impl Default for NotificationSettings {
             dnd_enabled: false,
             dnd_start_hour: 22, // 10 PM
             dnd_end_hour: 8,    // 8 AM
+            dnd_start_minute: 0,
+            dnd_end_minute: 0,
+            warning_mode: WarningMode::Percentage,
+            warning_minutes: default_warning_minutes(),
+            limit_check_seconds: default_limit_check_seconds(),
+            version: current_settings_version(),
         }
     }
 }
 
+/// File in the config directory that notification settings are saved to
+const NOTIFICATION_SETTINGS_FILE: &str = "notification_settings.json";
+
 pub struct NotificationManager {
     settings: RwLock<NotificationSettings>,
     muted: AtomicBool,
+    /// Set once the GUI is up; notifications then go through the plugin
+    app_handle: OnceLock<AppHandle>,
+    /// File settings are saved to; None keeps them in memory
+    settings_path: Option<PathBuf>,
 }
 
 impl NotificationManager {
+    /// Manager with default settings kept in memory
     pub fn new() -> Self {
+        Self::with_settings_path(None)
+    }
+
+    /// Manager with the settings saved by the last run
+    pub fn load() -> Self {
+        Self::with_settings_path(settings_store::settings_path(NOTIFICATION_SETTINGS_FILE))
+    }
+
+    /// Manager saving its settings to `settings_path`; None keeps them in memory
+    pub(crate) fn with_settings_path(settings_path: Option<PathBuf>) -> Self {
+        let settings: NotificationSettings =
+            settings_store::load_or_default(settings_path.as_deref());
         Self {
-            settings: RwLock::new(NotificationSettings::default()),
+            settings: RwLock::new(settings),
             muted: AtomicBool::new(false),
+            app_handle: OnceLock::new(),
+            settings_path,
         }
     }
 
+    /// Send notifications through the notification plugin from now on
+    pub fn set_app_handle(&self, handle: AppHandle) {
+        let _ = self.app_handle.set(handle);
+    }
+
     pub async fn get_settings(&self) -> NotificationSettings {
         self.settings.read().await.clone()
     }
 
-    pub async fn update_settings(&self, settings: NotificationSettings) {
+    pub async fn update_settings(&self, mut settings: NotificationSettings) {
+        let repairs = settings_validation::validate_notification_settings(&mut settings);
+        settings_validation::log_repairs(&repairs);
+        if let Some(path) = &self.settings_path {
+            if let Err(e) = settings_store::save(path, &settings) {
+                tracing::error!(error = %e, "Failed to save notification settings");
+            }
+        }
         *self.settings.write().await = settings;
     }
 
+    /// Validate the current settings in place, returning any repairs made
+    pub async fn validate_settings(&self) -> Vec<SettingsRepair> {
+        let mut settings = self.settings.write().await;
+        let repairs = settings_validation::validate_notification_settings(&mut settings);
+        settings_validation::log_repairs(&repairs);
+        repairs
+    }
+
     /// Check if notifications should be shown based on DND schedule
     pub async fn should_notify(&self) -> bool {
         let settings = self.settings.read().await;
@@ -59,24 +197,7 @@ impl NotificationManager {
             return false;
         }
 
-        if settings.dnd_enabled {
-            let current_hour = chrono::Local::now().hour();
-
-            // Handle overnight DND (e.g., 22:00 to 08:00)
-            if settings.dnd_start_hour > settings.dnd_end_hour {
-                // DND is active if current hour is >= start OR < end
-                if current_hour >= settings.dnd_start_hour || current_hour < settings.dnd_end_hour {
-                    return false;
-                }
-            } else {
-                // Normal range (e.g., 09:00 to 17:00)
-                if current_hour >= settings.dnd_start_hour && current_hour < settings.dnd_end_hour {
-                    return false;
-                }
-            }
-        }
-
-        true
+        !settings.is_dnd_at(chrono::Local::now().time())
     }
 
     /// Get the warning threshold percentage
@@ -115,7 +236,7 @@ impl NotificationManager {
             return Ok(());
         }
 
-        if crate::notifications::send_notification_with_urgency(title, body, urgency) {
+        if crate::notifications::notify(self.app_handle.get(), title, body, urgency) {
             Ok(())
         } else {
             Err("Failed to send notification".to_string())
@@ -129,11 +250,10 @@ impl Default for NotificationManager {
     }
 }
 
-use chrono::Timelike;
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::TempPath;
 
     #[test]
     fn test_default_settings() {
@@ -159,4 +279,112 @@ mod tests {
         manager.unmute();
         assert!(!manager.is_muted());
     }
+
+    #[tokio::test]
+    async fn test_settings_persist_across_restarts() {
+        let path = TempPath::new("notification-settings.json");
+
+        let manager = NotificationManager::with_settings_path(Some(path.to_path_buf()));
+        manager
+            .update_settings(NotificationSettings {
+                warning_threshold: 60,
+                dnd_enabled: true,
+                warning_mode: WarningMode::Both,
+                ..Default::default()
+            })
+            .await;
+
+        let reopened = NotificationManager::with_settings_path(Some(path.to_path_buf()));
+        let settings = reopened.get_settings().await;
+        assert_eq!(settings.warning_threshold, 60);
+        assert!(settings.dnd_enabled);
+        assert_eq!(settings.warning_mode, WarningMode::Both);
+    }
+
+    fn settings_with_mode(mode: WarningMode) -> NotificationSettings {
+        NotificationSettings {
+            warning_mode: mode,
+            warning_minutes: 10,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_percentage_warning_mode() {
+        let settings = settings_with_mode(WarningMode::Percentage);
+
+        // 5 minute limit: warns at 4 minutes used (80%)
+        assert!(!settings.should_warn(3 * 60, 5 * 60));
+        assert!(settings.should_warn(4 * 60, 5 * 60));
+
+        // 3 hour limit: warns at 144 minutes used, long before 10 minutes remain
+        assert!(!settings.should_warn(120 * 60, 180 * 60));
+        assert!(settings.should_warn(144 * 60, 180 * 60));
+    }
+
+    #[test]
+    fn test_absolute_minutes_warning_mode() {
+        let settings = settings_with_mode(WarningMode::AbsoluteMinutes);
+
+        // 5 minute limit: always within 10 minutes of the limit
+        assert!(settings.should_warn(0, 5 * 60));
+
+        // 3 hour limit: warns only once 10 minutes remain
+        assert!(!settings.should_warn(144 * 60, 180 * 60));
+        assert!(settings.should_warn(170 * 60, 180 * 60));
+    }
+
+    #[test]
+    fn test_both_warning_mode() {
+        let settings = settings_with_mode(WarningMode::Both);
+
+        // Short limit: the minutes rule fires first
+        assert!(settings.should_warn(60, 5 * 60));
+
+        // Long limit: the percentage rule fires first
+        assert!(settings.should_warn(144 * 60, 180 * 60));
+        assert!(!settings.should_warn(60 * 60, 180 * 60));
+    }
+
+    #[test]
+    fn test_zero_limit_never_warns() {
+        assert!(!settings_with_mode(WarningMode::Both).should_warn(60, 0));
+    }
+
+    #[test]
+    fn test_overnight_dnd_with_minutes() {
+        let settings = NotificationSettings {
+            dnd_enabled: true,
+            dnd_start_hour: 22,
+            dnd_start_minute: 30,
+            dnd_end_hour: 8,
+            dnd_end_minute: 15,
+            ..Default::default()
+        };
+        let at = |h, m| chrono::NaiveTime::from_hms_opt(h, m, 0).unwrap();
+
+        assert!(!settings.is_dnd_at(at(22, 29)));
+        assert!(settings.is_dnd_at(at(22, 30)));
+        assert!(settings.is_dnd_at(at(0, 0)));
+        assert!(settings.is_dnd_at(at(8, 14)));
+        assert!(!settings.is_dnd_at(at(8, 15)));
+        assert!(!settings.is_dnd_at(at(12, 0)));
+    }
+
+    #[test]
+    fn test_settings_without_dnd_minutes_deserialize() {
+        let json = r#"{
+            "enabled": true,
+            "warning_threshold": 80,
+            "exceeded_threshold": 100,
+            "dnd_enabled": true,
+            "dnd_start_hour": 22,
+            "dnd_end_hour": 8
+        }"#;
+
+        let settings: NotificationSettings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.dnd_start_minute, 0);
+        assert_eq!(settings.dnd_end_minute, 0);
+        assert!(settings.is_dnd_at(chrono::NaiveTime::from_hms_opt(22, 0, 0).unwrap()));
+    }
 }
@@ -1,4 +1,25 @@
 use std::process::Command;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// Send a desktop notification through the notification plugin when the GUI
+/// is running, and through the platform tools below only in headless
+/// background mode where there is no `AppHandle`.
+///
+/// The plugin has no urgency levels, so `urgency` only applies without an app.
+pub fn notify(app: Option<&AppHandle>, title: &str, body: &str, urgency: &str) -> bool {
+    let Some(app) = app else {
+        return send_notification_with_urgency(title, body, urgency);
+    };
+
+    match app.notification().builder().title(title).body(body).show() {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!(error = %e, "Notification plugin failed, falling back to platform tools");
+            send_notification_with_urgency(title, body, urgency)
+        }
+    }
+}
 
 /// Send a desktop notification using platform-native tools.
 ///
@@ -8,6 +29,7 @@ use std::process::Command;
 /// - Linux: uses `notify-send` (libnotify)
 /// - Windows: uses PowerShell toast notifications
 /// - macOS: uses `osascript` display notification
+#[allow(dead_code)]
 pub fn send_notification(title: &str, body: &str) -> bool {
     send_notification_with_urgency(title, body, "normal")
 }
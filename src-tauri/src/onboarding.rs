@@ -0,0 +1,137 @@
+//! Setup checklist for new installs
+//!
+//! Aggregates the things a new user should have in place (working window
+//! tracking, autostart, at least one limit or goal) into one status the UI can
+//! render as a checklist.
+
+use serde::{Deserialize, Serialize};
+
+/// One checklist entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnboardingItem {
+    pub done: bool,
+    /// What the user can do to complete this item
+    pub hint: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnboardingStatus {
+    pub tracking_permitted: OnboardingItem,
+    pub autostart_enabled: OnboardingItem,
+    pub limits_set: OnboardingItem,
+    pub goals_set: OnboardingItem,
+    pub activity_seen: OnboardingItem,
+    /// True when every item is done
+    pub complete: bool,
+}
+
+/// The raw facts the checklist is built from
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OnboardingFacts {
+    pub tracking_permitted: bool,
+    pub autostart_enabled: bool,
+    pub limit_count: usize,
+    pub goal_count: usize,
+    pub session_count: i64,
+}
+
+fn item(done: bool, hint: &str) -> OnboardingItem {
+    OnboardingItem {
+        done,
+        hint: hint.to_string(),
+    }
+}
+
+/// Hint for getting window tracking working on this platform
+fn tracking_hint() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "Grant Screen Recording permission in System Settings > Privacy & Security"
+    } else if cfg!(target_os = "linux") {
+        "On Wayland, window tracking needs Hyprland or Sway; otherwise log in with an X11 session"
+    } else {
+        "Make sure the app is allowed to see the active window"
+    }
+}
+
+pub fn onboarding_status(facts: OnboardingFacts) -> OnboardingStatus {
+    let status = OnboardingStatus {
+        tracking_permitted: item(facts.tracking_permitted, tracking_hint()),
+        autostart_enabled: item(
+            facts.autostart_enabled,
+            "Turn on autostart in Settings so usage is tracked from login",
+        ),
+        limits_set: item(
+            facts.limit_count > 0,
+            "Set a daily limit on an app you'd like to use less",
+        ),
+        goals_set: item(
+            facts.goal_count > 0,
+            "Add a goal, such as less than 4 hours of screen time a day",
+        ),
+        activity_seen: item(
+            facts.session_count > 0,
+            "Use your computer for a minute; activity will appear on the dashboard",
+        ),
+        complete: false,
+    };
+
+    OnboardingStatus {
+        complete: [
+            &status.tracking_permitted,
+            &status.autostart_enabled,
+            &status.limits_set,
+            &status.goals_set,
+            &status.activity_seen,
+        ]
+        .iter()
+        .all(|item| item.done),
+        ..status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fresh_install_has_nothing_done() {
+        let status = onboarding_status(OnboardingFacts::default());
+
+        assert!(!status.tracking_permitted.done);
+        assert!(!status.autostart_enabled.done);
+        assert!(!status.limits_set.done);
+        assert!(!status.goals_set.done);
+        assert!(!status.activity_seen.done);
+        assert!(!status.complete);
+        assert!(!status.limits_set.hint.is_empty());
+    }
+
+    #[test]
+    fn test_configured_install_is_complete() {
+        let status = onboarding_status(OnboardingFacts {
+            tracking_permitted: true,
+            autostart_enabled: true,
+            limit_count: 2,
+            goal_count: 1,
+            session_count: 40,
+        });
+
+        assert!(status.limits_set.done);
+        assert!(status.activity_seen.done);
+        assert!(status.complete);
+    }
+
+    #[test]
+    fn test_partial_setup_is_not_complete() {
+        let status = onboarding_status(OnboardingFacts {
+            tracking_permitted: true,
+            session_count: 3,
+            ..Default::default()
+        });
+
+        assert!(status.tracking_permitted.done);
+        assert!(status.activity_seen.done);
+        assert!(!status.goals_set.done);
+        assert!(!status.complete);
+    }
+}
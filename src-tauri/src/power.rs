@@ -0,0 +1,293 @@
+//! Eco tracking mode for running on battery
+//!
+//! In eco mode the tracker polls the active window less often and commits
+//! session durations to the database less often. Sessions are timestamped, so
+//! recorded durations stay correct; only app switches are detected at the
+//! coarser tick granularity.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Tracker tick interval in normal mode
+pub const NORMAL_TICK_SECONDS: u64 = 1;
+/// Tracker tick interval in eco mode
+pub const ECO_TICK_SECONDS: u64 = 5;
+/// How often the open session is committed in normal mode, unless configured
+/// otherwise
+pub const NORMAL_COMMIT_SECONDS: u64 = 5;
+/// Eco mode commits the open session no more often than this
+pub const ECO_COMMIT_SECONDS: u64 = 30;
+/// Bounds for the configurable commit interval; 1 commits on every tick
+pub const MIN_COMMIT_SECONDS: u64 = 1;
+pub const MAX_COMMIT_SECONDS: u64 = 5 * 60;
+/// app_meta key holding the saved commit interval in seconds
+pub const COMMIT_SECONDS_KEY: &str = "commit_seconds";
+/// Default for how often limits are checked, regardless of mode
+pub const LIMIT_CHECK_SECONDS: u64 = 10;
+/// How often `Auto` mode re-checks the power source
+pub const POWER_CHECK_SECONDS: u64 = 60;
+/// app_meta key holding the saved [`EcoMode`]
+pub const SETTINGS_KEY: &str = "eco_mode";
+
+/// User preference for eco mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EcoMode {
+    /// Enable eco mode while running on battery
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+/// Whether eco mode should be active for the given preference and power source
+pub fn eco_active(mode: EcoMode, on_battery: bool) -> bool {
+    match mode {
+        EcoMode::Auto => on_battery,
+        EcoMode::On => true,
+        EcoMode::Off => false,
+    }
+}
+
+/// How often the tracker polls the active window
+pub fn tick_interval(eco: bool) -> Duration {
+    Duration::from_secs(if eco {
+        ECO_TICK_SECONDS
+    } else {
+        NORMAL_TICK_SECONDS
+    })
+}
+
+/// Number of ticks between session commits every `commit_seconds`
+pub fn commit_interval_ticks(commit_seconds: u64, eco: bool) -> u32 {
+    let commit = if eco {
+        commit_seconds.max(ECO_COMMIT_SECONDS)
+    } else {
+        commit_seconds
+    };
+    ticks_for(commit, tick_interval(eco))
+}
+
+/// Number of ticks between limit checks every `check_seconds`
+pub fn limit_check_ticks(check_seconds: u64, eco: bool) -> u32 {
+    ticks_for(check_seconds, tick_interval(eco))
+}
+
+fn ticks_for(seconds: u64, tick: Duration) -> u32 {
+    (seconds / tick.as_secs().max(1)).max(1) as u32
+}
+
+/// Whether the system is currently running on battery.
+/// Returns false when the power source can't be determined.
+///
+/// Reads the power source directly rather than spawning a helper process, so
+/// it is cheap enough to call from the tracker loop.
+pub fn is_on_battery() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        linux_on_battery(std::path::Path::new("/sys/class/power_supply"))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        macos::on_battery()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows::on_battery()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        false
+    }
+}
+
+/// Decide from sysfs power supplies: an online mains adapter means we're not
+/// on battery; otherwise a discharging battery means we are.
+#[cfg(target_os = "linux")]
+fn linux_on_battery(root: &std::path::Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return false;
+    };
+
+    let read = |path: std::path::PathBuf| {
+        std::fs::read_to_string(path)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut discharging = false;
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        match read(dir.join("type")).as_str() {
+            "Mains" | "USB" => {
+                if read(dir.join("online")) == "1" {
+                    return false;
+                }
+            }
+            "Battery" => {
+                if read(dir.join("status")) == "Discharging" {
+                    discharging = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    discharging
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ffi::{c_char, c_void, CStr};
+
+    type CFTypeRef = *const c_void;
+
+    const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPSCopyPowerSourcesInfo() -> CFTypeRef;
+        fn IOPSGetProvidingPowerSourceType(snapshot: CFTypeRef) -> CFTypeRef;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringGetCString(
+            string: CFTypeRef,
+            buffer: *mut c_char,
+            buffer_size: isize,
+            encoding: u32,
+        ) -> u8;
+        fn CFRelease(cf: CFTypeRef);
+    }
+
+    /// Whether IOKit reports "Battery Power" as the providing power source
+    pub fn on_battery() -> bool {
+        // SAFETY: the snapshot is released once read, and the source type
+        // string is owned by the snapshot (Get rule), so it isn't released.
+        unsafe {
+            let snapshot = IOPSCopyPowerSourcesInfo();
+            if snapshot.is_null() {
+                return false;
+            }
+
+            let source = IOPSGetProvidingPowerSourceType(snapshot);
+            let mut buffer = [0 as c_char; 64];
+            let on_battery = !source.is_null()
+                && CFStringGetCString(
+                    source,
+                    buffer.as_mut_ptr(),
+                    buffer.len() as isize,
+                    CF_STRING_ENCODING_UTF8,
+                ) != 0
+                && CStr::from_ptr(buffer.as_ptr()).to_bytes() == b"Battery Power";
+
+            CFRelease(snapshot);
+            on_battery
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    /// SYSTEM_POWER_STATUS from winbase.h
+    #[repr(C)]
+    #[derive(Default)]
+    struct SystemPowerStatus {
+        ac_line_status: u8,
+        battery_flag: u8,
+        battery_life_percent: u8,
+        system_status_flag: u8,
+        battery_life_time: u32,
+        battery_full_life_time: u32,
+    }
+
+    /// ACLineStatus value for "offline", i.e. running on battery
+    const AC_LINE_OFFLINE: u8 = 0;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+    }
+
+    /// Whether the AC adapter is reported offline
+    pub fn on_battery() -> bool {
+        let mut status = SystemPowerStatus::default();
+        // SAFETY: `status` is a valid, writable SYSTEM_POWER_STATUS
+        let ok = unsafe { GetSystemPowerStatus(&mut status) } != 0;
+        ok && status.ac_line_status == AC_LINE_OFFLINE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_follows_power_source() {
+        assert!(eco_active(EcoMode::Auto, true));
+        assert!(!eco_active(EcoMode::Auto, false));
+        assert!(eco_active(EcoMode::On, false));
+        assert!(!eco_active(EcoMode::Off, true));
+    }
+
+    #[test]
+    fn test_commit_interval_scales_with_mode() {
+        let commit_seconds =
+            |commit, eco| tick_interval(eco).as_secs() * commit_interval_ticks(commit, eco) as u64;
+
+        assert_eq!(
+            commit_seconds(NORMAL_COMMIT_SECONDS, false),
+            NORMAL_COMMIT_SECONDS
+        );
+        assert_eq!(
+            commit_seconds(NORMAL_COMMIT_SECONDS, true),
+            ECO_COMMIT_SECONDS
+        );
+        assert!(commit_interval_ticks(NORMAL_COMMIT_SECONDS, true) < ECO_COMMIT_SECONDS as u32);
+        // A configured interval applies as is, except below eco mode's floor
+        assert_eq!(commit_interval_ticks(1, false), 1);
+        assert_eq!(commit_seconds(120, false), 120);
+        assert_eq!(commit_seconds(120, true), 120);
+    }
+
+    #[test]
+    fn test_limit_checks_keep_wall_clock_cadence() {
+        assert_eq!(limit_check_ticks(LIMIT_CHECK_SECONDS, false), 10);
+        assert_eq!(limit_check_ticks(LIMIT_CHECK_SECONDS, true), 2);
+        assert_eq!(limit_check_ticks(30, true), 6);
+        // Never less than one tick
+        assert_eq!(limit_check_ticks(2, true), 1);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn supply(root: &std::path::Path, name: &str, files: &[(&str, &str)]) {
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (file, contents) in files {
+            std::fs::write(dir.join(file), format!("{contents}\n")).unwrap();
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_linux_battery_detection() {
+        let root = crate::test_support::TempPath::new("power");
+
+        supply(
+            &root,
+            "BAT0",
+            &[("type", "Battery"), ("status", "Discharging")],
+        );
+        supply(&root, "AC", &[("type", "Mains"), ("online", "0")]);
+        assert!(linux_on_battery(&root));
+        assert!(eco_active(EcoMode::Auto, linux_on_battery(&root)));
+
+        supply(&root, "AC", &[("type", "Mains"), ("online", "1")]);
+        assert!(!linux_on_battery(&root));
+
+        assert!(!linux_on_battery(&root.join("missing")));
+    }
+}
@@ -0,0 +1,74 @@
+//! JSON settings files in the app's config directory
+//!
+//! Settings that live outside the database are stored one file per section
+//! under `<config dir>/wellbeing`. Files are upgraded with `settings_version`
+//! on load; a missing or unreadable file falls back to the defaults.
+
+use crate::settings_version::{self, VersionedSettings};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Path of `filename` in the app's config directory, creating the directory
+/// if needed. None when the platform has no config directory.
+pub fn settings_path(filename: &str) -> Option<PathBuf> {
+    let dir = dirs::config_dir()?.join("wellbeing");
+    if !dir.exists() {
+        let _ = std::fs::create_dir_all(&dir);
+    }
+    Some(dir.join(filename))
+}
+
+/// Settings saved at `path`, or the defaults if there are none. With no path
+/// the defaults are returned.
+pub fn load_or_default<T: VersionedSettings + Default>(path: Option<&Path>) -> T {
+    let Some(path) = path.filter(|path| path.exists()) else {
+        return T::default();
+    };
+
+    match std::fs::read_to_string(path) {
+        Ok(json) => settings_version::load_settings(&json).unwrap_or_else(|e| {
+            tracing::warn!(settings = T::NAME, error = %e, "Ignoring unreadable settings");
+            T::default()
+        }),
+        Err(e) => {
+            tracing::warn!(settings = T::NAME, error = %e, "Failed to read settings");
+            T::default()
+        }
+    }
+}
+
+/// Write `settings` to `path` as pretty-printed JSON
+pub fn save<T: Serialize>(path: &Path, settings: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::break_reminder::BreakSettings;
+    use crate::notification_settings::NotificationSettings;
+    use crate::test_support::TempPath;
+
+    #[test]
+    fn test_missing_file_gives_defaults() {
+        let path = TempPath::new("settings-store-missing.json");
+        let settings: BreakSettings = load_or_default(Some(&*path));
+        assert_eq!(settings.work_minutes, BreakSettings::default().work_minutes);
+
+        let settings: BreakSettings = load_or_default(None);
+        assert_eq!(settings.work_minutes, BreakSettings::default().work_minutes);
+    }
+
+    #[test]
+    fn test_unreadable_file_gives_defaults() {
+        let path = TempPath::new("settings-store-garbage.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        let settings: NotificationSettings = load_or_default(Some(&*path));
+        assert_eq!(
+            settings.warning_threshold,
+            NotificationSettings::default().warning_threshold
+        );
+    }
+}
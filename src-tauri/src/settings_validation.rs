@@ -0,0 +1,509 @@
+//! Settings validation and normalization
+//!
+//! Persisted or hand-edited settings can end up in states the rest of the app
+//! doesn't expect (e.g. a warning threshold above the exceeded threshold, or a
+//! zero-length work interval). The validators here check each invariant,
+//! repair the value in place and describe what they changed so the caller can
+//! log it and surface it to the UI.
+
+use crate::break_reminder::{BreakReminder, BreakSettings};
+use crate::database::Database;
+use crate::focus_mode::{FocusManager, FocusSettings};
+use crate::notification_settings::{NotificationManager, NotificationSettings};
+use chrono::NaiveTime;
+use serde::Serialize;
+
+/// Upper bound for any minute-based interval (24 hours)
+const MAX_INTERVAL_MINUTES: u32 = 24 * 60;
+
+/// Longest allowed gap between limit checks
+const MAX_LIMIT_CHECK_SECONDS: u32 = 300;
+
+/// A single repair applied to a settings value
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SettingsRepair {
+    /// Settings section the repair applies to (e.g. "notifications")
+    pub section: String,
+    /// Field that was repaired
+    pub field: String,
+    /// Human-readable description of what was changed
+    pub message: String,
+}
+
+impl SettingsRepair {
+    fn new(section: &str, field: &str, message: String) -> Self {
+        Self {
+            section: section.to_string(),
+            field: field.to_string(),
+            message,
+        }
+    }
+}
+
+/// Result of validating all settings
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SettingsReport {
+    pub repairs: Vec<SettingsRepair>,
+}
+
+impl SettingsReport {
+    pub fn extend(&mut self, repairs: Vec<SettingsRepair>) {
+        self.repairs.extend(repairs);
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.repairs.is_empty()
+    }
+}
+
+/// Log each repair at warn level so fixes are visible in the logs
+pub fn log_repairs(repairs: &[SettingsRepair]) {
+    for repair in repairs {
+        tracing::warn!(
+            section = %repair.section,
+            field = %repair.field,
+            "Settings repaired: {}",
+            repair.message
+        );
+    }
+}
+
+/// Validate every settings module and the stored app limits, repairing
+/// anything out of range. Run at startup and on demand from the UI.
+pub async fn validate_settings(
+    focus_manager: &FocusManager,
+    break_reminder: &BreakReminder,
+    notification_manager: &NotificationManager,
+    db: &Database,
+) -> SettingsReport {
+    let mut report = SettingsReport::default();
+    report.extend(focus_manager.validate_settings().await);
+    report.extend(break_reminder.validate_settings().await);
+    report.extend(notification_manager.validate_settings().await);
+
+    match db.repair_invalid_limits() {
+        Ok(apps) => {
+            let repairs: Vec<SettingsRepair> = apps
+                .into_iter()
+                .map(|app| {
+                    SettingsRepair::new(
+                        "limits",
+                        "daily_limit_minutes",
+                        format!("removed negative daily limit for {}", app),
+                    )
+                })
+                .collect();
+            log_repairs(&repairs);
+            report.extend(repairs);
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to validate app limits");
+        }
+    }
+
+    if report.is_clean() {
+        tracing::debug!("Settings validation found no problems");
+    } else {
+        tracing::info!(
+            repairs = report.repairs.len(),
+            "Settings validation repaired invalid values"
+        );
+    }
+
+    report
+}
+
+/// Validate and repair notification settings in place
+pub fn validate_notification_settings(settings: &mut NotificationSettings) -> Vec<SettingsRepair> {
+    let mut repairs = Vec::new();
+    let defaults = NotificationSettings::default();
+
+    if settings.exceeded_threshold == 0 {
+        repairs.push(SettingsRepair::new(
+            "notifications",
+            "exceeded_threshold",
+            format!(
+                "exceeded threshold was 0%, reset to {}%",
+                defaults.exceeded_threshold
+            ),
+        ));
+        settings.exceeded_threshold = defaults.exceeded_threshold;
+    }
+
+    if settings.warning_threshold == 0 || settings.warning_threshold >= settings.exceeded_threshold
+    {
+        let repaired = (settings.exceeded_threshold * defaults.warning_threshold
+            / defaults.exceeded_threshold)
+            .clamp(1, settings.exceeded_threshold.saturating_sub(1).max(1));
+        repairs.push(SettingsRepair::new(
+            "notifications",
+            "warning_threshold",
+            format!(
+                "warning threshold {}% must be between 1% and the exceeded threshold {}%, set to {}%",
+                settings.warning_threshold, settings.exceeded_threshold, repaired
+            ),
+        ));
+        settings.warning_threshold = repaired;
+    }
+
+    if settings.warning_minutes == 0 || settings.warning_minutes > MAX_INTERVAL_MINUTES {
+        repairs.push(SettingsRepair::new(
+            "notifications",
+            "warning_minutes",
+            format!(
+                "warning lead time {} minutes is out of range, reset to {}",
+                settings.warning_minutes, defaults.warning_minutes
+            ),
+        ));
+        settings.warning_minutes = defaults.warning_minutes;
+    }
+
+    if settings.limit_check_seconds == 0 || settings.limit_check_seconds > MAX_LIMIT_CHECK_SECONDS {
+        repairs.push(SettingsRepair::new(
+            "notifications",
+            "limit_check_seconds",
+            format!(
+                "limit check interval {} seconds is out of range, reset to {}",
+                settings.limit_check_seconds, defaults.limit_check_seconds
+            ),
+        ));
+        settings.limit_check_seconds = defaults.limit_check_seconds;
+    }
+
+    if settings.dnd_start_hour > 23 {
+        repairs.push(SettingsRepair::new(
+            "notifications",
+            "dnd_start_hour",
+            format!(
+                "DND start hour {} is out of range, clamped to 23",
+                settings.dnd_start_hour
+            ),
+        ));
+        settings.dnd_start_hour = 23;
+    }
+
+    if settings.dnd_end_hour > 23 {
+        repairs.push(SettingsRepair::new(
+            "notifications",
+            "dnd_end_hour",
+            format!(
+                "DND end hour {} is out of range, clamped to 23",
+                settings.dnd_end_hour
+            ),
+        ));
+        settings.dnd_end_hour = 23;
+    }
+
+    if settings.dnd_start_minute > 59 {
+        repairs.push(SettingsRepair::new(
+            "notifications",
+            "dnd_start_minute",
+            format!(
+                "DND start minute {} is out of range, clamped to 59",
+                settings.dnd_start_minute
+            ),
+        ));
+        settings.dnd_start_minute = 59;
+    }
+
+    if settings.dnd_end_minute > 59 {
+        repairs.push(SettingsRepair::new(
+            "notifications",
+            "dnd_end_minute",
+            format!(
+                "DND end minute {} is out of range, clamped to 59",
+                settings.dnd_end_minute
+            ),
+        ));
+        settings.dnd_end_minute = 59;
+    }
+
+    if settings.dnd_enabled
+        && settings.dnd_start_hour == settings.dnd_end_hour
+        && settings.dnd_start_minute == settings.dnd_end_minute
+    {
+        repairs.push(SettingsRepair::new(
+            "notifications",
+            "dnd_enabled",
+            "DND start and end are equal (empty window), DND disabled".to_string(),
+        ));
+        settings.dnd_enabled = false;
+    }
+
+    repairs
+}
+
+/// Validate and repair break reminder settings in place
+pub fn validate_break_settings(settings: &mut BreakSettings) -> Vec<SettingsRepair> {
+    let mut repairs = Vec::new();
+    let defaults = BreakSettings::default();
+
+    if settings.work_minutes == 0 || settings.work_minutes > MAX_INTERVAL_MINUTES {
+        repairs.push(SettingsRepair::new(
+            "break",
+            "work_minutes",
+            format!(
+                "work interval of {} minutes is out of range, reset to {}",
+                settings.work_minutes, defaults.work_minutes
+            ),
+        ));
+        settings.work_minutes = defaults.work_minutes;
+    }
+
+    if settings.break_minutes == 0 || settings.break_minutes > MAX_INTERVAL_MINUTES {
+        repairs.push(SettingsRepair::new(
+            "break",
+            "break_minutes",
+            format!(
+                "break length of {} minutes is out of range, reset to {}",
+                settings.break_minutes, defaults.break_minutes
+            ),
+        ));
+        settings.break_minutes = defaults.break_minutes;
+    }
+
+    if settings.micro_break_interval_minutes == 0
+        || settings.micro_break_interval_minutes > MAX_INTERVAL_MINUTES
+    {
+        repairs.push(SettingsRepair::new(
+            "break",
+            "micro_break_interval_minutes",
+            format!(
+                "micro-break interval of {} minutes is out of range, reset to {}",
+                settings.micro_break_interval_minutes, defaults.micro_break_interval_minutes
+            ),
+        ));
+        settings.micro_break_interval_minutes = defaults.micro_break_interval_minutes;
+    }
+
+    repairs
+}
+
+/// Validate and repair focus mode settings in place
+pub fn validate_focus_settings(settings: &mut FocusSettings) -> Vec<SettingsRepair> {
+    let mut repairs = Vec::new();
+    let defaults = FocusSettings::default();
+
+    if settings.default_duration_minutes == 0
+        || settings.default_duration_minutes > MAX_INTERVAL_MINUTES
+    {
+        repairs.push(SettingsRepair::new(
+            "focus",
+            "default_duration_minutes",
+            format!(
+                "default duration of {} minutes is out of range, reset to {}",
+                settings.default_duration_minutes, defaults.default_duration_minutes
+            ),
+        ));
+        settings.default_duration_minutes = defaults.default_duration_minutes;
+    }
+
+    if settings.reward.bonus_minutes > MAX_INTERVAL_MINUTES {
+        repairs.push(SettingsRepair::new(
+            "focus",
+            "reward.bonus_minutes",
+            format!(
+                "reward of {} minutes is out of range, reset to {}",
+                settings.reward.bonus_minutes, defaults.reward.bonus_minutes
+            ),
+        ));
+        settings.reward.bonus_minutes = defaults.reward.bonus_minutes;
+    }
+
+    let original_len = settings.blocked_apps.len();
+    let mut seen = std::collections::HashSet::new();
+    settings.blocked_apps.retain(|app| {
+        let key = app.trim().to_lowercase();
+        !key.is_empty() && seen.insert(key)
+    });
+    if settings.blocked_apps.len() != original_len {
+        repairs.push(SettingsRepair::new(
+            "focus",
+            "blocked_apps",
+            format!(
+                "removed {} empty or duplicate blocked app(s)",
+                original_len - settings.blocked_apps.len()
+            ),
+        ));
+    }
+
+    for schedule in settings.schedules.iter_mut() {
+        let original_days = schedule.days.len();
+        schedule.days.retain(|d| *d <= 6);
+        if schedule.days.len() != original_days {
+            repairs.push(SettingsRepair::new(
+                "focus",
+                "schedules.days",
+                format!("schedule '{}' had invalid weekdays, removed", schedule.name),
+            ));
+        }
+
+        if !schedule.enabled {
+            continue;
+        }
+
+        let start = NaiveTime::parse_from_str(&schedule.start_time, "%H:%M").ok();
+        let end = NaiveTime::parse_from_str(&schedule.end_time, "%H:%M").ok();
+        let invalid_reason = match (start, end) {
+            (Some(start), Some(end)) if start == end => Some("start and end times are equal"),
+            (Some(_), Some(_)) => None,
+            _ => Some("start or end time is not in HH:MM format"),
+        };
+
+        if let Some(reason) = invalid_reason {
+            repairs.push(SettingsRepair::new(
+                "focus",
+                "schedules",
+                format!("schedule '{}' disabled: {}", schedule.name, reason),
+            ));
+            schedule.enabled = false;
+        }
+    }
+
+    repairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::focus_mode::FocusSchedule;
+
+    #[test]
+    fn test_default_settings_are_valid() {
+        assert!(validate_notification_settings(&mut NotificationSettings::default()).is_empty());
+        assert!(validate_break_settings(&mut BreakSettings::default()).is_empty());
+        assert!(validate_focus_settings(&mut FocusSettings::default()).is_empty());
+    }
+
+    #[test]
+    fn test_warning_above_exceeded_is_repaired() {
+        let mut settings = NotificationSettings {
+            warning_threshold: 120,
+            exceeded_threshold: 100,
+            ..Default::default()
+        };
+        let repairs = validate_notification_settings(&mut settings);
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(repairs[0].field, "warning_threshold");
+        assert_eq!(settings.warning_threshold, 80);
+        assert!(settings.warning_threshold < settings.exceeded_threshold);
+    }
+
+    #[test]
+    fn test_zero_exceeded_threshold_is_repaired() {
+        let mut settings = NotificationSettings {
+            exceeded_threshold: 0,
+            ..Default::default()
+        };
+        validate_notification_settings(&mut settings);
+        assert_eq!(settings.exceeded_threshold, 100);
+        assert_eq!(settings.warning_threshold, 80);
+    }
+
+    #[test]
+    fn test_dnd_start_equals_end_disables_dnd() {
+        let mut settings = NotificationSettings {
+            dnd_enabled: true,
+            dnd_start_hour: 22,
+            dnd_end_hour: 22,
+            ..Default::default()
+        };
+        let repairs = validate_notification_settings(&mut settings);
+        assert_eq!(repairs.len(), 1);
+        assert!(!settings.dnd_enabled);
+    }
+
+    #[test]
+    fn test_dnd_hours_out_of_range_are_clamped() {
+        let mut settings = NotificationSettings {
+            dnd_start_hour: 25,
+            dnd_end_hour: 30,
+            ..Default::default()
+        };
+        validate_notification_settings(&mut settings);
+        assert_eq!(settings.dnd_start_hour, 23);
+        assert_eq!(settings.dnd_end_hour, 23);
+    }
+
+    #[test]
+    fn test_zero_break_intervals_are_repaired() {
+        let mut settings = BreakSettings {
+            work_minutes: 0,
+            break_minutes: 0,
+            ..Default::default()
+        };
+        let repairs = validate_break_settings(&mut settings);
+        assert_eq!(repairs.len(), 2);
+        assert_eq!(settings.work_minutes, 25);
+        assert_eq!(settings.break_minutes, 5);
+    }
+
+    #[test]
+    fn test_excessive_focus_duration_is_repaired() {
+        let mut settings = FocusSettings {
+            default_duration_minutes: 100_000,
+            ..Default::default()
+        };
+        validate_focus_settings(&mut settings);
+        assert_eq!(settings.default_duration_minutes, 25);
+    }
+
+    #[test]
+    fn test_duplicate_blocked_apps_are_removed() {
+        let mut settings = FocusSettings {
+            blocked_apps: vec![
+                "Discord".to_string(),
+                "discord".to_string(),
+                "  ".to_string(),
+                "Firefox".to_string(),
+            ],
+            ..Default::default()
+        };
+        let repairs = validate_focus_settings(&mut settings);
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(
+            settings.blocked_apps,
+            vec!["Discord".to_string(), "Firefox".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_negative_limits_are_removed() {
+        let db = Database::new(std::path::PathBuf::from(":memory:")).unwrap();
+        db.set_limit_with_block("Firefox", -30, true).unwrap();
+        db.set_limit_with_block("Discord", 60, false).unwrap();
+
+        let repaired = db.repair_invalid_limits().unwrap();
+        assert_eq!(repaired, vec!["Firefox".to_string()]);
+
+        let limits = db.get_all_limits().unwrap();
+        assert_eq!(limits.len(), 1);
+        assert_eq!(limits[0].app_name, "Discord");
+    }
+
+    #[test]
+    fn test_invalid_schedules_are_disabled() {
+        let schedule = |name: &str, start: &str, end: &str, days: Vec<u8>| FocusSchedule {
+            id: name.to_string(),
+            name: name.to_string(),
+            days,
+            start_time: start.to_string(),
+            end_time: end.to_string(),
+            blocked_apps: vec![],
+            enabled: true,
+        };
+        let mut settings = FocusSettings {
+            schedules: vec![
+                schedule("equal", "09:00", "09:00", vec![1]),
+                schedule("garbage", "9am", "17:00", vec![1]),
+                schedule("bad days", "09:00", "17:00", vec![1, 7, 9]),
+            ],
+            ..Default::default()
+        };
+        validate_focus_settings(&mut settings);
+        assert!(!settings.schedules[0].enabled);
+        assert!(!settings.schedules[1].enabled);
+        assert!(settings.schedules[2].enabled);
+        assert_eq!(settings.schedules[2].days, vec![1]);
+    }
+}
@@ -0,0 +1,227 @@
+//! Versioning for persisted JSON settings
+//!
+//! Mirrors the database migration system for settings files: each settings
+//! struct carries a `version`, and loading a file written by an older version
+//! runs the upgrade steps in order before deserializing. Fields added since
+//! then are filled with their defaults.
+
+use crate::break_reminder::BreakSettings;
+use crate::focus_mode::FocusSettings;
+use crate::goals::GoalsState;
+use crate::notification_settings::NotificationSettings;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Version assumed for settings files written before versioning existed
+pub const UNVERSIONED: u32 = 1;
+
+/// A settings struct that is persisted as JSON and can be upgraded on load
+pub trait VersionedSettings: DeserializeOwned {
+    /// Settings section name used in logs
+    const NAME: &'static str;
+    /// Version written by this build
+    const CURRENT_VERSION: u32;
+
+    /// Upgrade raw JSON from `from_version` to `from_version + 1`
+    fn upgrade(from_version: u32, value: &mut Value);
+}
+
+/// Parse settings JSON, upgrading it from older versions first
+pub fn load_settings<T: VersionedSettings>(json: &str) -> Result<T, serde_json::Error> {
+    let mut value: Value = serde_json::from_str(json)?;
+    upgrade_settings::<T>(&mut value);
+    serde_json::from_value(value)
+}
+
+/// Run the upgrade steps needed to bring `value` to the current version.
+/// Returns the version the value had before upgrading.
+pub fn upgrade_settings<T: VersionedSettings>(value: &mut Value) -> u32 {
+    let original = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(UNVERSIONED);
+
+    if original > T::CURRENT_VERSION {
+        tracing::warn!(
+            settings = T::NAME,
+            version = original,
+            current = T::CURRENT_VERSION,
+            "Settings were written by a newer version; loading as-is"
+        );
+        return original;
+    }
+
+    let mut version = original;
+    while version < T::CURRENT_VERSION {
+        T::upgrade(version, value);
+        version += 1;
+    }
+
+    if let Some(object) = value.as_object_mut() {
+        object.insert("version".to_string(), Value::from(T::CURRENT_VERSION));
+    }
+
+    if original < T::CURRENT_VERSION {
+        tracing::info!(
+            settings = T::NAME,
+            from = original,
+            to = T::CURRENT_VERSION,
+            "Upgraded settings"
+        );
+    }
+    original
+}
+
+/// Insert `key` with `default` if it is missing
+fn fill_default(value: &mut Value, key: &str, default: Value) {
+    if let Some(object) = value.as_object_mut() {
+        object.entry(key.to_string()).or_insert(default);
+    }
+}
+
+impl VersionedSettings for NotificationSettings {
+    const NAME: &'static str = "notifications";
+    const CURRENT_VERSION: u32 = 4;
+
+    fn upgrade(from_version: u32, value: &mut Value) {
+        // v2: configurable warning mode and lead time
+        if from_version == 1 {
+            fill_default(value, "warning_mode", Value::from("percentage"));
+            fill_default(value, "warning_minutes", Value::from(10));
+        }
+        // v3: minute granularity for Do Not Disturb
+        if from_version == 2 {
+            fill_default(value, "dnd_start_minute", Value::from(0));
+            fill_default(value, "dnd_end_minute", Value::from(0));
+        }
+        // v4: configurable limit check interval
+        if from_version == 3 {
+            fill_default(value, "limit_check_seconds", Value::from(10));
+        }
+    }
+}
+
+impl VersionedSettings for BreakSettings {
+    const NAME: &'static str = "break";
+    const CURRENT_VERSION: u32 = 2;
+
+    fn upgrade(from_version: u32, value: &mut Value) {
+        // v2: micro-break reminders
+        if from_version == 1 {
+            fill_default(value, "micro_break_enabled", Value::from(false));
+            fill_default(value, "micro_break_interval_minutes", Value::from(20));
+        }
+    }
+}
+
+impl VersionedSettings for FocusSettings {
+    const NAME: &'static str = "focus";
+    const CURRENT_VERSION: u32 = 3;
+
+    fn upgrade(from_version: u32, value: &mut Value) {
+        // v2: focus completion reward
+        if from_version == 1 {
+            fill_default(
+                value,
+                "reward",
+                serde_json::to_value(crate::focus_mode::FocusReward::default()).unwrap_or_default(),
+            );
+        }
+        // v3: allow-list mode
+        if from_version == 2 {
+            fill_default(value, "mode", Value::from("block_list"));
+            fill_default(value, "allowed_apps", Value::Array(vec![]));
+        }
+    }
+}
+
+impl VersionedSettings for GoalsState {
+    const NAME: &'static str = "goals";
+    const CURRENT_VERSION: u32 = 3;
+
+    fn upgrade(from_version: u32, value: &mut Value) {
+        // v2: challenge tracking and the weekends toggle
+        if from_version == 1 {
+            fill_default(
+                value,
+                "challenge_results",
+                Value::Object(Default::default()),
+            );
+            fill_default(value, "completed_challenges", Value::Array(vec![]));
+            fill_default(value, "weekends_count", Value::from(true));
+        }
+        // v3: week start and weekly focus goal
+        if from_version == 2 {
+            fill_default(value, "week_start", Value::from("monday"));
+            fill_default(value, "weekly_focus_goal", Value::Null);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notification_settings::WarningMode;
+
+    #[test]
+    fn test_unversioned_notification_settings_upgrade() {
+        let v1 = r#"{
+            "enabled": true,
+            "warning_threshold": 75,
+            "exceeded_threshold": 100,
+            "dnd_enabled": false,
+            "dnd_start_hour": 22,
+            "dnd_end_hour": 8
+        }"#;
+
+        let settings: NotificationSettings = load_settings(v1).unwrap();
+
+        assert_eq!(settings.version, NotificationSettings::CURRENT_VERSION);
+        assert_eq!(settings.warning_threshold, 75);
+        assert_eq!(settings.warning_mode, WarningMode::Percentage);
+        assert_eq!(settings.warning_minutes, 10);
+        assert_eq!(settings.limit_check_seconds, 10);
+    }
+
+    #[test]
+    fn test_v1_goals_state_upgrade() {
+        let v1 = r#"{
+            "version": 1,
+            "goals": [],
+            "achievements": {},
+            "current_streak": 4,
+            "longest_streak": 9,
+            "total_goals_met": 20,
+            "focus_sessions_completed": 3
+        }"#;
+
+        let state: GoalsState = load_settings(v1).unwrap();
+
+        assert_eq!(state.version, GoalsState::CURRENT_VERSION);
+        assert_eq!(state.current_streak, 4);
+        assert!(state.weekends_count);
+        assert!(state.completed_challenges.is_empty());
+        assert_eq!(state.week_start, crate::goals::WeekStart::Monday);
+        assert!(state.weekly_focus_goal.is_none());
+    }
+
+    #[test]
+    fn test_current_version_is_untouched() {
+        let mut value = serde_json::to_value(BreakSettings::default()).unwrap();
+        let before = value.clone();
+
+        assert_eq!(
+            upgrade_settings::<BreakSettings>(&mut value),
+            BreakSettings::CURRENT_VERSION
+        );
+        assert_eq!(value, before);
+    }
+
+    #[test]
+    fn test_newer_version_is_loaded_as_is() {
+        let mut value = serde_json::json!({ "version": 99 });
+        assert_eq!(upgrade_settings::<FocusSettings>(&mut value), 99);
+        assert_eq!(value["version"], 99);
+    }
+}
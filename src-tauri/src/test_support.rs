@@ -0,0 +1,53 @@
+//! Shared fixtures for unit tests
+
+use crate::database::Database;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A fresh, fully migrated in-memory database
+pub(crate) fn test_db() -> Database {
+    Database::new(PathBuf::from(":memory:")).expect("in-memory database")
+}
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A fresh path in the system temp directory. Whatever ends up there - a file,
+/// a directory, or a database with its `-wal`/`-shm` files - is removed when
+/// this is dropped, so a failing test cleans up too.
+pub(crate) struct TempPath(PathBuf);
+
+impl TempPath {
+    /// `name` ends the file name, so it can carry an extension (`"goals.db"`)
+    pub(crate) fn new(name: &str) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        Self(std::env::temp_dir().join(format!(
+            "wellbeing-test-{}-{}-{}",
+            std::process::id(),
+            id,
+            name
+        )))
+    }
+}
+
+impl std::ops::Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", self.0.display(), suffix));
+        }
+    }
+}
@@ -1,31 +1,50 @@
+use crate::blocking::{
+    self, BlockFailureAction, BlockGrace, BlockMethod, BlockResult, BlockSettings, GraceAction,
+};
 use crate::database::Database;
+use crate::focus_mode::FocusManager;
+use crate::idle::{self, IdleProvider, IdleReason, IdleSignals};
 use crate::limit_popup::EmergencyAccessManager;
-use crate::notification_settings::NotificationManager;
-use crate::window_tracker::{extract_app_name, get_active_window_name};
-use std::collections::HashMap;
-use std::process::Command;
+use crate::notification_settings::{NotificationManager, NotificationSettings};
+use crate::power::{self, EcoMode};
+use crate::window_tracker::{
+    extract_app_name, get_active_window_info, get_active_window_name, title_aware_segment,
+    ActiveWindow, TitleAwareApp,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::Instant;
 use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 use tokio::time::interval;
 
-/// Notification thresholds
-const WARNING_THRESHOLD: f64 = 0.8; // 80% - send warning
-const EXCEEDED_THRESHOLD: f64 = 1.0; // 100% - limit exceeded
-const IDLE_THRESHOLD_SECONDS: u64 = 300; // 5 minutes
-
-/// How often (in seconds) to flush session duration to DB
-const SESSION_FLUSH_INTERVAL: u32 = 5;
-
 /// Maximum number of failed writes to buffer before dropping oldest
 const MAX_RETRY_BUFFER_SIZE: usize = 100;
 
+/// Windows of this app itself, which are never tracked or blocked
+const SELF_APP_NAMES: [&str; 2] = ["Digital Wellbeing", "limit-popup"];
+
 /// Notification types to track what we've already sent
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum NotificationType {
-    Warning,  // 80% threshold
-    Exceeded, // 100% threshold
+    Warning,  // warning_threshold (or warning_minutes) reached
+    Exceeded, // exceeded_threshold reached
+}
+
+/// Which limit notification, if any, is due for the given usage
+fn limit_notification(
+    settings: &NotificationSettings,
+    used_seconds: i64,
+    limit_seconds: i64,
+) -> Option<NotificationType> {
+    if settings.is_exceeded(used_seconds, limit_seconds) {
+        Some(NotificationType::Exceeded)
+    } else if settings.should_warn(used_seconds, limit_seconds) {
+        Some(NotificationType::Warning)
+    } else {
+        None
+    }
 }
 
 /// A pending DB operation that failed and needs retry
@@ -46,25 +65,62 @@ pub struct UsageTracker {
     current_app: Arc<Mutex<Option<String>>>,
     current_session_id: Arc<Mutex<Option<i64>>>,
     session_start: Arc<Mutex<Option<i64>>>,
+    /// Window title recorded with the open session
+    current_title: Arc<Mutex<Option<String>>>,
     /// Track which notifications have been sent for each app today
     /// Key: (app_name, notification_type), Value: true if sent
     sent_notifications: Arc<Mutex<HashMap<(String, NotificationType), bool>>>,
     /// The date we last reset notifications (to reset daily)
     last_reset_date: Arc<Mutex<String>>,
+    /// Apps that used up their limit today, announced as reset on the next day
+    limits_reached: Arc<Mutex<HashSet<String>>>,
     /// Emergency access manager for temporary access grants
     emergency_access: Arc<EmergencyAccessManager>,
     /// Notification manager for DND/mute-aware notifications
     notification_manager: Option<Arc<NotificationManager>>,
+    /// Focus mode, whose active session blocks apps on top of the limits
+    focus_manager: Option<Arc<FocusManager>>,
+    /// How blocking is enforced and escalated when it fails
+    block_settings: Arc<RwLock<BlockSettings>>,
     /// Tauri app handle for creating windows
     app_handle: Option<AppHandle>,
     /// Track if popup is currently shown for an app (to avoid multiple popups)
     popup_shown_for: Arc<Mutex<Option<String>>>,
+    /// Grace timers for over-limit apps before the popup is shown
+    block_grace: Arc<Mutex<BlockGrace>>,
     /// Counter for session flush interval (avoids unreliable modulo on timestamps)
     flush_counter: Arc<Mutex<u32>>,
+    /// Ticks between session flushes; grows in eco mode
+    flush_interval: AtomicU32,
+    /// Seconds between session flushes outside eco mode, shared with AppState
+    commit_seconds: Arc<RwLock<u64>>,
+    /// Eco mode preference, shared with AppState
+    eco_mode: Arc<RwLock<EcoMode>>,
+    /// Apps tracked separately per window title, shared with AppState
+    title_aware_apps: Arc<RwLock<Vec<TitleAwareApp>>>,
     /// Buffer of failed DB writes to retry
     retry_buffer: Arc<Mutex<Vec<PendingWrite>>>,
     /// Track the last successfully written end_time to detect data gaps
     last_written_end_time: Arc<Mutex<Option<i64>>>,
+    /// Open idle period: (idle_periods row id, reason)
+    idle_period: Arc<Mutex<Option<(i64, IdleReason)>>>,
+    /// Wall-clock time of the previous tick, used to detect suspend
+    last_tick_at: Arc<Mutex<Option<i64>>>,
+    /// Source of input idle time; the system by default
+    idle_provider: Arc<dyn IdleProvider>,
+    /// No input for this long stops tracking until input resumes; shared with AppState
+    idle_threshold_seconds: Arc<RwLock<u64>>,
+    /// Set while the user has paused tracking
+    paused: AtomicBool,
+}
+
+/// Reads idle time from the OS
+struct SystemIdleProvider;
+
+impl IdleProvider for SystemIdleProvider {
+    fn idle_seconds(&self) -> u64 {
+        get_idle_seconds()
+    }
 }
 
 impl UsageTracker {
@@ -75,15 +131,32 @@ impl UsageTracker {
             current_app: Arc::new(Mutex::new(None)),
             current_session_id: Arc::new(Mutex::new(None)),
             session_start: Arc::new(Mutex::new(None)),
+            current_title: Arc::new(Mutex::new(None)),
             sent_notifications: Arc::new(Mutex::new(HashMap::new())),
             last_reset_date: Arc::new(Mutex::new(today)),
+            limits_reached: Arc::new(Mutex::new(HashSet::new())),
             emergency_access,
             notification_manager: None,
+            focus_manager: None,
+            block_settings: Arc::new(RwLock::new(BlockSettings::default())),
             app_handle: None,
             popup_shown_for: Arc::new(Mutex::new(None)),
+            block_grace: Arc::new(Mutex::new(BlockGrace::default())),
             flush_counter: Arc::new(Mutex::new(0)),
+            flush_interval: AtomicU32::new(power::commit_interval_ticks(
+                power::NORMAL_COMMIT_SECONDS,
+                false,
+            )),
+            commit_seconds: Arc::new(RwLock::new(power::NORMAL_COMMIT_SECONDS)),
+            eco_mode: Arc::new(RwLock::new(EcoMode::default())),
+            title_aware_apps: Arc::new(RwLock::new(Vec::new())),
             retry_buffer: Arc::new(Mutex::new(Vec::new())),
             last_written_end_time: Arc::new(Mutex::new(None)),
+            idle_period: Arc::new(Mutex::new(None)),
+            last_tick_at: Arc::new(Mutex::new(None)),
+            idle_provider: Arc::new(SystemIdleProvider),
+            idle_threshold_seconds: Arc::new(RwLock::new(idle::DEFAULT_IDLE_THRESHOLD_SECONDS)),
+            paused: AtomicBool::new(false),
         }
     }
 
@@ -97,11 +170,179 @@ impl UsageTracker {
         self.notification_manager = Some(manager);
     }
 
+    /// Set the focus manager whose sessions block apps
+    pub fn set_focus_manager(&mut self, manager: Arc<FocusManager>) {
+        self.focus_manager = Some(manager);
+    }
+
+    /// Share block settings with the rest of the app
+    pub fn set_block_settings(&mut self, settings: Arc<RwLock<BlockSettings>>) {
+        self.block_settings = settings;
+    }
+
+    /// Share the eco mode preference with the rest of the app
+    pub fn set_eco_mode(&mut self, mode: Arc<RwLock<EcoMode>>) {
+        self.eco_mode = mode;
+    }
+
+    /// Share the title-aware app rules with the rest of the app
+    pub fn set_title_aware_apps(&mut self, apps: Arc<RwLock<Vec<TitleAwareApp>>>) {
+        self.title_aware_apps = apps;
+    }
+
+    /// Replace where idle time is read from
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn set_idle_provider(&mut self, provider: Arc<dyn IdleProvider>) {
+        self.idle_provider = provider;
+    }
+
+    /// Share the session commit interval with the rest of the app
+    pub fn set_commit_seconds(&mut self, seconds: Arc<RwLock<u64>>) {
+        self.commit_seconds = seconds;
+    }
+
+    /// Share the idle threshold with the rest of the app
+    pub fn set_idle_threshold(&mut self, seconds: Arc<RwLock<u64>>) {
+        self.idle_threshold_seconds = seconds;
+    }
+
+    /// Seconds between limit checks, from the notification settings
+    async fn limit_check_seconds(&self) -> u64 {
+        match self.notification_manager {
+            Some(ref manager) => manager.get_settings().await.limit_check_seconds as u64,
+            None => power::LIMIT_CHECK_SECONDS,
+        }
+    }
+
     /// Get a clone of the app handle
     pub fn app_handle_clone(&self) -> Option<AppHandle> {
         self.app_handle.clone()
     }
 
+    /// Stop recording usage until `resume_tracking`, ending the open session
+    pub async fn pause_tracking(&self) {
+        if self.paused.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        self.update_idle_period(None, now).await;
+
+        let mut current_app = self.current_app.lock().await;
+        let mut current_session_id = self.current_session_id.lock().await;
+        let mut session_start = self.session_start.lock().await;
+
+        if let (Some(session_id), Some(_)) = (*current_session_id, *session_start) {
+            if let Err(e) = self.write_session_duration(session_id, now).await {
+                tracing::error!(error = %e, session_id, "Failed to end session on pause");
+            }
+        }
+
+        // Forget the app so resuming starts a fresh session
+        *current_app = None;
+        *current_session_id = None;
+        *session_start = None;
+        *self.flush_counter.lock().await = 0;
+        // Paused ticks skip the idle check, so the pause would look like a suspend
+        *self.last_tick_at.lock().await = None;
+
+        tracing::info!("Usage tracking paused");
+    }
+
+    /// End the open session if it belongs to `app`, e.g. once `app` has been
+    /// excluded from or returned to tracking. The app is no longer current, so
+    /// the next tick on it starts over and opens a session only if `app` is
+    /// tracked.
+    pub async fn end_session_of(&self, app: &str) {
+        let mut current_app = self.current_app.lock().await;
+        if current_app.as_deref() != Some(app) {
+            return;
+        }
+        let mut current_session_id = self.current_session_id.lock().await;
+        let mut session_start = self.session_start.lock().await;
+        if let (Some(session_id), Some(_)) = (*current_session_id, *session_start) {
+            let now = chrono::Utc::now().timestamp();
+            if let Err(e) = self.write_session_duration(session_id, now).await {
+                tracing::error!(error = %e, session_id, "Failed to end session");
+            }
+        }
+        *current_session_id = None;
+        *session_start = None;
+        *current_app = None;
+        *self.current_title.lock().await = None;
+        *self.flush_counter.lock().await = 0;
+    }
+
+    /// End the open session and idle period and forget them, then run
+    /// `replace` before a tick can open new ones. Used when the database is
+    /// swapped out, so nothing is later written to rows that were replaced.
+    pub async fn end_session_during<T>(&self, replace: impl std::future::Future<Output = T>) -> T {
+        let now = chrono::Utc::now().timestamp();
+
+        let mut idle_period = self.idle_period.lock().await;
+        if let Some((id, _)) = idle_period.take() {
+            if let Err(e) = self.db.lock().await.end_idle_period(id, now) {
+                tracing::error!(error = %e, id, "Failed to end idle period");
+            }
+        }
+
+        let mut current_app = self.current_app.lock().await;
+        let mut current_session_id = self.current_session_id.lock().await;
+        let mut session_start = self.session_start.lock().await;
+        let mut current_title = self.current_title.lock().await;
+        if let (Some(session_id), Some(_)) = (*current_session_id, *session_start) {
+            if let Err(e) = self.write_session_duration(session_id, now).await {
+                tracing::error!(error = %e, session_id, "Failed to end session");
+            }
+        }
+        *current_app = None;
+        *current_session_id = None;
+        *session_start = None;
+        *current_title = None;
+        *self.flush_counter.lock().await = 0;
+        // Buffered writes point at the replaced rows as well
+        self.retry_buffer.lock().await.clear();
+        *self.last_written_end_time.lock().await = None;
+
+        replace.await
+    }
+
+    /// Start recording usage again after `pause_tracking`
+    pub async fn resume_tracking(&self) {
+        *self.last_tick_at.lock().await = None;
+        if self.paused.swap(false, Ordering::SeqCst) {
+            tracing::info!("Usage tracking resumed");
+        }
+    }
+
+    pub fn is_tracking_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Pause tracking if it is running, or resume it if paused. Returns
+    /// whether tracking is paused afterwards.
+    pub async fn toggle_tracking_paused(&self) -> bool {
+        if self.is_tracking_paused() {
+            self.resume_tracking().await;
+            false
+        } else {
+            self.pause_tracking().await;
+            true
+        }
+    }
+
+    /// The app currently being tracked and how long its session has run at `now`
+    pub async fn current_activity_at(&self, now: i64) -> (Option<String>, i64) {
+        let current_app = self.current_app.lock().await.clone();
+        let session_seconds = self
+            .session_start
+            .lock()
+            .await
+            .map(|start| (now - start).max(0))
+            .unwrap_or(0);
+        (current_app, session_seconds)
+    }
+
     /// Get a reference to the emergency access manager
     pub fn emergency_access(&self) -> &Arc<EmergencyAccessManager> {
         &self.emergency_access
@@ -113,8 +354,11 @@ impl UsageTracker {
     }
 
     pub async fn start_tracking(self: Arc<Self>) {
-        let mut ticker = interval(Duration::from_secs(1));
+        let mut eco_mode = *self.eco_mode.read().await;
+        let mut eco = eco_enabled(eco_mode);
+        let mut ticker = interval(power::tick_interval(eco));
         let mut limit_check_counter: u32 = 0;
+        let mut last_power_check = Instant::now();
 
         // Startup diagnostic: test window detection once and log result
         match get_active_window_name() {
@@ -132,28 +376,58 @@ impl UsageTracker {
         loop {
             ticker.tick().await;
 
+            // Pick up commit interval changes before this tick's flush
+            let commit_seconds = *self.commit_seconds.read().await;
+            self.flush_interval.store(
+                power::commit_interval_ticks(commit_seconds, eco),
+                Ordering::Relaxed,
+            );
+
             // Retry any buffered failed writes first
             self.retry_pending_writes().await;
 
-            // Track window every second
+            // Track window every tick
             if let Err(e) = self.track_window().await {
                 tracing::error!(error = %e, "Error tracking window");
             }
 
-            // Check limits every 10 seconds to reduce overhead
+            // Check limits every few seconds rather than every tick to reduce overhead
             limit_check_counter += 1;
-            if limit_check_counter >= 10 {
+            if limit_check_counter
+                >= power::limit_check_ticks(self.limit_check_seconds().await, eco)
+            {
                 limit_check_counter = 0;
                 if let Err(e) = self.check_limits_and_notify().await {
                     tracing::error!(error = %e, "Error checking limits");
                 }
             }
+
+            // Pick up eco mode changes; the power source is polled less often
+            let mode = *self.eco_mode.read().await;
+            let power_due = mode == EcoMode::Auto
+                && last_power_check.elapsed().as_secs() >= power::POWER_CHECK_SECONDS;
+            if mode != eco_mode || power_due {
+                eco_mode = mode;
+                let now_eco = eco_enabled(mode);
+                last_power_check = Instant::now();
+                if now_eco != eco {
+                    eco = now_eco;
+                    tracing::info!(eco, "Switching tracker eco mode");
+                    ticker = interval(power::tick_interval(eco));
+                    ticker.tick().await;
+                    limit_check_counter = 0;
+                }
+            }
         }
     }
 
     /// Finalize the current session (flush duration to DB).
     /// Call this on graceful shutdown to avoid losing the last session's data.
     pub async fn finalize_current_session(&self) {
+        // An open idle period would otherwise keep growing until next launch
+        self.update_idle_period(None, chrono::Utc::now().timestamp())
+            .await;
+
         let current_session_id = self.current_session_id.lock().await;
         let session_start = self.session_start.lock().await;
 
@@ -246,11 +520,22 @@ impl UsageTracker {
     }
 
     async fn track_window(&self) -> Result<(), String> {
-        let mut window_name = get_active_window_name()?;
+        let window = get_active_window_info()?;
+        self.track_active_window(window).await
+    }
+
+    /// Enforce limits for the focused `window` and record its usage
+    async fn track_active_window(&self, window: Option<ActiveWindow>) -> Result<(), String> {
+        // Nothing is recorded while paused (the session was ended on pause),
+        // but limits are still enforced
+        let paused = self.is_tracking_paused();
+
+        let window_title = window.as_ref().map(|w| w.title.clone()).unwrap_or_default();
+        let mut window_name = window.map(|w| w.name);
 
         // Diagnostic: log what the window detector returns (first 20 calls, then every 60th)
         {
-            use std::sync::atomic::{AtomicU64, Ordering};
+            use std::sync::atomic::AtomicU64;
             static TRACK_CALL_COUNT: AtomicU64 = AtomicU64::new(0);
             let call_num = TRACK_CALL_COUNT.fetch_add(1, Ordering::Relaxed);
             if call_num < 20 || call_num % 60 == 0 {
@@ -262,21 +547,31 @@ impl UsageTracker {
             }
         }
 
-        // Check for idle - platform-specific behavior
-        let idle_seconds = get_idle_seconds();
-
-        if idle_seconds >= IDLE_THRESHOLD_SECONDS {
+        let idle_reason = if paused {
+            None
+        } else {
+            self.check_idle(chrono::Utc::now().timestamp()).await
+        };
+        if idle_reason.is_some_and(|reason| reason != IdleReason::Suspended) {
             // User is idle, treat as no active window to stop tracking
-            tracing::debug!(idle_seconds, "User idle, clearing window_name");
+            tracing::debug!(reason = ?idle_reason, "User idle, clearing window_name");
             window_name = None;
         }
 
+        let mut session_title = None;
         let app_name = match window_name {
             Some(ref name) => {
                 let extracted = extract_app_name(name);
+                if let Some(app) = extracted.as_deref() {
+                    // Title-aware apps keep their name and are split by the
+                    // title segment recorded as the session's window title
+                    let title_aware_apps = self.title_aware_apps.read().await;
+                    session_title = title_aware_segment(app, &window_title, &title_aware_apps)
+                        .or_else(|| crate::window_tracker::session_title(&window_title, app));
+                }
                 // Diagnostic: log what extract_app_name returns
                 {
-                    use std::sync::atomic::{AtomicU64, Ordering};
+                    use std::sync::atomic::AtomicU64;
                     static EXTRACT_CALL_COUNT: AtomicU64 = AtomicU64::new(0);
                     let call_num = EXTRACT_CALL_COUNT.fetch_add(1, Ordering::Relaxed);
                     if call_num < 20 || call_num % 60 == 0 {
@@ -299,23 +594,16 @@ impl UsageTracker {
 
         let now = chrono::Utc::now().timestamp();
 
+        // Switching away from an over-limit app restarts its grace period
+        self.block_grace
+            .lock()
+            .await
+            .retain_active(app_name.as_deref());
+
         // Check if the current app should be blocked
         if let Some(ref app) = app_name {
-            if app != "Digital Wellbeing" && app != "limit-popup" {
-                let db = self.db.lock().await;
-                let is_blocked = db.is_app_blocked(app).unwrap_or(false);
-                drop(db); // Release lock before further operations
-
-                if is_blocked {
-                    // Check if app has emergency access
-                    if self.emergency_access.has_active_access(app).await {
-                        // Allow the app, emergency access is active
-                        tracing::debug!(app = %app, "App has emergency access, allowing");
-                    } else {
-                        // Show limit popup instead of blocking immediately
-                        self.show_limit_popup(app).await;
-                    }
-                }
+            if !SELF_APP_NAMES.contains(&app.as_str()) && self.needs_block(app, now).await {
+                self.handle_over_limit(app, now).await;
             }
         }
 
@@ -330,8 +618,14 @@ impl UsageTracker {
             }
         }
 
-        // Check if app changed
-        if *current_app != app_name {
+        if paused {
+            return Ok(());
+        }
+
+        // Check if the app or its (normalized) window title changed, so each
+        // session is recorded under the title it was actually spent on
+        let mut current_title = self.current_title.lock().await;
+        if *current_app != app_name || *current_title != session_title {
             // End previous session if exists
             if let (Some(session_id), Some(_)) = (*current_session_id, *session_start) {
                 if let Err(e) = self.write_session_duration(session_id, now).await {
@@ -343,44 +637,20 @@ impl UsageTracker {
             *self.flush_counter.lock().await = 0;
 
             // Start new session if we have an app
-            if let Some(ref app) = app_name {
-                // Skip tracking our own app
-                if app != "Digital Wellbeing" {
-                    let db = self.db.lock().await;
-                    match db.get_or_create_app(app, None) {
-                        Ok(app_id) => match db.start_session(app_id, now) {
-                            Ok(session_id) => {
-                                tracing::info!(app = %app, session_id, "Started tracking app");
-                                *current_session_id = Some(session_id);
-                                *session_start = Some(now);
-                            }
-                            Err(e) => {
-                                tracing::error!(error = %e, app = %app, "Failed to start session");
-                                *current_session_id = None;
-                                *session_start = None;
-                            }
-                        },
-                        Err(e) => {
-                            tracing::error!(error = %e, app = %app, "Failed to get/create app");
-                            *current_session_id = None;
-                            *session_start = None;
-                        }
-                    }
-                } else {
-                    *current_session_id = None;
-                    *session_start = None;
-                }
-            } else {
-                *current_session_id = None;
-                *session_start = None;
-            }
+            let session_id = match app_name {
+                Some(ref app) => self.open_session(app, now, session_title.as_deref()).await,
+                None => None,
+            };
+            *current_session_id = session_id;
+            *session_start = session_id.map(|_| now);
 
             *current_app = app_name;
+            *current_title = session_title;
         } else if let Some(session_id) = *current_session_id {
             // Same app - use counter-based flush instead of unreliable modulo on timestamps
             let mut counter = self.flush_counter.lock().await;
             *counter += 1;
-            if *counter >= SESSION_FLUSH_INTERVAL {
+            if *counter >= self.flush_interval.load(Ordering::Relaxed) {
                 *counter = 0;
                 if let Err(e) = self.write_session_duration(session_id, now).await {
                     tracing::error!(error = %e, session_id, "Failed to update session duration");
@@ -391,6 +661,115 @@ impl UsageTracker {
         Ok(())
     }
 
+    /// Start a session for `app` at `now`, unless it is never tracked: one of
+    /// our own windows, or an app the user excluded. Returns the new
+    /// session's id.
+    async fn open_session(&self, app: &str, now: i64, title: Option<&str>) -> Option<i64> {
+        if SELF_APP_NAMES.contains(&app) {
+            return None;
+        }
+
+        let db = self.db.lock().await;
+        match db.is_app_excluded(app) {
+            Ok(true) => return None,
+            Ok(false) => {}
+            Err(e) => tracing::error!(error = %e, app = %app, "Failed to check excluded apps"),
+        }
+        match db.get_or_create_app(app, None) {
+            Ok(app_id) => match db.start_session_with_title(app_id, now, title) {
+                Ok(session_id) => {
+                    tracing::info!(app = %app, session_id, "Started tracking app");
+                    Some(session_id)
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, app = %app, "Failed to start session");
+                    None
+                }
+            },
+            Err(e) => {
+                tracing::error!(error = %e, app = %app, "Failed to get/create app");
+                None
+            }
+        }
+    }
+
+    /// Classify the user's idleness at `tick_at` and keep the idle period
+    /// and suspend handling in step with it
+    async fn check_idle(&self, tick_at: i64) -> Option<IdleReason> {
+        let threshold = *self.idle_threshold_seconds.read().await;
+        let idle_seconds = self.idle_provider.idle_seconds();
+        let previous_tick = self.last_tick_at.lock().await.replace(tick_at);
+        let signals = IdleSignals {
+            idle_seconds,
+            // Only ask logind once input has stopped, to avoid a subprocess every tick
+            locked: idle_seconds >= threshold && self.idle_provider.is_locked(),
+            wake_gap_seconds: previous_tick.map_or(0, |previous| tick_at - previous),
+        };
+        let idle_reason = idle::classify_idle(&signals, threshold);
+
+        if idle_reason == Some(IdleReason::Suspended) {
+            self.handle_wake(previous_tick.unwrap_or(tick_at), tick_at)
+                .await;
+        } else {
+            self.update_idle_period(idle_reason, tick_at).await;
+        }
+        idle_reason
+    }
+
+    /// Open, switch or close the idle period to match the current idle reason
+    async fn update_idle_period(&self, reason: Option<IdleReason>, now: i64) {
+        let mut open = self.idle_period.lock().await;
+        match (*open, reason) {
+            (None, None) => return,
+            (Some((_, current)), Some(reason)) if current == reason => return,
+            _ => {}
+        }
+
+        let db = self.db.lock().await;
+        if let Some((id, _)) = open.take() {
+            if let Err(e) = db.end_idle_period(id, now) {
+                tracing::error!(error = %e, id, "Failed to end idle period");
+            }
+        }
+        if let Some(reason) = reason {
+            match db.start_idle_period(reason, now) {
+                Ok(id) => *open = Some((id, reason)),
+                Err(e) => {
+                    tracing::error!(error = %e, reason = ?reason, "Failed to start idle period")
+                }
+            }
+        }
+    }
+
+    /// The machine slept between `slept_at` and `woke_at`. Record the gap as a
+    /// suspended period and close the running session at `slept_at` so the
+    /// sleep isn't counted as usage.
+    async fn handle_wake(&self, slept_at: i64, woke_at: i64) {
+        tracing::info!(
+            slept_seconds = woke_at - slept_at,
+            "Detected resume from suspend"
+        );
+        self.update_idle_period(None, slept_at).await;
+
+        {
+            let db = self.db.lock().await;
+            if let Err(e) = db.record_idle_period(IdleReason::Suspended, slept_at, woke_at) {
+                tracing::error!(error = %e, "Failed to record suspended period");
+            }
+        }
+
+        let mut current_app = self.current_app.lock().await;
+        let mut current_session_id = self.current_session_id.lock().await;
+        let mut session_start = self.session_start.lock().await;
+        if let Some(session_id) = current_session_id.take() {
+            if let Err(e) = self.write_session_duration(session_id, slept_at).await {
+                tracing::error!(error = %e, session_id, "Failed to end session before suspend");
+            }
+        }
+        *session_start = None;
+        *current_app = None;
+    }
+
     /// Write session duration to DB with retry buffering on failure
     async fn write_session_duration(&self, session_id: i64, end_time: i64) -> Result<(), String> {
         let db = self.db.lock().await;
@@ -422,6 +801,12 @@ impl UsageTracker {
             .get_all_limit_status()
             .map_err(|e| format!("Failed to get limit status: {}", e))?;
         drop(db);
+        self.note_limits_reached(&limit_statuses).await;
+
+        let settings = match self.notification_manager {
+            Some(ref manager) => manager.get_settings().await,
+            None => NotificationSettings::default(),
+        };
 
         for (app_name, limit_minutes, used_seconds, _block_when_exceeded) in limit_statuses {
             let limit_seconds = (limit_minutes as i64) * 60;
@@ -431,45 +816,76 @@ impl UsageTracker {
 
             let usage_ratio = used_seconds as f64 / limit_seconds as f64;
 
-            // Check if exceeded (100%)
-            if usage_ratio >= EXCEEDED_THRESHOLD {
-                self.send_notification_if_not_sent(
-                    &app_name,
-                    NotificationType::Exceeded,
-                    &format!("Time limit exceeded for {}", app_name),
-                    &format!(
-                        "{} has exceeded its daily limit of {} minutes.",
-                        app_name, limit_minutes
-                    ),
-                )
-                .await;
-            }
-            // Check if approaching (80%)
-            else if usage_ratio >= WARNING_THRESHOLD {
-                let remaining_minutes = ((limit_seconds - used_seconds) / 60).max(1);
-                self.send_notification_if_not_sent(
-                    &app_name,
-                    NotificationType::Warning,
-                    &format!("{} - {} min remaining", app_name, remaining_minutes),
-                    &format!("You've used 80% of your daily limit for {}.", app_name),
-                )
-                .await;
+            match limit_notification(&settings, used_seconds, limit_seconds) {
+                Some(NotificationType::Exceeded) => {
+                    self.send_notification_if_not_sent(
+                        &app_name,
+                        NotificationType::Exceeded,
+                        &format!("Time limit exceeded for {}", app_name),
+                        &format!(
+                            "{} has exceeded its daily limit of {} minutes.",
+                            app_name, limit_minutes
+                        ),
+                    )
+                    .await;
+                }
+                Some(NotificationType::Warning) => {
+                    let remaining_minutes = ((limit_seconds - used_seconds) / 60).max(1);
+                    let percent_used = (usage_ratio * 100.0) as u32;
+                    self.send_notification_if_not_sent(
+                        &app_name,
+                        NotificationType::Warning,
+                        &format!("{} - {} min remaining", app_name, remaining_minutes),
+                        &format!(
+                            "You've used {}% of your daily limit for {}.",
+                            percent_used, app_name
+                        ),
+                    )
+                    .await;
+                }
+                None => {}
             }
         }
 
         Ok(())
     }
 
+    /// Remember the apps at or over 100% of their limit
+    async fn note_limits_reached(&self, limit_statuses: &[(String, i32, i64, bool)]) {
+        let mut reached = self.limits_reached.lock().await;
+        for (app_name, limit_minutes, used_seconds, _) in limit_statuses {
+            if *limit_minutes > 0 && *used_seconds >= (*limit_minutes as i64) * 60 {
+                reached.insert(app_name.clone());
+            }
+        }
+    }
+
     async fn reset_notifications_if_new_day(&self) {
         let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-        let mut last_reset = self.last_reset_date.lock().await;
+        for app_name in self.start_new_day(today).await {
+            self.send_system_notification(
+                &format!("{} is available again", app_name),
+                &format!("Your limit for {} has reset", app_name),
+            )
+            .await;
+        }
+    }
 
-        if *last_reset != today {
-            let mut notifications = self.sent_notifications.lock().await;
-            notifications.clear();
-            *last_reset = today;
-            tracing::info!("Reset notification tracking for new day");
+    /// Clear the notification tracking if `today` is a new day. Returns the
+    /// apps that used up their limit on the previous day, sorted by name.
+    async fn start_new_day(&self, today: String) -> Vec<String> {
+        let mut last_reset = self.last_reset_date.lock().await;
+        if *last_reset == today {
+            return Vec::new();
         }
+
+        self.sent_notifications.lock().await.clear();
+        *last_reset = today;
+        tracing::info!("Reset notification tracking for new day");
+
+        let mut reset: Vec<String> = self.limits_reached.lock().await.drain().collect();
+        reset.sort();
+        reset
     }
 
     async fn send_notification_if_not_sent(
@@ -500,18 +916,73 @@ impl UsageTracker {
 
     /// Send a notification, respecting NotificationManager DND/mute settings if available
     async fn send_system_notification(&self, title: &str, body: &str) -> bool {
-        if let Some(ref manager) = self.notification_manager {
-            // Use the notification manager which respects DND and mute settings
-            match manager.send_notification(title, body, "normal").await {
-                Ok(()) => true,
-                Err(e) => {
-                    tracing::debug!(error = %e, "Notification suppressed or failed");
-                    false
-                }
+        send_notification_via(
+            self.notification_manager.as_ref(),
+            self.app_handle.as_ref(),
+            title,
+            body,
+        )
+        .await
+    }
+
+    /// Whether `app_name` is over a blocking limit with no emergency access left
+    /// at `now`. While access lasts the shown popup is forgotten, so the popup
+    /// comes back when the access runs out with the app still in front.
+    async fn needs_block(&self, app_name: &str, now: i64) -> bool {
+        // An active focus session blocks its apps whatever their limits, and
+        // emergency access doesn't lift it
+        if let Some(ref focus_manager) = self.focus_manager {
+            if focus_manager.should_block_app(app_name).await {
+                return true;
             }
-        } else {
-            // Fallback: direct send (background mode without notification manager)
-            crate::notifications::send_notification(title, body)
+        }
+
+        let is_blocked = self
+            .db
+            .lock()
+            .await
+            .is_app_blocked(app_name)
+            .unwrap_or(false);
+        if !is_blocked {
+            return false;
+        }
+
+        if self
+            .emergency_access
+            .has_active_access_at(app_name, now)
+            .await
+        {
+            tracing::debug!(app = %app_name, "App has emergency access, allowing");
+            let mut popup_shown = self.popup_shown_for.lock().await;
+            if popup_shown.as_deref() == Some(app_name) {
+                *popup_shown = None;
+            }
+            return false;
+        }
+
+        true
+    }
+
+    /// Give an over-limit app its grace period, then show the limit popup
+    async fn handle_over_limit(&self, app_name: &str, now: i64) {
+        let grace_seconds = self.block_settings.read().await.block_grace_seconds;
+        let action = self
+            .block_grace
+            .lock()
+            .await
+            .check(app_name, now, grace_seconds);
+
+        match action {
+            GraceAction::Warn { seconds_left } => {
+                self.send_system_notification(
+                    &format!("{} limit reached", app_name),
+                    &format!("Closing in {} seconds", seconds_left),
+                )
+                .await;
+            }
+            GraceAction::Wait => {}
+            // Show limit popup instead of blocking immediately
+            GraceAction::Block => self.show_limit_popup(app_name).await,
         }
     }
 
@@ -527,55 +998,24 @@ impl UsageTracker {
             *popup_shown = Some(app_name.to_string());
         }
 
-        if let Some(ref handle) = self.app_handle {
-            // Check if popup window already exists
-            if handle.get_webview_window("limit-popup").is_some() {
-                // Close existing popup first
-                if let Some(window) = handle.get_webview_window("limit-popup") {
-                    let _ = window.close();
-                }
-            }
-
-            // Create URL with app name as query parameter
-            let url = format!("/limit-popup?app={}", urlencoding::encode(app_name));
-
-            // Create the popup window
-            match WebviewWindowBuilder::new(handle, "limit-popup", WebviewUrl::App(url.into()))
-                .title("App Limit Reached")
-                .inner_size(420.0, 280.0)
-                .resizable(false)
-                .decorations(false)
-                .always_on_top(true)
-                .center()
-                .focused(true)
-                .build()
-            {
-                Ok(_) => {
-                    tracing::info!(app = %app_name, "Limit popup shown");
-                }
-                Err(e) => {
-                    tracing::error!(error = %e, app = %app_name, "Failed to create limit popup");
-                    // Fallback to system notification
-                    let _ = self
-                        .send_system_notification(
-                            &format!("{} blocked", app_name),
-                            "Daily time limit exceeded. The app will be closed.",
-                        )
-                        .await;
-                    self.block_app(app_name);
-                }
-            }
-        } else {
-            // No app handle, fallback to direct blocking
-            tracing::warn!("No app handle available, falling back to direct blocking");
-            let _ = self
-                .send_system_notification(
-                    &format!("{} blocked", app_name),
-                    "Daily time limit exceeded. The app will be closed.",
-                )
-                .await;
-            self.block_app(app_name);
+        if open_limit_popup_window(self.app_handle.as_ref(), app_name) {
+            return;
         }
+
+        // Fallback to system notification and direct blocking
+        let _ = self
+            .send_system_notification(
+                &format!("{} blocked", app_name),
+                "Daily time limit exceeded. The app will be closed.",
+            )
+            .await;
+
+        // Closing and verifying takes a few seconds; don't hold up the tick
+        let blocker = self.blocker();
+        let app_name = app_name.to_string();
+        tokio::spawn(async move {
+            blocker.block_app(&app_name).await;
+        });
     }
 
     /// Close the limit popup window
@@ -587,33 +1027,170 @@ impl UsageTracker {
         }
     }
 
-    /// Block/close an app (called when user clicks "Quit App" or emergency access expires)
-    pub fn block_app(&self, app_name: &str) {
-        #[cfg(target_os = "linux")]
-        {
-            // Send notification before blocking (fire-and-forget, don't await)
-            let _ = crate::notifications::send_notification(
-                &format!("{} blocked", app_name),
-                "Daily time limit exceeded. The app will be closed.",
+    /// Handle for closing apps, usable without holding on to the tracker
+    pub fn blocker(&self) -> AppBlocker {
+        AppBlocker {
+            block_settings: Arc::clone(&self.block_settings),
+            notification_manager: self.notification_manager.clone(),
+            app_handle: self.app_handle.clone(),
+        }
+    }
+}
+
+/// Send a notification through `manager` if there is one, so DND and mute
+/// settings apply, or directly otherwise
+async fn send_notification_via(
+    manager: Option<&Arc<NotificationManager>>,
+    handle: Option<&AppHandle>,
+    title: &str,
+    body: &str,
+) -> bool {
+    if let Some(manager) = manager {
+        // Use the notification manager which respects DND and mute settings
+        match manager.send_notification(title, body, "normal").await {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::debug!(error = %e, "Notification suppressed or failed");
+                false
+            }
+        }
+    } else {
+        // Fallback: direct send (background mode without notification manager)
+        crate::notifications::notify(handle, title, body, "normal")
+    }
+}
+
+/// Create (or re-create) the limit popup window for an app.
+/// Returns false if the popup couldn't be shown.
+fn open_limit_popup_window(handle: Option<&AppHandle>, app_name: &str) -> bool {
+    let Some(handle) = handle else {
+        tracing::warn!("No app handle available, falling back to direct blocking");
+        return false;
+    };
+
+    // Close existing popup first
+    if let Some(window) = handle.get_webview_window("limit-popup") {
+        let _ = window.close();
+    }
+
+    // Create URL with app name as query parameter
+    let url = format!("/limit-popup?app={}", urlencoding::encode(app_name));
+
+    // Create the popup window
+    match WebviewWindowBuilder::new(handle, "limit-popup", WebviewUrl::App(url.into()))
+        .title("App Limit Reached")
+        .inner_size(420.0, 280.0)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .center()
+        .focused(true)
+        .build()
+    {
+        Ok(_) => {
+            tracing::info!(app = %app_name, "Limit popup shown");
+            true
+        }
+        Err(e) => {
+            tracing::error!(error = %e, app = %app_name, "Failed to create limit popup");
+            false
+        }
+    }
+}
+
+/// Closes over-limit apps. Cheap to clone, so a block attempt can run as its
+/// own task while the tracker keeps ticking.
+#[derive(Clone)]
+pub struct AppBlocker {
+    block_settings: Arc<RwLock<BlockSettings>>,
+    notification_manager: Option<Arc<NotificationManager>>,
+    app_handle: Option<AppHandle>,
+}
+
+impl AppBlocker {
+    /// Block/close an app (called when user clicks "Quit App" or emergency access expires).
+    ///
+    /// Re-checks the active window afterwards and, if the app is still in
+    /// front, escalates according to the configured [`BlockFailureAction`].
+    pub async fn block_app(&self, app_name: &str) -> BlockResult {
+        let settings = self.block_settings.read().await.clone();
+        if settings.dry_run {
+            let _ = crate::notifications::notify(
+                self.app_handle.as_ref(),
+                &format!("{} would be blocked", app_name),
+                "Daily time limit exceeded. Dry run is on, so the app stays open.",
+                "normal",
             );
+        } else {
+            #[cfg(target_os = "linux")]
+            {
+                // Send notification before blocking (fire-and-forget, don't await)
+                let _ = crate::notifications::notify(
+                    self.app_handle.as_ref(),
+                    &format!("{} blocked", app_name),
+                    "Daily time limit exceeded. The app will be closed.",
+                    "normal",
+                );
+            }
+        }
+
+        let method = blocking::close_app(app_name, &settings).await;
+        if matches!(method, BlockMethod::Unsupported | BlockMethod::DryRun) {
+            return BlockResult {
+                attempted: false,
+                succeeded: false,
+                method,
+            };
+        }
+
+        let mut succeeded = blocking::verify_block(app_name).await;
 
-            // Try to close windows of the app using wmctrl
-            let _ = Command::new("wmctrl").args(["-c", app_name]).output();
+        if !succeeded {
+            let action = settings.on_failure;
+            tracing::warn!(app = %app_name, action = ?action, "App still active after block attempt");
 
-            // Also try xdotool to close active window if it matches
-            let _ = Command::new("xdotool")
-                .args(["getactivewindow", "windowclose"])
-                .output();
+            match action {
+                BlockFailureAction::Retry => {
+                    blocking::close_app(app_name, &settings).await;
+                    succeeded = blocking::verify_block(app_name).await;
+                    if !succeeded {
+                        self.notify_block_failed(app_name).await;
+                    }
+                }
+                BlockFailureAction::Notify => {
+                    self.notify_block_failed(app_name).await;
+                }
+                BlockFailureAction::Overlay => {
+                    if !open_limit_popup_window(self.app_handle.as_ref(), app_name) {
+                        self.notify_block_failed(app_name).await;
+                    }
+                }
+            }
         }
 
-        #[cfg(target_os = "windows")]
-        {
-            // On Windows, use taskkill (less aggressive approach - send SIGTERM)
-            let _ = Command::new("taskkill")
-                .args(["/IM", &format!("{}.exe", app_name), "/F"])
-                .output();
+        BlockResult {
+            attempted: true,
+            succeeded,
+            method,
         }
     }
+
+    /// Tell the user that enforcement didn't work for an app
+    async fn notify_block_failed(&self, app_name: &str) {
+        let _ = send_notification_via(
+            self.notification_manager.as_ref(),
+            self.app_handle.as_ref(),
+            &format!("Couldn't close {} automatically", app_name),
+            "Your daily limit has been reached. Please close the app yourself.",
+        )
+        .await;
+    }
+}
+
+/// Whether eco mode should be active for `mode`, checking the power source
+/// only when it matters
+fn eco_enabled(mode: EcoMode) -> bool {
+    power::eco_active(mode, mode == EcoMode::Auto && power::is_on_battery())
 }
 
 /// Get user idle time in seconds, cross-platform.
@@ -700,8 +1277,8 @@ fn get_idle_seconds_wayland() -> u64 {
             // Output format: "b true\n" or "b false\n"
             if stdout.contains("true") {
                 // We know user is idle but don't know for how long.
-                // Return the threshold + 1 to trigger idle detection.
-                return IDLE_THRESHOLD_SECONDS + 1;
+                // Report the longest threshold so any configured one is reached.
+                return idle::MAX_IDLE_THRESHOLD_SECONDS;
             }
             return 0;
         }
@@ -711,3 +1288,383 @@ fn get_idle_seconds_wayland() -> u64 {
     tracing::trace!("Wayland idle detection failed, assuming active");
     0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::atomic::AtomicU64;
+
+    /// Idle time set by the test; never locked
+    struct MockIdle(AtomicU64);
+
+    impl IdleProvider for MockIdle {
+        fn idle_seconds(&self) -> u64 {
+            self.0.load(Ordering::Relaxed)
+        }
+
+        fn is_locked(&self) -> bool {
+            false
+        }
+    }
+
+    fn test_tracker(idle: Arc<MockIdle>) -> UsageTracker {
+        let db = Database::new(PathBuf::from(":memory:")).expect("in-memory database");
+        let mut tracker = UsageTracker::new(
+            Arc::new(Mutex::new(db)),
+            Arc::new(EmergencyAccessManager::new()),
+        );
+        tracker.set_idle_provider(idle);
+        tracker
+    }
+
+    #[tokio::test]
+    async fn test_current_activity_session_age() {
+        let tracker = test_tracker(Arc::new(MockIdle(AtomicU64::new(0))));
+        assert_eq!(tracker.current_activity_at(1_000).await, (None, 0));
+
+        *tracker.current_app.lock().await = Some("Firefox".to_string());
+        *tracker.session_start.lock().await = Some(1_000);
+        assert_eq!(
+            tracker.current_activity_at(1_180).await,
+            (Some("Firefox".to_string()), 180)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_idle_beyond_threshold_stops_tracking() {
+        let idle = Arc::new(MockIdle(AtomicU64::new(0)));
+        let tracker = test_tracker(Arc::clone(&idle));
+
+        let start = 1_700_000_000;
+        assert_eq!(tracker.check_idle(start).await, None);
+
+        // Away for longer than the threshold
+        idle.0.store(301, Ordering::Relaxed);
+        assert_eq!(tracker.check_idle(start + 1).await, Some(IdleReason::Away));
+        assert!(tracker.idle_period.lock().await.is_some());
+
+        // Input resumes
+        idle.0.store(0, Ordering::Relaxed);
+        assert_eq!(tracker.check_idle(start + 2).await, None);
+        assert!(tracker.idle_period.lock().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_idle_threshold_is_configurable() {
+        let idle = Arc::new(MockIdle(AtomicU64::new(400)));
+        let mut tracker = test_tracker(Arc::clone(&idle));
+        let threshold = Arc::new(RwLock::new(600));
+        tracker.set_idle_threshold(Arc::clone(&threshold));
+
+        assert_eq!(tracker.check_idle(1_700_000_000).await, None);
+
+        // Changes made elsewhere apply on the next tick
+        *threshold.write().await = 120;
+        assert_eq!(
+            tracker.check_idle(1_700_000_001).await,
+            Some(IdleReason::Away)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pause_ends_the_open_session() {
+        let tracker = test_tracker(Arc::new(MockIdle(AtomicU64::new(0))));
+        let start = chrono::Utc::now().timestamp() - 60;
+        let session_id = {
+            let db = tracker.db.lock().await;
+            let app_id = db.get_or_create_app("Firefox", None).unwrap();
+            db.start_session(app_id, start).unwrap()
+        };
+        *tracker.current_app.lock().await = Some("Firefox".to_string());
+        *tracker.current_session_id.lock().await = Some(session_id);
+        *tracker.session_start.lock().await = Some(start);
+
+        tracker.pause_tracking().await;
+        assert!(tracker.is_tracking_paused());
+        assert!(tracker.current_app.lock().await.is_none());
+        assert!(tracker.current_session_id.lock().await.is_none());
+
+        let sessions = {
+            let db = tracker.db.lock().await;
+            let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+            db.get_sessions_for_date(&today).unwrap()
+        };
+        assert!(sessions[0].duration_seconds >= 60);
+
+        // Paused ticks record nothing
+        let window = ActiveWindow {
+            name: "firefox".to_string(),
+            title: "Mozilla Firefox".to_string(),
+        };
+        assert!(tracker.track_active_window(Some(window)).await.is_ok());
+        assert!(tracker.current_session_id.lock().await.is_none());
+
+        tracker.resume_tracking().await;
+        assert!(!tracker.is_tracking_paused());
+    }
+
+    #[tokio::test]
+    async fn test_long_pause_is_not_a_suspend() {
+        let tracker = test_tracker(Arc::new(MockIdle(AtomicU64::new(0))));
+        let paused_at = chrono::Utc::now().timestamp();
+        assert_eq!(tracker.check_idle(paused_at).await, None);
+
+        tracker.pause_tracking().await;
+        tracker.resume_tracking().await;
+
+        // The first tick after a pause longer than a suspend gap
+        let resumed_at = paused_at + idle::SUSPEND_GAP_SECONDS + 60;
+        assert_eq!(tracker.check_idle(resumed_at).await, None);
+        let today = chrono::Local::now().date_naive();
+        let idle = tracker.db.lock().await.get_idle_ratio_for(today).unwrap();
+        assert_eq!(idle.idle_seconds, 0);
+    }
+
+    #[tokio::test]
+    async fn test_session_is_forgotten_while_the_database_is_replaced() {
+        let tracker = test_tracker(Arc::new(MockIdle(AtomicU64::new(0))));
+        let start = chrono::Utc::now().timestamp() - 60;
+        let session_id = {
+            let db = tracker.db.lock().await;
+            let app_id = db.get_or_create_app("Firefox", None).unwrap();
+            db.start_session(app_id, start).unwrap()
+        };
+        *tracker.current_app.lock().await = Some("Firefox".to_string());
+        *tracker.current_session_id.lock().await = Some(session_id);
+        *tracker.session_start.lock().await = Some(start);
+        tracker
+            .update_idle_period(Some(IdleReason::Away), start)
+            .await;
+
+        // Both were closed before the replacement runs
+        let db = Arc::clone(&tracker.db);
+        let open_idle_periods = tracker
+            .end_session_during(async move { db.lock().await.close_open_idle_periods().unwrap() })
+            .await;
+        assert_eq!(open_idle_periods, 0);
+        let sessions = {
+            let db = tracker.db.lock().await;
+            let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+            db.get_sessions_for_date(&today).unwrap()
+        };
+        assert!(sessions[0].duration_seconds >= 60);
+        assert!(tracker.current_app.lock().await.is_none());
+        assert!(tracker.current_session_id.lock().await.is_none());
+        assert!(tracker.idle_period.lock().await.is_none());
+        // Tracking itself carries on
+        assert!(!tracker.is_tracking_paused());
+    }
+
+    #[tokio::test]
+    async fn test_focus_session_blocks_apps_outside_the_allow_list() {
+        let mut tracker = test_tracker(Arc::new(MockIdle(AtomicU64::new(0))));
+        let focus_manager = Arc::new(FocusManager::with_state_path(None));
+        tracker.set_focus_manager(Arc::clone(&focus_manager));
+        let now = chrono::Utc::now().timestamp();
+
+        focus_manager
+            .update_settings(crate::focus_mode::FocusSettings {
+                mode: crate::focus_mode::FocusMode::AllowList,
+                allowed_apps: vec!["Code".to_string()],
+                notify_on_start: false,
+                notify_on_end: false,
+                ..Default::default()
+            })
+            .await;
+        assert!(!tracker.needs_block("Discord", now).await);
+
+        focus_manager.start_session(Some(30), None, false).await;
+        assert!(tracker.needs_block("Discord", now).await);
+        assert!(!tracker.needs_block("Code", now).await);
+        assert!(!tracker.needs_block("Digital Wellbeing", now).await);
+
+        focus_manager.stop_session().await;
+        assert!(!tracker.needs_block("Discord", now).await);
+    }
+
+    #[tokio::test]
+    async fn test_excluded_app_is_not_tracked() {
+        let tracker = test_tracker(Arc::new(MockIdle(AtomicU64::new(0))));
+        tracker
+            .db
+            .lock()
+            .await
+            .add_excluded_app("KeePassXC")
+            .unwrap();
+        let now = chrono::Utc::now().timestamp();
+
+        assert_eq!(tracker.open_session("KeePassXC", now, None).await, None);
+        assert_eq!(
+            tracker.open_session("Digital Wellbeing", now, None).await,
+            None
+        );
+        assert!(tracker.open_session("Firefox", now, None).await.is_some());
+
+        let db = tracker.db.lock().await;
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let sessions = db.get_sessions_for_date(&today).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].app_name, "Firefox");
+    }
+
+    #[tokio::test]
+    async fn test_excluding_the_current_app_ends_its_session() {
+        let tracker = test_tracker(Arc::new(MockIdle(AtomicU64::new(0))));
+        let start = chrono::Utc::now().timestamp() - 60;
+        let session_id = {
+            let db = tracker.db.lock().await;
+            let app_id = db.get_or_create_app("Firefox", None).unwrap();
+            db.start_session(app_id, start).unwrap()
+        };
+        *tracker.current_app.lock().await = Some("Firefox".to_string());
+        *tracker.current_session_id.lock().await = Some(session_id);
+        *tracker.session_start.lock().await = Some(start);
+
+        // Another app's exclusion leaves the session alone
+        tracker.end_session_of("Slack").await;
+        assert_eq!(*tracker.current_session_id.lock().await, Some(session_id));
+
+        tracker.db.lock().await.add_excluded_app("Firefox").unwrap();
+        tracker.end_session_of("Firefox").await;
+        assert!(tracker.current_session_id.lock().await.is_none());
+
+        // Staying on the app doesn't start recording it again
+        let window = || ActiveWindow {
+            name: "firefox".to_string(),
+            title: "Mozilla Firefox".to_string(),
+        };
+        assert!(tracker.track_active_window(Some(window())).await.is_ok());
+        assert!(tracker.current_session_id.lock().await.is_none());
+
+        // Once it's tracked again, the next tick on it opens a session
+        assert!(tracker
+            .db
+            .lock()
+            .await
+            .remove_excluded_app("Firefox")
+            .unwrap());
+        tracker.end_session_of("Firefox").await;
+        assert!(tracker.track_active_window(Some(window())).await.is_ok());
+        assert!(tracker.current_session_id.lock().await.is_some());
+
+        let db = tracker.db.lock().await;
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let sessions = db.get_sessions_for_date(&today).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert!(sessions[0].duration_seconds >= 60);
+    }
+
+    #[tokio::test]
+    async fn test_excluded_app_is_still_blocked() {
+        let tracker = test_tracker(Arc::new(MockIdle(AtomicU64::new(0))));
+        {
+            let db = tracker.db.lock().await;
+            db.set_limit_with_block("Firefox", 1, true).unwrap();
+            db.add_excluded_app("Firefox").unwrap();
+            let app_id = db.get_or_create_app("Firefox", None).unwrap();
+            let start = chrono::Utc::now().timestamp() - 120;
+            let session = db.start_session(app_id, start).unwrap();
+            db.end_session(session, start + 120).unwrap();
+        }
+
+        // Exclusion only stops recording; the limit's grace period still starts
+        let window = ActiveWindow {
+            name: "firefox".to_string(),
+            title: "Mozilla Firefox".to_string(),
+        };
+        assert!(tracker.track_active_window(Some(window)).await.is_ok());
+        assert!(tracker.current_session_id.lock().await.is_none());
+        let now = chrono::Utc::now().timestamp();
+        assert_eq!(
+            tracker.block_grace.lock().await.check("Firefox", now, 30),
+            GraceAction::Wait
+        );
+    }
+
+    #[tokio::test]
+    async fn test_limit_reset_announced_once_at_rollover() {
+        let tracker = test_tracker(Arc::new(MockIdle(AtomicU64::new(0))));
+        let limit_statuses = {
+            let db = tracker.db.lock().await;
+            db.set_limit("Reddit", 1).unwrap();
+            db.set_limit("Firefox", 10).unwrap();
+            let app_id = db.get_or_create_app("Reddit", None).unwrap();
+            let start = chrono::Utc::now().timestamp() - 60;
+            let session = db.start_session(app_id, start).unwrap();
+            db.end_session(session, start + 60).unwrap();
+            db.get_all_limit_status().unwrap()
+        };
+        tracker.note_limits_reached(&limit_statuses).await;
+        tracker.note_limits_reached(&limit_statuses).await;
+
+        let yesterday = tracker.last_reset_date.lock().await.clone();
+        assert!(tracker.start_new_day(yesterday).await.is_empty());
+
+        assert_eq!(
+            tracker.start_new_day("2099-01-01".to_string()).await,
+            vec!["Reddit".to_string()]
+        );
+        assert!(tracker
+            .start_new_day("2099-01-01".to_string())
+            .await
+            .is_empty());
+        assert!(tracker
+            .start_new_day("2099-01-02".to_string())
+            .await
+            .is_empty());
+    }
+
+    #[test]
+    fn test_limit_notification_uses_configured_thresholds() {
+        let settings = NotificationSettings {
+            warning_threshold: 50,
+            exceeded_threshold: 120,
+            ..Default::default()
+        };
+        let limit = 60 * 60;
+
+        assert_eq!(limit_notification(&settings, 29 * 60, limit), None);
+        assert_eq!(
+            limit_notification(&settings, 30 * 60, limit),
+            Some(NotificationType::Warning)
+        );
+        // Past the limit but below the exceeded threshold: still a warning
+        assert_eq!(
+            limit_notification(&settings, 65 * 60, limit),
+            Some(NotificationType::Warning)
+        );
+        assert_eq!(
+            limit_notification(&settings, 72 * 60, limit),
+            Some(NotificationType::Exceeded)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_timed_access_blocks_again() {
+        let tracker = test_tracker(Arc::new(MockIdle(AtomicU64::new(0))));
+        {
+            let db = tracker.db.lock().await;
+            db.set_limit_with_block("Reddit", 1, true).unwrap();
+            let app_id = db.get_or_create_app("Reddit", None).unwrap();
+            let start = chrono::Utc::now().timestamp() - 120;
+            let session = db.start_session(app_id, start).unwrap();
+            db.end_session(session, start + 120).unwrap();
+        }
+        let now = chrono::Utc::now().timestamp();
+        assert!(tracker.needs_block("Reddit", now).await);
+
+        // The popup was shown, then the user granted themselves a minute
+        *tracker.popup_shown_for.lock().await = Some("Reddit".to_string());
+        tracker
+            .emergency_access
+            .grant_access_with_duration("Reddit", 60)
+            .await
+            .unwrap();
+        assert!(!tracker.needs_block("Reddit", now + 30).await);
+        assert!(tracker.popup_shown_for.lock().await.is_none());
+
+        // Once the minute is up the app is blocked and the popup can show again
+        assert!(tracker.needs_block("Reddit", now + 61).await);
+    }
+}
@@ -2,9 +2,124 @@ use crate::AppState;
 use tauri::{
     menu::{Menu, MenuItem, PredefinedMenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Manager, Runtime,
+    AppHandle, Manager, Runtime,
 };
 
+/// ID of the app's tray icon, used to look it up for updates
+const TRAY_ID: &str = "main-tray";
+
+/// Menu items whose state changes with focus mode or tracking
+struct TrayMenuItems<R: Runtime> {
+    focus_start: MenuItem<R>,
+    focus_stop: MenuItem<R>,
+    tracking_toggle: MenuItem<R>,
+}
+
+/// Snapshot of app state shown in the tray
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrayStatus {
+    pub focus_active: bool,
+    /// Minutes left in a timed focus session
+    pub focus_minutes_remaining: Option<u32>,
+    pub break_due: bool,
+    pub today_total_seconds: i64,
+    pub tracking_paused: bool,
+    /// A strict focus session is running and can't be stopped yet
+    pub focus_strict: bool,
+}
+
+/// Which focus items should be enabled: (start, stop)
+pub fn focus_menu_enabled(focus_active: bool, focus_strict: bool) -> (bool, bool) {
+    (!focus_active, focus_active && !focus_strict)
+}
+
+/// Label of the pause/resume tracking item
+pub fn tracking_menu_text(tracking_paused: bool) -> &'static str {
+    if tracking_paused {
+        "Resume Tracking"
+    } else {
+        "Pause Tracking"
+    }
+}
+
+/// Whether the background tracker is paused; false before it starts
+async fn tracking_paused(state: &AppState) -> bool {
+    state
+        .background_tracker
+        .lock()
+        .await
+        .as_ref()
+        .is_some_and(|tracker| tracker.is_tracking_paused())
+}
+
+/// Build the tray tooltip for the current state
+pub fn tray_tooltip(status: &TrayStatus) -> String {
+    let mut parts = vec!["Digital Wellbeing".to_string()];
+
+    if status.tracking_paused {
+        parts.push("Tracking paused".to_string());
+    }
+
+    if status.focus_active {
+        match status.focus_minutes_remaining {
+            Some(minutes) => parts.push(format!("Focus: {}m left", minutes)),
+            None => parts.push("Focus mode on".to_string()),
+        }
+    } else if status.break_due {
+        parts.push("Break due".to_string());
+    }
+
+    let hours = status.today_total_seconds / 3600;
+    let minutes = (status.today_total_seconds % 3600) / 60;
+    if hours > 0 {
+        parts.push(format!("Today: {}h {}m", hours, minutes));
+    } else {
+        parts.push(format!("Today: {}m", minutes));
+    }
+
+    parts.join(" - ")
+}
+
+/// Refresh the tray tooltip and focus menu items from current app state
+pub async fn refresh_tray<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+
+    let session = state.focus_manager.get_session().await;
+    let today_total_seconds = {
+        let db = state.db.lock().await;
+        db.get_daily_summary()
+            .map(|summary| summary.total_seconds)
+            .unwrap_or(0)
+    };
+
+    let status = TrayStatus {
+        focus_active: session.is_active,
+        focus_minutes_remaining: session.minutes_remaining,
+        break_due: state.break_reminder.is_break_due(),
+        today_total_seconds,
+        tracking_paused: tracking_paused(&state).await,
+        focus_strict: session.is_strict_locked(chrono::Utc::now().timestamp()),
+    };
+
+    if let Some(items) = app.try_state::<TrayMenuItems<R>>() {
+        let (start_enabled, stop_enabled) =
+            focus_menu_enabled(status.focus_active, status.focus_strict);
+        let _ = items.focus_start.set_enabled(start_enabled);
+        let _ = items.focus_stop.set_enabled(stop_enabled);
+        let _ = items
+            .tracking_toggle
+            .set_text(tracking_menu_text(status.tracking_paused));
+    }
+
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        if let Err(e) = tray.set_tooltip(Some(tray_tooltip(&status))) {
+            tracing::debug!(error = %e, "Failed to update tray tooltip");
+        }
+    }
+}
+
 /// Initialize the system tray with menu
 pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
     // Create menu items
@@ -20,6 +135,13 @@ pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
     )?;
     let focus_stop_item =
         MenuItem::with_id(app, "focus_stop", "Stop Focus Mode", true, None::<&str>)?;
+    let tracking_toggle_item = MenuItem::with_id(
+        app,
+        "tracking_toggle",
+        tracking_menu_text(false),
+        true,
+        None::<&str>,
+    )?;
     let separator2 = PredefinedMenuItem::separator(app)?;
     let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
 
@@ -32,13 +154,24 @@ pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
             &separator1,
             &focus_start_item,
             &focus_stop_item,
+            &tracking_toggle_item,
             &separator2,
             &quit_item,
         ],
     )?;
 
+    let (start_enabled, stop_enabled) = focus_menu_enabled(false, false);
+    focus_start_item.set_enabled(start_enabled)?;
+    focus_stop_item.set_enabled(stop_enabled)?;
+
+    app.manage(TrayMenuItems {
+        focus_start: focus_start_item.clone(),
+        focus_stop: focus_stop_item.clone(),
+        tracking_toggle: tracking_toggle_item.clone(),
+    });
+
     // Create the tray icon
-    let _tray = TrayIconBuilder::new()
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .icon(app.default_window_icon().unwrap().clone())
         .menu(&menu)
         .tooltip("Digital Wellbeing")
@@ -59,11 +192,15 @@ pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
                 let app_handle = app.clone();
                 tauri::async_runtime::spawn(async move {
                     if let Some(state) = app_handle.try_state::<AppState>() {
-                        let session = state.focus_manager.start_session(Some(25), None).await;
+                        let session = state
+                            .focus_manager
+                            .start_session(Some(25), None, false)
+                            .await;
                         if session.is_active {
                             tracing::info!("Focus mode started from tray (25 min)");
                         }
                     }
+                    refresh_tray(&app_handle).await;
                 });
             }
             "focus_stop" => {
@@ -71,11 +208,32 @@ pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
                 let app_handle = app.clone();
                 tauri::async_runtime::spawn(async move {
                     if let Some(state) = app_handle.try_state::<AppState>() {
-                        if state.focus_manager.is_active() {
-                            state.focus_manager.stop_session().await;
+                        if let Some(ended) = state.focus_manager.stop_session().await {
+                            crate::record_focus_history(
+                                &state.db,
+                                &state.focus_manager,
+                                &state.goals_state,
+                                &ended,
+                                false,
+                            )
+                            .await;
                             tracing::info!("Focus mode stopped from tray");
                         }
                     }
+                    refresh_tray(&app_handle).await;
+                });
+            }
+            "tracking_toggle" => {
+                // Pause or resume usage tracking from the tray
+                let app_handle = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Some(state) = app_handle.try_state::<AppState>() {
+                        let tracker = state.background_tracker.lock().await.clone();
+                        if let Some(tracker) = tracker {
+                            tracker.toggle_tracking_paused().await;
+                        }
+                    }
+                    refresh_tray(&app_handle).await;
                 });
             }
             "quit" => {
@@ -106,3 +264,64 @@ pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_menu_enabled() {
+        // Inactive: only "Start Focus" is available
+        assert_eq!(focus_menu_enabled(false, false), (true, false));
+        // Active: only "Stop Focus" is available
+        assert_eq!(focus_menu_enabled(true, false), (false, true));
+        // Strict: neither until the timer ends
+        assert_eq!(focus_menu_enabled(true, true), (false, false));
+    }
+
+    #[test]
+    fn test_tray_tooltip() {
+        let idle = TrayStatus {
+            today_total_seconds: 2 * 3600 + 5 * 60,
+            ..Default::default()
+        };
+        assert_eq!(tray_tooltip(&idle), "Digital Wellbeing - Today: 2h 5m");
+
+        let focused = TrayStatus {
+            focus_active: true,
+            focus_minutes_remaining: Some(18),
+            break_due: true,
+            today_total_seconds: 600,
+            tracking_paused: false,
+            focus_strict: false,
+        };
+        assert_eq!(
+            tray_tooltip(&focused),
+            "Digital Wellbeing - Focus: 18m left - Today: 10m"
+        );
+
+        let break_due = TrayStatus {
+            break_due: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            tray_tooltip(&break_due),
+            "Digital Wellbeing - Break due - Today: 0m"
+        );
+
+        let paused = TrayStatus {
+            tracking_paused: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            tray_tooltip(&paused),
+            "Digital Wellbeing - Tracking paused - Today: 0m"
+        );
+    }
+
+    #[test]
+    fn test_tracking_menu_text() {
+        assert_eq!(tracking_menu_text(false), "Pause Tracking");
+        assert_eq!(tracking_menu_text(true), "Resume Tracking");
+    }
+}
@@ -1,5 +1,6 @@
 use active_win_pos_rs::get_active_window;
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU8, Ordering};
 
@@ -289,6 +290,246 @@ static EXACT_MATCH_MAP: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(||
     map
 });
 
+/// Which part of a window title identifies the app
+#[derive(Clone, Copy)]
+enum TitlePart {
+    /// First segment (e.g. Wine: "Notepad++ - file.txt")
+    First,
+    /// Last segment (e.g. Electron: "general - Slack", JVM: "project – IntelliJ IDEA")
+    Last,
+}
+
+/// Window classes reported by shared runtimes rather than the app itself.
+/// For these, the real app is identified from the window title instead.
+static GENERIC_CLASS_RULES: &[(&str, TitlePart)] = &[
+    ("java", TitlePart::Last),
+    ("javaw", TitlePart::Last),
+    ("sun-awt-x11-xframepeer", TitlePart::Last),
+    ("electron", TitlePart::Last),
+    ("wine", TitlePart::First),
+    ("wine64", TitlePart::First),
+    ("wine-preloader", TitlePart::First),
+    ("wine64-preloader", TitlePart::First),
+];
+
+/// Separators commonly used between the document and app parts of a title
+const TITLE_SEPARATORS: &[&str] = &[" - ", " \u{2013} ", " \u{2014} ", " | "];
+
+/// Words that follow a count in badge text, e.g. "Slack | 5 new items"
+const BADGE_WORDS: &[&str] = &[
+    "new item",
+    "new items",
+    "new message",
+    "new messages",
+    "unread",
+    "notification",
+    "notifications",
+    "mention",
+    "mentions",
+];
+
+/// A number of unread items, optionally capped like "99+"
+fn is_count(text: &str) -> bool {
+    let digits = text.strip_suffix('+').unwrap_or(text);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Text after a leading "(3)" or "[12]" count
+fn strip_leading_count(title: &str) -> Option<&str> {
+    [('(', ')'), ('[', ']')]
+        .into_iter()
+        .find_map(|(open, close)| {
+            let (count, rest) = title.strip_prefix(open)?.split_once(close)?;
+            is_count(count).then_some(rest)
+        })
+}
+
+/// Badge text such as "5 new items" or "(2 unread)"
+fn is_badge(segment: &str) -> bool {
+    let segment = segment
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .unwrap_or(segment);
+    segment.split_once(' ').is_some_and(|(count, words)| {
+        is_count(count) && BADGE_WORDS.contains(&words.trim().to_lowercase().as_str())
+    })
+}
+
+/// Strip unread counts and badge text that apps add to their window titles,
+/// e.g. "(3) Discord | #general" -> "Discord | #general" and
+/// "Slack | 5 new items" -> "Slack". Titles that are nothing but a badge are
+/// returned trimmed but otherwise unchanged.
+pub fn strip_title_badges(title: &str) -> &str {
+    let trimmed = title.trim();
+    let mut rest = trimmed;
+
+    loop {
+        if let Some(after) = strip_leading_count(rest) {
+            rest = after.trim_start();
+        } else if let Some(after) = rest.strip_prefix('\u{2022}') {
+            // Unread dot, e.g. "• Discord"
+            rest = after.trim_start();
+        } else {
+            break;
+        }
+    }
+
+    'suffix: loop {
+        for separator in TITLE_SEPARATORS {
+            if let Some((before, badge)) = rest.rsplit_once(separator) {
+                if is_badge(badge.trim()) {
+                    rest = before.trim_end();
+                    continue 'suffix;
+                }
+            }
+        }
+        break;
+    }
+
+    if rest.is_empty() {
+        trimmed
+    } else {
+        rest
+    }
+}
+
+/// Longest window title stored with a usage session, in characters
+pub const MAX_SESSION_TITLE_CHARS: usize = 256;
+
+/// Window title to store with a usage session of `app_name`: badges and a
+/// trailing " - AppName" segment are removed to keep the number of distinct
+/// titles down, and the result is truncated to `MAX_SESSION_TITLE_CHARS`.
+pub fn session_title(title: &str, app_name: &str) -> Option<String> {
+    let mut title = strip_title_badges(title);
+
+    // Only the last segment can be the app suffix
+    let last_separator = TITLE_SEPARATORS
+        .iter()
+        .filter_map(|separator| title.rfind(separator).map(|at| (at, separator.len())))
+        .max();
+    if let Some((at, len)) = last_separator {
+        let suffix = title[at + len..].to_lowercase();
+        if !app_name.is_empty() && suffix.contains(&app_name.to_lowercase()) {
+            title = title[..at].trim_end();
+        }
+    }
+
+    if title.is_empty() || title.eq_ignore_ascii_case(app_name) {
+        return None;
+    }
+    Some(title.chars().take(MAX_SESSION_TITLE_CHARS).collect())
+}
+
+/// Pick the title segment that names the app
+fn title_segment(title: &str, part: TitlePart) -> Option<String> {
+    let title = strip_title_badges(title);
+    let mut segments = vec![title];
+    for separator in TITLE_SEPARATORS {
+        segments = segments
+            .into_iter()
+            .flat_map(|s| s.split(separator))
+            .collect();
+    }
+    let mut segments = segments
+        .into_iter()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    let segment = match part {
+        TitlePart::First => segments.next(),
+        TitlePart::Last => segments.last(),
+    };
+    segment.map(str::to_string)
+}
+
+/// Which part of the window title a title-aware app is split by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TitleRule {
+    /// First separator-delimited segment, e.g. the page in "Page - Firefox"
+    First,
+    /// Last segment, e.g. the project in "main.rs - my-project"
+    Last,
+    /// The whole title, e.g. "user@host: ~" in a terminal
+    Full,
+}
+
+/// app_meta key holding the saved list of [`TitleAwareApp`]s
+pub const TITLE_AWARE_APPS_KEY: &str = "title_aware_apps";
+
+/// An app whose usage is tracked separately per window title
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TitleAwareApp {
+    pub app_name: String,
+    pub rule: TitleRule,
+}
+
+/// Longest title segment kept for a title-aware app
+const MAX_TITLE_SEGMENT_CHARS: usize = 60;
+
+/// The title segment a title-aware app's session is recorded with, stored as
+/// the session's window title under the app's own name. None for other apps
+/// and for titles without a usable segment.
+pub fn title_aware_segment(app_name: &str, title: &str, rules: &[TitleAwareApp]) -> Option<String> {
+    let rule = rules
+        .iter()
+        .find(|rule| rule.app_name.eq_ignore_ascii_case(app_name))?;
+
+    let segment = match rule.rule {
+        TitleRule::First => title_segment(title, TitlePart::First),
+        TitleRule::Last => title_segment(title, TitlePart::Last),
+        TitleRule::Full => Some(strip_title_badges(title).to_string()).filter(|t| !t.is_empty()),
+    };
+
+    segment
+        .filter(|segment| !segment.eq_ignore_ascii_case(app_name))
+        .map(|segment| {
+            let segment: String = segment.chars().take(MAX_TITLE_SEGMENT_CHARS).collect();
+            segment.trim_end().to_string()
+        })
+}
+
+/// Choose the string that best identifies the app behind a window.
+///
+/// Prefers the app name / window class, falling back to the title when the
+/// class is empty. Generic runtime classes (JVM, Electron, Wine) defer to the
+/// title so those apps don't all collapse into one bucket.
+pub fn window_identity(app_name: &str, title: &str) -> Option<String> {
+    let title = title.trim();
+    if app_name.is_empty() {
+        return (!title.is_empty()).then(|| title.to_string());
+    }
+
+    let class = app_name.to_lowercase();
+    let class = class.strip_suffix(".exe").unwrap_or(&class);
+    if let Some((_, part)) = GENERIC_CLASS_RULES
+        .iter()
+        .find(|(generic, _)| *generic == class)
+    {
+        if let Some(segment) = title_segment(title, *part) {
+            return Some(segment);
+        }
+    }
+
+    Some(app_name.to_string())
+}
+
+/// The focused window: the string that identifies its app, and its title
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveWindow {
+    pub name: String,
+    pub title: String,
+}
+
+impl ActiveWindow {
+    fn identify(app_name: &str, title: &str) -> Option<Self> {
+        window_identity(app_name, title).map(|name| ActiveWindow {
+            name,
+            title: title.trim().to_string(),
+        })
+    }
+}
+
 /// Detection backend: 0 = unknown, 1 = wayland-hyprland, 2 = wayland-sway, 3 = x11
 static DETECTION_BACKEND: AtomicU8 = AtomicU8::new(0);
 
@@ -298,9 +539,16 @@ static DETECTION_BACKEND: AtomicU8 = AtomicU8::new(0);
 /// - Hyprland: `hyprctl activewindow -j`
 /// - Sway: `swaymsg -t get_tree`
 /// - X11: `active-win-pos-rs` (xcb)
+/// - Other Wayland compositors: `active-win-pos-rs` through XWayland, then
+///   `get_active_window_wayland` when that finds nothing
 ///
 /// On Windows/macOS, uses `active-win-pos-rs` directly.
 pub fn get_active_window_name() -> Result<Option<String>, String> {
+    Ok(get_active_window_info()?.map(|window| window.name))
+}
+
+/// Get the currently active window with its title (cross-platform)
+pub fn get_active_window_info() -> Result<Option<ActiveWindow>, String> {
     #[cfg(target_os = "linux")]
     {
         let backend = DETECTION_BACKEND.load(Ordering::Relaxed);
@@ -327,11 +575,11 @@ pub fn get_active_window_name() -> Result<Option<String>, String> {
                 // Fall through to X11
                 DETECTION_BACKEND.store(3, Ordering::Relaxed);
                 tracing::info!("Window detection: using X11 (active-win-pos-rs)");
-                get_active_window_x11()
+                get_active_window_x11_or_wayland()
             }
             1 => get_active_window_hyprland(),
             2 => get_active_window_sway(),
-            _ => get_active_window_x11(),
+            _ => get_active_window_x11_or_wayland(),
         }
     }
 
@@ -353,7 +601,7 @@ fn which_exists(cmd: &str) -> bool {
 
 /// Hyprland: get active window via `hyprctl activewindow -j`
 #[cfg(target_os = "linux")]
-fn get_active_window_hyprland() -> Result<Option<String>, String> {
+fn get_active_window_hyprland() -> Result<Option<ActiveWindow>, String> {
     let output = std::process::Command::new("hyprctl")
         .args(["activewindow", "-j"])
         .output()
@@ -371,18 +619,12 @@ fn get_active_window_hyprland() -> Result<Option<String>, String> {
     let class = json.get("class").and_then(|v| v.as_str()).unwrap_or("");
     let title = json.get("title").and_then(|v| v.as_str()).unwrap_or("");
 
-    if !class.is_empty() {
-        Ok(Some(class.to_string()))
-    } else if !title.is_empty() {
-        Ok(Some(title.to_string()))
-    } else {
-        Ok(None)
-    }
+    Ok(ActiveWindow::identify(class, title))
 }
 
 /// Sway: get active window via `swaymsg -t get_tree`
 #[cfg(target_os = "linux")]
-fn get_active_window_sway() -> Result<Option<String>, String> {
+fn get_active_window_sway() -> Result<Option<ActiveWindow>, String> {
     let output = std::process::Command::new("swaymsg")
         .args(["-t", "get_tree"])
         .output()
@@ -397,37 +639,33 @@ fn get_active_window_sway() -> Result<Option<String>, String> {
         serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse sway tree: {}", e))?;
 
     // Recursively find the focused window
-    if let Some(name) = find_sway_focused(&tree) {
-        Ok(Some(name))
-    } else {
-        Ok(None)
-    }
+    Ok(find_sway_focused(&tree))
 }
 
 /// Recursively find the focused node in sway's tree
 #[cfg(target_os = "linux")]
-fn find_sway_focused(node: &serde_json::Value) -> Option<String> {
+fn find_sway_focused(node: &serde_json::Value) -> Option<ActiveWindow> {
     if node
         .get("focused")
         .and_then(|v| v.as_bool())
         .unwrap_or(false)
     {
         // Prefer app_id (Wayland native), fall back to window_properties.class (XWayland)
+        let name = node.get("name").and_then(|v| v.as_str()).unwrap_or("");
         if let Some(app_id) = node.get("app_id").and_then(|v| v.as_str()) {
             if !app_id.is_empty() {
-                return Some(app_id.to_string());
+                return ActiveWindow::identify(app_id, name);
             }
         }
         if let Some(props) = node.get("window_properties") {
             if let Some(class) = props.get("class").and_then(|v| v.as_str()) {
                 if !class.is_empty() {
-                    return Some(class.to_string());
+                    return ActiveWindow::identify(class, name);
                 }
             }
         }
-        let name = node.get("name").and_then(|v| v.as_str()).unwrap_or("");
         if !name.is_empty() {
-            return Some(name.to_string());
+            return ActiveWindow::identify("", name);
         }
     }
 
@@ -450,24 +688,135 @@ fn find_sway_focused(node: &serde_json::Value) -> Option<String> {
     None
 }
 
+/// X11, falling back to asking the compositor when a Wayland session's
+/// focused window isn't visible through XWayland
+#[cfg(target_os = "linux")]
+fn get_active_window_x11_or_wayland() -> Result<Option<ActiveWindow>, String> {
+    let active = get_active_window_x11()?;
+    if active.is_some() || std::env::var("XDG_SESSION_TYPE").unwrap_or_default() != "wayland" {
+        return Ok(active);
+    }
+    get_active_window_wayland()
+}
+
+/// Set once GNOME Shell refused `Eval` (or `gdbus` is missing), which won't
+/// change for the rest of the session, so it isn't asked on every tick
+#[cfg(target_os = "linux")]
+static GNOME_EVAL_UNAVAILABLE: std::sync::atomic::AtomicBool =
+    std::sync::atomic::AtomicBool::new(false);
+
+/// Wayland compositors without a CLI of their own. Supported:
+/// - GNOME Shell, through `org.gnome.Shell.Eval` over D-Bus (`gdbus`). Eval is
+///   only allowed in unsafe mode on GNOME 41+, otherwise this finds nothing.
+/// - KDE Plasma (KWin), through `kdotool`, which uses KWin's scripting interface
+///
+/// Returns `Ok(None)` when neither is available.
+#[cfg(target_os = "linux")]
+fn get_active_window_wayland() -> Result<Option<ActiveWindow>, String> {
+    let desktop = std::env::var("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .to_lowercase();
+
+    if desktop.contains("gnome") {
+        if GNOME_EVAL_UNAVAILABLE.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        let stdout = command_stdout(
+            "gdbus",
+            &[
+                "call",
+                "--session",
+                "--dest",
+                "org.gnome.Shell",
+                "--object-path",
+                "/org/gnome/Shell",
+                "--method",
+                "org.gnome.Shell.Eval",
+                GNOME_FOCUS_WINDOW_JS,
+            ],
+        );
+        if !gnome_eval_ran(stdout.as_deref()) {
+            GNOME_EVAL_UNAVAILABLE.store(true, Ordering::Relaxed);
+            tracing::warn!(
+                "GNOME Shell Eval is unavailable, no longer detecting windows through it"
+            );
+            return Ok(None);
+        }
+        return Ok(stdout.as_deref().and_then(parse_gnome_eval_output));
+    }
+
+    if desktop.contains("kde") {
+        let class = command_stdout("kdotool", &["getactivewindow", "getwindowclassname"]);
+        let title = command_stdout("kdotool", &["getactivewindow", "getwindowname"]);
+        return Ok(class.and_then(|class| {
+            ActiveWindow::identify(class.trim(), title.as_deref().unwrap_or("").trim())
+        }));
+    }
+
+    Ok(None)
+}
+
+/// GNOME Shell script returning the focused window's class and title as JSON
+#[cfg(target_os = "linux")]
+const GNOME_FOCUS_WINDOW_JS: &str = "(() => { const w = global.display.focus_window; \
+    return w ? JSON.stringify({ wm_class: w.get_wm_class(), title: w.get_title() }) : ''; })()";
+
+/// Whether `gdbus` output shows GNOME Shell ran the script. It answers
+/// `(false, '')` when Eval is disabled; None means `gdbus` itself failed.
+#[cfg(any(target_os = "linux", test))]
+fn gnome_eval_ran(output: Option<&str>) -> bool {
+    output.is_some_and(|output| output.trim().starts_with("(true, "))
+}
+
+/// Parse `gdbus` output of `org.gnome.Shell.Eval`, e.g.
+/// `(true, '{"wm_class":"firefox","title":"Mozilla Firefox"}')`
+#[cfg(any(target_os = "linux", test))]
+fn parse_gnome_eval_output(output: &str) -> Option<ActiveWindow> {
+    let quoted = output.trim().strip_prefix("(true, ")?.strip_suffix(')')?;
+    let quote = quoted.chars().next()?;
+    if !matches!(quote, '\'' | '"') {
+        return None;
+    }
+    let inner = quoted.strip_prefix(quote)?.strip_suffix(quote)?;
+
+    // Undo GVariant string escaping
+    let mut json = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            json.extend(chars.next());
+        } else {
+            json.push(c);
+        }
+    }
+
+    let value: serde_json::Value = serde_json::from_str(&json).ok()?;
+    let class = value.get("wm_class").and_then(|v| v.as_str()).unwrap_or("");
+    let title = value.get("title").and_then(|v| v.as_str()).unwrap_or("");
+    ActiveWindow::identify(class, title)
+}
+
 /// X11 / Windows / macOS: use active-win-pos-rs
-fn get_active_window_x11() -> Result<Option<String>, String> {
+fn get_active_window_x11() -> Result<Option<ActiveWindow>, String> {
     match get_active_window() {
         Ok(window) => {
             // Prefer the app_name (process name / window class), fall back to title
-            let name = if !window.app_name.is_empty() {
-                window.app_name
-            } else if !window.title.is_empty() {
-                window.title
-            } else {
+            let Some(active) = ActiveWindow::identify(&window.app_name, &window.title) else {
                 return Ok(None);
             };
 
             // On Windows, strip the .exe extension from app names
             #[cfg(target_os = "windows")]
-            let name = name.strip_suffix(".exe").unwrap_or(&name).to_string();
+            let active = ActiveWindow {
+                name: active
+                    .name
+                    .strip_suffix(".exe")
+                    .unwrap_or(&active.name)
+                    .to_string(),
+                ..active
+            };
 
-            Ok(Some(name))
+            Ok(Some(active))
         }
         Err(e) => {
             // Log the first few failures so silent breakage is visible.
@@ -489,12 +838,231 @@ fn get_active_window_x11() -> Result<Option<String>, String> {
     }
 }
 
+/// Whether the active window can be detected at all. False usually means a
+/// missing OS permission or an unsupported Wayland compositor.
+pub fn check_tracking_permissions() -> bool {
+    matches!(get_active_window_name(), Ok(Some(_)))
+}
+
+/// Names of apps that currently have a visible top-level window, sorted and
+/// deduplicated ignoring case.
+///
+/// - Hyprland: mapped, unhidden clients from `hyprctl clients -j`
+/// - Sway: every window in `swaymsg -t get_tree`
+/// - X11: `wmctrl -lx`
+/// - Windows: owners of visible, unowned windows from `EnumWindows`
+/// - macOS: visible foreground processes from System Events
+///
+/// Best effort: returns an empty list when the platform tool isn't available.
+/// Shells out or walks every window, so call it off the async runtime.
+pub fn get_running_apps() -> Vec<String> {
+    let mut apps: Vec<String> = running_window_classes()
+        .iter()
+        .filter_map(|class| extract_app_name(class))
+        .collect();
+    apps.sort_by_key(|app| app.to_lowercase());
+    apps.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    apps
+}
+
+/// Run a command and return its stdout if it succeeded
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn command_stdout(cmd: &str, args: &[&str]) -> Option<String> {
+    std::process::Command::new(cmd)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(target_os = "linux")]
+fn running_window_classes() -> Vec<String> {
+    match DETECTION_BACKEND.load(Ordering::Relaxed) {
+        1 => command_stdout("hyprctl", &["clients", "-j"])
+            .and_then(|stdout| serde_json::from_str::<serde_json::Value>(&stdout).ok())
+            .and_then(|json| json.as_array().cloned())
+            .unwrap_or_default()
+            .iter()
+            .filter(|client| {
+                let flag = |key: &str| client.get(key).and_then(|v| v.as_bool());
+                flag("mapped").unwrap_or(true) && !flag("hidden").unwrap_or(false)
+            })
+            .filter_map(|client| client.get("class").and_then(|v| v.as_str()))
+            .map(str::to_string)
+            .collect(),
+        2 => {
+            let mut classes = Vec::new();
+            if let Some(tree) = command_stdout("swaymsg", &["-t", "get_tree"])
+                .and_then(|stdout| serde_json::from_str::<serde_json::Value>(&stdout).ok())
+            {
+                collect_sway_classes(&tree, &mut classes);
+            }
+            classes
+        }
+        // wmctrl -lx prints WM_CLASS as "instance.Class" in the third column
+        _ => command_stdout("wmctrl", &["-lx"])
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(2))
+            .map(|wm_class| {
+                wm_class
+                    .split_once('.')
+                    .map_or(wm_class, |(_, class)| class)
+                    .to_string()
+            })
+            .collect(),
+    }
+}
+
+/// Collect the app id or class of every window in sway's tree
+#[cfg(target_os = "linux")]
+fn collect_sway_classes(node: &serde_json::Value, classes: &mut Vec<String>) {
+    let app_id = node.get("app_id").and_then(|v| v.as_str());
+    let class = node
+        .get("window_properties")
+        .and_then(|props| props.get("class"))
+        .and_then(|v| v.as_str());
+    if let Some(name) = app_id.or(class).filter(|name| !name.is_empty()) {
+        classes.push(name.to_string());
+    }
+
+    for key in ["nodes", "floating_nodes"] {
+        if let Some(children) = node.get(key).and_then(|v| v.as_array()) {
+            for child in children {
+                collect_sway_classes(child, classes);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn running_window_classes() -> Vec<String> {
+    win32::visible_window_processes()
+}
+
+/// Top-level window enumeration through user32
+#[cfg(target_os = "windows")]
+mod win32 {
+    use std::ffi::c_void;
+
+    type Hwnd = *mut c_void;
+    type Handle = *mut c_void;
+
+    const GW_OWNER: u32 = 4;
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    const MAX_PATH: usize = 260;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn EnumWindows(callback: extern "system" fn(Hwnd, isize) -> i32, lparam: isize) -> i32;
+        fn IsWindowVisible(hwnd: Hwnd) -> i32;
+        fn GetWindow(hwnd: Hwnd, cmd: u32) -> Hwnd;
+        fn GetWindowThreadProcessId(hwnd: Hwnd, process_id: *mut u32) -> u32;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit_handle: i32, process_id: u32) -> Handle;
+        fn QueryFullProcessImageNameW(
+            process: Handle,
+            flags: u32,
+            name: *mut u16,
+            size: *mut u32,
+        ) -> i32;
+        fn CloseHandle(handle: Handle) -> i32;
+    }
+
+    /// Collect the process id of each visible window that has no owner,
+    /// which skips tool windows and dialogs
+    extern "system" fn collect_window_process(hwnd: Hwnd, lparam: isize) -> i32 {
+        // SAFETY: lparam is the Vec passed to EnumWindows below, which
+        // outlives the enumeration
+        let process_ids = unsafe { &mut *(lparam as *mut Vec<u32>) };
+        unsafe {
+            if IsWindowVisible(hwnd) != 0 && GetWindow(hwnd, GW_OWNER).is_null() {
+                let mut process_id = 0;
+                GetWindowThreadProcessId(hwnd, &mut process_id);
+                process_ids.push(process_id);
+            }
+        }
+        1
+    }
+
+    /// Executable names, without `.exe`, of processes owning a visible
+    /// top-level window
+    pub fn visible_window_processes() -> Vec<String> {
+        let mut process_ids: Vec<u32> = Vec::new();
+        // SAFETY: the callback only touches `process_ids` during this call
+        unsafe {
+            EnumWindows(
+                collect_window_process,
+                &mut process_ids as *mut Vec<u32> as isize,
+            );
+        }
+        process_ids.sort_unstable();
+        process_ids.dedup();
+        process_ids.into_iter().filter_map(process_name).collect()
+    }
+
+    fn process_name(process_id: u32) -> Option<String> {
+        let mut buffer = [0u16; MAX_PATH];
+        let mut size = buffer.len() as u32;
+        // SAFETY: the handle is closed before returning and the buffer size
+        // is passed along with it
+        let ok = unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id);
+            if process.is_null() {
+                return None;
+            }
+            let ok = QueryFullProcessImageNameW(process, 0, buffer.as_mut_ptr(), &mut size) != 0;
+            CloseHandle(process);
+            ok
+        };
+        if !ok {
+            return None;
+        }
+
+        let path = String::from_utf16_lossy(&buffer[..size as usize]);
+        let file = path.rsplit('\\').next()?;
+        let stem = match file.len().checked_sub(4) {
+            Some(end) if file[end..].eq_ignore_ascii_case(".exe") => &file[..end],
+            _ => file,
+        };
+        Some(stem.to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn running_window_classes() -> Vec<String> {
+    command_stdout(
+        "osascript",
+        &[
+            "-e",
+            "tell application \"System Events\" to get name of every process whose visible is true and background only is false",
+        ],
+    )
+    .unwrap_or_default()
+    .trim()
+    .split(", ")
+    .map(str::to_string)
+    .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn running_window_classes() -> Vec<String> {
+    Vec::new()
+}
+
 /// Extract application name from window class or title
 pub fn extract_app_name(window_name: &str) -> Option<String> {
     if window_name.is_empty() {
         return None;
     }
 
+    // Unread counts and badges would otherwise leak into the matching below
+    let window_name = strip_title_badges(window_name);
+
     // Normalize the name
     let name_lower = window_name.to_lowercase();
 
@@ -565,6 +1133,24 @@ pub fn extract_app_name(window_name: &str) -> Option<String> {
     }
 }
 
+/// The display name `name` is a known alias of in `APP_MAPPINGS`, if any.
+/// Unlike `extract_app_name` this has no generic fallback.
+pub fn mapped_display_name(name: &str) -> Option<&'static str> {
+    let name_lower = name.to_lowercase();
+    if let Some(&display_name) = EXACT_MATCH_MAP.get(name_lower.as_str()) {
+        return Some(display_name);
+    }
+    APP_MAPPINGS
+        .iter()
+        .find(|mapping| {
+            mapping.display_name.to_lowercase() == name_lower
+                || mapping
+                    .contains
+                    .is_some_and(|pattern| name_lower.contains(pattern))
+        })
+        .map(|mapping| mapping.display_name)
+}
+
 /// Capitalize the first character of a string
 fn capitalize_first(s: &str) -> String {
     let mut chars = s.chars();
@@ -719,4 +1305,211 @@ mod tests {
             Some("PowerShell".to_string())
         );
     }
+
+    #[test]
+    fn test_window_identity_prefers_class() {
+        assert_eq!(
+            window_identity("firefox", "Some page - Mozilla Firefox"),
+            Some("firefox".to_string())
+        );
+        assert_eq!(
+            window_identity("", "Untitled"),
+            Some("Untitled".to_string())
+        );
+        assert_eq!(window_identity("", ""), None);
+    }
+
+    #[test]
+    fn test_window_identity_generic_classes_use_title() {
+        let electron = window_identity("electron", "general - Slack").unwrap();
+        assert_eq!(extract_app_name(&electron), Some("Slack".to_string()));
+
+        let jvm = window_identity(
+            "java",
+            "wellbeing \u{2013} Main.java \u{2013} IntelliJ IDEA",
+        )
+        .unwrap();
+        assert_eq!(jvm, "IntelliJ IDEA");
+
+        let wine = window_identity("wine64-preloader", "Notepad++ - notes.txt").unwrap();
+        assert_eq!(wine, "Notepad++");
+
+        // Windows process names keep their .exe suffix at this point
+        assert_eq!(
+            window_identity("java.exe", "Minecraft 1.20.1"),
+            Some("Minecraft 1.20.1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_window_identity_generic_class_without_title() {
+        assert_eq!(
+            window_identity("Electron", ""),
+            Some("Electron".to_string())
+        );
+    }
+
+    #[test]
+    fn test_title_aware_apps_split_by_title() {
+        let rules = vec![
+            TitleAwareApp {
+                app_name: "Alacritty".to_string(),
+                rule: TitleRule::Full,
+            },
+            TitleAwareApp {
+                app_name: "Visual Studio Code".to_string(),
+                rule: TitleRule::Last,
+            },
+        ];
+
+        assert_eq!(
+            title_aware_segment("Alacritty", "deploy@prod-db: ~", &rules).as_deref(),
+            Some("deploy@prod-db: ~")
+        );
+        assert_eq!(
+            title_aware_segment("Alacritty", "me@laptop: ~/src", &rules).as_deref(),
+            Some("me@laptop: ~/src")
+        );
+        assert_eq!(
+            title_aware_segment("Visual Studio Code", "main.rs - wellbeing", &rules).as_deref(),
+            Some("wellbeing")
+        );
+    }
+
+    #[test]
+    fn test_other_apps_ignore_title() {
+        let rules = vec![TitleAwareApp {
+            app_name: "Alacritty".to_string(),
+            rule: TitleRule::Full,
+        }];
+
+        assert_eq!(
+            title_aware_segment("Firefox", "GitHub - Mozilla Firefox", &rules),
+            None
+        );
+        // An empty title leaves even a title-aware app unsplit
+        assert_eq!(title_aware_segment("Alacritty", "  ", &rules), None);
+    }
+
+    #[test]
+    fn test_strip_title_badges() {
+        assert_eq!(strip_title_badges("(12) Firefox"), "Firefox");
+        assert_eq!(
+            strip_title_badges("[99+] Inbox - Thunderbird"),
+            "Inbox - Thunderbird"
+        );
+        assert_eq!(
+            strip_title_badges("(3) Discord | #general"),
+            "Discord | #general"
+        );
+        assert_eq!(strip_title_badges("Slack | 5 new items"), "Slack");
+        assert_eq!(
+            strip_title_badges("\u{2022} Discord - (2 unread)"),
+            "Discord"
+        );
+        // Not badges
+        assert_eq!(
+            strip_title_badges("Report (2) - LibreOffice"),
+            "Report (2) - LibreOffice"
+        );
+        assert_eq!(strip_title_badges("Top 10 - Firefox"), "Top 10 - Firefox");
+        assert_eq!(strip_title_badges("(3)"), "(3)");
+    }
+
+    #[test]
+    fn test_session_title() {
+        assert_eq!(
+            session_title("Rust docs - Mozilla Firefox", "Firefox").as_deref(),
+            Some("Rust docs")
+        );
+        assert_eq!(
+            session_title(
+                "(3) main.rs \u{2014} wellbeing - Visual Studio Code",
+                "Visual Studio Code"
+            )
+            .as_deref(),
+            Some("main.rs \u{2014} wellbeing")
+        );
+        // Suffixes that don't name the app are kept
+        assert_eq!(
+            session_title("Inbox - Work", "Firefox").as_deref(),
+            Some("Inbox - Work")
+        );
+        // Only the last segment is checked
+        assert_eq!(
+            session_title("Firefox tips - Blog \u{2014} Mozilla Firefox", "Firefox").as_deref(),
+            Some("Firefox tips - Blog")
+        );
+        // Nothing left but the app name
+        assert_eq!(session_title("Firefox", "Firefox"), None);
+        assert_eq!(session_title("   ", "Firefox"), None);
+
+        let long = format!("{} - Firefox", "\u{e9}".repeat(400));
+        let title = session_title(&long, "Firefox").unwrap();
+        assert_eq!(title.chars().count(), MAX_SESSION_TITLE_CHARS);
+    }
+
+    #[test]
+    fn test_extract_app_name_ignores_badges() {
+        assert_eq!(
+            extract_app_name("(12) Firefox"),
+            Some("Firefox".to_string())
+        );
+        assert_eq!(
+            extract_app_name("(3) Discord | #general"),
+            Some("Discord".to_string())
+        );
+        assert_eq!(
+            extract_app_name("Slack | 5 new items"),
+            Some("Slack".to_string())
+        );
+    }
+
+    #[test]
+    fn test_title_aware_name_ignores_badges() {
+        let rules = vec![TitleAwareApp {
+            app_name: "Discord".to_string(),
+            rule: TitleRule::Last,
+        }];
+        assert_eq!(
+            title_aware_segment("Discord", "(3) #general | Discord | 4 new messages", &rules),
+            None
+        );
+        let rules = vec![TitleAwareApp {
+            app_name: "Discord".to_string(),
+            rule: TitleRule::First,
+        }];
+        assert_eq!(
+            title_aware_segment("Discord", "(3) #general | Discord", &rules).as_deref(),
+            Some("#general")
+        );
+    }
+
+    #[test]
+    fn test_parse_gnome_eval_output() {
+        let active = parse_gnome_eval_output(
+            "(true, '{\"wm_class\":\"firefox\",\"title\":\"Rust - Mozilla Firefox\"}')\n",
+        )
+        .unwrap();
+        assert_eq!(active.name, "firefox");
+        assert_eq!(active.title, "Rust - Mozilla Firefox");
+
+        // GVariant escapes quotes and backslashes inside the string
+        let active = parse_gnome_eval_output(
+            r#"(true, '{"wm_class":"org.gnome.Nautilus","title":"Bob\'s \\"files\\""}')"#,
+        )
+        .unwrap();
+        assert_eq!(active.name, "org.gnome.Nautilus");
+        assert_eq!(active.title, "Bob's \"files\"");
+
+        // Eval disabled, or no focused window
+        assert_eq!(parse_gnome_eval_output("(false, '')"), None);
+        assert_eq!(parse_gnome_eval_output("(true, '')"), None);
+        assert_eq!(parse_gnome_eval_output("garbage"), None);
+
+        // Only a refusal or a failed call stops further attempts
+        assert!(gnome_eval_ran(Some("(true, '')\n")));
+        assert!(!gnome_eval_ran(Some("(false, '')")));
+        assert!(!gnome_eval_ran(None));
+    }
 }